@@ -16,11 +16,12 @@ use chrono::Utc;
 use uuid::Uuid;
 
 use crate::error::{ApiError, Result, TedError};
+use crate::hooks::LifecycleHooks;
 use crate::llm::message::{ContentBlock, Message, MessageContent};
 use crate::llm::provider::{
     CompletionRequest, ContentBlockResponse, LlmProvider, StopReason, ToolChoice, ToolDefinition,
 };
-use crate::tools::{ToolContext, ToolOutput, ToolRegistry, ToolResult};
+use crate::tools::{FileReadTracker, ToolContext, ToolOutput, ToolRegistry, ToolResult};
 
 use super::context::AgentContext;
 use super::memory::{apply_memory_strategy, compact_to_budget, MemoryAction};
@@ -252,7 +253,16 @@ impl AgentRunner {
             Some(context.config.working_dir.clone()),
             agent_id,
             true, // Subagents run in trust mode within their permission scope
+        )
+        // Each agent tracks its own reads so concurrent agents can't clobber each other's edits
+        .with_read_tracker(FileReadTracker::new());
+
+        // Lifecycle hooks from the agent's workspace settings
+        let hooks = LifecycleHooks::load_or_default(
+            &context.config.working_dir,
+            Some(&context.config.working_dir),
         );
+        let mut stop_continuations: usize = 0;
 
         let mut errors: Vec<String> = Vec::new();
         let mut last_output = String::new();
@@ -430,7 +440,13 @@ impl AgentRunner {
             // If there are tool uses, execute them
             if has_tool_use {
                 let tool_results = self
-                    .execute_tools(&response.content, &context, &tool_context, &progress)
+                    .execute_tools(
+                        &response.content,
+                        &context,
+                        &tool_context,
+                        &hooks,
+                        &progress,
+                    )
                     .await?;
 
                 // Track file access
@@ -446,6 +462,31 @@ impl AgentRunner {
             // Check stop reason
             match response.stop_reason {
                 Some(StopReason::EndTurn) if !has_tool_use => {
+                    // SubagentStop hooks may send the agent back to work
+                    if stop_continuations < crate::hooks::MAX_STOP_CONTINUATIONS {
+                        let outcome = hooks
+                            .subagent_stop(
+                                agent_id,
+                                &agent_name,
+                                &agent_type,
+                                &last_output,
+                                stop_continuations > 0,
+                            )
+                            .await;
+                        if let Some(reason) = outcome.block_reason {
+                            stop_continuations += 1;
+                            if self.config.verbose && !self.config.quiet {
+                                eprintln!("  [{}] SubagentStop hook: {}", agent_name, reason);
+                            }
+                            context
+                                .add_message(Message::user(crate::hooks::stop_feedback_message(
+                                    &reason,
+                                )))
+                                .await?;
+                            continue;
+                        }
+                    }
+
                     // Agent is done
                     if self.config.verbose && !self.config.quiet {
                         eprintln!("  [{}] End turn (completing)", agent_name);
@@ -651,6 +692,7 @@ impl AgentRunner {
         content: &[ContentBlockResponse],
         agent_context: &AgentContext,
         tool_context: &ToolContext,
+        hooks: &LifecycleHooks,
         progress: &Option<ProgressSender>,
     ) -> Result<Vec<ToolResult>> {
         let mut results = Vec::new();
@@ -696,6 +738,26 @@ impl AgentRunner {
                     }
                 };
 
+                // PreToolUse hooks may veto the call or rewrite its input
+                let pre_hooks = hooks
+                    .pre_tool_use(tool_context.session_id, id, name, input)
+                    .await;
+                if let Some(reason) = &pre_hooks.block_reason {
+                    send_progress(AgentProgressEvent::ToolComplete {
+                        tool_name: name.clone(),
+                        success: false,
+                    });
+                    results.push(ToolResult {
+                        tool_use_id: id.clone(),
+                        output: ToolOutput::Error(format!(
+                            "Blocked by PreToolUse hook: {}",
+                            reason
+                        )),
+                    });
+                    continue;
+                }
+                let input = pre_hooks.tool_input.as_ref().unwrap_or(input);
+
                 // Create input summary for progress reporting
                 let input_summary = summarize_tool_input(name, input);
 
@@ -715,7 +777,18 @@ impl AgentRunner {
                     eprintln!("  [{}] → Tool: {}", agent_context.config.name, name);
                 }
 
-                match tool.execute(id.clone(), input.clone(), tool_context).await {
+                let executed = match tool.execute(id.clone(), input.clone(), tool_context).await {
+                    Ok(result) => {
+                        let result = pre_hooks.apply_to_result(result);
+                        let post_hooks = hooks
+                            .post_tool_use(tool_context.session_id, name, input, &result)
+                            .await;
+                        Ok(post_hooks.apply_to_result(result))
+                    }
+                    Err(e) => Err(e),
+                };
+
+                match executed {
                     Ok(result) => {
                         let success = !result.is_error();
                         send_progress(AgentProgressEvent::ToolComplete {
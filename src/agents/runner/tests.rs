@@ -1463,7 +1463,13 @@ async fn test_execute_tools_disallowed_tool() {
     }];
 
     let results = runner
-        .execute_tools(&content, &context, &tool_context, &None)
+        .execute_tools(
+            &content,
+            &context,
+            &tool_context,
+            &LifecycleHooks::default(),
+            &None,
+        )
         .await
         .unwrap();
 
@@ -1496,7 +1502,13 @@ async fn test_execute_tools_unknown_tool() {
     }];
 
     let results = runner
-        .execute_tools(&content, &context, &tool_context, &None)
+        .execute_tools(
+            &content,
+            &context,
+            &tool_context,
+            &LifecycleHooks::default(),
+            &None,
+        )
         .await
         .unwrap();
 
@@ -1528,7 +1540,13 @@ async fn test_execute_tools_text_block_ignored() {
     ];
 
     let results = runner
-        .execute_tools(&content, &context, &tool_context, &None)
+        .execute_tools(
+            &content,
+            &context,
+            &tool_context,
+            &LifecycleHooks::default(),
+            &None,
+        )
         .await
         .unwrap();
 
@@ -1536,6 +1554,52 @@ async fn test_execute_tools_text_block_ignored() {
     assert!(results.is_empty());
 }
 
+#[tokio::test]
+async fn test_execute_tools_pre_tool_use_hook_blocks() {
+    let temp = tempfile::TempDir::new().unwrap();
+    let runner = make_test_runner();
+    let config = AgentConfig::new("implement", "Add feature", temp.path().to_path_buf());
+    let context = AgentContext::new(config);
+    let tool_context = ToolContext::new(
+        temp.path().to_path_buf(),
+        Some(temp.path().to_path_buf()),
+        Uuid::new_v4(),
+        true,
+    );
+    let hooks = LifecycleHooks::from_config(
+        &crate::config::HooksConfig {
+            pre_tool_use: vec![crate::config::HookRule {
+                matcher: Some("file_write".to_string()),
+                command: "echo 'agents may not write here' >&2; exit 2".to_string(),
+                timeout_secs: None,
+            }],
+            ..Default::default()
+        },
+        temp.path().to_path_buf(),
+    )
+    .with_audit_log(crate::audit::HookAuditLog::new(
+        temp.path().join("hooks.jsonl"),
+    ));
+
+    let content = vec![ContentBlockResponse::ToolUse {
+        id: "tool-1".to_string(),
+        name: "file_write".to_string(),
+        input: serde_json::json!({"path": "out.txt", "content": "x"}),
+    }];
+
+    let results = runner
+        .execute_tools(&content, &context, &tool_context, &hooks, &None)
+        .await
+        .unwrap();
+
+    assert_eq!(results.len(), 1);
+    assert!(results[0].is_error());
+    assert!(results[0]
+        .output_text()
+        .contains("agents may not write here"));
+    assert!(!temp.path().join("out.txt").exists());
+}
+
 // ==================== track_file_access Tests ====================
 
 #[test]
@@ -3,10 +3,12 @@
 
 //! Local append-only audit logging.
 //!
-//! This module currently stores permission decisions to JSONL for local review
-//! and compliance workflows.
+//! This module stores permission decisions, secret redactions, lifecycle
+//! hook decisions and MCP sampling requests to JSONL for local review and
+//! compliance workflows.
 
 use chrono::{DateTime, Utc};
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use std::fs::OpenOptions;
 use std::io::Write;
@@ -91,6 +93,9 @@ impl PermissionAuditEvent {
                 self.policy_scope = Some("project".to_string());
                 self.policy_path = Some(path.display().to_string());
             }
+            PolicySource::Builtin => {
+                self.policy_scope = Some("builtin".to_string());
+            }
         }
         self
     }
@@ -118,54 +123,278 @@ impl PermissionAuditLog {
     }
 
     pub fn append(&self, event: &PermissionAuditEvent) -> Result<()> {
-        if let Some(parent) = self.path.parent() {
-            std::fs::create_dir_all(parent)?;
+        append_jsonl(&self.path, event)
+    }
+
+    pub fn read_recent(&self, limit: usize) -> Result<Vec<PermissionAuditEvent>> {
+        read_recent_jsonl(&self.path, limit, "permission")
+    }
+}
+
+/// Append-only event describing a secret redacted from an outgoing request.
+///
+/// The secret itself is never recorded; only its kind, placeholder and length.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct RedactionAuditEvent {
+    pub timestamp: DateTime<Utc>,
+    pub provider: String,
+    pub kind: String,
+    pub placeholder: String,
+    pub location: String,
+    pub secret_length: usize,
+}
+
+impl RedactionAuditEvent {
+    /// Build an event with current timestamp.
+    pub fn new(
+        provider: String,
+        kind: String,
+        placeholder: String,
+        location: String,
+        secret_length: usize,
+    ) -> Self {
+        Self {
+            timestamp: Utc::now(),
+            provider,
+            kind,
+            placeholder,
+            location,
+            secret_length,
         }
+    }
+}
 
-        let json = serde_json::to_string(event)?;
-        let mut file = OpenOptions::new()
-            .create(true)
-            .append(true)
-            .open(&self.path)?;
-        file.write_all(json.as_bytes())?;
-        file.write_all(b"\n")?;
-        Ok(())
+/// Append-only JSONL log for secret redactions.
+#[derive(Debug, Clone)]
+pub struct RedactionAuditLog {
+    path: PathBuf,
+}
+
+impl Default for RedactionAuditLog {
+    fn default() -> Self {
+        Self::new(Settings::redaction_audit_log_path())
     }
+}
 
-    pub fn read_recent(&self, limit: usize) -> Result<Vec<PermissionAuditEvent>> {
-        if limit == 0 || !self.path.exists() {
-            return Ok(Vec::new());
+impl RedactionAuditLog {
+    pub fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn append(&self, event: &RedactionAuditEvent) -> Result<()> {
+        append_jsonl(&self.path, event)
+    }
+
+    pub fn read_recent(&self, limit: usize) -> Result<Vec<RedactionAuditEvent>> {
+        read_recent_jsonl(&self.path, limit, "redaction")
+    }
+}
+
+/// Lifecycle hook decision recorded to the audit log.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum HookDecision {
+    Allow,
+    Block,
+    Modify,
+    Annotate,
+    Error,
+}
+
+/// Append-only event describing the outcome of one hook command.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct HookAuditEvent {
+    pub timestamp: DateTime<Utc>,
+    pub event: String,
+    pub command: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tool_name: Option<String>,
+    pub decision: HookDecision,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub exit_code: Option<i32>,
+    pub duration_ms: u64,
+}
+
+impl HookAuditEvent {
+    /// Build an event with current timestamp.
+    pub fn new(event: String, command: String, decision: HookDecision, duration_ms: u64) -> Self {
+        Self {
+            timestamp: Utc::now(),
+            event,
+            command,
+            tool_name: None,
+            decision,
+            reason: None,
+            exit_code: None,
+            duration_ms,
         }
+    }
+}
 
-        let raw = std::fs::read_to_string(&self.path)?;
-        let mut parsed: Vec<PermissionAuditEvent> = raw
-            .lines()
-            .filter_map(|line| {
-                let trimmed = line.trim();
-                if trimmed.is_empty() {
-                    return None;
-                }
-                match serde_json::from_str::<PermissionAuditEvent>(trimmed) {
-                    Ok(event) => Some(event),
-                    Err(err) => {
-                        tracing::warn!(
-                            target: "ted.audit.permissions",
-                            error = %err,
-                            "Skipping malformed permission audit log line"
-                        );
-                        None
-                    }
-                }
-            })
-            .collect();
+/// Append-only JSONL log for lifecycle hook decisions.
+#[derive(Debug, Clone)]
+pub struct HookAuditLog {
+    path: PathBuf,
+}
+
+impl Default for HookAuditLog {
+    fn default() -> Self {
+        Self::new(Settings::hooks_audit_log_path())
+    }
+}
+
+impl HookAuditLog {
+    pub fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
 
-        if parsed.len() > limit {
-            let keep_start = parsed.len() - limit;
-            parsed.drain(0..keep_start);
+    pub fn append(&self, event: &HookAuditEvent) -> Result<()> {
+        append_jsonl(&self.path, event)
+    }
+
+    pub fn read_recent(&self, limit: usize) -> Result<Vec<HookAuditEvent>> {
+        read_recent_jsonl(&self.path, limit, "hook")
+    }
+}
+
+/// Outcome of a `sampling/createMessage` request from an MCP server.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SamplingDecision {
+    Completed,
+    Denied,
+    LimitExceeded,
+    Failed,
+}
+
+/// Append-only event describing one sampling request and its token usage.
+///
+/// Prompt and completion text are never recorded.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SamplingAuditEvent {
+    pub timestamp: DateTime<Utc>,
+    pub server: String,
+    pub provider: String,
+    pub model: String,
+    pub decision: SamplingDecision,
+    pub max_tokens: u32,
+    #[serde(default)]
+    pub input_tokens: u32,
+    #[serde(default)]
+    pub output_tokens: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+}
+
+impl SamplingAuditEvent {
+    /// Build an event with current timestamp and no usage.
+    pub fn new(
+        server: String,
+        provider: String,
+        model: String,
+        decision: SamplingDecision,
+        max_tokens: u32,
+    ) -> Self {
+        Self {
+            timestamp: Utc::now(),
+            server,
+            provider,
+            model,
+            decision,
+            max_tokens,
+            input_tokens: 0,
+            output_tokens: 0,
+            note: None,
         }
+    }
+}
+
+/// Append-only JSONL log for MCP sampling requests.
+#[derive(Debug, Clone)]
+pub struct SamplingAuditLog {
+    path: PathBuf,
+}
+
+impl Default for SamplingAuditLog {
+    fn default() -> Self {
+        Self::new(Settings::sampling_audit_log_path())
+    }
+}
+
+impl SamplingAuditLog {
+    pub fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
 
-        Ok(parsed)
+    pub fn append(&self, event: &SamplingAuditEvent) -> Result<()> {
+        append_jsonl(&self.path, event)
     }
+
+    pub fn read_recent(&self, limit: usize) -> Result<Vec<SamplingAuditEvent>> {
+        read_recent_jsonl(&self.path, limit, "sampling")
+    }
+}
+
+fn append_jsonl<T: Serialize>(path: &Path, event: &T) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    let json = serde_json::to_string(event)?;
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    file.write_all(json.as_bytes())?;
+    file.write_all(b"\n")?;
+    Ok(())
+}
+
+fn read_recent_jsonl<T: DeserializeOwned>(path: &Path, limit: usize, kind: &str) -> Result<Vec<T>> {
+    if limit == 0 || !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let raw = std::fs::read_to_string(path)?;
+    let mut parsed: Vec<T> = raw
+        .lines()
+        .filter_map(|line| {
+            let trimmed = line.trim();
+            if trimmed.is_empty() {
+                return None;
+            }
+            match serde_json::from_str::<T>(trimmed) {
+                Ok(event) => Some(event),
+                Err(err) => {
+                    tracing::warn!(
+                        target: "ted.audit",
+                        error = %err,
+                        log = kind,
+                        "Skipping malformed audit log line"
+                    );
+                    None
+                }
+            }
+        })
+        .collect();
+
+    if parsed.len() > limit {
+        let keep_start = parsed.len() - limit;
+        parsed.drain(0..keep_start);
+    }
+
+    Ok(parsed)
 }
 
 #[cfg(test)]
@@ -213,4 +442,65 @@ mod tests {
         let events = log.read_recent(20).unwrap();
         assert!(events.is_empty());
     }
+
+    #[test]
+    fn test_redaction_audit_append_and_read_recent() {
+        let temp = tempdir().unwrap();
+        let log = RedactionAuditLog::new(temp.path().join("audit").join("redactions.jsonl"));
+
+        log.append(&RedactionAuditEvent::new(
+            "anthropic".to_string(),
+            "aws_access_key".to_string(),
+            "[REDACTED:aws_access_key#1]".to_string(),
+            "messages[2].tool_result".to_string(),
+            20,
+        ))
+        .unwrap();
+
+        let events = log.read_recent(10).unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].kind, "aws_access_key");
+        assert_eq!(events[0].secret_length, 20);
+        assert!(log.path().exists());
+    }
+
+    #[test]
+    fn test_hook_audit_append_and_read_recent() {
+        let temp = tempdir().unwrap();
+        let log = HookAuditLog::new(temp.path().join("audit").join("hooks.jsonl"));
+
+        let mut event = HookAuditEvent::new(
+            "PreToolUse".to_string(),
+            "./guard.sh".to_string(),
+            HookDecision::Block,
+            12,
+        );
+        event.tool_name = Some("shell".to_string());
+        event.reason = Some("rm is not allowed".to_string());
+        event.exit_code = Some(2);
+        log.append(&event).unwrap();
+
+        let events = log.read_recent(10).unwrap();
+        assert_eq!(events, vec![event]);
+    }
+
+    #[test]
+    fn test_sampling_audit_append_and_read_recent() {
+        let temp = tempdir().unwrap();
+        let log = SamplingAuditLog::new(temp.path().join("audit").join("sampling.jsonl"));
+
+        let mut event = SamplingAuditEvent::new(
+            "docs".to_string(),
+            "anthropic".to_string(),
+            "claude-sonnet-4".to_string(),
+            SamplingDecision::Completed,
+            1024,
+        );
+        event.input_tokens = 120;
+        event.output_tokens = 48;
+        log.append(&event).unwrap();
+
+        let events = log.read_recent(10).unwrap();
+        assert_eq!(events, vec![event]);
+    }
 }
@@ -79,6 +79,13 @@ pub struct BeadsArgs {
     pub value: Option<String>,
 }
 
+/// Arguments for /plan-mode command
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct PlanModeArgs {
+    /// Subcommand: None (enter plan mode), "on", "off", "approve", "status"
+    pub subcommand: Option<String>,
+}
+
 /// Arguments for /model command
 #[derive(Debug, Clone, PartialEq, Eq, Default)]
 pub struct ModelArgs {
@@ -111,6 +118,10 @@ pub enum ChatCommand {
     Plans,
     /// List plans
     PlansList,
+    /// Enter, leave or approve plan mode
+    PlanMode(PlanModeArgs),
+    /// Show external MCP server status
+    Mcp,
     /// Model commands (list, download, load, info, switch)
     Model(ModelArgs),
     /// Show active caps
@@ -210,6 +221,9 @@ pub fn parse_command(input: &str) -> ChatCommand {
     }
 
     // Check for plans commands
+    if let Some(args) = input_parser::parse_plan_mode_command(trimmed) {
+        return ChatCommand::PlanMode(args);
+    }
     let lower = trimmed.to_lowercase();
     if lower == "/plans list" || lower == "/plan list" {
         return ChatCommand::PlansList;
@@ -218,6 +232,11 @@ pub fn parse_command(input: &str) -> ChatCommand {
         return ChatCommand::Plans;
     }
 
+    // Check for MCP status command
+    if input_parser::is_mcp_command(trimmed) {
+        return ChatCommand::Mcp;
+    }
+
     // Check for model commands
     if let Some(args) = input_parser::parse_model_command(trimmed) {
         return ChatCommand::Model(args);
@@ -387,10 +406,14 @@ Model & Capabilities:
   /cap clear  - Remove all caps
   /cap create <name> - Create a new cap
   /cap list   - List available caps
+  /mcp        - Show external MCP servers and their tools
 
 Plans:
   /plans      - Open plans browser
   /plans list - List all plans
+  /plan-mode  - Enter plan mode (read-only until a plan is approved)
+  /plan-mode approve - Approve this session's plan and start executing
+  /plan-mode off     - Leave plan mode without approving
 
 Shell Commands:
   >command    - Execute a shell command directly
@@ -539,6 +562,13 @@ mod tests {
         assert_eq!(parse_command("/plan"), ChatCommand::Plans);
         assert_eq!(parse_command("/plans list"), ChatCommand::PlansList);
         assert_eq!(parse_command("/plan list"), ChatCommand::PlansList);
+        assert_eq!(parse_command("/mcp"), ChatCommand::Mcp);
+        assert_eq!(
+            parse_command("/plan-mode approve"),
+            ChatCommand::PlanMode(PlanModeArgs {
+                subcommand: Some("approve".to_string())
+            })
+        );
     }
 
     #[test]
@@ -743,6 +773,7 @@ mod tests {
         assert!(help.contains("/model"));
         assert!(help.contains("/caps"));
         assert!(help.contains("/plans"));
+        assert!(help.contains("/mcp"));
         assert!(help.contains("exit"));
         assert!(help.contains(">command"));
     }
@@ -90,6 +90,13 @@ pub fn format_tool_input_summary(tool_name: &str, input: &serde_json::Value) ->
                 "Updating plan".to_string()
             }
         }
+        "ask_user" => {
+            if let Some(question) = input.get("question").and_then(|v| v.as_str()) {
+                format!("Asking: {}", truncate_string(question, 40))
+            } else {
+                "Asking user".to_string()
+            }
+        }
         _ => {
             // Generic formatting for unknown tools
             format!("Running {}", tool_name)
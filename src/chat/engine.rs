@@ -449,6 +449,7 @@ pub async fn run_agent_loop_inner(
 ) -> Result<bool> {
     let mut tool_call_tracker = ToolCallTracker::new(MAX_RECENT_TOOL_CALLS);
     let mut turn_index: usize = 0;
+    let mut stop_continuations: usize = 0;
 
     loop {
         turn_index += 1;
@@ -576,6 +577,28 @@ pub async fn run_agent_loop_inner(
         }
 
         if stop_reason != Some(StopReason::ToolUse) {
+            if stop_continuations < crate::hooks::MAX_STOP_CONTINUATIONS {
+                let outcome = tool_executor
+                    .hooks()
+                    .stop(
+                        tool_executor.context().session_id,
+                        &text_content,
+                        stop_continuations > 0,
+                    )
+                    .await;
+                if let Some(reason) = outcome.block_reason {
+                    stop_continuations += 1;
+                    tracing::info!(
+                        target: "ted.chat.engine",
+                        turn = turn_index,
+                        reason = %reason,
+                        "stop hook asked the agent to continue"
+                    );
+                    conversation.push(Message::user(crate::hooks::stop_feedback_message(&reason)));
+                    continue;
+                }
+            }
+
             observer.on_agent_complete()?;
             tracing::debug!(
                 target: "ted.chat.engine",
@@ -613,7 +636,7 @@ pub async fn get_response_with_context_retry(
             .with_tools(request_tools)
             .with_tool_choice(tool_choice);
 
-        let mut effective_system = conversation.system_prompt.clone().unwrap_or_default();
+        let mut effective_system = conversation.effective_system().unwrap_or_default();
         if let Some(hint) = extra_system_hint {
             if !effective_system.is_empty() {
                 effective_system.push_str("\n\n");
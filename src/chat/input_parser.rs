@@ -12,7 +12,8 @@ use crate::llm::provider::ContentBlockResponse;
 use crate::beads::BeadStatus;
 
 use super::commands::{
-    BeadsArgs, CommitArgs, ExplainArgs, FixArgs, ModelArgs, ReviewArgs, SkillsArgs, TestArgs,
+    BeadsArgs, CommitArgs, ExplainArgs, FixArgs, ModelArgs, PlanModeArgs, ReviewArgs, SkillsArgs,
+    TestArgs,
 };
 
 /// Parse a shell command from user input.
@@ -88,6 +89,29 @@ pub fn is_plans_command(input: &str) -> bool {
     trimmed == "/plans" || trimmed == "/plan"
 }
 
+/// Check if user input is an MCP server status command.
+pub fn is_mcp_command(input: &str) -> bool {
+    input.trim().to_lowercase() == "/mcp"
+}
+
+/// Parse /plan-mode command and arguments.
+/// Supports: /plan-mode, /plan-mode on, /plan-mode off, /plan-mode approve,
+///           /plan-mode status
+pub fn parse_plan_mode_command(input: &str) -> Option<PlanModeArgs> {
+    let lower = input.trim().to_lowercase();
+    let rest = lower.strip_prefix("/plan-mode")?;
+    if !rest.is_empty() && !rest.starts_with(' ') {
+        return None;
+    }
+
+    let subcommand = match rest.trim() {
+        "" => None,
+        "exit" => Some("off".to_string()),
+        other => Some(other.to_string()),
+    };
+    Some(PlanModeArgs { subcommand })
+}
+
 /// Check if user input is a model command.
 pub fn is_model_command(input: &str) -> bool {
     let trimmed = input.trim().to_lowercase();
@@ -983,6 +1007,16 @@ mod tests {
         assert!(!is_new_command("/newone"));
     }
 
+    // ==================== is_mcp_command tests ====================
+
+    #[test]
+    fn test_is_mcp_command() {
+        assert!(is_mcp_command("/mcp"));
+        assert!(is_mcp_command("  /MCP "));
+        assert!(!is_mcp_command("/mcp list"));
+        assert!(!is_mcp_command("mcp"));
+    }
+
     // ==================== is_plans_command tests ====================
 
     #[test]
@@ -998,6 +1032,30 @@ mod tests {
         assert!(!is_plans_command("/planning"));
     }
 
+    // ==================== parse_plan_mode_command tests ====================
+
+    #[test]
+    fn test_parse_plan_mode_command() {
+        assert_eq!(
+            parse_plan_mode_command("/plan-mode"),
+            Some(PlanModeArgs::default())
+        );
+        assert_eq!(
+            parse_plan_mode_command("  /Plan-Mode Approve "),
+            Some(PlanModeArgs {
+                subcommand: Some("approve".to_string())
+            })
+        );
+        assert_eq!(
+            parse_plan_mode_command("/plan-mode exit"),
+            Some(PlanModeArgs {
+                subcommand: Some("off".to_string())
+            })
+        );
+        assert!(parse_plan_mode_command("/plan-modes").is_none());
+        assert!(parse_plan_mode_command("/plan").is_none());
+    }
+
     // ==================== is_model_command tests ====================
 
     #[test]
@@ -18,7 +18,7 @@ use crate::llm::message::Conversation;
 use crate::llm::provider::LlmProvider;
 use crate::llm::ProviderFactory;
 use crate::skills::SkillRegistry;
-use crate::tools::{ToolContext, ToolExecutor};
+use crate::tools::{FileReadTracker, ToolContext, ToolExecutor};
 
 /// Increment message count, optionally set initial summary, and persist session metadata.
 pub fn record_message_and_persist(
@@ -324,7 +324,8 @@ impl ChatSessionBuilder {
             session_id.0,
             self.trust_mode,
         )
-        .with_files_in_context(self.files_in_context.clone());
+        .with_files_in_context(self.files_in_context.clone())
+        .with_read_tracker(FileReadTracker::new());
         let mut tool_executor = ToolExecutor::new(tool_context, self.trust_mode);
 
         // Initialize skill registry
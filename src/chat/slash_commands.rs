@@ -9,11 +9,18 @@
 
 use std::path::Path;
 
+use uuid::Uuid;
+
 use crate::beads::{init_beads, Bead, BeadId, BeadStatus, BeadStore};
+use crate::llm::message::Conversation;
+use crate::plans::{PlanStatus, PlanStore};
 use crate::skills::SkillRegistry;
+use crate::tools::plan_mode::{approved_plan_context, PLAN_MODE_CONTEXT};
+use crate::tools::PlanMode;
 
 use super::commands::{
-    BeadsArgs, CommitArgs, ExplainArgs, FixArgs, ModelArgs, ReviewArgs, SkillsArgs, TestArgs,
+    BeadsArgs, CommitArgs, ExplainArgs, FixArgs, ModelArgs, PlanModeArgs, ReviewArgs, SkillsArgs,
+    TestArgs,
 };
 use super::input_parser::parse_bead_status;
 use crate::models::{scan_for_models, DownloadRegistry, ModelCategory};
@@ -649,6 +656,94 @@ fn execute_beads_stats(store: &BeadStore) -> SlashCommandResult {
     SlashCommandResult::Message(output)
 }
 
+// === Plan Mode Command Execution ===
+
+/// Execute /plan-mode command
+///
+/// Entering plan mode pins the plan mode instructions to the conversation.
+/// Approving pins the session's latest plan instead and switches the shared
+/// switch back to execution mode.
+pub fn execute_plan_mode(
+    args: &PlanModeArgs,
+    plan_mode: &PlanMode,
+    conversation: &mut Conversation,
+    session_id: Uuid,
+) -> SlashCommandResult {
+    match args.subcommand.as_deref() {
+        None | Some("on") => {
+            if plan_mode.is_enabled() {
+                return SlashCommandResult::Message("Plan mode is already on.".to_string());
+            }
+            plan_mode.set_enabled(true);
+            conversation.set_pinned_context(Some(PLAN_MODE_CONTEXT.to_string()));
+            SlashCommandResult::Message(
+                "Plan mode on. Ted can explore but not change files until you approve a plan.\n\
+                 Approve it with /plan-mode approve, or leave with /plan-mode off."
+                    .to_string(),
+            )
+        }
+        Some("off") => {
+            if !plan_mode.is_enabled() {
+                return SlashCommandResult::Message("Plan mode is already off.".to_string());
+            }
+            plan_mode.set_enabled(false);
+            if conversation.pinned_context.as_deref() == Some(PLAN_MODE_CONTEXT) {
+                conversation.set_pinned_context(None);
+            }
+            SlashCommandResult::Message("Plan mode off. All tools are available again.".to_string())
+        }
+        Some("status") => SlashCommandResult::Message(if plan_mode.is_enabled() {
+            "Plan mode is on: mutating tools are disabled.".to_string()
+        } else {
+            "Plan mode is off.".to_string()
+        }),
+        Some("approve") => execute_plan_mode_approve(plan_mode, conversation, session_id),
+        Some(cmd) => SlashCommandResult::Error(format!(
+            "Unknown plan-mode subcommand: {}. Use: on, off, approve, status",
+            cmd
+        )),
+    }
+}
+
+/// Approve the session's latest plan and switch to execution mode
+fn execute_plan_mode_approve(
+    plan_mode: &PlanMode,
+    conversation: &mut Conversation,
+    session_id: Uuid,
+) -> SlashCommandResult {
+    if !plan_mode.is_enabled() {
+        return SlashCommandResult::Error(
+            "Plan mode is off. Enter it with /plan-mode first.".to_string(),
+        );
+    }
+
+    let mut store = match PlanStore::open() {
+        Ok(store) => store,
+        Err(e) => return SlashCommandResult::Error(format!("Failed to open plans: {}", e)),
+    };
+    let Some(plan_id) = store.latest_for_session(session_id).map(|info| info.id) else {
+        return SlashCommandResult::Error(
+            "No plan for this session yet. Ask Ted to write one with plan_update first."
+                .to_string(),
+        );
+    };
+    let plan = match store.get(plan_id) {
+        Ok(Some(plan)) => plan,
+        Ok(None) => return SlashCommandResult::Error(format!("Plan file missing for {}", plan_id)),
+        Err(e) => return SlashCommandResult::Error(format!("Failed to load plan: {}", e)),
+    };
+    if let Err(e) = store.set_status(plan_id, PlanStatus::Active) {
+        return SlashCommandResult::Error(format!("Failed to update plan: {}", e));
+    }
+
+    plan_mode.set_enabled(false);
+    conversation.set_pinned_context(Some(approved_plan_context(&plan)));
+    SlashCommandResult::SendToLlm(format!(
+        "I approve the plan \"{}\". Plan mode is off; start implementing it.",
+        plan.info.title
+    ))
+}
+
 // === Model Command Execution ===
 
 fn normalize_model_lookup_name(input: &str) -> String {
@@ -1566,6 +1661,93 @@ mod tests {
 
     // === Model Command Tests ===
 
+    fn plan_mode_args(subcommand: Option<&str>) -> PlanModeArgs {
+        PlanModeArgs {
+            subcommand: subcommand.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_execute_plan_mode_on_and_off() {
+        let plan_mode = PlanMode::default();
+        let mut conversation = Conversation::new();
+
+        let result = execute_plan_mode(
+            &plan_mode_args(None),
+            &plan_mode,
+            &mut conversation,
+            Uuid::new_v4(),
+        );
+        assert!(
+            matches!(result, SlashCommandResult::Message(msg) if msg.starts_with("Plan mode on"))
+        );
+        assert!(plan_mode.is_enabled());
+        assert_eq!(
+            conversation.pinned_context.as_deref(),
+            Some(PLAN_MODE_CONTEXT)
+        );
+
+        execute_plan_mode(
+            &plan_mode_args(Some("off")),
+            &plan_mode,
+            &mut conversation,
+            Uuid::new_v4(),
+        );
+        assert!(!plan_mode.is_enabled());
+        assert!(conversation.pinned_context.is_none());
+    }
+
+    #[test]
+    fn test_execute_plan_mode_approve_requires_plan_mode() {
+        let plan_mode = PlanMode::default();
+        let mut conversation = Conversation::new();
+        let result = execute_plan_mode(
+            &plan_mode_args(Some("approve")),
+            &plan_mode,
+            &mut conversation,
+            Uuid::new_v4(),
+        );
+        assert!(matches!(result, SlashCommandResult::Error(msg) if msg.contains("/plan-mode")));
+    }
+
+    #[test]
+    fn test_execute_plan_mode_approve_pins_session_plan() {
+        with_temp_home(|_| {
+            let session_id = Uuid::new_v4();
+            let plan_mode = PlanMode::new(true);
+            let mut conversation = Conversation::new();
+
+            let result = execute_plan_mode(
+                &plan_mode_args(Some("approve")),
+                &plan_mode,
+                &mut conversation,
+                session_id,
+            );
+            assert!(matches!(result, SlashCommandResult::Error(msg) if msg.starts_with("No plan")));
+            assert!(plan_mode.is_enabled());
+
+            let mut store = PlanStore::open().unwrap();
+            let plan = store
+                .create("Add caching", "- [ ] Add cache layer")
+                .unwrap();
+            store.link_session(plan.info.id, session_id).unwrap();
+
+            let result = execute_plan_mode(
+                &plan_mode_args(Some("approve")),
+                &plan_mode,
+                &mut conversation,
+                session_id,
+            );
+            assert!(
+                matches!(result, SlashCommandResult::SendToLlm(msg) if msg.contains("Add caching"))
+            );
+            assert!(!plan_mode.is_enabled());
+            let pinned = conversation.pinned_context.unwrap();
+            assert!(pinned.starts_with("## Approved plan: Add caching"));
+            assert!(pinned.contains("- [ ] Add cache layer"));
+        });
+    }
+
     #[test]
     fn test_execute_model_unknown_subcommand() {
         let args = ModelArgs {
@@ -115,11 +115,21 @@ pub struct SystemArgs {
 }
 
 /// Arguments for the MCP subcommand
-#[derive(clap::Args, Debug)]
+#[derive(clap::Args, Debug, Default)]
 pub struct McpArgs {
     /// Project directory to expose tools for
     #[arg(short, long)]
     pub project: Option<String>,
+
+    /// Serve the Streamable HTTP transport on this address (e.g. 127.0.0.1:8765)
+    /// instead of stdio
+    #[arg(long, value_name = "ADDR")]
+    pub http: Option<String>,
+
+    /// Bearer token required from HTTP clients (falls back to TED_MCP_TOKEN);
+    /// mandatory when ADDR is not a loopback address
+    #[arg(long, value_name = "TOKEN", requires = "http")]
+    pub http_token: Option<String>,
 }
 
 /// Arguments for the chat subcommand
@@ -148,6 +158,10 @@ pub struct ChatArgs {
     #[arg(long)]
     pub trust: bool,
 
+    /// Plan mode: explore read-only until a plan is approved with /plan-mode approve
+    #[arg(long)]
+    pub plan: bool,
+
     /// Disable streaming output
     #[arg(long)]
     pub no_stream: bool,
@@ -601,6 +615,16 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_chat_with_plan() {
+        let cli = Cli::parse_from(["ted", "chat", "--plan"]);
+        if let Some(Commands::Chat(args)) = cli.command {
+            assert!(args.plan);
+        } else {
+            panic!("Expected Chat command");
+        }
+    }
+
     #[test]
     fn test_chat_with_no_stream() {
         let cli = Cli::parse_from(["ted", "chat", "--no-stream"]);
@@ -1392,6 +1416,30 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_mcp_with_http() {
+        let cli = Cli::parse_from([
+            "ted",
+            "mcp",
+            "--http",
+            "127.0.0.1:8765",
+            "--http-token",
+            "secret",
+        ]);
+        if let Some(Commands::Mcp(args)) = cli.command {
+            assert_eq!(args.http, Some("127.0.0.1:8765".to_string()));
+            assert_eq!(args.http_token, Some("secret".to_string()));
+        } else {
+            panic!("Expected Mcp command");
+        }
+    }
+
+    #[test]
+    fn test_mcp_http_token_requires_http() {
+        let result = Cli::try_parse_from(["ted", "mcp", "--http-token", "secret"]);
+        assert!(result.is_err());
+    }
+
     // ==================== LSP Command ====================
 
     #[test]
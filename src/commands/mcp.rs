@@ -3,9 +3,17 @@
 
 //! MCP server command
 
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
 use crate::cli::args::McpArgs;
-use crate::error::Result;
-use crate::mcp::McpServer;
+use crate::config::Settings;
+use crate::error::{Result, TedError};
+use crate::mcp::transport::http::{
+    HttpTransport, HttpTransportConfig, ServerFactory, MCP_ENDPOINT,
+};
+use crate::mcp::{McpServer, TedAgentTool};
 use crate::tools::ToolExecutor;
 
 /// Execute the MCP server command
@@ -18,15 +26,40 @@ pub async fn execute(args: &McpArgs) -> Result<()> {
 
     let project_dir = if let Some(ref project) = args.project {
         eprintln!("[TED MCP] Project directory: {}", project);
-        std::path::PathBuf::from(project)
+        PathBuf::from(project)
     } else {
         std::env::current_dir()?
     };
 
+    if let Some(ref addr) = args.http {
+        return serve_http(addr, args.http_token.clone(), project_dir).await;
+    }
+
+    let server = build_server(&project_dir, true).await;
+
+    eprintln!("[TED MCP] Server ready - listening on stdio");
+    eprintln!("[TED MCP] Compatible with Claude Desktop and other MCP clients");
+
+    // In unit tests, avoid entering the blocking stdio run loop.
+    #[cfg(test)]
+    {
+        let _ = server;
+        Ok(())
+    }
+
+    #[cfg(not(test))]
+    {
+        // Run the server
+        server.run().await
+    }
+}
+
+/// Create an MCP server with every built-in tool registered
+async fn build_server(project_dir: &Path, verbose: bool) -> McpServer {
     // Create tool context for executor
     let context = crate::tools::ToolContext::new(
-        project_dir.clone(),
-        Some(project_dir),
+        project_dir.to_path_buf(),
+        Some(project_dir.to_path_buf()),
         uuid::Uuid::new_v4(),
         false, // Not in trust mode by default
     );
@@ -41,38 +74,86 @@ pub async fn execute(args: &McpArgs) -> Result<()> {
     let registry = crate::tools::ToolRegistry::with_builtins();
     for tool_name in registry.names() {
         if let Some(tool) = registry.get(tool_name) {
-            eprintln!("[TED MCP] Registering tool: {}", tool_name);
+            if verbose {
+                eprintln!("[TED MCP] Registering tool: {}", tool_name);
+            }
             server.register_tool(tool.clone()).await;
         }
     }
 
-    eprintln!("[TED MCP] Registered {} tools", registry.len());
-    eprintln!("[TED MCP] Server ready - listening on stdio");
-    eprintln!("[TED MCP] Compatible with Claude Desktop and other MCP clients");
+    // Delegating whole tasks needs the configured provider
+    let settings = Settings::load().unwrap_or_else(|e| {
+        eprintln!("[TED MCP] Warning: failed to load settings: {}", e);
+        Settings::default()
+    });
+    server
+        .register_tool(Arc::new(TedAgentTool::from_settings(
+            settings,
+            project_dir.to_path_buf(),
+        )))
+        .await;
 
-    // In unit tests, avoid entering the blocking stdio run loop.
+    if verbose {
+        eprintln!("[TED MCP] Registered {} tools", registry.len() + 1);
+    }
+    server
+}
+
+/// Serve the Streamable HTTP transport, one server per session
+async fn serve_http(addr: &str, token: Option<String>, project_dir: PathBuf) -> Result<()> {
+    let addr: SocketAddr = addr
+        .parse()
+        .map_err(|_| TedError::InvalidInput(format!("Invalid HTTP address: {}", addr)))?;
+    let bearer_token = token
+        .or_else(|| std::env::var("TED_MCP_TOKEN").ok())
+        .filter(|token| !token.is_empty());
+    if bearer_token.is_none() && !addr.ip().is_loopback() {
+        return Err(TedError::InvalidInput(format!(
+            "Refusing to serve on {} without a bearer token; set --http-token or TED_MCP_TOKEN, or bind to a loopback address",
+            addr
+        )));
+    }
+
+    let factory: ServerFactory = Arc::new(move || {
+        let project_dir = project_dir.clone();
+        Box::pin(async move { build_server(&project_dir, false).await })
+    });
+    let transport = HttpTransport::new(
+        factory,
+        HttpTransportConfig {
+            bearer_token,
+            ..Default::default()
+        },
+    );
+
+    eprintln!(
+        "[TED MCP] Server ready - listening on http://{}{}",
+        addr, MCP_ENDPOINT
+    );
+
+    // In unit tests, avoid binding a port and serving forever.
     #[cfg(test)]
     {
+        let _ = transport;
         Ok(())
     }
 
     #[cfg(not(test))]
     {
-        // Run the server
-        server.run().await
+        let listener = tokio::net::TcpListener::bind(addr).await?;
+        transport.serve(listener).await
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::path::PathBuf;
 
     // ==================== McpArgs tests ====================
 
     #[test]
     fn test_mcp_args_default() {
-        let args = McpArgs { project: None };
+        let args = McpArgs::default();
         assert!(args.project.is_none());
     }
 
@@ -80,6 +161,7 @@ mod tests {
     fn test_mcp_args_with_project() {
         let args = McpArgs {
             project: Some("/path/to/project".to_string()),
+            ..Default::default()
         };
         assert!(args.project.is_some());
         assert_eq!(args.project.unwrap(), "/path/to/project");
@@ -89,6 +171,7 @@ mod tests {
     fn test_mcp_args_project_path_conversion() {
         let args = McpArgs {
             project: Some("/my/project".to_string()),
+            ..Default::default()
         };
 
         let project_dir = if let Some(ref project) = args.project {
@@ -102,7 +185,7 @@ mod tests {
 
     #[test]
     fn test_mcp_args_none_falls_back_to_current_dir() {
-        let args = McpArgs { project: None };
+        let args = McpArgs::default();
 
         let project_dir = if let Some(ref project) = args.project {
             std::path::PathBuf::from(project)
@@ -305,16 +388,18 @@ mod tests {
     #[test]
     fn test_mcp_args_structs() {
         // Test various McpArgs configurations
-        let args_none = McpArgs { project: None };
+        let args_none = McpArgs::default();
         assert!(args_none.project.is_none());
 
         let args_some = McpArgs {
             project: Some("/my/project".to_string()),
+            ..Default::default()
         };
         assert!(args_some.project.is_some());
 
         let args_empty = McpArgs {
             project: Some(String::new()),
+            ..Default::default()
         };
         assert!(args_empty.project.unwrap().is_empty());
     }
@@ -323,6 +408,7 @@ mod tests {
     fn test_project_dir_resolution_with_project() {
         let args = McpArgs {
             project: Some("/custom/path".to_string()),
+            ..Default::default()
         };
 
         let project_dir = if let Some(ref project) = args.project {
@@ -336,7 +422,7 @@ mod tests {
 
     #[test]
     fn test_project_dir_resolution_without_project() {
-        let args = McpArgs { project: None };
+        let args = McpArgs::default();
 
         let project_dir = if let Some(ref project) = args.project {
             std::path::PathBuf::from(project)
@@ -429,7 +515,7 @@ mod tests {
     #[tokio::test]
     async fn test_execute_setup_components() {
         // Test all the components that execute() sets up
-        let args = McpArgs { project: None };
+        let args = McpArgs::default();
 
         // 1. Project directory resolution
         let project_dir = if let Some(ref project) = args.project {
@@ -469,6 +555,7 @@ mod tests {
         let temp_dir = tempfile::TempDir::new().unwrap();
         let args = McpArgs {
             project: Some(temp_dir.path().to_str().unwrap().to_string()),
+            ..Default::default()
         };
 
         let project_dir = if let Some(ref project) = args.project {
@@ -532,6 +619,7 @@ mod tests {
         // Directly test line 19-21: if let Some(ref project) = args.project
         let args = McpArgs {
             project: Some("/test/path".to_string()),
+            ..Default::default()
         };
 
         let project_dir = if let Some(ref project) = args.project {
@@ -547,7 +635,7 @@ mod tests {
     #[tokio::test]
     async fn test_execute_project_dir_with_none() {
         // Directly test line 23: std::env::current_dir()?
-        let args = McpArgs { project: None };
+        let args = McpArgs::default();
 
         let project_dir = if let Some(ref project) = args.project {
             eprintln!("[TED MCP] Project directory: {}", project);
@@ -575,6 +663,7 @@ mod tests {
         // Lines 19-24
         let args = McpArgs {
             project: Some("/tmp/test".to_string()),
+            ..Default::default()
         };
         let project_dir = if let Some(ref project) = args.project {
             eprintln!("[TED MCP] Project directory: {}", project);
@@ -619,6 +708,7 @@ mod tests {
         // Edge case: empty string for project
         let args = McpArgs {
             project: Some(String::new()),
+            ..Default::default()
         };
 
         let project_dir = if let Some(ref project) = args.project {
@@ -703,7 +793,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_execute_real_entrypoint_none_project_timeout_or_eof() {
-        let args = McpArgs { project: None };
+        let args = McpArgs::default();
         let result = execute(&args).await;
         assert!(result.is_ok());
     }
@@ -713,8 +803,54 @@ mod tests {
         let temp = tempfile::TempDir::new().unwrap();
         let args = McpArgs {
             project: Some(temp.path().to_string_lossy().to_string()),
+            ..Default::default()
         };
         let result = execute(&args).await;
         assert!(result.is_ok());
     }
+
+    #[tokio::test]
+    async fn test_execute_http_entrypoint() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let args = McpArgs {
+            project: Some(temp.path().to_string_lossy().to_string()),
+            http: Some("127.0.0.1:0".to_string()),
+            http_token: Some("secret".to_string()),
+        };
+        assert!(execute(&args).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_execute_http_requires_token_off_loopback() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let args = McpArgs {
+            project: Some(temp.path().to_string_lossy().to_string()),
+            http: Some("0.0.0.0:0".to_string()),
+            http_token: None,
+        };
+        if std::env::var_os("TED_MCP_TOKEN").is_none() {
+            assert!(matches!(
+                execute(&args).await,
+                Err(TedError::InvalidInput(message)) if message.contains("bearer token")
+            ));
+        }
+
+        let args = McpArgs {
+            http_token: Some("secret".to_string()),
+            ..args
+        };
+        assert!(execute(&args).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_execute_http_rejects_invalid_address() {
+        let args = McpArgs {
+            http: Some("not-an-address".to_string()),
+            ..Default::default()
+        };
+        assert!(matches!(
+            execute(&args).await,
+            Err(TedError::InvalidInput(_))
+        ));
+    }
 }
@@ -32,23 +32,31 @@ pub struct CustomCommand {
 
 /// Discover custom commands from filesystem
 pub fn discover_commands() -> Result<HashMap<String, CustomCommand>> {
+    let project_commands_dir = std::env::current_dir()
+        .ok()
+        .map(|cwd| cwd.join(".ted").join("commands"));
+    discover_commands_in(&Settings::commands_dir(), project_commands_dir.as_deref())
+}
+
+/// Discover custom commands from explicit user and project directories.
+/// Project commands override user commands with the same name.
+pub fn discover_commands_in(
+    user_commands_dir: &Path,
+    project_commands_dir: Option<&Path>,
+) -> Result<HashMap<String, CustomCommand>> {
     let mut commands = HashMap::new();
 
     // User-global commands (lowest priority)
-    let user_commands_dir = Settings::commands_dir();
     if user_commands_dir.exists() {
-        for cmd in list_commands_in_dir(&user_commands_dir, false)? {
+        for cmd in list_commands_in_dir(user_commands_dir, false)? {
             commands.insert(cmd.name.clone(), cmd);
         }
     }
 
     // Project-local commands (highest priority, overrides user-global)
-    if let Ok(cwd) = std::env::current_dir() {
-        let project_commands_dir = cwd.join(".ted").join("commands");
-        if project_commands_dir.exists() {
-            for cmd in list_commands_in_dir(&project_commands_dir, true)? {
-                commands.insert(cmd.name.clone(), cmd);
-            }
+    if let Some(project_commands_dir) = project_commands_dir.filter(|dir| dir.exists()) {
+        for cmd in list_commands_in_dir(project_commands_dir, true)? {
+            commands.insert(cmd.name.clone(), cmd);
         }
     }
 
@@ -56,7 +64,7 @@ pub fn discover_commands() -> Result<HashMap<String, CustomCommand>> {
 }
 
 /// List commands in a directory
-fn list_commands_in_dir(dir: &PathBuf, is_local: bool) -> Result<Vec<CustomCommand>> {
+fn list_commands_in_dir(dir: &Path, is_local: bool) -> Result<Vec<CustomCommand>> {
     let mut commands = Vec::new();
 
     for entry in std::fs::read_dir(dir)? {
@@ -291,7 +299,7 @@ mod tests {
         perms.set_mode(0o755);
         std::fs::set_permissions(&script_path, perms).unwrap();
 
-        let commands = list_commands_in_dir(&temp_dir.path().to_path_buf(), true).unwrap();
+        let commands = list_commands_in_dir(temp_dir.path(), true).unwrap();
 
         assert_eq!(commands.len(), 1);
         assert_eq!(commands[0].name, "test-script");
@@ -305,7 +313,7 @@ mod tests {
         // Create a subdirectory (should be skipped)
         std::fs::create_dir(temp_dir.path().join("subdir")).unwrap();
 
-        let commands = list_commands_in_dir(&temp_dir.path().to_path_buf(), false).unwrap();
+        let commands = list_commands_in_dir(temp_dir.path(), false).unwrap();
 
         // Directory should be skipped
         assert!(commands.is_empty() || !commands.iter().any(|c| c.name == "subdir"));
@@ -367,7 +375,7 @@ mod tests {
 
         // Don't make it executable - leave default permissions
 
-        let commands = list_commands_in_dir(&temp_dir.path().to_path_buf(), false).unwrap();
+        let commands = list_commands_in_dir(temp_dir.path(), false).unwrap();
 
         // Non-executable file should be skipped
         assert!(commands.is_empty());
@@ -390,7 +398,7 @@ mod tests {
             std::fs::set_permissions(&script_path, perms).unwrap();
         }
 
-        let commands = list_commands_in_dir(&temp_dir.path().to_path_buf(), true).unwrap();
+        let commands = list_commands_in_dir(temp_dir.path(), true).unwrap();
 
         assert_eq!(commands.len(), 3);
 
@@ -403,7 +411,7 @@ mod tests {
     #[test]
     fn test_list_commands_in_empty_dir() {
         let temp_dir = TempDir::new().unwrap();
-        let commands = list_commands_in_dir(&temp_dir.path().to_path_buf(), true).unwrap();
+        let commands = list_commands_in_dir(temp_dir.path(), true).unwrap();
         assert!(commands.is_empty());
     }
 
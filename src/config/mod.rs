@@ -6,5 +6,7 @@
 //! Handles loading, saving, and managing user settings.
 
 pub mod settings;
+pub mod tedignore;
 
 pub use settings::*;
+pub use tedignore::{TedIgnore, TEDIGNORE_FILE};
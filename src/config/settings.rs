@@ -54,6 +54,36 @@ pub struct Settings {
     /// Embeddings configuration (for semantic search)
     #[serde(default)]
     pub embeddings: EmbeddingsConfig,
+
+    /// Formatters and linters run on files after `file_edit`/`file_write`
+    #[serde(default)]
+    pub post_edit: PostEditConfig,
+
+    /// Secret redaction for content sent to LLM providers
+    #[serde(default)]
+    pub redaction: RedactionConfig,
+
+    /// User commands run on agent lifecycle events
+    #[serde(default)]
+    pub hooks: HooksConfig,
+
+    /// External MCP servers whose tools are mounted as Ted tools
+    #[serde(
+        default,
+        rename = "mcpServers",
+        alias = "mcp_servers",
+        skip_serializing_if = "HashMap::is_empty"
+    )]
+    pub mcp_servers: HashMap<String, McpServerConfig>,
+
+    /// Language server (`ted lsp`) settings
+    #[serde(default)]
+    pub lsp: LspConfig,
+
+    /// Project roots whose own `.ted/settings.json` may run commands through
+    /// hooks, post-edit rules and MCP servers. Only read from user settings.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub trusted_projects: Vec<PathBuf>,
 }
 
 /// Configuration for LLM providers
@@ -320,6 +350,299 @@ fn default_embeddings_model() -> String {
     "all-minilm-l6-v2".to_string()
 }
 
+/// Post-edit formatter and linter pipeline configuration
+///
+/// Rules may also be declared in `<project>/.ted/settings.json`; project rules
+/// run after user rules.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PostEditConfig {
+    /// Enable the pipeline
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+
+    /// Timeout for each formatter/linter command in seconds
+    #[serde(default = "default_post_edit_timeout_secs")]
+    pub timeout_secs: u64,
+
+    /// Rules matching files to formatter and linter commands
+    #[serde(default)]
+    pub rules: Vec<PostEditRule>,
+
+    /// Project roots whose own `.ted/settings.json` rules may run.
+    ///
+    /// Only read from user settings: project rules are shell commands, so a
+    /// cloned repository can't opt itself in.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub trusted_projects: Vec<PathBuf>,
+}
+
+impl Default for PostEditConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            timeout_secs: default_post_edit_timeout_secs(),
+            rules: Vec::new(),
+            trusted_projects: Vec::new(),
+        }
+    }
+}
+
+/// A single post-edit rule
+///
+/// Commands run through `sh -c` from the project root. `{file}` is replaced
+/// with the edited file's path; without it the path is appended.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PostEditRule {
+    /// Language name matched by file extension (e.g. "rust", "typescript", "python")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+
+    /// Glob patterns matched against the path relative to the project root
+    #[serde(default)]
+    pub globs: Vec<String>,
+
+    /// Formatter commands; their changes are kept without involving the model
+    #[serde(default)]
+    pub formatters: Vec<String>,
+
+    /// Linter commands; new diagnostics are appended to the tool result
+    #[serde(default)]
+    pub linters: Vec<String>,
+}
+
+fn default_post_edit_timeout_secs() -> u64 {
+    30
+}
+
+/// An external MCP server started over stdio
+///
+/// Same shape as an entry under `mcpServers` in `mcp-config.json`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct McpServerConfig {
+    /// Program to run
+    pub command: String,
+
+    /// Program arguments
+    #[serde(default)]
+    pub args: Vec<String>,
+
+    /// Extra environment variables
+    #[serde(default)]
+    pub env: HashMap<String, String>,
+
+    /// Skip this server without removing its entry
+    #[serde(default)]
+    pub disabled: bool,
+
+    /// Timeout for each request in seconds
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout_secs: Option<u64>,
+
+    /// Limits for `sampling/createMessage` requests from this server
+    #[serde(default, skip_serializing_if = "McpSamplingConfig::is_default")]
+    pub sampling: McpSamplingConfig,
+}
+
+/// Sampling limits for one MCP server
+///
+/// Servers may ask Ted to run completions through the active provider. The
+/// first request of a session needs the user's approval.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct McpSamplingConfig {
+    /// Refuse sampling requests and don't advertise the capability
+    #[serde(default)]
+    pub disabled: bool,
+
+    /// Upper bound on `maxTokens` for a single request (default: 4096)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_tokens_per_request: Option<u32>,
+
+    /// Input plus output tokens the server may use per session (default: 100000)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_total_tokens: Option<u64>,
+}
+
+impl McpSamplingConfig {
+    fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// Lifecycle hook configuration
+///
+/// Hooks may also be declared in `<project>/.ted/settings.json`; project hooks
+/// run after user hooks.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HooksConfig {
+    /// Enable hooks
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+
+    /// Default timeout for each hook command in seconds
+    #[serde(default = "default_hook_timeout_secs")]
+    pub timeout_secs: u64,
+
+    /// Run before a tool call; may block it or rewrite its input
+    #[serde(default)]
+    pub pre_tool_use: Vec<HookRule>,
+
+    /// Run after a tool call; may flag or annotate its result
+    #[serde(default)]
+    pub post_tool_use: Vec<HookRule>,
+
+    /// Run when the user submits a prompt; may block, rewrite or annotate it
+    #[serde(default)]
+    pub user_prompt_submit: Vec<HookRule>,
+
+    /// Run when the main agent finishes a turn; may ask it to keep going
+    #[serde(default)]
+    pub stop: Vec<HookRule>,
+
+    /// Run when a subagent finishes; may ask it to keep going
+    #[serde(default)]
+    pub subagent_stop: Vec<HookRule>,
+}
+
+impl Default for HooksConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            timeout_secs: default_hook_timeout_secs(),
+            pre_tool_use: Vec::new(),
+            post_tool_use: Vec::new(),
+            user_prompt_submit: Vec::new(),
+            stop: Vec::new(),
+            subagent_stop: Vec::new(),
+        }
+    }
+}
+
+/// A single hook command
+///
+/// Commands run through `sh -c` from the project root with the event payload
+/// as JSON on stdin.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct HookRule {
+    /// Regex matched against the tool name (tool events only); empty or "*" matches every tool
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub matcher: Option<String>,
+
+    /// Shell command to run
+    pub command: String,
+
+    /// Timeout override in seconds
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout_secs: Option<u64>,
+}
+
+fn default_hook_timeout_secs() -> u64 {
+    30
+}
+
+/// Secret redaction configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RedactionConfig {
+    /// Force redaction on or off; by default it is on for every provider except "local"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub enabled: Option<bool>,
+
+    /// Also redact high-entropy tokens that no known pattern matches
+    #[serde(default = "default_true")]
+    pub entropy_detection: bool,
+
+    /// Additional regexes to redact; if a pattern has a capture group, only
+    /// the first group is replaced
+    #[serde(default)]
+    pub custom_patterns: Vec<RedactionPattern>,
+}
+
+impl Default for RedactionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: None,
+            entropy_detection: true,
+            custom_patterns: Vec::new(),
+        }
+    }
+}
+
+impl RedactionConfig {
+    /// Whether redaction applies to requests sent to the given provider
+    pub fn is_enabled_for(&self, provider_name: &str) -> bool {
+        self.enabled.unwrap_or(provider_name != "local")
+    }
+}
+
+/// A named custom redaction pattern
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RedactionPattern {
+    /// Label used in placeholders and audit events
+    pub name: String,
+    /// Regular expression to match
+    pub pattern: String,
+}
+
+/// Language server configuration
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LspConfig {
+    /// AI review of saved documents
+    #[serde(default)]
+    pub review: LspReviewConfig,
+}
+
+/// Review-on-save configuration for `ted lsp`
+///
+/// Each save of an enabled document sends the changed lines to the default
+/// model with the `code-reviewer` cap and publishes the findings as
+/// diagnostics.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LspReviewConfig {
+    /// Review saved documents (default: off)
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Per-language overrides keyed by LSP language id, e.g. `{"rust": true}`
+    #[serde(default)]
+    pub languages: HashMap<String, bool>,
+
+    /// Minimum seconds between two reviews of the same document
+    #[serde(default = "default_review_min_interval_secs")]
+    pub min_interval_secs: u64,
+
+    /// Maximum reviews per hour across all documents
+    #[serde(default = "default_review_max_per_hour")]
+    pub max_per_hour: u32,
+}
+
+impl Default for LspReviewConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            languages: HashMap::new(),
+            min_interval_secs: default_review_min_interval_secs(),
+            max_per_hour: default_review_max_per_hour(),
+        }
+    }
+}
+
+impl LspReviewConfig {
+    /// Whether documents with the given language id are reviewed on save
+    pub fn is_enabled_for(&self, language_id: &str) -> bool {
+        self.languages
+            .get(language_id)
+            .copied()
+            .unwrap_or(self.enabled)
+    }
+}
+
+fn default_review_min_interval_secs() -> u64 {
+    30
+}
+
+fn default_review_max_per_hour() -> u32 {
+    60
+}
+
 /// Hardware-specific configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct HardwareConfig {
@@ -1711,4 +2034,25 @@ mod tests {
         let settings = Settings::default();
         assert!(!settings.is_provider_configured("nonexistent"));
     }
+
+    #[test]
+    fn test_lsp_review_config_language_overrides() {
+        let settings: Settings = serde_json::from_str(
+            r#"{"lsp": {"review": {"enabled": true, "languages": {"markdown": false}}}}"#,
+        )
+        .unwrap();
+        let review = &settings.lsp.review;
+
+        assert!(review.is_enabled_for("rust"));
+        assert!(!review.is_enabled_for("markdown"));
+        assert_eq!(review.min_interval_secs, 30);
+        assert_eq!(review.max_per_hour, 60);
+
+        let defaults = LspReviewConfig {
+            languages: HashMap::from([("python".to_string(), true)]),
+            ..Default::default()
+        };
+        assert!(defaults.is_enabled_for("python"));
+        assert!(!defaults.is_enabled_for("rust"));
+    }
 }
@@ -3,7 +3,7 @@
 
 use std::path::{Path, PathBuf};
 
-use crate::error::Result;
+use crate::error::{Result, TedError};
 
 use super::migration;
 use super::Settings;
@@ -14,6 +14,41 @@ impl Settings {
         Self::ted_home().join("settings.json")
     }
 
+    /// Read the top-level `trusted_projects` list from the user settings
+    /// file at `user_path`, without loading the rest of the settings.
+    pub fn read_trusted_projects(user_path: &Path) -> Result<Vec<PathBuf>> {
+        if !user_path.exists() {
+            return Ok(Vec::new());
+        }
+        let raw = std::fs::read_to_string(user_path)?;
+        let value: serde_json::Value = serde_json::from_str(&raw).map_err(|err| {
+            TedError::Config(format!(
+                "Failed to parse settings '{}': {}",
+                user_path.display(),
+                err
+            ))
+        })?;
+        let Some(list) = value.get("trusted_projects") else {
+            return Ok(Vec::new());
+        };
+        serde_json::from_value(list.clone()).map_err(|err| {
+            TedError::Config(format!(
+                "Invalid trusted_projects in '{}': {}",
+                user_path.display(),
+                err
+            ))
+        })
+    }
+
+    /// Whether a project path lies under one of the trusted project roots
+    pub fn is_trusted_project(trusted_projects: &[PathBuf], project_path: &Path) -> bool {
+        let canonical = |path: &Path| path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+        let project_path = canonical(project_path);
+        trusted_projects
+            .iter()
+            .any(|root| project_path.starts_with(canonical(root)))
+    }
+
     /// Load settings from the default path.
     pub fn load() -> Result<Self> {
         Self::load_from(&Self::default_path())
@@ -130,11 +165,36 @@ impl Settings {
         project_root.join(".ted").join("permissions.toml")
     }
 
+    /// Get the project settings file path (`<project>/.ted/settings.json`).
+    pub fn project_settings_path(project_root: &Path) -> PathBuf {
+        project_root.join(".ted").join("settings.json")
+    }
+
     /// Get the append-only permissions audit log path.
     pub fn permissions_audit_log_path() -> PathBuf {
         Self::audit_dir().join("permissions.jsonl")
     }
 
+    /// Get the append-only lifecycle hook audit log path.
+    pub fn hooks_audit_log_path() -> PathBuf {
+        Self::audit_dir().join("hooks.jsonl")
+    }
+
+    /// Get the append-only MCP sampling audit log path.
+    pub fn sampling_audit_log_path() -> PathBuf {
+        Self::audit_dir().join("sampling.jsonl")
+    }
+
+    /// Get the user-level `.tedignore` path.
+    pub fn user_tedignore_path() -> PathBuf {
+        Self::ted_home().join(".tedignore")
+    }
+
+    /// Get the append-only secret redaction audit log path.
+    pub fn redaction_audit_log_path() -> PathBuf {
+        Self::audit_dir().join("redactions.jsonl")
+    }
+
     /// Ensure all required directories exist.
     pub fn ensure_directories() -> Result<()> {
         let mut dirs = vec![
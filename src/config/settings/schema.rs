@@ -3,6 +3,8 @@
 
 pub use super::{
     AnthropicConfig, AppearanceConfig, BlackmanConfig, ContextConfig, ConversationConfig,
-    DefaultsConfig, EmbeddingsConfig, GoogleConfig, HardwareConfig, LocalLlmConfig, ModelRateLimit,
-    OpenAIConfig, OpenRouterConfig, ProvidersConfig, RateLimitsConfig, ResilienceConfig, Settings,
+    DefaultsConfig, EmbeddingsConfig, GoogleConfig, HardwareConfig, HookRule, HooksConfig,
+    LocalLlmConfig, LspConfig, LspReviewConfig, McpSamplingConfig, McpServerConfig, ModelRateLimit,
+    OpenAIConfig, OpenRouterConfig, PostEditConfig, PostEditRule, ProvidersConfig,
+    RateLimitsConfig, RedactionConfig, RedactionPattern, ResilienceConfig, Settings,
 };
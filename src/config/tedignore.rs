@@ -0,0 +1,272 @@
+// SPDX-License-Identifier: AGPL-3.0-or-later
+// Copyright (C) 2025 Blackman Artificial Intelligence Technologies Inc.
+
+//! `.tedignore` support
+//!
+//! A `.tedignore` file uses gitignore syntax to list paths the agent may never
+//! read, list or index. Rules are combined from the user-level
+//! `~/.ted/.tedignore` and the project's `<root>/.tedignore`; project rules
+//! come last so they can re-include (`!pattern`) paths the user file hides.
+//! All patterns are anchored at the project root.
+
+use std::path::{Component, Path, PathBuf};
+use std::sync::Arc;
+
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+
+use crate::config::Settings;
+
+/// Name of the ignore file at project and user level
+pub const TEDIGNORE_FILE: &str = ".tedignore";
+
+/// Compiled `.tedignore` rules for a project
+#[derive(Debug, Clone)]
+pub struct TedIgnore {
+    root: PathBuf,
+    matcher: Arc<Gitignore>,
+}
+
+impl Default for TedIgnore {
+    fn default() -> Self {
+        Self::empty(PathBuf::new())
+    }
+}
+
+impl TedIgnore {
+    /// Rules that ignore nothing
+    pub fn empty(root: PathBuf) -> Self {
+        Self {
+            root,
+            matcher: Arc::new(Gitignore::empty()),
+        }
+    }
+
+    /// Load user and project `.tedignore` files for `project_root`.
+    pub fn load(project_root: &Path) -> Self {
+        Self::load_from_paths(
+            project_root,
+            Some(&Settings::user_tedignore_path()),
+            Some(&project_root.join(TEDIGNORE_FILE)),
+        )
+    }
+
+    /// Load rules from explicit user/project file paths. Missing files are
+    /// skipped; malformed lines are logged and ignored.
+    pub fn load_from_paths(
+        project_root: &Path,
+        user: Option<&Path>,
+        project: Option<&Path>,
+    ) -> Self {
+        let root = project_root
+            .canonicalize()
+            .unwrap_or_else(|_| project_root.to_path_buf());
+        let mut builder = GitignoreBuilder::new(&root);
+
+        if let Some(user) = user.filter(|p| p.is_file()) {
+            // User patterns live outside the project, so add them line by line
+            // to anchor them at the project root rather than at ~/.ted.
+            match std::fs::read_to_string(user) {
+                Ok(content) => {
+                    for line in content.lines() {
+                        if let Err(e) = builder.add_line(Some(user.to_path_buf()), line) {
+                            tracing::warn!("Invalid pattern in {}: {}", user.display(), e);
+                        }
+                    }
+                }
+                Err(e) => tracing::warn!("Failed to read {}: {}", user.display(), e),
+            }
+        }
+
+        if let Some(project) = project.filter(|p| p.is_file()) {
+            if let Some(e) = builder.add(project) {
+                tracing::warn!("Invalid pattern in {}: {}", project.display(), e);
+            }
+        }
+
+        let matcher = builder.build().unwrap_or_else(|e| {
+            tracing::warn!("Failed to compile .tedignore rules: {}", e);
+            Gitignore::empty()
+        });
+
+        Self {
+            root,
+            matcher: Arc::new(matcher),
+        }
+    }
+
+    /// Build rules from gitignore-syntax lines (mainly for tests and embedding).
+    pub fn from_patterns(project_root: &Path, patterns: &[&str]) -> Self {
+        let root = project_root
+            .canonicalize()
+            .unwrap_or_else(|_| project_root.to_path_buf());
+        let mut builder = GitignoreBuilder::new(&root);
+        for pattern in patterns {
+            let _ = builder.add_line(None, pattern);
+        }
+        Self {
+            matcher: Arc::new(builder.build().unwrap_or_else(|_| Gitignore::empty())),
+            root,
+        }
+    }
+
+    /// Project root the rules are anchored at
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Whether no rules are configured
+    pub fn is_empty(&self) -> bool {
+        self.matcher.is_empty()
+    }
+
+    /// Whether `path` (absolute, or relative to the project root) is ignored,
+    /// either directly or because one of its parent directories is.
+    ///
+    /// Paths outside the project root are never ignored.
+    pub fn is_ignored(&self, path: &Path) -> bool {
+        if self.matcher.is_empty() {
+            return false;
+        }
+        let Some(relative) = self.relative_path(path) else {
+            return false;
+        };
+        if relative.as_os_str().is_empty() {
+            return false;
+        }
+        let absolute = self.root.join(&relative);
+        let is_dir = absolute.is_dir();
+        self.matcher
+            .matched_path_or_any_parents(&relative, is_dir)
+            .is_ignore()
+    }
+
+    /// Error message for an ignored path, or `None` if access is allowed
+    pub fn check(&self, path: &Path) -> Option<String> {
+        if self.is_ignored(path) {
+            Some(format!(
+                "Path is ignored by {}: {}",
+                TEDIGNORE_FILE,
+                path.display()
+            ))
+        } else {
+            None
+        }
+    }
+
+    fn relative_path(&self, path: &Path) -> Option<PathBuf> {
+        let absolute = if path.is_absolute() {
+            path.to_path_buf()
+        } else {
+            self.root.join(path)
+        };
+        let resolved = resolve(&absolute);
+        resolved
+            .strip_prefix(&self.root)
+            .ok()
+            .map(|p| p.to_path_buf())
+    }
+}
+
+/// Canonicalize as much of `path` as exists, so new files and symlinked roots
+/// compare equal to the canonical project root, then normalize `.`/`..`.
+fn resolve(path: &Path) -> PathBuf {
+    if let Ok(canonical) = path.canonicalize() {
+        return canonical;
+    }
+
+    let mut existing = path.to_path_buf();
+    let mut rest = Vec::new();
+    while !existing.exists() {
+        match (existing.file_name(), existing.parent()) {
+            (Some(name), Some(parent)) => {
+                rest.push(name.to_os_string());
+                existing = parent.to_path_buf();
+            }
+            _ => break,
+        }
+    }
+
+    let mut resolved = existing.canonicalize().unwrap_or(existing);
+    for name in rest.into_iter().rev() {
+        resolved.push(name);
+    }
+
+    let mut normalized = PathBuf::new();
+    for component in resolved.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            other => normalized.push(other),
+        }
+    }
+    normalized
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_empty_ignores_nothing() {
+        let temp = TempDir::new().unwrap();
+        let ignore = TedIgnore::load_from_paths(temp.path(), None, None);
+        assert!(ignore.is_empty());
+        assert!(!ignore.is_ignored(&temp.path().join("secrets/key.pem")));
+    }
+
+    #[test]
+    fn test_ignores_directory_contents() {
+        let temp = TempDir::new().unwrap();
+        std::fs::create_dir_all(temp.path().join("secrets")).unwrap();
+        std::fs::write(temp.path().join("secrets/prod.env"), "X=1").unwrap();
+        let ignore = TedIgnore::from_patterns(temp.path(), &["secrets/"]);
+
+        assert!(ignore.is_ignored(&temp.path().join("secrets")));
+        assert!(ignore.is_ignored(&temp.path().join("secrets/prod.env")));
+        assert!(ignore.is_ignored(Path::new("secrets/prod.env")));
+        assert!(!ignore.is_ignored(&temp.path().join("src/main.rs")));
+    }
+
+    #[test]
+    fn test_ignores_new_and_dotted_paths() {
+        let temp = TempDir::new().unwrap();
+        let ignore = TedIgnore::from_patterns(temp.path(), &["*.pem", "dumps/**"]);
+
+        assert!(ignore.is_ignored(&temp.path().join("deploy/not-yet-created.pem")));
+        assert!(ignore.is_ignored(&temp.path().join("src/../dumps/customers.csv")));
+        assert!(!ignore.is_ignored(&temp.path().join("src/lib.rs")));
+    }
+
+    #[test]
+    fn test_paths_outside_root_are_not_ignored() {
+        let temp = TempDir::new().unwrap();
+        let ignore = TedIgnore::from_patterns(temp.path(), &["*"]);
+        assert!(!ignore.is_ignored(Path::new("/definitely/elsewhere.txt")));
+        assert!(!ignore.is_ignored(temp.path()));
+    }
+
+    #[test]
+    fn test_project_rules_can_reinclude_user_rules() {
+        let temp = TempDir::new().unwrap();
+        let user = temp.path().join("user.tedignore");
+        std::fs::write(&user, "*.csv\n").unwrap();
+        let project = temp.path().join(TEDIGNORE_FILE);
+        std::fs::write(&project, "!fixtures/*.csv\n").unwrap();
+
+        let ignore = TedIgnore::load_from_paths(temp.path(), Some(&user), Some(&project));
+        assert!(ignore.is_ignored(&temp.path().join("data/customers.csv")));
+        assert!(!ignore.is_ignored(&temp.path().join("fixtures/sample.csv")));
+    }
+
+    #[test]
+    fn test_check_message() {
+        let temp = TempDir::new().unwrap();
+        let ignore = TedIgnore::from_patterns(temp.path(), &["secrets/"]);
+        let message = ignore.check(Path::new("secrets/a.txt")).unwrap();
+        assert!(message.contains("Path is ignored by .tedignore"));
+        assert!(ignore.check(Path::new("src/a.txt")).is_none());
+    }
+}
@@ -9,6 +9,7 @@
 use std::collections::HashSet;
 use std::path::{Path, PathBuf};
 
+use crate::config::TedIgnore;
 use crate::error::Result;
 
 /// Configuration for file tree generation
@@ -22,6 +23,8 @@ pub struct FileTreeConfig {
     pub ignore_dirs: HashSet<String>,
     /// File extensions to include (empty = all)
     pub include_extensions: HashSet<String>,
+    /// `.tedignore` rules; matching entries are left out of the tree
+    pub tedignore: TedIgnore,
 }
 
 impl Default for FileTreeConfig {
@@ -53,6 +56,17 @@ impl Default for FileTreeConfig {
             max_files: 500,
             ignore_dirs,
             include_extensions: HashSet::new(), // Include all by default
+            tedignore: TedIgnore::default(),
+        }
+    }
+}
+
+impl FileTreeConfig {
+    /// Default configuration honouring the project's `.tedignore` rules
+    pub fn for_project(root: &Path) -> Self {
+        Self {
+            tedignore: TedIgnore::load(root),
+            ..Self::default()
         }
     }
 }
@@ -155,6 +169,10 @@ impl FileTree {
                 continue;
             }
 
+            if config.tedignore.is_ignored(&path) {
+                continue;
+            }
+
             // Filter by extension if configured
             if !is_dir && !config.include_extensions.is_empty() {
                 if let Some(ext) = path.extension().and_then(|e| e.to_str()) {
@@ -439,4 +457,17 @@ mod tests {
         assert!(config.ignore_dirs.contains(".git"));
         assert!(config.include_extensions.is_empty());
     }
+
+    #[test]
+    fn test_generate_tree_honours_tedignore() {
+        let temp = create_test_structure();
+        std::fs::write(temp.path().join(".tedignore"), "tests/\nREADME.md\n").unwrap();
+
+        let tree =
+            FileTree::generate(temp.path(), &FileTreeConfig::for_project(temp.path())).unwrap();
+
+        assert!(tree.as_string().contains("src/"));
+        assert!(!tree.as_string().contains("tests/"));
+        assert!(!tree.as_string().contains("README.md"));
+    }
 }
@@ -144,7 +144,7 @@ impl ContextManager {
     /// Generate or refresh the file tree and store as core memory chunk
     pub async fn refresh_file_tree(&self) -> Result<()> {
         if let Some(ref root) = self.project_root {
-            let config = FileTreeConfig::default();
+            let config = FileTreeConfig::for_project(root);
             let tree = FileTree::generate(root, &config)?;
 
             // Store the file tree as a core memory chunk (never compacted)
@@ -171,7 +171,7 @@ impl ContextManager {
     /// Generate or refresh project context (CLAUDE.md, AGENTS.md, .cursorrules, etc.)
     pub async fn refresh_project_context(&self) -> Result<()> {
         if let Some(ref root) = self.project_root {
-            let config = ProjectContextConfig::default();
+            let config = ProjectContextConfig::for_project(root);
             match ProjectContext::discover(root, &config) {
                 Ok(context) => {
                     if !context.is_empty() {
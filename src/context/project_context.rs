@@ -14,6 +14,7 @@ use std::path::{Path, PathBuf};
 
 use serde::Deserialize;
 
+use crate::config::TedIgnore;
 use crate::error::Result;
 
 /// Configuration for project context discovery and loading
@@ -27,6 +28,8 @@ pub struct ProjectContextConfig {
     pub max_total_size: usize,
     /// Maximum depth to search for subdirectory CLAUDE.md/AGENTS.md files
     pub subdirectory_depth: usize,
+    /// `.tedignore` rules; matching context files are never loaded
+    pub tedignore: TedIgnore,
 }
 
 impl Default for ProjectContextConfig {
@@ -36,6 +39,17 @@ impl Default for ProjectContextConfig {
             enable_cursor_rules: true,
             max_total_size: 100_000, // 100KB
             subdirectory_depth: 3,
+            tedignore: TedIgnore::default(),
+        }
+    }
+}
+
+impl ProjectContextConfig {
+    /// Default configuration honouring the project's `.tedignore` rules
+    pub fn for_project(root: &Path) -> Self {
+        Self {
+            tedignore: TedIgnore::load(root),
+            ..Self::default()
         }
     }
 }
@@ -131,6 +145,8 @@ impl ProjectContext {
             Self::discover_mdc_rules(project_root, &mut files);
         }
 
+        files.retain(|f| !config.tedignore.is_ignored(&f.path));
+
         // Sort by priority
         files.sort_by_key(|f| f.priority);
 
@@ -1168,4 +1184,19 @@ TS only"#,
         assert!(context.total_size() > "Hello World".len());
         assert!(context.total_size() > 0);
     }
+
+    #[test]
+    fn test_discover_skips_tedignored_files() {
+        let temp_dir = TempDir::new().unwrap();
+        std::fs::create_dir_all(temp_dir.path().join("private")).unwrap();
+        std::fs::write(temp_dir.path().join("private/CLAUDE.md"), "Hidden").unwrap();
+        std::fs::write(temp_dir.path().join("CLAUDE.md"), "Visible").unwrap();
+        std::fs::write(temp_dir.path().join(".tedignore"), "private/\n").unwrap();
+
+        let config = ProjectContextConfig::for_project(temp_dir.path());
+        let context = ProjectContext::discover(temp_dir.path(), &config).unwrap();
+
+        assert!(context.to_context_string().contains("Visible"));
+        assert!(!context.to_context_string().contains("Hidden"));
+    }
 }
@@ -10,6 +10,12 @@
 use serde::{Deserialize, Serialize};
 use serde_json;
 use std::io::{self, Write};
+use std::sync::Arc;
+
+use tokio::io::{AsyncBufRead, AsyncBufReadExt};
+use tokio::sync::mpsc;
+
+use crate::tools::{PendingQuestion, UserQuestion};
 
 /// Base event structure
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -57,6 +63,21 @@ pub struct FileEditData {
     pub line: Option<usize>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub text: Option<String>,
+    /// Target cell when the file is a Jupyter notebook
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cell: Option<NotebookCellData>,
+}
+
+/// Notebook cell targeted by a `file_edit` event
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NotebookCellData {
+    /// Cell id; absent for cells that don't exist yet (inserts)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cell_id: Option<String>,
+    pub cell_type: String,
+    /// 0-based position (for inserts and moves, the target position)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub index: Option<usize>,
 }
 
 /// File delete event data
@@ -86,6 +107,16 @@ pub struct CommandOutputData {
     pub exit_code: Option<i32>,
 }
 
+/// Tool progress event data
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ToolProgressData {
+    pub tool_use_id: String,
+    pub tool_name: String,
+    pub message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub percent: Option<u8>,
+}
+
 /// Status event data
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StatusData {
@@ -156,6 +187,84 @@ pub struct HistoryMessageData {
     pub content: String,
 }
 
+/// Answer to a `question` event, read from stdin.
+///
+/// Hosts write one line per answer: either a JSON object such as
+/// `{"id": "toolu_1", "answer": "postgres"}` (a null `answer` dismisses the
+/// question) or plain text, which answers the current question.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QuestionAnswerData {
+    #[serde(default)]
+    pub id: Option<String>,
+    #[serde(default)]
+    pub answer: Option<String>,
+}
+
+impl QuestionAnswerData {
+    /// Parse a stdin line; blank lines yield `None`.
+    pub fn parse(line: &str) -> Option<Self> {
+        let line = line.trim();
+        if line.is_empty() {
+            return None;
+        }
+        if line.starts_with('{') {
+            if let Ok(answer) = serde_json::from_str::<Self>(line) {
+                return Some(answer);
+            }
+        }
+        Some(Self {
+            id: None,
+            answer: Some(line.to_string()),
+        })
+    }
+}
+
+/// Emit a `question` event for each `ask_user` call and answer it from
+/// `reader` (stdin in embedded mode).
+///
+/// Answers whose `id` doesn't match the current question are skipped. When
+/// the reader closes, the current and all later questions are dismissed.
+pub async fn answer_questions<R: AsyncBufRead + Unpin>(
+    mut questions: mpsc::UnboundedReceiver<PendingQuestion>,
+    emitter: Arc<JsonLEmitter>,
+    reader: R,
+) {
+    let mut lines = reader.lines();
+    let mut reader_open = true;
+
+    while let Some(pending) = questions.recv().await {
+        if !reader_open {
+            pending.answer(None);
+            continue;
+        }
+        let _ = emitter.emit_question(&pending.question);
+
+        let answer = loop {
+            match lines.next_line().await {
+                Ok(Some(line)) => match QuestionAnswerData::parse(&line) {
+                    Some(answer)
+                        if answer
+                            .id
+                            .as_deref()
+                            .is_none_or(|id| id == pending.question.id) =>
+                    {
+                        break answer.answer;
+                    }
+                    Some(answer) => {
+                        tracing::warn!("Ignoring answer for unknown question {:?}", answer.id);
+                    }
+                    None => {}
+                },
+                Ok(None) | Err(_) => {
+                    reader_open = false;
+                    break None;
+                }
+            }
+        };
+        pending.answer(answer);
+    }
+}
+
 /// JSONL event emitter
 pub struct JsonLEmitter {
     session_id: String,
@@ -216,6 +325,30 @@ impl JsonLEmitter {
         self.emit("plan", PlanData { steps })
     }
 
+    /// Emit progress reported by a long-running tool.
+    pub fn emit_tool_progress(
+        &self,
+        tool_use_id: String,
+        tool_name: String,
+        message: String,
+        percent: Option<u8>,
+    ) -> io::Result<()> {
+        self.emit(
+            "tool_progress",
+            ToolProgressData {
+                tool_use_id,
+                tool_name,
+                message,
+                percent,
+            },
+        )
+    }
+
+    /// Emit an `ask_user` question; the host answers it on stdin.
+    pub fn emit_question(&self, question: &UserQuestion) -> io::Result<()> {
+        self.emit("question", question)
+    }
+
     pub fn emit_file_create(
         &self,
         path: String,
@@ -250,6 +383,33 @@ impl JsonLEmitter {
                 new_text,
                 line,
                 text,
+                cell: None,
+            },
+        )
+    }
+
+    /// Emit a `file_edit` event for a notebook cell change.
+    ///
+    /// `operation` is the cell operation (`edit`, `insert`, `delete` or
+    /// `move`); `old_text`/`new_text` are the cell sources.
+    pub fn emit_notebook_edit(
+        &self,
+        path: String,
+        operation: String,
+        old_text: Option<String>,
+        new_text: Option<String>,
+        cell: NotebookCellData,
+    ) -> io::Result<()> {
+        self.emit(
+            "file_edit",
+            FileEditData {
+                path,
+                operation,
+                old_text,
+                new_text,
+                line: None,
+                text: None,
+                cell: Some(cell),
             },
         )
     }
@@ -511,6 +671,7 @@ mod tests {
             new_text: Some("new".to_string()),
             line: None,
             text: None,
+            cell: None,
         };
 
         assert_eq!(data.path, "/test.rs");
@@ -705,6 +866,7 @@ mod tests {
             new_text: Some("new".to_string()),
             line: Some(10),
             text: Some("line text".to_string()),
+            cell: None,
         };
         let cloned = data.clone();
 
@@ -1496,4 +1658,128 @@ mod tests {
         );
         assert!(result.is_ok());
     }
+
+    fn test_question(id: &str) -> UserQuestion {
+        UserQuestion {
+            id: id.to_string(),
+            question: "Which database?".to_string(),
+            options: vec!["sqlite".to_string(), "postgres".to_string()],
+            allow_free_text: false,
+            default: None,
+        }
+    }
+
+    #[test]
+    fn test_question_answer_parse() {
+        assert_eq!(QuestionAnswerData::parse("  "), None);
+        assert_eq!(
+            QuestionAnswerData::parse("postgres"),
+            Some(QuestionAnswerData {
+                id: None,
+                answer: Some("postgres".to_string()),
+            })
+        );
+        assert_eq!(
+            QuestionAnswerData::parse(r#"{"id": "q1", "answer": null}"#),
+            Some(QuestionAnswerData {
+                id: Some("q1".to_string()),
+                answer: None,
+            })
+        );
+    }
+
+    #[test]
+    fn test_jsonl_emitter_emit_tool_progress() {
+        let buffer = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let emitter = JsonLEmitter::with_buffer("test-session".to_string(), buffer.clone());
+        emitter
+            .emit_tool_progress(
+                "toolu_1".to_string(),
+                "indexer".to_string(),
+                "Indexing".to_string(),
+                Some(40),
+            )
+            .unwrap();
+
+        let lines = buffer.lock().unwrap();
+        let event: serde_json::Value = serde_json::from_str(&lines[0]).unwrap();
+        assert_eq!(event["type"], "tool_progress");
+        assert_eq!(event["data"]["tool_use_id"], "toolu_1");
+        assert_eq!(event["data"]["percent"], 40);
+    }
+
+    #[test]
+    fn test_jsonl_emitter_emit_question() {
+        let buffer = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let emitter = JsonLEmitter::with_buffer("test-session".to_string(), buffer.clone());
+        emitter.emit_question(&test_question("q1")).unwrap();
+
+        let lines = buffer.lock().unwrap();
+        let event: serde_json::Value = serde_json::from_str(&lines[0]).unwrap();
+        assert_eq!(event["type"], "question");
+        assert_eq!(event["data"]["id"], "q1");
+        assert_eq!(event["data"]["options"][1], "postgres");
+        assert_eq!(event["data"]["allow_free_text"], false);
+    }
+
+    #[test]
+    fn test_emit_notebook_edit_includes_cell() {
+        let buffer = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let emitter = JsonLEmitter::with_buffer("test-session".to_string(), buffer.clone());
+        emitter
+            .emit_notebook_edit(
+                "analysis.ipynb".to_string(),
+                "edit".to_string(),
+                Some("x = 1".to_string()),
+                Some("x = 2".to_string()),
+                NotebookCellData {
+                    cell_id: Some("a1b2".to_string()),
+                    cell_type: "code".to_string(),
+                    index: Some(3),
+                },
+            )
+            .unwrap();
+
+        let lines = buffer.lock().unwrap();
+        let event: serde_json::Value = serde_json::from_str(&lines[0]).unwrap();
+        assert_eq!(event["type"], "file_edit");
+        assert_eq!(event["data"]["operation"], "edit");
+        assert_eq!(event["data"]["new_text"], "x = 2");
+        assert_eq!(event["data"]["cell"]["cell_id"], "a1b2");
+        assert_eq!(event["data"]["cell"]["index"], 3);
+    }
+
+    #[tokio::test]
+    async fn test_answer_questions_from_reader() {
+        let buffer = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let emitter = Arc::new(JsonLEmitter::with_buffer(
+            "test-session".to_string(),
+            buffer.clone(),
+        ));
+        let input =
+            "{\"id\": \"other\", \"answer\": \"x\"}\n{\"id\": \"q1\", \"answer\": \"postgres\"}\n";
+        let (tx, rx) = mpsc::unbounded_channel();
+        let task = tokio::spawn(answer_questions(rx, emitter, input.as_bytes()));
+
+        let (respond_to, first) = tokio::sync::oneshot::channel();
+        tx.send(PendingQuestion {
+            question: test_question("q1"),
+            respond_to,
+        })
+        .unwrap();
+        assert_eq!(first.await.unwrap().as_deref(), Some("postgres"));
+
+        // Input is exhausted, so later questions are dismissed.
+        let (respond_to, second) = tokio::sync::oneshot::channel();
+        tx.send(PendingQuestion {
+            question: test_question("q2"),
+            respond_to,
+        })
+        .unwrap();
+        assert_eq!(second.await.unwrap(), None);
+
+        drop(tx);
+        task.await.unwrap();
+        assert_eq!(buffer.lock().unwrap().len(), 2);
+    }
 }
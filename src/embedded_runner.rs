@@ -13,8 +13,8 @@ mod tooling;
 
 use self::history::{extract_history_messages, HistoryMessage};
 use self::tooling::{
-    extract_tool_uses_from_text_with_adapters, is_file_mod_tool, parse_tool_from_json,
-    EmbeddedToolExecutionStrategy,
+    extract_tool_uses_from_text_with_adapters, is_file_mod_tool, notebook_edit_preview,
+    parse_tool_from_json, EmbeddedToolExecutionStrategy,
 };
 #[cfg(test)]
 use self::tooling::{
@@ -32,13 +32,16 @@ use crate::context::{recall, summarizer};
 use crate::embedded::{JsonLEmitter, PlanStep};
 use crate::embeddings::EmbeddingGenerator;
 use crate::error::{ApiError, Result, TedError};
+use crate::llm::factory::ProviderFactory;
 use crate::llm::message::{ContentBlock, Conversation, Message, MessageContent};
 use crate::llm::provider::{ContentBlockResponse, LlmProvider};
 use crate::llm::providers::{
     AnthropicProvider, BlackmanProvider, LocalProvider, OpenRouterProvider,
 };
 use crate::models::download::BinaryDownloader;
-use crate::tools::{ShellOutputEvent, ToolContext, ToolExecutor};
+use crate::tools::{
+    FileReadTracker, ShellOutputEvent, ToolContext, ToolExecutor, ToolProgressEvent,
+};
 
 /// Embedded-mode observer for shared chat engine streaming callbacks.
 struct EmbeddedStreamObserver {
@@ -204,6 +207,8 @@ async fn run_embedded_chat_with_emitter(
             Box::new(provider)
         }
     };
+    let provider: Arc<dyn LlmProvider> =
+        ProviderFactory::with_redaction(Arc::from(provider), &provider_name, &settings);
 
     // Load caps
     let mut cap_names: Vec<String> = if args.cap.is_empty() {
@@ -278,6 +283,28 @@ async fn run_embedded_chat_with_emitter(
     // Create channel for shell output streaming
     let (shell_tx, mut shell_rx) = mpsc::unbounded_channel::<ShellOutputEvent>();
 
+    // Progress from long-running external tools becomes `tool_progress` events
+    let (tool_progress_tx, mut tool_progress_rx) = mpsc::unbounded_channel::<ToolProgressEvent>();
+    let progress_emitter = Arc::clone(&emitter);
+    tokio::spawn(async move {
+        while let Some(event) = tool_progress_rx.recv().await {
+            let _ = progress_emitter.emit_tool_progress(
+                event.tool_use_id,
+                event.tool_name,
+                event.message,
+                event.percent,
+            );
+        }
+    });
+
+    // ask_user questions are emitted as `question` events and answered on stdin
+    let (question_tx, question_rx) = mpsc::unbounded_channel();
+    tokio::spawn(crate::embedded::answer_questions(
+        question_rx,
+        Arc::clone(&emitter),
+        tokio::io::BufReader::new(tokio::io::stdin()),
+    ));
+
     // Create tool context and executor with shell output sender
     let tool_context = ToolContext::new(
         working_directory.clone(),
@@ -286,12 +313,18 @@ async fn run_embedded_chat_with_emitter(
         args.trust,
     )
     .with_shell_output_sender(shell_tx)
-    .with_files_in_context(args.files_in_context.clone());
+    .with_question_sender(question_tx)
+    .with_progress_sender(tool_progress_tx)
+    .with_files_in_context(args.files_in_context.clone())
+    .with_read_tracker(FileReadTracker::new());
     let mut tool_executor = if args.no_tools {
         eprintln!("[TOOLS] Disabled for this turn (--no-tools)");
         ToolExecutor::new_without_tools(tool_context, args.trust)
     } else {
-        ToolExecutor::new(tool_context, args.trust)
+        let mut executor = ToolExecutor::new(tool_context, args.trust);
+        executor.connect_mcp_servers().await;
+        executor.enable_mcp_sampling(Arc::clone(&provider), model.clone());
+        executor
     };
     let policy_load_warning = tool_executor.policy_load_warning().map(|w| w.to_string());
     if let Some(warning) = policy_load_warning.as_ref() {
@@ -434,6 +467,23 @@ async fn run_embedded_chat_with_emitter(
         }
     }
 
+    // UserPromptSubmit hooks may block, rewrite or annotate the prompt
+    let prompt_hooks = tool_executor
+        .hooks()
+        .user_prompt_submit(session_id, &prompt)
+        .await;
+    if let Some(reason) = prompt_hooks.block_reason.clone() {
+        emitter.emit_error(
+            "hook_blocked".to_string(),
+            format!("Prompt blocked by hook: {}", reason),
+            None,
+            None,
+        )?;
+        emitter.emit_completion(false, "Prompt blocked by hook".to_string(), Vec::new())?;
+        return Ok(());
+    }
+    let prompt = prompt_hooks.apply_to_prompt(&prompt);
+
     // Add user message
     messages.push(Message::user(prompt.clone()));
 
@@ -466,6 +516,7 @@ async fn run_embedded_chat_with_emitter(
     let mut tools_executed = 0;
     let interrupted = Arc::new(std::sync::atomic::AtomicBool::new(false));
     let mut tool_call_tracker = chat::ToolCallTracker::new(chat::engine::MAX_RECENT_TOOL_CALLS);
+    let mut stop_continuations: usize = 0;
 
     // Main agent loop
     let max_turns = 25;
@@ -616,6 +667,20 @@ async fn run_embedded_chat_with_emitter(
             messages.push(Message::assistant_blocks(assistant_blocks));
         }
 
+        // If no tool uses, we're done - unless a Stop hook sends the agent back to work
+        if tool_uses.is_empty() && stop_continuations < crate::hooks::MAX_STOP_CONTINUATIONS {
+            let outcome = tool_executor
+                .hooks()
+                .stop(session_id, &current_text, stop_continuations > 0)
+                .await;
+            if let Some(reason) = outcome.block_reason {
+                stop_continuations += 1;
+                emitter.emit_status("running", format!("Stop hook: {}", reason), None)?;
+                messages.push(Message::user(crate::hooks::stop_feedback_message(&reason)));
+                continue;
+            }
+        }
+
         // If no tool uses, we're done - but emit history first
         if tool_uses.is_empty() {
             if let Err(e) = emitter.emit_conversation_history(extract_history_messages(&messages)) {
@@ -716,6 +781,19 @@ async fn run_embedded_chat_with_emitter(
                         files_changed.push(path.to_string());
                     }
                 }
+            } else if name_lower == "notebook_edit" {
+                if let Some(preview) = notebook_edit_preview(input, &working_directory) {
+                    if !files_changed.contains(&preview.path) {
+                        files_changed.push(preview.path.clone());
+                    }
+                    emitter.emit_notebook_edit(
+                        preview.path,
+                        preview.operation,
+                        preview.old_text,
+                        preview.new_text,
+                        preview.cell,
+                    )?;
+                }
             } else if name_lower == "shell" {
                 if let Some(command) = input.get("command").and_then(|v| v.as_str()) {
                     emitter.emit_command(command.to_string(), None, None)?;
@@ -1523,6 +1523,48 @@ reason = "embedded parity deny"
         .contains("embedded parity deny"));
 }
 
+#[test]
+fn test_notebook_edit_preview_reads_current_cell() {
+    let temp = tempfile::tempdir().unwrap();
+    std::fs::write(
+        temp.path().join("nb.ipynb"),
+        json!({
+            "cells": [
+                {"cell_type": "markdown", "id": "md", "metadata": {}, "source": ["# Title"]},
+                {"cell_type": "code", "id": "run", "metadata": {}, "execution_count": null,
+                 "outputs": [], "source": ["x = 1"]}
+            ],
+            "metadata": {}, "nbformat": 4, "nbformat_minor": 5
+        })
+        .to_string(),
+    )
+    .unwrap();
+
+    let preview = super::tooling::notebook_edit_preview(
+        &json!({"path": "nb.ipynb", "operation": "edit", "cell_id": "run", "source": "x = 2"}),
+        temp.path(),
+    )
+    .unwrap();
+    assert_eq!(preview.operation, "edit");
+    assert_eq!(preview.old_text.as_deref(), Some("x = 1"));
+    assert_eq!(preview.new_text.as_deref(), Some("x = 2"));
+    assert_eq!(preview.cell.cell_id.as_deref(), Some("run"));
+    assert_eq!(preview.cell.cell_type, "code");
+    assert_eq!(preview.cell.index, Some(1));
+
+    let insert = super::tooling::notebook_edit_preview(
+        &json!({"path": "nb.ipynb", "operation": "insert", "cell_type": "markdown",
+                "source": "## Notes", "index": 0}),
+        temp.path(),
+    )
+    .unwrap();
+    assert_eq!(insert.old_text, None);
+    assert_eq!(insert.cell.cell_id, None);
+    assert_eq!(insert.cell.cell_type, "markdown");
+    assert_eq!(insert.cell.index, Some(0));
+    assert!(super::tooling::is_file_mod_tool("notebook_edit"));
+}
+
 // ==================== Integration tests for run_embedded_chat ====================
 
 mod integration {
@@ -1542,6 +1584,7 @@ mod integration {
             resume: None,
             trust: true,
             no_stream: false,
+            plan: false,
             no_tools: false,
             model_path: None,
             embedded: true,
@@ -6,6 +6,7 @@ use std::collections::HashSet;
 use std::sync::Arc;
 
 use crate::chat;
+use crate::embedded::NotebookCellData;
 use crate::error::Result;
 use crate::tools::ToolExecutor;
 
@@ -410,10 +411,93 @@ pub(super) fn map_shell_arguments(args: &serde_json::Value) -> serde_json::Value
 pub(super) fn is_file_mod_tool(name: &str) -> bool {
     matches!(
         name.to_lowercase().as_str(),
-        "file_write" | "file_edit" | "file_delete" | "create_file" | "edit_file" | "delete_file"
+        "file_write"
+            | "file_edit"
+            | "file_delete"
+            | "create_file"
+            | "edit_file"
+            | "delete_file"
+            | "notebook_edit"
     )
 }
 
+/// A `notebook_edit` call described for the `file_edit` preview event
+#[derive(Debug, Clone)]
+pub(super) struct NotebookEditPreview {
+    pub path: String,
+    pub operation: String,
+    pub old_text: Option<String>,
+    pub new_text: Option<String>,
+    pub cell: NotebookCellData,
+}
+
+/// Describe a `notebook_edit` call, reading the current cell from disk.
+pub(super) fn notebook_edit_preview(
+    input: &serde_json::Value,
+    working_directory: &std::path::Path,
+) -> Option<NotebookEditPreview> {
+    let path = input.get("path").and_then(|v| v.as_str())?;
+    let operation = input
+        .get("operation")
+        .and_then(|v| v.as_str())
+        .unwrap_or("edit");
+    let new_text = input
+        .get("source")
+        .and_then(|v| v.as_str())
+        .map(str::to_string);
+    let index = input
+        .get("index")
+        .and_then(|v| v.as_u64())
+        .map(|i| i as usize);
+    let requested_type = input
+        .get("cell_type")
+        .and_then(|v| v.as_str())
+        .map(str::to_string);
+
+    let existing = input
+        .get("cell_id")
+        .and_then(|v| v.as_str())
+        .filter(|_| operation != "insert")
+        .and_then(|cell_ref| {
+            crate::tools::builtin::find_notebook_cell(&working_directory.join(path), cell_ref)
+        });
+
+    let cell = match &existing {
+        Some(info) => NotebookCellData {
+            cell_id: Some(info.cell_id.clone()),
+            cell_type: requested_type.unwrap_or_else(|| info.cell_type.clone()),
+            index: if operation == "move" {
+                index
+            } else {
+                Some(info.index)
+            },
+        },
+        None => NotebookCellData {
+            cell_id: input
+                .get("cell_id")
+                .and_then(|v| v.as_str())
+                .filter(|_| operation != "insert")
+                .map(str::to_string),
+            cell_type: requested_type.unwrap_or_else(|| "code".to_string()),
+            index,
+        },
+    };
+
+    Some(NotebookEditPreview {
+        path: path.to_string(),
+        operation: operation.to_string(),
+        old_text: existing
+            .filter(|_| operation != "move")
+            .map(|info| info.source),
+        new_text: if matches!(operation, "edit" | "insert") {
+            new_text
+        } else {
+            None
+        },
+        cell,
+    })
+}
+
 fn review_mode_mock_result(name: &str, input: &serde_json::Value) -> String {
     match name {
         "file_write" | "create_file" => {
@@ -428,6 +512,13 @@ fn review_mode_mock_result(name: &str, input: &serde_json::Value) -> String {
             let path = input.get("path").and_then(|v| v.as_str()).unwrap_or("file");
             format!("Successfully deleted {} (pending review)", path)
         }
+        "notebook_edit" => {
+            let path = input
+                .get("path")
+                .and_then(|v| v.as_str())
+                .unwrap_or("notebook");
+            format!("Successfully updated a cell in {} (pending review)", path)
+        }
         _ => "Operation completed (pending review)".to_string(),
     }
 }
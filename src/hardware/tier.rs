@@ -143,6 +143,19 @@ impl HardwareTier {
         }
     }
 
+    /// Get the latency budget for an inline (ghost text) completion in milliseconds
+    pub fn inline_completion_budget_ms(&self) -> u64 {
+        match self {
+            HardwareTier::UltraTiny => 3000,
+            HardwareTier::Ancient => 2500,
+            HardwareTier::Tiny => 2000,
+            HardwareTier::Small => 1500,
+            HardwareTier::Medium => 1000,
+            HardwareTier::Large => 700,
+            HardwareTier::Cloud => 1500,
+        }
+    }
+
     /// Get what this tier can build
     pub fn capabilities(&self) -> Vec<&'static str> {
         match self {
@@ -385,6 +398,19 @@ mod tests {
         assert!(cloud_max < ultra_max);
     }
 
+    #[test]
+    fn test_inline_completion_budget_ms() {
+        assert_eq!(HardwareTier::UltraTiny.inline_completion_budget_ms(), 3000);
+        assert_eq!(HardwareTier::Large.inline_completion_budget_ms(), 700);
+        assert_eq!(HardwareTier::Cloud.inline_completion_budget_ms(), 1500);
+
+        // Faster local hardware gets a tighter budget
+        assert!(
+            HardwareTier::Medium.inline_completion_budget_ms()
+                < HardwareTier::Tiny.inline_completion_budget_ms()
+        );
+    }
+
     // ===== capabilities tests =====
 
     #[test]
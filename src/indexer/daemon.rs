@@ -17,6 +17,7 @@ use notify::{Config, Event, EventKind, RecommendedWatcher, RecursiveMode, Watche
 
 use super::config::{DaemonConfig, DaemonEvent};
 use super::{Indexer, IndexerConfig};
+use crate::config::TedIgnore;
 use crate::error::{Result, TedError};
 
 /// Handle to a running daemon.
@@ -220,13 +221,24 @@ impl Daemon {
         let mut pending = PendingChanges::default();
         let mut last_persist = Instant::now();
         let mut rename_from: Option<PathBuf> = None;
+        let tedignore = indexer
+            .lock()
+            .map(|idx| idx.tedignore().clone())
+            .unwrap_or_default();
 
         loop {
             // Try to receive with timeout
             match fs_rx.recv_timeout(Duration::from_millis(100)) {
                 Ok(event) => {
                     // Process the event
-                    Self::accumulate_event(&event, &root, &config, &mut pending, &mut rename_from);
+                    Self::accumulate_event(
+                        &event,
+                        &root,
+                        &config,
+                        &tedignore,
+                        &mut pending,
+                        &mut rename_from,
+                    );
                 }
                 Err(mpsc::RecvTimeoutError::Disconnected) => {
                     // Channel closed, exit
@@ -265,6 +277,7 @@ impl Daemon {
         event: &Event,
         root: &Path,
         config: &DaemonConfig,
+        tedignore: &TedIgnore,
         pending: &mut PendingChanges,
         rename_from: &mut Option<PathBuf>,
     ) {
@@ -275,7 +288,7 @@ impl Daemon {
 
         for path in &event.paths {
             // Skip paths that should be ignored
-            if config.should_ignore(path) {
+            if config.should_ignore(path) || tedignore.is_ignored(path) {
                 continue;
             }
 
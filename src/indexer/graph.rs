@@ -145,21 +145,23 @@ impl DependencyGraph {
             stats.imports_found += imports.len();
 
             // Get the parser to resolve imports
+            let mut resolved_count = 0;
             if let Some(parser) = registry.parser_for_path(path) {
                 for import in &imports {
                     if let Some(resolved) =
                         parser.resolve_import(import, &relative_path, &self.project_root)
                     {
                         self.add_edge(relative_path.clone(), resolved);
-                        stats.imports_resolved += 1;
+                        resolved_count += 1;
                     }
                 }
             }
+            stats.imports_resolved += resolved_count;
 
             // Update node stats
             if let Some(node) = self.get_node_mut(&relative_path) {
                 node.import_count = imports.len();
-                node.unresolved_count = imports.len() - stats.imports_resolved;
+                node.unresolved_count = imports.len() - resolved_count;
             }
         }
 
@@ -670,4 +672,31 @@ mod tests {
 
         assert_eq!(stats.resolution_rate(), 100.0);
     }
+
+    #[test]
+    fn test_build_from_files_counts_unresolved_per_file() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let root = temp.path();
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        let lib = root.join("src/lib.rs");
+        let config = root.join("src/config.rs");
+        let main = root.join("src/main.rs");
+        std::fs::write(&lib, "mod config;\n").unwrap();
+        std::fs::write(&config, "").unwrap();
+        std::fs::write(&main, "use serde::Serialize;\n").unwrap();
+
+        let mut graph = DependencyGraph::new(root.to_path_buf());
+        let files = [
+            (lib.as_path(), "mod config;\n"),
+            (config.as_path(), ""),
+            (main.as_path(), "use serde::Serialize;\n"),
+        ];
+        let stats = graph
+            .build_from_files(files.into_iter(), &ParserRegistry::new())
+            .unwrap();
+
+        assert_eq!(stats.imports_resolved, 1);
+        let main = graph.get_node(Path::new("src/main.rs")).unwrap();
+        assert_eq!(main.unresolved_count, main.import_count);
+    }
 }
@@ -67,6 +67,7 @@ pub use recall::{
 pub use scorer::{Scorer, ScoringConfig};
 pub use vector::{cosine_similarity, reciprocal_rank_fusion, HybridSearchResult, VectorIndex};
 
+use crate::config::TedIgnore;
 use crate::error::{Result, TedError};
 
 /// Configuration for semantic search
@@ -179,6 +180,8 @@ pub struct Indexer {
     parsers: ParserRegistry,
     /// Vector index for semantic search (optional, enabled via config)
     vector_index: Option<VectorIndex>,
+    /// `.tedignore` rules; ignored files are never indexed
+    tedignore: TedIgnore,
 }
 
 impl Indexer {
@@ -202,6 +205,8 @@ impl Indexer {
             None
         };
 
+        let tedignore = TedIgnore::load(&root);
+
         Ok(Self {
             root,
             config,
@@ -212,6 +217,7 @@ impl Indexer {
             graph,
             parsers,
             vector_index,
+            tedignore,
         })
     }
 
@@ -230,6 +236,11 @@ impl Indexer {
         &self.scorer
     }
 
+    /// Get the `.tedignore` rules.
+    pub fn tedignore(&self) -> &TedIgnore {
+        &self.tedignore
+    }
+
     /// Check if git is available.
     pub fn has_git(&self) -> bool {
         self.git.is_some()
@@ -326,12 +337,15 @@ impl Indexer {
             }
         }
 
-        // Remove stale entries (files that no longer exist)
+        // Remove stale entries (files that no longer exist or are now ignored)
         let stale: Vec<_> = self
             .index
             .files
             .keys()
-            .filter(|p| !self.root.join(p).exists())
+            .filter(|p| {
+                let full = self.root.join(p);
+                !full.exists() || self.tedignore.is_ignored(&full)
+            })
             .cloned()
             .collect();
 
@@ -349,6 +363,92 @@ impl Indexer {
         Ok(stats)
     }
 
+    /// Re-index files modified at or after `since`, add new files and drop
+    /// deleted ones, without rebuilding the whole dependency graph.
+    ///
+    /// Returns the changed paths, relative to the project root.
+    pub fn refresh_changed(&mut self, since: std::time::SystemTime) -> Result<Vec<PathBuf>> {
+        let mut changed = Vec::new();
+
+        for path in self.collect_files()? {
+            let relative = path.strip_prefix(&self.root).unwrap_or(&path).to_path_buf();
+            let modified = std::fs::metadata(&path).and_then(|m| m.modified()).ok();
+            if self.index.get_file(&relative).is_none() || modified.is_some_and(|m| m >= since) {
+                self.refresh_file(&path)?;
+                changed.push(relative);
+            }
+        }
+
+        let removed: Vec<PathBuf> = self
+            .index
+            .files
+            .keys()
+            .filter(|p| !self.root.join(p).exists())
+            .cloned()
+            .collect();
+        for path in removed {
+            self.index.remove_file(&path);
+            self.graph.remove_file(&path);
+            changed.push(path);
+        }
+
+        Ok(changed)
+    }
+
+    /// Re-read one file and update its metadata and dependency edges.
+    ///
+    /// A file that no longer exists is removed from the index.
+    pub fn refresh_file(&mut self, path: &Path) -> Result<()> {
+        let full = if path.is_absolute() {
+            path.to_path_buf()
+        } else {
+            self.root.join(path)
+        };
+        let relative = full.strip_prefix(&self.root).unwrap_or(&full).to_path_buf();
+
+        let Ok(content) = std::fs::read_to_string(&full) else {
+            self.index.remove_file(&relative);
+            self.graph.remove_file(&relative);
+            return Ok(());
+        };
+
+        self.update_file_content(&full, &content)
+    }
+
+    /// Update one file's metadata and dependency edges from `content`, such
+    /// as an unsaved editor buffer, without reading the file.
+    pub fn update_file_content(&mut self, path: &Path, content: &str) -> Result<()> {
+        let full = if path.is_absolute() {
+            path.to_path_buf()
+        } else {
+            self.root.join(path)
+        };
+        let relative = full.strip_prefix(&self.root).unwrap_or(&full).to_path_buf();
+
+        let mut file_memory = self
+            .index
+            .files
+            .remove(&relative)
+            .unwrap_or_else(|| FileMemory::new(relative.clone()));
+        file_memory.byte_size = content.len() as u64;
+        file_memory.line_count = content.lines().count() as u32;
+        if let Some(ext) = full.extension() {
+            file_memory.language = Language::from_extension(&ext.to_string_lossy());
+        }
+
+        self.graph.ensure_node(relative.clone());
+        self.graph.update_file(&full, content, &self.parsers)?;
+        if let Some(node) = self.graph.get_node(&relative) {
+            file_memory.centrality_score = node.centrality;
+            file_memory.dependencies = node.dependencies.clone();
+            file_memory.dependents = node.dependents.clone();
+        }
+        file_memory.retention_score = self.scorer.file_retention_score(&file_memory);
+        self.index.upsert_file(file_memory);
+
+        Ok(())
+    }
+
     /// Record an access to a file (updates recency and frequency).
     pub fn record_file_access(&mut self, path: &Path) {
         let relative = path.strip_prefix(&self.root).unwrap_or(path);
@@ -485,7 +585,7 @@ impl Indexer {
             .into_iter()
             .filter_entry(|e| {
                 let path = e.path();
-                self.config.should_index(path)
+                self.config.should_index(path) && !self.tedignore.is_ignored(path)
             })
         {
             let entry =
@@ -767,6 +867,51 @@ mod tests {
         let _ = stats.duration_ms;
     }
 
+    #[test]
+    fn test_indexer_refresh_changed() {
+        let temp = create_test_project();
+        let mut indexer = Indexer::new(temp.path(), IndexerConfig::default()).unwrap();
+        indexer.full_scan().unwrap();
+        let since = std::time::SystemTime::now();
+
+        std::fs::write(temp.path().join("src/utils.rs"), "pub fn helper() {}").unwrap();
+        std::fs::remove_file(temp.path().join("src/main.rs")).unwrap();
+        let changed = indexer.refresh_changed(since).unwrap();
+
+        assert!(changed.contains(&PathBuf::from("src/utils.rs")));
+        assert!(changed.contains(&PathBuf::from("src/main.rs")));
+        assert!(indexer
+            .index()
+            .get_file(Path::new("src/utils.rs"))
+            .is_some());
+        assert!(indexer.index().get_file(Path::new("src/main.rs")).is_none());
+
+        // lib.rs declares `mod utils`, so the new file now has a dependent
+        indexer.refresh_file(Path::new("src/lib.rs")).unwrap();
+        assert!(indexer
+            .dependents_of(Path::new("src/utils.rs"))
+            .contains(Path::new("src/lib.rs")));
+    }
+
+    #[test]
+    fn test_indexer_update_file_content_uses_unsaved_content() {
+        let temp = create_test_project();
+        std::fs::write(temp.path().join("src/utils.rs"), "pub fn helper() {}").unwrap();
+        let mut indexer = Indexer::new(temp.path(), IndexerConfig::default()).unwrap();
+        indexer.full_scan().unwrap();
+        assert!(indexer
+            .dependents_of(Path::new("src/utils.rs"))
+            .contains(Path::new("src/lib.rs")));
+
+        indexer
+            .update_file_content(Path::new("src/lib.rs"), "pub fn standalone() {}")
+            .unwrap();
+        assert!(indexer.dependents_of(Path::new("src/utils.rs")).is_empty());
+        // The file on disk is untouched
+        let on_disk = std::fs::read_to_string(temp.path().join("src/lib.rs")).unwrap();
+        assert_eq!(on_disk, "pub mod utils;");
+    }
+
     #[test]
     fn test_indexer_record_access() {
         let temp = create_test_project();
@@ -828,4 +973,25 @@ mod tests {
         assert_eq!(stats.files_indexed, 0);
         assert_eq!(stats.files_removed, 0);
     }
+
+    #[test]
+    fn test_indexer_skips_tedignored_files() {
+        let temp = create_test_project();
+        std::fs::create_dir_all(temp.path().join("secrets")).unwrap();
+        std::fs::write(
+            temp.path().join("secrets/keys.rs"),
+            "const K: &str = \"x\";",
+        )
+        .unwrap();
+        std::fs::write(temp.path().join(".tedignore"), "secrets/\n").unwrap();
+
+        let mut indexer = Indexer::new(temp.path(), IndexerConfig::default()).unwrap();
+        indexer.full_scan().unwrap();
+
+        assert!(indexer.index().get_file(Path::new("src/main.rs")).is_some());
+        assert!(indexer
+            .index()
+            .get_file(Path::new("secrets/keys.rs"))
+            .is_none());
+    }
 }
@@ -33,6 +33,7 @@ pub mod embeddings;
 pub mod error;
 pub mod hardware;
 pub mod history;
+pub mod hooks;
 pub mod indexer;
 pub mod llm;
 pub mod lsp;
@@ -12,6 +12,7 @@ use crate::config::Settings;
 use crate::error::{Result, TedError};
 use crate::llm::provider::LlmProvider;
 use crate::llm::providers::{AnthropicProvider, LocalProvider, OpenRouterProvider};
+use crate::llm::redaction::RedactingProvider;
 use crate::models::download::BinaryDownloader;
 
 fn expected_instruct_slug(model: &str) -> Option<&'static str> {
@@ -50,11 +51,25 @@ impl ProviderFactory {
         settings: &Settings,
         _perform_health_check: bool,
     ) -> Result<Arc<dyn LlmProvider>> {
-        match provider_name {
-            "local" => Self::create_local(settings).await,
-            "openrouter" => Self::create_openrouter(settings),
-            "blackman" => Self::create_blackman(settings),
-            _ => Self::create_anthropic(settings),
+        let provider = match provider_name {
+            "local" => Self::create_local(settings).await?,
+            "openrouter" => Self::create_openrouter(settings)?,
+            "blackman" => Self::create_blackman(settings)?,
+            _ => Self::create_anthropic(settings)?,
+        };
+        Ok(Self::with_redaction(provider, provider_name, settings))
+    }
+
+    /// Wrap a provider in secret redaction when enabled for `provider_name`
+    pub fn with_redaction(
+        provider: Arc<dyn LlmProvider>,
+        provider_name: &str,
+        settings: &Settings,
+    ) -> Arc<dyn LlmProvider> {
+        if settings.redaction.is_enabled_for(provider_name) {
+            Arc::new(RedactingProvider::new(provider, &settings.redaction))
+        } else {
+            provider
         }
     }
 
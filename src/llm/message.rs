@@ -260,6 +260,11 @@ pub struct Conversation {
     /// System prompt (if any)
     pub system_prompt: Option<String>,
 
+    /// Context sent after the system prompt on every request, such as the
+    /// plan mode instructions or an approved plan. Kept apart from the system
+    /// prompt so re-rendering caps doesn't drop it.
+    pub pinned_context: Option<String>,
+
     /// Token estimation configuration
     config: ConversationConfig,
 }
@@ -275,6 +280,7 @@ impl Conversation {
         Self {
             messages: Vec::new(),
             system_prompt: None,
+            pinned_context: None,
             config,
         }
     }
@@ -284,6 +290,7 @@ impl Conversation {
         Self {
             messages: vec![],
             system_prompt: Some(system_prompt.into()),
+            pinned_context: None,
             config: ConversationConfig::default(),
         }
     }
@@ -296,6 +303,7 @@ impl Conversation {
         Self {
             messages: vec![],
             system_prompt: Some(system_prompt.into()),
+            pinned_context: None,
             config,
         }
     }
@@ -310,6 +318,22 @@ impl Conversation {
         self.system_prompt = Some(system_prompt.into());
     }
 
+    /// Set or clear the pinned context
+    pub fn set_pinned_context(&mut self, context: Option<String>) {
+        self.pinned_context = context;
+    }
+
+    /// The system prompt followed by the pinned context, as sent to the provider
+    pub fn effective_system(&self) -> Option<String> {
+        match (&self.system_prompt, &self.pinned_context) {
+            (Some(system), Some(pinned)) if !system.is_empty() => {
+                Some(format!("{}\n\n{}", system, pinned))
+            }
+            (_, Some(pinned)) => Some(pinned.clone()),
+            (system, None) => system.clone(),
+        }
+    }
+
     /// Add a message to the conversation
     pub fn push(&mut self, message: Message) {
         self.messages.push(message);
@@ -348,8 +372,7 @@ impl Conversation {
     pub fn estimate_tokens(&self) -> u32 {
         let chars_per_token = self.config.chars_per_token as usize;
         let system_tokens = self
-            .system_prompt
-            .as_ref()
+            .effective_system()
             .map(|s| (s.len() / chars_per_token) as u32)
             .unwrap_or(0);
 
@@ -368,8 +391,7 @@ impl Conversation {
     pub fn truncate_to_fit(&self, max_tokens: u32) -> (Vec<Message>, bool) {
         let chars_per_token = self.config.chars_per_token as usize;
         let system_tokens = self
-            .system_prompt
-            .as_ref()
+            .effective_system()
             .map(|s| (s.len() / chars_per_token) as u32)
             .unwrap_or(0);
 
@@ -407,8 +429,7 @@ impl Conversation {
     pub fn trim_to_fit(&mut self, max_tokens: u32) -> usize {
         let chars_per_token = self.config.chars_per_token as usize;
         let system_tokens = self
-            .system_prompt
-            .as_ref()
+            .effective_system()
             .map(|s| (s.len() / chars_per_token) as u32)
             .unwrap_or(0);
 
@@ -696,6 +717,28 @@ mod tests {
         assert_eq!(conv.system_prompt, Some("New system prompt".to_string()));
     }
 
+    #[test]
+    fn test_conversation_effective_system_appends_pinned_context() {
+        let mut conv = Conversation::new();
+        assert!(conv.effective_system().is_none());
+
+        conv.set_pinned_context(Some("Approved plan".to_string()));
+        assert_eq!(conv.effective_system().as_deref(), Some("Approved plan"));
+
+        conv.set_system("You are helpful");
+        assert_eq!(
+            conv.effective_system().as_deref(),
+            Some("You are helpful\n\nApproved plan")
+        );
+
+        conv.clear();
+        conv.set_system("Re-rendered");
+        assert!(conv.effective_system().unwrap().ends_with("Approved plan"));
+
+        conv.set_pinned_context(None);
+        assert_eq!(conv.effective_system().as_deref(), Some("Re-rendered"));
+    }
+
     #[test]
     fn test_conversation_push() {
         let mut conv = Conversation::new();
@@ -14,8 +14,8 @@ use std::sync::{Arc, Mutex};
 
 use crate::error::Result;
 use crate::llm::provider::{
-    CompletionRequest, CompletionResponse, ContentBlockResponse, LlmProvider, ModelInfo,
-    StopReason, StreamEvent, Usage,
+    CompletionRequest, CompletionResponse, ContentBlockResponse, FimRequest, LlmProvider,
+    ModelInfo, StopReason, StreamEvent, Usage,
 };
 
 /// A mock LLM provider for testing
@@ -31,6 +31,10 @@ pub struct MockProvider {
     recorded_requests: Arc<Mutex<Vec<CompletionRequest>>>,
     /// Available models
     models: Vec<ModelInfo>,
+    /// Fill-in-the-middle completion, if the mock supports it
+    fim_response: Option<String>,
+    /// Recorded fill-in-the-middle requests
+    recorded_fim_requests: Arc<Mutex<Vec<FimRequest>>>,
 }
 
 /// A pre-configured response for the mock provider
@@ -72,6 +76,8 @@ impl MockProvider {
             call_count: Arc::new(AtomicUsize::new(0)),
             recorded_requests: Arc::new(Mutex::new(vec![])),
             models: vec![Self::default_model()],
+            fim_response: None,
+            recorded_fim_requests: Arc::new(Mutex::new(vec![])),
         }
     }
 
@@ -158,6 +164,17 @@ impl MockProvider {
         self
     }
 
+    /// Support fill-in-the-middle, answering with `text`
+    pub fn with_fim_response(mut self, text: impl Into<String>) -> Self {
+        self.fim_response = Some(text.into());
+        self
+    }
+
+    /// Get all recorded fill-in-the-middle requests
+    pub fn recorded_fim_requests(&self) -> Vec<FimRequest> {
+        self.recorded_fim_requests.lock().unwrap().clone()
+    }
+
     /// Add custom models
     pub fn with_models(mut self, models: Vec<ModelInfo>) -> Self {
         self.models = models;
@@ -325,6 +342,14 @@ impl LlmProvider for MockProvider {
         // Simple approximation: ~4 characters per token
         Ok((text.len() / 4).max(1) as u32)
     }
+
+    async fn fill_in_middle(&self, request: FimRequest) -> Result<Option<String>> {
+        if self.fim_response.is_none() {
+            return Ok(None);
+        }
+        self.recorded_fim_requests.lock().unwrap().push(request);
+        Ok(self.fim_response.clone())
+    }
 }
 
 #[cfg(test)]
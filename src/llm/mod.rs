@@ -11,6 +11,7 @@ pub mod message;
 pub mod provider;
 pub mod providers;
 pub mod rate_budget;
+pub mod redaction;
 pub mod retry;
 
 #[cfg(test)]
@@ -21,6 +22,7 @@ pub use factory::ProviderFactory;
 pub use message::*;
 pub use provider::*;
 pub use rate_budget::*;
+pub use redaction::{RedactingProvider, SecretRedactor};
 pub use retry::*;
 
 #[cfg(test)]
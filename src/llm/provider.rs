@@ -9,6 +9,7 @@ use async_trait::async_trait;
 use futures::Stream;
 use serde::{Deserialize, Serialize};
 use std::pin::Pin;
+use std::time::Duration;
 
 use crate::error::Result;
 use crate::llm::message::Message;
@@ -41,6 +42,30 @@ pub trait LlmProvider: Send + Sync {
 
     /// Count tokens for a text (provider-specific tokenization)
     fn count_tokens(&self, text: &str, model: &str) -> Result<u32>;
+
+    /// Fill-in-the-middle completion, for providers with a native infill
+    /// endpoint. `None` means there is none and callers should fall back to
+    /// a chat completion.
+    async fn fill_in_middle(&self, _request: FimRequest) -> Result<Option<String>> {
+        Ok(None)
+    }
+}
+
+/// Request for a fill-in-the-middle completion
+#[derive(Debug, Clone, Default)]
+pub struct FimRequest {
+    /// Text before the cursor
+    pub prefix: String,
+    /// Text after the cursor
+    pub suffix: String,
+    /// Other files as context, as `(path, content)`
+    pub extra: Vec<(String, String)>,
+    /// Maximum tokens to generate
+    pub max_tokens: u32,
+    /// Sampling temperature
+    pub temperature: f32,
+    /// Generation time limit, for providers that can enforce one
+    pub time_limit: Option<Duration>,
 }
 
 /// Request for completion
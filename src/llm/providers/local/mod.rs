@@ -22,8 +22,8 @@ use tokio::sync::Mutex;
 use crate::error::{ApiError, Result, TedError};
 use crate::llm::message::{ContentBlock, Message, MessageContent, Role, ToolResultContent};
 use crate::llm::provider::{
-    CompletionRequest, CompletionResponse, ContentBlockDelta, ContentBlockResponse, LlmProvider,
-    ModelInfo, StopReason, StreamEvent, ToolChoice, ToolDefinition, Usage,
+    CompletionRequest, CompletionResponse, ContentBlockDelta, ContentBlockResponse, FimRequest,
+    LlmProvider, ModelInfo, StopReason, StreamEvent, ToolChoice, ToolDefinition, Usage,
 };
 
 use server::LlamaServer;
@@ -682,6 +682,86 @@ impl LlmProvider for LocalProvider {
         // Approximate: ~4 characters per token
         Ok((text.len() as f64 / 4.0).ceil() as u32)
     }
+
+    async fn fill_in_middle(&self, request: FimRequest) -> Result<Option<String>> {
+        let base_url = self.ensure_server().await?;
+        let url = format!("{}/infill", base_url);
+        let body = InfillRequest {
+            input_prefix: request.prefix,
+            input_suffix: request.suffix,
+            input_extra: request
+                .extra
+                .into_iter()
+                .map(|(filename, text)| InfillExtra { filename, text })
+                .collect(),
+            n_predict: request.max_tokens,
+            temperature: request.temperature,
+            cache_prompt: true,
+            t_max_predict_ms: request.time_limit.map(|limit| limit.as_millis() as u64),
+        };
+
+        let response = self
+            .client
+            .post(&url)
+            .header("Content-Type", "application/json")
+            .json(&body)
+            .send()
+            .await
+            .map_err(|e| {
+                TedError::Api(ApiError::Network(format!(
+                    "Failed to connect to local llama-server: {}",
+                    e
+                )))
+            })?;
+
+        let status = response.status().as_u16();
+        // Older servers have no /infill; models without FIM tokens reject it
+        if matches!(status, 400 | 404 | 501) {
+            tracing::debug!("Local llama-server has no infill support (HTTP {})", status);
+            return Ok(None);
+        }
+        if !response.status().is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(TedError::Api(ApiError::ServerError {
+                status,
+                message: format!("Local llama-server error: {}", body),
+            }));
+        }
+
+        let infill: InfillResponse = response.json().await.map_err(|e| {
+            TedError::Api(ApiError::InvalidResponse(format!(
+                "Failed to parse local infill response: {}",
+                e
+            )))
+        })?;
+        Ok(Some(infill.content))
+    }
+}
+
+// llama-server fill-in-the-middle API types
+
+#[derive(Debug, Serialize)]
+struct InfillRequest {
+    input_prefix: String,
+    input_suffix: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    input_extra: Vec<InfillExtra>,
+    n_predict: u32,
+    temperature: f32,
+    cache_prompt: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    t_max_predict_ms: Option<u64>,
+}
+
+#[derive(Debug, Serialize)]
+struct InfillExtra {
+    filename: String,
+    text: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct InfillResponse {
+    content: String,
 }
 
 // OpenAI-compatible API types (used by llama-server)
@@ -979,4 +1059,84 @@ mod tests {
         let count = provider.count_tokens("Hello world!", "test").unwrap();
         assert_eq!(count, 3); // 12 chars / 4 = 3
     }
+
+    /// Serve one HTTP request with a fixed response, returning the request
+    async fn serve_once(
+        status: &'static str,
+        body: &'static str,
+    ) -> (String, tokio::task::JoinHandle<String>) {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let handle = tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut request = Vec::new();
+            let mut buf = [0u8; 4096];
+            loop {
+                let n = socket.read(&mut buf).await.unwrap();
+                request.extend_from_slice(&buf[..n]);
+                let text = String::from_utf8_lossy(&request);
+                if let Some(header_end) = text.find("\r\n\r\n") {
+                    let length = text[..header_end]
+                        .lines()
+                        .find_map(|line| {
+                            line.to_lowercase()
+                                .strip_prefix("content-length:")
+                                .map(|v| v.trim().parse::<usize>().unwrap())
+                        })
+                        .unwrap_or(0);
+                    if request.len() >= header_end + 4 + length {
+                        break;
+                    }
+                }
+            }
+            let response = format!(
+                "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                status,
+                body.len(),
+                body
+            );
+            socket.write_all(response.as_bytes()).await.unwrap();
+            String::from_utf8_lossy(&request).to_string()
+        });
+        (base_url, handle)
+    }
+
+    #[tokio::test]
+    async fn test_fill_in_middle_uses_infill_endpoint() {
+        let (base_url, server) = serve_once("200 OK", r#"{"content":"a + b"}"#).await;
+        let provider = LocalProvider::with_external_server(base_url, "test".to_string(), None);
+
+        let completion = provider
+            .fill_in_middle(FimRequest {
+                prefix: "fn add(a: u32, b: u32) -> u32 {\n    ".to_string(),
+                suffix: "\n}\n".to_string(),
+                extra: vec![("src/lib.rs".to_string(), "mod add;".to_string())],
+                max_tokens: 64,
+                temperature: 0.1,
+                time_limit: Some(Duration::from_millis(800)),
+            })
+            .await
+            .unwrap();
+        assert_eq!(completion.as_deref(), Some("a + b"));
+
+        let request = server.await.unwrap();
+        assert!(request.starts_with("POST /infill"));
+        assert!(request.contains(r#""input_extra":[{"filename":"src/lib.rs","text":"mod add;"}]"#));
+        assert!(request.contains(r#""t_max_predict_ms":800"#));
+    }
+
+    #[tokio::test]
+    async fn test_fill_in_middle_unsupported_returns_none() {
+        let (base_url, _server) =
+            serve_once("501 Not Implemented", r#"{"error":"infill not supported"}"#).await;
+        let provider = LocalProvider::with_external_server(base_url, "test".to_string(), None);
+
+        let completion = provider
+            .fill_in_middle(FimRequest::default())
+            .await
+            .unwrap();
+        assert!(completion.is_none());
+    }
 }
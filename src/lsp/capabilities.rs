@@ -3,6 +3,8 @@
 
 use tower_lsp::lsp_types::*;
 
+use super::code_actions::RUN_CODE_ACTION_COMMAND;
+
 /// Define server capabilities
 pub fn server_capabilities() -> ServerCapabilities {
     ServerCapabilities {
@@ -43,10 +45,21 @@ pub fn server_capabilities() -> ServerCapabilities {
         // Hover
         hover_provider: Some(HoverProviderCapability::Simple(true)),
 
+        // References and symbols, answered from the indexer
+        references_provider: Some(OneOf::Left(true)),
+        document_symbol_provider: Some(OneOf::Left(true)),
+        workspace_symbol_provider: Some(OneOf::Left(true)),
+
+        // Ted code actions, run through the execute command
+        code_action_provider: Some(CodeActionProviderCapability::Simple(true)),
+        execute_command_provider: Some(ExecuteCommandOptions {
+            commands: vec![RUN_CODE_ACTION_COMMAND.to_string()],
+            work_done_progress_options: WorkDoneProgressOptions {
+                work_done_progress: Some(true),
+            },
+        }),
+
         // We don't support these yet, but could add them later:
-        // - references_provider
-        // - document_symbol_provider
-        // - code_action_provider
         // - document_formatting_provider
         // - rename_provider
         // - diagnostic_provider
@@ -76,6 +89,25 @@ mod tests {
         assert!(caps.definition_provider.is_some());
     }
 
+    #[test]
+    fn test_server_capabilities_includes_references_and_symbols() {
+        let caps = server_capabilities();
+        assert_eq!(caps.references_provider, Some(OneOf::Left(true)));
+        assert_eq!(caps.document_symbol_provider, Some(OneOf::Left(true)));
+        assert_eq!(caps.workspace_symbol_provider, Some(OneOf::Left(true)));
+    }
+
+    #[test]
+    fn test_server_capabilities_includes_code_actions() {
+        let caps = server_capabilities();
+        assert_eq!(
+            caps.code_action_provider,
+            Some(CodeActionProviderCapability::Simple(true))
+        );
+        let commands = caps.execute_command_provider.unwrap().commands;
+        assert_eq!(commands, vec!["ted.runCodeAction".to_string()]);
+    }
+
     #[test]
     fn test_completion_trigger_characters() {
         let caps = server_capabilities();
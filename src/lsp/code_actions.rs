@@ -0,0 +1,671 @@
+// SPDX-License-Identifier: AGPL-3.0-or-later
+// Copyright (C) 2025 Blackman Artificial Intelligence Technologies Inc.
+
+//! Code actions that run Ted on a selection or diagnostic
+//!
+//! `textDocument/codeAction` only lists the actions; each one carries a
+//! `ted.runCodeAction` command. Running it sends the selection and its
+//! surroundings through the chat engine, reports progress via `$/progress`
+//! and turns the reply into a `WorkspaceEdit` (or, for "Explain", a message).
+
+use std::path::Path;
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc;
+use tower_lsp::lsp_types::*;
+use tower_lsp::Client;
+
+use super::model::LspModel;
+use super::progress::ProgressReporter;
+use super::server::DocumentState;
+use crate::chat::agent::extract_text_content;
+use crate::chat::engine::{get_response_with_retry, AgentLoopObserver};
+use crate::error::{Result, TedError};
+use crate::llm::message::Message;
+use crate::llm::provider::{CompletionRequest, LlmProvider};
+
+/// Command every Ted code action runs
+pub const RUN_CODE_ACTION_COMMAND: &str = "ted.runCodeAction";
+
+/// Lines of surrounding code sent along with the selection
+const CONTEXT_LINES: usize = 60;
+const MAX_RESPONSE_TOKENS: u32 = 4096;
+/// Characters received between two progress reports
+const PROGRESS_INTERVAL_CHARS: usize = 400;
+const DEFAULT_REFACTOR_INSTRUCTION: &str =
+    "improve readability and structure without changing behaviour";
+
+const SYSTEM_PROMPT: &str = "You are Ted, an AI coding assistant working inside the user's \
+editor. Answer about the code you are shown. When asked for code, reply with exactly one \
+fenced code block and nothing else.";
+
+/// One of the actions Ted offers
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TedAction {
+    Explain,
+    FixDiagnostic,
+    AddTests,
+    Refactor,
+}
+
+impl TedAction {
+    fn title(self) -> &'static str {
+        match self {
+            TedAction::Explain => "Ted: Explain",
+            TedAction::FixDiagnostic => "Ted: Fix diagnostic",
+            TedAction::AddTests => "Ted: Add tests",
+            TedAction::Refactor => "Ted: Refactor…",
+        }
+    }
+
+    fn kind(self) -> CodeActionKind {
+        match self {
+            TedAction::FixDiagnostic => CodeActionKind::QUICKFIX,
+            TedAction::Refactor => CodeActionKind::REFACTOR_REWRITE,
+            TedAction::Explain | TedAction::AddTests => CodeActionKind::EMPTY,
+        }
+    }
+}
+
+/// Arguments of the `ted.runCodeAction` command
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CodeActionArgs {
+    pub action: TedAction,
+    pub uri: Url,
+    pub range: Range,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub diagnostic: Option<Diagnostic>,
+    /// What to change, for "Refactor…"; clients may prompt the user for it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub instruction: Option<String>,
+}
+
+/// Result of running an action
+#[derive(Debug, Clone, PartialEq)]
+pub enum ActionOutcome {
+    Edit(WorkspaceEdit),
+    Explanation(String),
+}
+
+/// Actions available for a selection and the diagnostics in it
+pub fn code_actions(params: &CodeActionParams) -> Vec<CodeActionOrCommand> {
+    let uri = &params.text_document.uri;
+    let mut actions = Vec::new();
+
+    for diagnostic in &params.context.diagnostics {
+        let message: String = diagnostic.message.lines().next().unwrap_or("").to_string();
+        let title = format!("Ted: Fix \"{}\"", truncate(&message, 60));
+        let args = CodeActionArgs {
+            action: TedAction::FixDiagnostic,
+            uri: uri.clone(),
+            range: diagnostic.range,
+            diagnostic: Some(diagnostic.clone()),
+            instruction: None,
+        };
+        actions.push(code_action(title, args, Some(vec![diagnostic.clone()])));
+    }
+
+    if params.range.start != params.range.end {
+        for action in [TedAction::Explain, TedAction::AddTests, TedAction::Refactor] {
+            let args = CodeActionArgs {
+                action,
+                uri: uri.clone(),
+                range: params.range,
+                diagnostic: None,
+                instruction: None,
+            };
+            actions.push(code_action(action.title().to_string(), args, None));
+        }
+    }
+
+    if let Some(only) = &params.context.only {
+        actions.retain(|action| match action {
+            CodeActionOrCommand::CodeAction(action) => action
+                .kind
+                .as_ref()
+                .is_some_and(|kind| kind_matches(kind, only)),
+            CodeActionOrCommand::Command(_) => false,
+        });
+    }
+
+    actions
+}
+
+fn code_action(
+    title: String,
+    args: CodeActionArgs,
+    diagnostics: Option<Vec<Diagnostic>>,
+) -> CodeActionOrCommand {
+    CodeActionOrCommand::CodeAction(CodeAction {
+        title: title.clone(),
+        kind: Some(args.action.kind()),
+        diagnostics,
+        edit: None,
+        command: Some(Command {
+            title,
+            command: RUN_CODE_ACTION_COMMAND.to_string(),
+            arguments: serde_json::to_value(&args).ok().map(|args| vec![args]),
+        }),
+        is_preferred: None,
+        disabled: None,
+        data: None,
+    })
+}
+
+/// Whether `kind` was requested: `refactor` also covers `refactor.rewrite`
+fn kind_matches(kind: &CodeActionKind, only: &[CodeActionKind]) -> bool {
+    let kind = kind.as_str();
+    !kind.is_empty()
+        && only.iter().any(|requested| {
+            let requested = requested.as_str();
+            kind == requested
+                || kind
+                    .strip_prefix(requested)
+                    .is_some_and(|rest| rest.starts_with('.'))
+        })
+}
+
+/// Run an action through the chat engine, reporting progress to the client
+pub async fn run_code_action(
+    client: &Client,
+    model: &LspModel,
+    doc: &DocumentState,
+    args: &CodeActionArgs,
+    workspace: Option<&Path>,
+    work_done_token: Option<ProgressToken>,
+) -> Result<ActionOutcome> {
+    let provider = model.provider().await?;
+    let request = build_request(model.model(), doc, args, workspace);
+
+    let progress = ProgressReporter::begin(client, work_done_token, args.action.title()).await;
+    let (delta_tx, mut delta_rx) = mpsc::unbounded_channel::<usize>();
+    let forward = async {
+        let mut received = 0;
+        let mut reported = 0;
+        while let Some(chars) = delta_rx.recv().await {
+            received += chars;
+            if received - reported >= PROGRESS_INTERVAL_CHARS {
+                reported = received;
+                progress
+                    .report(format!("Received {} characters", received))
+                    .await;
+            }
+        }
+    };
+    let (result, ()) = tokio::join!(complete(provider, request, delta_tx), forward);
+
+    let text = match result {
+        Ok(text) => text,
+        Err(e) => {
+            progress.end("Failed").await;
+            return Err(e);
+        }
+    };
+    progress.end("Done").await;
+
+    Ok(outcome(doc, args, &text))
+}
+
+/// Forwards the size of each streamed text delta
+struct DeltaObserver {
+    deltas: mpsc::UnboundedSender<usize>,
+}
+
+impl AgentLoopObserver for DeltaObserver {
+    fn on_text_delta(&mut self, text: &str) -> Result<()> {
+        let _ = self.deltas.send(text.chars().count());
+        Ok(())
+    }
+}
+
+/// Stream one completion and return its text.
+///
+/// The engine's observer isn't `Send`, so the request runs on a blocking
+/// thread that drives it to completion.
+async fn complete(
+    provider: Arc<dyn LlmProvider>,
+    request: CompletionRequest,
+    deltas: mpsc::UnboundedSender<usize>,
+) -> Result<String> {
+    let handle = tokio::runtime::Handle::current();
+    let task = tokio::task::spawn_blocking(move || {
+        handle.block_on(async move {
+            let mut observer = DeltaObserver { deltas };
+            let (content, _) =
+                get_response_with_retry(provider.as_ref(), request, true, &[], &mut observer)
+                    .await?;
+            Ok(extract_text_content(&content))
+        })
+    });
+    match task.await {
+        Ok(result) => result,
+        Err(e) => Err(TedError::Lsp(format!("Code action task failed: {}", e))),
+    }
+}
+
+/// Prompt for an action: the selection, the code around it and what to do
+fn build_request(
+    model: &str,
+    doc: &DocumentState,
+    args: &CodeActionArgs,
+    workspace: Option<&Path>,
+) -> CompletionRequest {
+    let lines: Vec<&str> = doc.content.lines().collect();
+    let target = line_range(&doc.content, args.range);
+    let first = target.start.line as usize;
+    let last = (target.end.line as usize).max(first + 1).min(lines.len());
+    let lang = doc.language_id.as_str();
+
+    let path = doc
+        .uri
+        .to_file_path()
+        .ok()
+        .map(
+            |path| match workspace.and_then(|root| path.strip_prefix(root).ok()) {
+                Some(relative) => relative.display().to_string(),
+                None => path.display().to_string(),
+            },
+        )
+        .unwrap_or_else(|| doc.uri.to_string());
+
+    let before_start = first.saturating_sub(CONTEXT_LINES);
+    let after_end = (last + CONTEXT_LINES).min(lines.len());
+    let before = lines
+        .get(before_start..first)
+        .unwrap_or_default()
+        .join("\n");
+    let selected = lines.get(first..last).unwrap_or_default().join("\n");
+    let after = lines.get(last..after_end).unwrap_or_default().join("\n");
+
+    let mut prompt = format!("File: {} ({})\n\n", path, lang);
+    if !before.is_empty() {
+        prompt.push_str(&format!(
+            "Code before the selection:\n```{}\n{}\n```\n\n",
+            lang, before
+        ));
+    }
+    prompt.push_str(&format!(
+        "Selected lines {}-{}:\n```{}\n{}\n```\n\n",
+        first + 1,
+        last,
+        lang,
+        selected
+    ));
+    if !after.is_empty() {
+        prompt.push_str(&format!(
+            "Code after the selection:\n```{}\n{}\n```\n\n",
+            lang, after
+        ));
+    }
+
+    let replace = "Reply with only the replacement for the selected lines, in a single \
+                   fenced code block, keeping the surrounding indentation.";
+    let task = match args.action {
+        TedAction::Explain => "Explain what the selected code does and how, including \
+                               anything surprising. Be concise; don't repeat the code."
+            .to_string(),
+        TedAction::FixDiagnostic => {
+            let diagnostic = match &args.diagnostic {
+                Some(diagnostic) => format!(
+                    "{}{} (line {})",
+                    diagnostic
+                        .source
+                        .as_deref()
+                        .map(|source| format!("[{}] ", source))
+                        .unwrap_or_default(),
+                    diagnostic.message,
+                    diagnostic.range.start.line + 1
+                ),
+                None => "the problem in the selected lines".to_string(),
+            };
+            format!("Fix this diagnostic: {}\n\n{}", diagnostic, replace)
+        }
+        TedAction::AddTests => "Write unit tests for the selected code, following this \
+                                file's language and test conventions. They will be appended \
+                                to the end of the file. Reply with only the test code, in a \
+                                single fenced code block."
+            .to_string(),
+        TedAction::Refactor => format!(
+            "Refactor the selected lines: {}. Keep the behaviour unchanged.\n\n{}",
+            args.instruction
+                .as_deref()
+                .map(str::trim)
+                .filter(|instruction| !instruction.is_empty())
+                .unwrap_or(DEFAULT_REFACTOR_INSTRUCTION),
+            replace
+        ),
+    };
+    prompt.push_str(&task);
+
+    CompletionRequest::new(model, vec![Message::user(prompt)])
+        .with_system(SYSTEM_PROMPT)
+        .with_max_tokens(MAX_RESPONSE_TOKENS)
+        .with_temperature(0.2)
+}
+
+/// Turn the model's reply into an edit of the document
+fn outcome(doc: &DocumentState, args: &CodeActionArgs, reply: &str) -> ActionOutcome {
+    let edit = match args.action {
+        TedAction::Explain => return ActionOutcome::Explanation(reply.trim().to_string()),
+        TedAction::FixDiagnostic | TedAction::Refactor => {
+            let mut new_text = extract_code(reply);
+            new_text.push('\n');
+            TextEdit {
+                range: line_range(&doc.content, args.range),
+                new_text,
+            }
+        }
+        TedAction::AddTests => {
+            let end = document_end(&doc.content);
+            let separator = if doc.content.ends_with('\n') || doc.content.is_empty() {
+                "\n"
+            } else {
+                "\n\n"
+            };
+            TextEdit {
+                range: Range { start: end, end },
+                new_text: format!("{}{}\n", separator, extract_code(reply)),
+            }
+        }
+    };
+
+    ActionOutcome::Edit(versioned_edit(doc, vec![edit]))
+}
+
+/// A workspace edit pinned to the document version it was computed from, so
+/// the client rejects it if the user has typed since
+pub(super) fn versioned_edit(doc: &DocumentState, edits: Vec<TextEdit>) -> WorkspaceEdit {
+    WorkspaceEdit {
+        document_changes: Some(DocumentChanges::Edits(vec![TextDocumentEdit {
+            text_document: OptionalVersionedTextDocumentIdentifier {
+                uri: doc.uri.clone(),
+                version: Some(doc.version),
+            },
+            edits: edits.into_iter().map(OneOf::Left).collect(),
+        }])),
+        ..Default::default()
+    }
+}
+
+/// The whole lines `range` touches, ending at the start of the next line
+fn line_range(content: &str, range: Range) -> Range {
+    let end_line = if range.end.character == 0 && range.end.line > range.start.line {
+        range.end.line
+    } else {
+        range.end.line + 1
+    };
+    let line_count = content.lines().count() as u32;
+    let end = if end_line < line_count || (end_line == line_count && content.ends_with('\n')) {
+        Position::new(end_line, 0)
+    } else {
+        document_end(content)
+    };
+    Range {
+        start: Position::new(range.start.line.min(end.line), 0),
+        end,
+    }
+}
+
+pub(super) fn document_end(content: &str) -> Position {
+    let line = content.matches('\n').count() as u32;
+    let last = content.rsplit('\n').next().unwrap_or("");
+    Position::new(line, last.encode_utf16().count() as u32)
+}
+
+/// Contents of the first fenced code block, or the whole reply
+fn extract_code(reply: &str) -> String {
+    let Some(open) = reply.find("```") else {
+        return reply.trim().to_string();
+    };
+    let after_fence = &reply[open + 3..];
+    let Some(body_start) = after_fence.find('\n') else {
+        return reply.trim().to_string();
+    };
+    let body = &after_fence[body_start + 1..];
+    let body = match body.find("```") {
+        Some(close) => &body[..close],
+        None => body,
+    };
+    body.trim_end().to_string()
+}
+
+fn truncate(text: &str, max_chars: usize) -> String {
+    if text.chars().count() <= max_chars {
+        text.to_string()
+    } else {
+        let mut truncated: String = text.chars().take(max_chars - 1).collect();
+        truncated.push('…');
+        truncated
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::llm::MockProvider;
+
+    fn doc(content: &str) -> DocumentState {
+        DocumentState {
+            uri: Url::parse("file:///project/src/lib.rs").unwrap(),
+            content: content.to_string(),
+            version: 1,
+            language_id: "rust".to_string(),
+        }
+    }
+
+    fn params(range: Range, diagnostics: Vec<Diagnostic>) -> CodeActionParams {
+        CodeActionParams {
+            text_document: TextDocumentIdentifier::new(
+                Url::parse("file:///project/src/lib.rs").unwrap(),
+            ),
+            range,
+            context: CodeActionContext {
+                diagnostics,
+                only: None,
+                trigger_kind: None,
+            },
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        }
+    }
+
+    fn args(action: TedAction, range: Range) -> CodeActionArgs {
+        CodeActionArgs {
+            action,
+            uri: Url::parse("file:///project/src/lib.rs").unwrap(),
+            range,
+            diagnostic: None,
+            instruction: None,
+        }
+    }
+
+    /// The text edits of a single-document versioned edit, with its version
+    fn text_edits(edit: &WorkspaceEdit) -> (Option<i32>, Vec<TextEdit>) {
+        let Some(DocumentChanges::Edits(changes)) = &edit.document_changes else {
+            panic!("expected versioned document edits");
+        };
+        let edits = changes[0]
+            .edits
+            .iter()
+            .map(|edit| match edit {
+                OneOf::Left(edit) => edit.clone(),
+                OneOf::Right(annotated) => annotated.text_edit.clone(),
+            })
+            .collect();
+        (changes[0].text_document.version, edits)
+    }
+
+    fn titles(actions: &[CodeActionOrCommand]) -> Vec<String> {
+        actions
+            .iter()
+            .map(|action| match action {
+                CodeActionOrCommand::CodeAction(action) => action.title.clone(),
+                CodeActionOrCommand::Command(command) => command.title.clone(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_code_actions_for_selection_and_diagnostics() {
+        let selection = Range::new(Position::new(1, 0), Position::new(3, 1));
+        let diagnostic = Diagnostic::new_simple(
+            Range::new(Position::new(2, 4), Position::new(2, 9)),
+            "mismatched types\nexpected u32".to_string(),
+        );
+        let actions = code_actions(&params(selection, vec![diagnostic.clone()]));
+        assert_eq!(
+            titles(&actions),
+            vec![
+                "Ted: Fix \"mismatched types\"",
+                "Ted: Explain",
+                "Ted: Add tests",
+                "Ted: Refactor…",
+            ]
+        );
+
+        let CodeActionOrCommand::CodeAction(fix) = &actions[0] else {
+            panic!("expected a code action");
+        };
+        assert_eq!(fix.kind, Some(CodeActionKind::QUICKFIX));
+        let command = fix.command.as_ref().unwrap();
+        assert_eq!(command.command, RUN_CODE_ACTION_COMMAND);
+        let args: CodeActionArgs =
+            serde_json::from_value(command.arguments.as_ref().unwrap()[0].clone()).unwrap();
+        assert_eq!(args.action, TedAction::FixDiagnostic);
+        assert_eq!(args.range, diagnostic.range);
+        assert_eq!(args.diagnostic, Some(diagnostic));
+    }
+
+    #[test]
+    fn test_code_actions_empty_selection_and_only_filter() {
+        let cursor = Range::new(Position::new(1, 0), Position::new(1, 0));
+        assert!(code_actions(&params(cursor, Vec::new())).is_empty());
+
+        let selection = Range::new(Position::new(1, 0), Position::new(3, 1));
+        let mut params = params(selection, Vec::new());
+        params.context.only = Some(vec![CodeActionKind::REFACTOR]);
+        assert_eq!(titles(&code_actions(&params)), vec!["Ted: Refactor…"]);
+    }
+
+    #[test]
+    fn test_code_action_args_serialization() {
+        let value = serde_json::to_value(args(TedAction::AddTests, Range::default())).unwrap();
+        assert_eq!(value["action"], "add_tests");
+        assert!(value.get("diagnostic").is_none());
+    }
+
+    #[test]
+    fn test_build_request_includes_selection_and_instruction() {
+        let doc = doc("use std::fmt;\n\nfn add(a: u32, b: u32) -> u32 {\n    a + b\n}\n");
+        let mut args = args(
+            TedAction::Refactor,
+            Range::new(Position::new(2, 0), Position::new(4, 1)),
+        );
+        args.instruction = Some("use checked_add".to_string());
+
+        let request = build_request("mock-model", &doc, &args, Some(Path::new("/project")));
+        let prompt = request.messages[0].text().unwrap();
+        assert!(prompt.starts_with("File: src/lib.rs (rust)"));
+        assert!(prompt.contains("Selected lines 3-5:\n```rust\nfn add"));
+        assert!(prompt.contains("Code before the selection:\n```rust\nuse std::fmt;"));
+        assert!(prompt.contains("Refactor the selected lines: use checked_add."));
+        assert_eq!(request.system.as_deref(), Some(SYSTEM_PROMPT));
+    }
+
+    #[test]
+    fn test_outcome_replaces_whole_lines() {
+        let doc = doc("fn a() {}\nfn b() { 1 + }\nfn c() {}\n");
+        let args = args(
+            TedAction::FixDiagnostic,
+            Range::new(Position::new(1, 9), Position::new(1, 14)),
+        );
+        let ActionOutcome::Edit(edit) = outcome(
+            &doc,
+            &args,
+            "Here you go:\n```rust\nfn b() { 1 + 1 }\n```\nDone.",
+        ) else {
+            panic!("expected an edit");
+        };
+        assert!(edit.changes.is_none());
+        let (version, edits) = text_edits(&edit);
+        assert_eq!(version, Some(doc.version));
+        assert_eq!(
+            edits[0].range,
+            Range::new(Position::new(1, 0), Position::new(2, 0))
+        );
+        assert_eq!(edits[0].new_text, "fn b() { 1 + 1 }\n");
+    }
+
+    #[test]
+    fn test_outcome_appends_tests_and_explains() {
+        let doc = doc("fn a() {}");
+        let args_tests = args(TedAction::AddTests, Range::default());
+        let ActionOutcome::Edit(edit) =
+            outcome(&doc, &args_tests, "```rust\n#[test]\nfn t() {}\n```")
+        else {
+            panic!("expected an edit");
+        };
+        let (version, edits) = text_edits(&edit);
+        assert_eq!(version, Some(doc.version));
+        assert_eq!(
+            edits[0].range,
+            Range::new(Position::new(0, 9), Position::new(0, 9))
+        );
+        assert_eq!(edits[0].new_text, "\n\n#[test]\nfn t() {}\n");
+
+        let explained = outcome(
+            &doc,
+            &args(TedAction::Explain, Range::default()),
+            " Does a. ",
+        );
+        assert_eq!(explained, ActionOutcome::Explanation("Does a.".to_string()));
+    }
+
+    #[test]
+    fn test_line_range_at_end_without_newline() {
+        let content = "a\nb";
+        assert_eq!(
+            line_range(
+                content,
+                Range::new(Position::new(1, 0), Position::new(1, 1))
+            ),
+            Range::new(Position::new(1, 0), Position::new(1, 1))
+        );
+        // A selection ending at column 0 stops before that line
+        assert_eq!(
+            line_range(
+                content,
+                Range::new(Position::new(0, 0), Position::new(1, 0))
+            ),
+            Range::new(Position::new(0, 0), Position::new(1, 0))
+        );
+    }
+
+    #[test]
+    fn test_extract_code() {
+        assert_eq!(extract_code("```\nx\n```"), "x");
+        assert_eq!(
+            extract_code("```python\nx = 1\ny = 2\n```\ntrailing"),
+            "x = 1\ny = 2"
+        );
+        assert_eq!(extract_code("  plain  "), "plain");
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_complete_streams_through_engine() {
+        let provider = Arc::new(MockProvider::new().with_response("```rust\nfn b() {}\n```"));
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let request = CompletionRequest::new("mock-model", vec![Message::user("hi")]);
+
+        let text = complete(provider, request, tx).await.unwrap();
+        assert_eq!(text, "```rust\nfn b() {}\n```");
+
+        let mut received = 0;
+        while let Some(chars) = rx.recv().await {
+            received += chars;
+        }
+        assert_eq!(received, text.chars().count());
+    }
+}
@@ -49,7 +49,7 @@ pub async fn provide_definition(
 }
 
 /// Get the word at a given column position
-fn get_word_at_position(line: &str, col: usize) -> String {
+pub(super) fn get_word_at_position(line: &str, col: usize) -> String {
     let chars: Vec<char> = line.chars().collect();
     if col >= chars.len() {
         return String::new();
@@ -74,7 +74,7 @@ fn get_word_at_position(line: &str, col: usize) -> String {
     chars[start..end].iter().collect()
 }
 
-fn is_identifier_char(c: char) -> bool {
+pub(super) fn is_identifier_char(c: char) -> bool {
     c.is_alphanumeric() || c == '_' || c == '$'
 }
 
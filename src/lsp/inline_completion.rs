@@ -0,0 +1,456 @@
+// SPDX-License-Identifier: AGPL-3.0-or-later
+// Copyright (C) 2025 Blackman Artificial Intelligence Technologies Inc.
+
+//! Inline (ghost text) completions
+//!
+//! `textDocument/inlineCompletion` is newer than lsp-types 0.94, so its
+//! request types live here, the method is registered as a custom method and
+//! the capability is registered dynamically once the client is initialized.
+//!
+//! Completions come from the provider's fill-in-the-middle endpoint (the
+//! local llama-server's `/infill`), or from a short chat completion when it
+//! has none. Automatic requests are debounced, a newer request supersedes
+//! one still running, and every completion has to arrive within the
+//! hardware tier's latency budget.
+
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tokio::sync::{watch, OnceCell};
+use tower_lsp::lsp_types::*;
+
+use super::model::LspModel;
+use crate::chat::agent::extract_text_content;
+use crate::error::Result;
+use crate::hardware::{HardwareTier, SystemProfile};
+use crate::llm::message::Message;
+use crate::llm::provider::{CompletionRequest, FimRequest, LlmProvider};
+
+pub const INLINE_COMPLETION_METHOD: &str = "textDocument/inlineCompletion";
+
+/// `InlineCompletionTriggerKind.Automatic`
+const TRIGGER_AUTOMATIC: u8 = 2;
+
+/// Quiet period before an automatic request goes to the model
+const DEBOUNCE: Duration = Duration::from_millis(150);
+const MAX_PREFIX_CHARS: usize = 6000;
+const MAX_SUFFIX_CHARS: usize = 2000;
+pub const MAX_RELATED_FILES: usize = 3;
+const MAX_RELATED_CHARS: usize = 2000;
+const MAX_COMPLETION_TOKENS: u32 = 128;
+const MAX_COMPLETION_LINES: usize = 16;
+
+const CURSOR: &str = "<CURSOR>";
+const CHAT_SYSTEM_PROMPT: &str = "You are a code completion engine. Reply with only the \
+text to insert at <CURSOR>: no explanations, no code fences, and nothing that repeats the \
+code before or after the cursor.";
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InlineCompletionParams {
+    pub text_document: TextDocumentIdentifier,
+    pub position: Position,
+    #[serde(default)]
+    pub context: Option<InlineCompletionContext>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InlineCompletionContext {
+    /// 1 = invoked explicitly, 2 = automatic while typing
+    pub trigger_kind: u8,
+}
+
+impl InlineCompletionParams {
+    /// Requests without a context count as automatic
+    pub fn is_automatic(&self) -> bool {
+        self.context
+            .as_ref()
+            .is_none_or(|context| context.trigger_kind == TRIGGER_AUTOMATIC)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct InlineCompletionList {
+    pub items: Vec<InlineCompletionItem>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InlineCompletionItem {
+    pub insert_text: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub range: Option<Range>,
+}
+
+/// A claimed request slot, superseded by the next call to `begin`
+pub struct Ticket {
+    id: u64,
+    latest: watch::Receiver<u64>,
+}
+
+impl Ticket {
+    async fn superseded(&mut self) {
+        let id = self.id;
+        // The sender lives as long as the completer, so this only returns
+        // once a newer request arrived
+        let _ = self.latest.wait_for(|latest| *latest != id).await;
+    }
+}
+
+/// Serves inline completions one at a time
+pub struct InlineCompleter {
+    model: Arc<LspModel>,
+    latest: watch::Sender<u64>,
+    tier: OnceCell<HardwareTier>,
+}
+
+impl InlineCompleter {
+    pub fn new(model: Arc<LspModel>) -> Self {
+        Self {
+            model,
+            latest: watch::channel(0).0,
+            tier: OnceCell::new(),
+        }
+    }
+
+    /// Claim a slot for a new request, superseding any earlier one.
+    ///
+    /// Automatic requests wait out the debounce first and get `None` when
+    /// another request arrives meanwhile.
+    pub async fn begin(&self, automatic: bool) -> Option<Ticket> {
+        self.latest.send_modify(|latest| *latest += 1);
+        let mut ticket = Ticket {
+            id: *self.latest.borrow(),
+            latest: self.latest.subscribe(),
+        };
+        if automatic {
+            tokio::select! {
+                _ = tokio::time::sleep(DEBOUNCE) => {}
+                _ = ticket.superseded() => return None,
+            }
+        }
+        Some(ticket)
+    }
+
+    /// Hardware tier the latency budget comes from; cloud providers don't
+    /// depend on local hardware
+    pub async fn tier(&self) -> HardwareTier {
+        *self
+            .tier
+            .get_or_init(|| async {
+                if self.model.provider_name() != "local" {
+                    return HardwareTier::Cloud;
+                }
+                tokio::task::spawn_blocking(SystemProfile::detect)
+                    .await
+                    .ok()
+                    .and_then(|profile| profile.ok())
+                    .map_or(HardwareTier::Small, |profile| profile.tier)
+            })
+            .await
+    }
+
+    /// Complete at the cursor, or `None` when superseded, over budget or
+    /// without a suggestion
+    pub async fn complete(&self, mut ticket: Ticket, request: FimRequest) -> Option<String> {
+        let budget = Duration::from_millis(self.tier().await.inline_completion_budget_ms());
+        let suffix = request.suffix.clone();
+        let request = FimRequest {
+            time_limit: Some(budget),
+            ..request
+        };
+        let fetch = async {
+            let provider = self.model.provider().await?;
+            fetch(provider.as_ref(), self.model.model(), request).await
+        };
+
+        tokio::select! {
+            _ = ticket.superseded() => {
+                tracing::debug!("Inline completion superseded");
+                None
+            }
+            result = tokio::time::timeout(budget, fetch) => match result {
+                Ok(Ok(text)) => clean_completion(&text, &suffix),
+                Ok(Err(e)) => {
+                    tracing::debug!("Inline completion failed: {}", e);
+                    None
+                }
+                Err(_) => {
+                    tracing::debug!("Inline completion exceeded {:?} budget", budget);
+                    None
+                }
+            },
+        }
+    }
+}
+
+/// Request for the text around `position`, with related files as context
+pub fn fim_request(
+    content: &str,
+    position: Position,
+    related: Vec<(PathBuf, String)>,
+) -> FimRequest {
+    let offset = byte_offset(content, position);
+    let (before, after) = content.split_at(offset);
+
+    let prefix_start = before
+        .char_indices()
+        .rev()
+        .nth(MAX_PREFIX_CHARS.saturating_sub(1))
+        .map_or(0, |(index, _)| index);
+    let suffix: String = after.chars().take(MAX_SUFFIX_CHARS).collect();
+
+    FimRequest {
+        prefix: before[prefix_start..].to_string(),
+        suffix,
+        extra: related
+            .into_iter()
+            .map(|(path, text)| {
+                let text: String = text.chars().take(MAX_RELATED_CHARS).collect();
+                (path.display().to_string(), text)
+            })
+            .collect(),
+        max_tokens: MAX_COMPLETION_TOKENS,
+        temperature: 0.1,
+        time_limit: None,
+    }
+}
+
+/// Native fill-in-the-middle when the provider has it, else a chat prompt
+async fn fetch(provider: &dyn LlmProvider, model: &str, request: FimRequest) -> Result<String> {
+    if let Some(text) = provider.fill_in_middle(request.clone()).await? {
+        return Ok(text);
+    }
+
+    let response = provider
+        .complete(
+            CompletionRequest::new(model, vec![Message::user(chat_prompt(&request))])
+                .with_system(CHAT_SYSTEM_PROMPT)
+                .with_max_tokens(request.max_tokens)
+                .with_temperature(request.temperature),
+        )
+        .await?;
+    Ok(strip_fences(&extract_text_content(&response.content)))
+}
+
+fn chat_prompt(request: &FimRequest) -> String {
+    let mut prompt = String::new();
+    if !request.extra.is_empty() {
+        prompt.push_str("Related files:\n");
+        for (path, text) in &request.extra {
+            prompt.push_str(&format!("--- {} ---\n{}\n", path, text));
+        }
+        prompt.push('\n');
+    }
+    prompt.push_str(&format!(
+        "Complete the code at {}:\n{}{}{}",
+        CURSOR, request.prefix, CURSOR, request.suffix
+    ));
+    prompt
+}
+
+/// Chat models sometimes fence their answer anyway
+fn strip_fences(text: &str) -> String {
+    let trimmed = text.trim();
+    let Some(rest) = trimmed.strip_prefix("```") else {
+        return text.to_string();
+    };
+    let body = rest.split_once('\n').map_or("", |(_, body)| body);
+    body.strip_suffix("```").unwrap_or(body).to_string()
+}
+
+/// Drop trailing whitespace, overlong suggestions and text that only repeats
+/// what follows the cursor
+fn clean_completion(text: &str, suffix: &str) -> Option<String> {
+    let text = text.trim_end();
+    if text.trim().is_empty() {
+        return None;
+    }
+    let text: String = text
+        .lines()
+        .take(MAX_COMPLETION_LINES)
+        .collect::<Vec<_>>()
+        .join("\n");
+    let rest_of_line = suffix.lines().next().unwrap_or("").trim();
+    if !rest_of_line.is_empty() && text.trim() == rest_of_line {
+        return None;
+    }
+    Some(text)
+}
+
+/// Byte offset of an LSP position (UTF-16 columns), clamped to the content
+fn byte_offset(content: &str, position: Position) -> usize {
+    let mut offset = 0;
+    for (index, line) in content.split_inclusive('\n').enumerate() {
+        if index == position.line as usize {
+            let mut units = 0;
+            for (byte, ch) in line.char_indices() {
+                if units >= position.character as usize || ch == '\n' {
+                    return offset + byte;
+                }
+                units += ch.len_utf16();
+            }
+            return offset + line.len();
+        }
+        offset += line.len();
+    }
+    content.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::llm::MockProvider;
+
+    fn completer(provider: MockProvider) -> InlineCompleter {
+        let completer = InlineCompleter::new(Arc::new(LspModel::with_provider(
+            Arc::new(provider),
+            "mock-model",
+        )));
+        completer.tier.set(HardwareTier::Cloud).unwrap();
+        completer
+    }
+
+    #[test]
+    fn test_params_deserialize() {
+        let params: InlineCompletionParams = serde_json::from_value(serde_json::json!({
+            "textDocument": {"uri": "file:///project/src/lib.rs"},
+            "position": {"line": 3, "character": 8},
+            "context": {"triggerKind": 1}
+        }))
+        .unwrap();
+        assert_eq!(params.position, Position::new(3, 8));
+        assert!(!params.is_automatic());
+
+        let params: InlineCompletionParams = serde_json::from_value(serde_json::json!({
+            "textDocument": {"uri": "file:///project/src/lib.rs"},
+            "position": {"line": 0, "character": 0}
+        }))
+        .unwrap();
+        assert!(params.is_automatic());
+    }
+
+    #[test]
+    fn test_list_serialization() {
+        let list = InlineCompletionList {
+            items: vec![InlineCompletionItem {
+                insert_text: "a + b".to_string(),
+                range: None,
+            }],
+        };
+        assert_eq!(
+            serde_json::to_value(list).unwrap(),
+            serde_json::json!({"items": [{"insertText": "a + b"}]})
+        );
+    }
+
+    #[test]
+    fn test_fim_request_splits_at_utf16_position() {
+        let content = "let s = \"é😀\";\nlet t = 1;\n";
+        // The cursor sits after the emoji, which is two UTF-16 units
+        let request = fim_request(content, Position::new(0, 12), Vec::new());
+        assert_eq!(request.prefix, "let s = \"é😀");
+        assert_eq!(request.suffix, "\";\nlet t = 1;\n");
+
+        let request = fim_request(
+            content,
+            Position::new(1, 0),
+            vec![(PathBuf::from("src/a.rs"), "x".repeat(5000))],
+        );
+        assert_eq!(request.suffix, "let t = 1;\n");
+        assert_eq!(request.extra[0].0, "src/a.rs");
+        assert_eq!(request.extra[0].1.len(), MAX_RELATED_CHARS);
+    }
+
+    #[test]
+    fn test_clean_completion() {
+        assert_eq!(
+            clean_completion("a + b   \n\n", "\n}"),
+            Some("a + b".to_string())
+        );
+        assert_eq!(clean_completion("  \n", ""), None);
+        assert_eq!(clean_completion(");", ");\n"), None);
+        let long = "x\n".repeat(40);
+        assert_eq!(
+            clean_completion(&long, "").unwrap().lines().count(),
+            MAX_COMPLETION_LINES
+        );
+    }
+
+    #[test]
+    fn test_strip_fences() {
+        assert_eq!(strip_fences("```rust\na + b\n```"), "a + b\n");
+        assert_eq!(strip_fences("    a + b"), "    a + b");
+    }
+
+    #[tokio::test]
+    async fn test_complete_prefers_fill_in_middle() {
+        let provider = MockProvider::new().with_fim_response("a + b");
+        let completer = completer(provider.clone());
+
+        let ticket = completer.begin(false).await.unwrap();
+        let request = fim_request(
+            "fn add() -> u32 {\n    \n}",
+            Position::new(1, 4),
+            Vec::new(),
+        );
+        let text = completer.complete(ticket, request).await;
+
+        assert_eq!(text.as_deref(), Some("a + b"));
+        assert_eq!(provider.call_count(), 0);
+        let sent = &provider.recorded_fim_requests()[0];
+        assert_eq!(sent.prefix, "fn add() -> u32 {\n    ");
+        assert_eq!(sent.time_limit, Some(Duration::from_millis(1500)));
+    }
+
+    #[tokio::test]
+    async fn test_complete_falls_back_to_chat() {
+        let provider = MockProvider::new().with_response("```\nb)\n```");
+        let completer = completer(provider.clone());
+
+        let ticket = completer.begin(false).await.unwrap();
+        let text = completer
+            .complete(
+                ticket,
+                fim_request(
+                    "max(a, ",
+                    Position::new(0, 7),
+                    vec![(PathBuf::from("src/a.rs"), "fn max() {}".to_string())],
+                ),
+            )
+            .await;
+
+        assert_eq!(text.as_deref(), Some("b)"));
+        let request = provider.last_request().unwrap();
+        let prompt = request.messages[0].text().unwrap();
+        assert!(prompt.contains("--- src/a.rs ---\nfn max() {}"));
+        assert!(prompt.ends_with("max(a, <CURSOR>"));
+        assert_eq!(request.system.as_deref(), Some(CHAT_SYSTEM_PROMPT));
+    }
+
+    #[tokio::test]
+    async fn test_newer_request_supersedes_debounced_one() {
+        let completer = Arc::new(completer(MockProvider::new().with_fim_response("x")));
+
+        let first = tokio::spawn({
+            let completer = completer.clone();
+            async move { completer.begin(true).await.is_some() }
+        });
+        tokio::task::yield_now().await;
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        let second = completer.begin(true).await;
+
+        assert!(!first.await.unwrap());
+        let ticket = second.unwrap();
+        assert_eq!(
+            completer
+                .complete(ticket, fim_request("", Position::new(0, 0), Vec::new()))
+                .await
+                .as_deref(),
+            Some("x")
+        );
+    }
+}
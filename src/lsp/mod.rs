@@ -6,14 +6,24 @@
 //! This module provides LSP support for IDE integration, including:
 //! - Autocomplete suggestions based on project context
 //! - Go-to-definition using file indexing
+//! - References, document symbols and workspace symbols from the indexer
 //! - Hover information
+//! - Code actions that explain, fix, test or refactor a selection with Ted
+//! - Inline (ghost text) completions via fill-in-the-middle
+//! - AI review of saved documents, published as diagnostics with quick fixes
 //! - Diagnostics (via linting)
 
 mod capabilities;
+mod code_actions;
 mod completion;
 mod definition;
 mod hover;
+mod inline_completion;
+mod model;
+mod progress;
+mod review;
 mod server;
+mod symbols;
 
 pub use server::TedLanguageServer;
 
@@ -24,7 +34,12 @@ use tower_lsp::{LspService, Server};
 pub async fn start_server() -> anyhow::Result<()> {
     tracing::info!("Starting Ted LSP server...");
 
-    let (service, socket) = LspService::new(TedLanguageServer::new);
+    let (service, socket) = LspService::build(TedLanguageServer::new)
+        .custom_method(
+            inline_completion::INLINE_COMPLETION_METHOD,
+            TedLanguageServer::inline_completion,
+        )
+        .finish();
 
     let stdin = stdin();
     let stdout = stdout();
@@ -0,0 +1,64 @@
+// SPDX-License-Identifier: AGPL-3.0-or-later
+// Copyright (C) 2025 Blackman Artificial Intelligence Technologies Inc.
+
+//! LLM provider shared by the server's AI features
+//!
+//! Created on first use, so `ted lsp` starts (and serves navigation) without
+//! credentials.
+
+use std::sync::Arc;
+
+use tokio::sync::OnceCell;
+
+use crate::config::Settings;
+use crate::error::Result;
+use crate::llm::factory::ProviderFactory;
+use crate::llm::provider::LlmProvider;
+
+/// Default provider and model from the user's settings
+pub struct LspModel {
+    settings: Settings,
+    provider_name: String,
+    model: String,
+    provider: OnceCell<Arc<dyn LlmProvider>>,
+}
+
+impl LspModel {
+    /// Model using the default provider and model from `settings`
+    pub fn from_settings(settings: Settings) -> Self {
+        let provider_name = ProviderFactory::resolve_provider_name(None, &settings);
+        let model = ProviderFactory::default_model(&provider_name, &settings);
+        Self {
+            settings,
+            provider_name,
+            model,
+            provider: OnceCell::new(),
+        }
+    }
+
+    /// Model using an already created provider
+    #[cfg(test)]
+    pub fn with_provider(provider: Arc<dyn LlmProvider>, model: impl Into<String>) -> Self {
+        Self {
+            provider_name: provider.name().to_string(),
+            settings: Settings::default(),
+            model: model.into(),
+            provider: OnceCell::new_with(Some(provider)),
+        }
+    }
+
+    pub fn provider_name(&self) -> &str {
+        &self.provider_name
+    }
+
+    pub fn model(&self) -> &str {
+        &self.model
+    }
+
+    pub async fn provider(&self) -> Result<Arc<dyn LlmProvider>> {
+        self.provider
+            .get_or_try_init(|| ProviderFactory::create(&self.provider_name, &self.settings, false))
+            .await
+            .cloned()
+    }
+}
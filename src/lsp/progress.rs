@@ -0,0 +1,77 @@
+// SPDX-License-Identifier: AGPL-3.0-or-later
+// Copyright (C) 2025 Blackman Artificial Intelligence Technologies Inc.
+
+//! `$/progress` reporting for long-running requests
+
+use tower_lsp::lsp_types::notification::Progress;
+use tower_lsp::lsp_types::request::WorkDoneProgressCreate;
+use tower_lsp::lsp_types::*;
+use tower_lsp::Client;
+
+/// Work-done progress for one request.
+///
+/// Uses the token the client sent with the request, or asks the client to
+/// create one. Clients that support neither simply get no progress.
+pub struct ProgressReporter {
+    client: Client,
+    token: Option<ProgressToken>,
+}
+
+impl ProgressReporter {
+    /// Start reporting under `title`
+    pub async fn begin(client: &Client, token: Option<ProgressToken>, title: &str) -> Self {
+        let token = match token {
+            Some(token) => Some(token),
+            None => {
+                let token = ProgressToken::String(format!("ted-{}", uuid::Uuid::new_v4()));
+                client
+                    .send_request::<WorkDoneProgressCreate>(WorkDoneProgressCreateParams {
+                        token: token.clone(),
+                    })
+                    .await
+                    .ok()
+                    .map(|()| token)
+            }
+        };
+        let reporter = Self {
+            client: client.clone(),
+            token,
+        };
+        reporter
+            .send(WorkDoneProgress::Begin(WorkDoneProgressBegin {
+                title: title.to_string(),
+                cancellable: Some(false),
+                message: None,
+                percentage: None,
+            }))
+            .await;
+        reporter
+    }
+
+    pub async fn report(&self, message: impl Into<String>) {
+        self.send(WorkDoneProgress::Report(WorkDoneProgressReport {
+            cancellable: Some(false),
+            message: Some(message.into()),
+            percentage: None,
+        }))
+        .await;
+    }
+
+    pub async fn end(self, message: impl Into<String>) {
+        self.send(WorkDoneProgress::End(WorkDoneProgressEnd {
+            message: Some(message.into()),
+        }))
+        .await;
+    }
+
+    async fn send(&self, progress: WorkDoneProgress) {
+        if let Some(token) = &self.token {
+            self.client
+                .send_notification::<Progress>(ProgressParams {
+                    token: token.clone(),
+                    value: ProgressParamsValue::WorkDone(progress),
+                })
+                .await;
+        }
+    }
+}
@@ -0,0 +1,767 @@
+// SPDX-License-Identifier: AGPL-3.0-or-later
+// Copyright (C) 2025 Blackman Artificial Intelligence Technologies Inc.
+
+//! AI review of saved documents
+//!
+//! On save, the lines that changed since the document's last review are sent
+//! to the default model with the `code-reviewer` cap. Findings are published
+//! as diagnostics; those with a suggested replacement carry it as a
+//! `TextEdit` in `Diagnostic::data` and are offered as quick fixes.
+//!
+//! Findings outside the changed lines are carried over, results are cached
+//! by content hash, and reviews are rate-limited per document and per hour.
+
+use std::collections::{HashMap, VecDeque};
+use std::path::Path;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use difflib::sequencematcher::SequenceMatcher;
+use serde::Deserialize;
+use tower_lsp::lsp_types::*;
+
+use super::code_actions::{document_end, versioned_edit};
+use super::model::LspModel;
+use super::server::DocumentState;
+use crate::caps::render::render_system_prompt;
+use crate::caps::{CapLoader, CapResolver};
+use crate::chat::agent::extract_text_content;
+use crate::config::{LspReviewConfig, Settings};
+use crate::error::{Result, TedError};
+use crate::llm::message::Message;
+use crate::llm::provider::CompletionRequest;
+use crate::tools::read_tracker::hash_content;
+
+/// `Diagnostic::source` of review findings
+pub const REVIEW_SOURCE: &str = "ted-review";
+
+const REVIEW_CAP: &str = "code-reviewer";
+/// Changed lines reviewed per save
+const MAX_REVIEW_LINES: usize = 300;
+/// Unchanged lines shown around the reviewed ones
+const CONTEXT_LINES: usize = 20;
+const MAX_RESPONSE_TOKENS: u32 = 2048;
+const MAX_CACHED_REVIEWS: usize = 128;
+
+const FALLBACK_SYSTEM_PROMPT: &str = "You are a careful code reviewer. Point out bugs, \
+security problems and clear maintainability issues; ignore style nits.";
+
+const INSTRUCTIONS: &str = "Report only real problems in the lines to review, not in the \
+surrounding code. Reply with only a JSON array, `[]` if there is nothing to report. Each \
+item is an object with `line` (first affected line number), optional `end_line`, \
+`severity` (\"error\", \"warning\", \"info\" or \"hint\"), a one-sentence `message` and, \
+when there is a concrete fix, `suggestion`: the full replacement text for lines `line` \
+to `end_line`, with their indentation.";
+
+/// One finding as the model reports it
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+struct Finding {
+    line: u32,
+    #[serde(default)]
+    end_line: Option<u32>,
+    #[serde(default)]
+    severity: String,
+    message: String,
+    #[serde(default)]
+    suggestion: Option<String>,
+}
+
+/// System prompt and sampling settings from the `code-reviewer` cap
+struct ReviewPrompt {
+    system: String,
+    temperature: f32,
+    max_tokens: u32,
+}
+
+/// Content and findings of a document's last review
+#[derive(Debug, Clone)]
+struct Reviewed {
+    content: String,
+    diagnostics: Vec<Diagnostic>,
+}
+
+/// Reviews documents on save
+pub struct Reviewer {
+    model: Arc<LspModel>,
+    config: LspReviewConfig,
+    prompt: OnceLock<ReviewPrompt>,
+    state: Mutex<ReviewState>,
+}
+
+#[derive(Default)]
+struct ReviewState {
+    documents: HashMap<Url, Reviewed>,
+    /// Findings by hash of language and content
+    cache: HashMap<String, Vec<Diagnostic>>,
+    limiter: RateLimiter,
+}
+
+impl Reviewer {
+    pub fn new(model: Arc<LspModel>, config: LspReviewConfig) -> Self {
+        let limiter = RateLimiter::new(
+            Duration::from_secs(config.min_interval_secs),
+            config.max_per_hour,
+        );
+        Self {
+            model,
+            config,
+            prompt: OnceLock::new(),
+            state: Mutex::new(ReviewState {
+                limiter,
+                ..Default::default()
+            }),
+        }
+    }
+
+    pub fn is_enabled_for(&self, language_id: &str) -> bool {
+        self.config.is_enabled_for(language_id)
+    }
+
+    /// Remember an opened document's content, so the first save only
+    /// reviews what changed since
+    pub fn open(&self, doc: &DocumentState) {
+        if !self.is_enabled_for(&doc.language_id) {
+            return;
+        }
+        self.lock().documents.insert(
+            doc.uri.clone(),
+            Reviewed {
+                content: doc.content.clone(),
+                diagnostics: Vec::new(),
+            },
+        );
+    }
+
+    pub fn close(&self, uri: &Url) {
+        self.lock().documents.remove(uri);
+    }
+
+    /// Review a saved document.
+    ///
+    /// Returns the diagnostics to publish, or `None` when review is disabled
+    /// for its language, nothing changed or the rate limit was hit.
+    pub async fn review(
+        &self,
+        doc: &DocumentState,
+        workspace: Option<&Path>,
+    ) -> Result<Option<Vec<Diagnostic>>> {
+        if !self.is_enabled_for(&doc.language_id) {
+            return Ok(None);
+        }
+        let key = hash_content(&format!("{}\0{}", doc.language_id, doc.content));
+
+        let (previous, region) = {
+            let mut state = self.lock();
+            if let Some(diagnostics) = state.cache.get(&key).cloned() {
+                state.documents.insert(
+                    doc.uri.clone(),
+                    Reviewed {
+                        content: doc.content.clone(),
+                        diagnostics: diagnostics.clone(),
+                    },
+                );
+                return Ok(Some(diagnostics));
+            }
+            let previous = state.documents.get(&doc.uri).cloned();
+            let region = match &previous {
+                Some(previous) => changed_region(&previous.content, &doc.content),
+                None if doc.content.trim().is_empty() => None,
+                None => Some((0, doc.content.lines().count())),
+            };
+            let Some(region) = region else {
+                return Ok(None);
+            };
+            if !state.limiter.try_acquire(&doc.uri, Instant::now()) {
+                tracing::debug!("Review of {} skipped by rate limit", doc.uri);
+                return Ok(None);
+            }
+            (previous, region)
+        };
+        let region = (region.0, region.1.min(region.0 + MAX_REVIEW_LINES));
+
+        let provider = self.model.provider().await?;
+        let request = self.build_request(doc, region, workspace);
+        let response = provider.complete(request).await?;
+        let findings = parse_findings(&extract_text_content(&response.content))?;
+
+        let mut diagnostics = match &previous {
+            Some(previous) => carry_over(previous, &doc.content, region),
+            None => Vec::new(),
+        };
+        diagnostics.extend(
+            findings
+                .into_iter()
+                .filter_map(|finding| to_diagnostic(&doc.content, region, finding)),
+        );
+        diagnostics.sort_by_key(|diagnostic| {
+            (
+                diagnostic.range.start.line,
+                diagnostic.range.start.character,
+            )
+        });
+
+        let mut state = self.lock();
+        if state.cache.len() >= MAX_CACHED_REVIEWS {
+            state.cache.clear();
+        }
+        state.cache.insert(key, diagnostics.clone());
+        state.documents.insert(
+            doc.uri.clone(),
+            Reviewed {
+                content: doc.content.clone(),
+                diagnostics: diagnostics.clone(),
+            },
+        );
+        Ok(Some(diagnostics))
+    }
+
+    /// "Apply suggested fix" actions for review diagnostics in `params`.
+    ///
+    /// Only offered while the document still matches the reviewed content;
+    /// the suggestion comes from the diagnostic's `data`, or from the last
+    /// review when the client dropped it. Edits are pinned to `doc.version`.
+    pub fn quick_fixes(
+        &self,
+        params: &CodeActionParams,
+        doc: &DocumentState,
+    ) -> Vec<CodeActionOrCommand> {
+        let uri = &params.text_document.uri;
+        let state = self.lock();
+        let Some(reviewed) = state
+            .documents
+            .get(uri)
+            .filter(|r| r.content == doc.content)
+        else {
+            return Vec::new();
+        };
+
+        params
+            .context
+            .diagnostics
+            .iter()
+            .filter(|diagnostic| diagnostic.source.as_deref() == Some(REVIEW_SOURCE))
+            .filter_map(|diagnostic| {
+                let edit = suggested_edit(diagnostic).or_else(|| {
+                    reviewed
+                        .diagnostics
+                        .iter()
+                        .find(|d| d.range == diagnostic.range && d.message == diagnostic.message)
+                        .and_then(suggested_edit)
+                })?;
+                Some(CodeActionOrCommand::CodeAction(CodeAction {
+                    title: "Ted: Apply suggested fix".to_string(),
+                    kind: Some(CodeActionKind::QUICKFIX),
+                    diagnostics: Some(vec![diagnostic.clone()]),
+                    edit: Some(versioned_edit(doc, vec![edit])),
+                    command: None,
+                    is_preferred: Some(true),
+                    disabled: None,
+                    data: None,
+                }))
+            })
+            .collect()
+    }
+
+    fn build_request(
+        &self,
+        doc: &DocumentState,
+        region: (usize, usize),
+        workspace: Option<&Path>,
+    ) -> CompletionRequest {
+        let prompt = self.prompt(workspace);
+        let lines: Vec<&str> = doc.content.lines().collect();
+        let (first, last) = region;
+        let lang = doc.language_id.as_str();
+
+        let numbered = |range: std::ops::Range<usize>| -> String {
+            range
+                .filter_map(|i| lines.get(i).map(|line| format!("{:>5} | {}", i + 1, line)))
+                .collect::<Vec<_>>()
+                .join("\n")
+        };
+        let before = numbered(first.saturating_sub(CONTEXT_LINES)..first);
+        let reviewed = numbered(first..last.max(first + 1));
+        let after = numbered(last..(last + CONTEXT_LINES).min(lines.len()));
+
+        let path = doc
+            .uri
+            .to_file_path()
+            .ok()
+            .map(
+                |path| match workspace.and_then(|root| path.strip_prefix(root).ok()) {
+                    Some(relative) => relative.display().to_string(),
+                    None => path.display().to_string(),
+                },
+            )
+            .unwrap_or_else(|| doc.uri.to_string());
+
+        let mut message = format!("File: {} ({})\n\n", path, lang);
+        if !before.is_empty() {
+            message.push_str(&format!("Code before:\n```{}\n{}\n```\n\n", lang, before));
+        }
+        message.push_str(&format!(
+            "Lines to review ({}-{}):\n```{}\n{}\n```\n\n",
+            first + 1,
+            last.max(first + 1),
+            lang,
+            reviewed
+        ));
+        if !after.is_empty() {
+            message.push_str(&format!("Code after:\n```{}\n{}\n```\n\n", lang, after));
+        }
+        message.push_str(INSTRUCTIONS);
+
+        CompletionRequest::new(self.model.model(), vec![Message::user(message)])
+            .with_system(&prompt.system)
+            .with_max_tokens(prompt.max_tokens)
+            .with_temperature(prompt.temperature)
+    }
+
+    fn prompt(&self, workspace: Option<&Path>) -> &ReviewPrompt {
+        self.prompt.get_or_init(|| {
+            let project_caps = workspace
+                .map(|root| root.join(".ted").join("caps"))
+                .filter(|dir| dir.exists());
+            let loader = CapLoader::with_paths(project_caps, Settings::caps_dir());
+            match CapResolver::new(loader).resolve_and_merge(&[REVIEW_CAP.to_string()]) {
+                Ok(cap) => ReviewPrompt {
+                    system: render_system_prompt(&cap),
+                    temperature: cap.temperature().unwrap_or(0.2),
+                    max_tokens: cap
+                        .max_tokens()
+                        .unwrap_or(MAX_RESPONSE_TOKENS)
+                        .min(MAX_RESPONSE_TOKENS),
+                },
+                Err(e) => {
+                    tracing::warn!("Failed to load the {} cap: {}", REVIEW_CAP, e);
+                    ReviewPrompt {
+                        system: FALLBACK_SYSTEM_PROMPT.to_string(),
+                        temperature: 0.2,
+                        max_tokens: MAX_RESPONSE_TOKENS,
+                    }
+                }
+            }
+        })
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, ReviewState> {
+        self.state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+/// Limits reviews per document and per hour
+struct RateLimiter {
+    min_interval: Duration,
+    max_per_hour: u32,
+    last: HashMap<Url, Instant>,
+    recent: VecDeque<Instant>,
+}
+
+impl Default for RateLimiter {
+    fn default() -> Self {
+        let config = LspReviewConfig::default();
+        Self::new(
+            Duration::from_secs(config.min_interval_secs),
+            config.max_per_hour,
+        )
+    }
+}
+
+impl RateLimiter {
+    fn new(min_interval: Duration, max_per_hour: u32) -> Self {
+        Self {
+            min_interval,
+            max_per_hour,
+            last: HashMap::new(),
+            recent: VecDeque::new(),
+        }
+    }
+
+    /// Record a review of `uri` at `now` if the limits allow it
+    fn try_acquire(&mut self, uri: &Url, now: Instant) -> bool {
+        let hour = Duration::from_secs(3600);
+        while self
+            .recent
+            .front()
+            .is_some_and(|at| now.duration_since(*at) >= hour)
+        {
+            self.recent.pop_front();
+        }
+        if self.recent.len() >= self.max_per_hour as usize {
+            return false;
+        }
+        if self
+            .last
+            .get(uri)
+            .is_some_and(|at| now.duration_since(*at) < self.min_interval)
+        {
+            return false;
+        }
+        self.last.insert(uri.clone(), now);
+        self.recent.push_back(now);
+        true
+    }
+}
+
+/// Lines of `new` (start, end exclusive) spanning every change from `old`,
+/// or `None` when nothing changed
+fn changed_region(old: &str, new: &str) -> Option<(usize, usize)> {
+    let old_lines: Vec<&str> = old.lines().collect();
+    let new_lines: Vec<&str> = new.lines().collect();
+    let mut matcher = SequenceMatcher::new(&old_lines, &new_lines);
+    let changed: Vec<_> = matcher
+        .get_opcodes()
+        .into_iter()
+        .filter(|op| op.tag != "equal")
+        .collect();
+    let start = changed.first()?.second_start;
+    let end = changed.last()?.second_end;
+    // A pure deletion still gets the line that took its place reviewed
+    let end = end.max(start + 1).min(new_lines.len().max(start));
+    Some((start, end))
+}
+
+/// Findings of the previous review on unchanged lines outside `region`,
+/// moved to where those lines are now
+fn carry_over(previous: &Reviewed, content: &str, region: (usize, usize)) -> Vec<Diagnostic> {
+    let old_lines: Vec<&str> = previous.content.lines().collect();
+    let new_lines: Vec<&str> = content.lines().collect();
+    let mut matcher = SequenceMatcher::new(&old_lines, &new_lines);
+    let equal: Vec<_> = matcher
+        .get_opcodes()
+        .into_iter()
+        .filter(|op| op.tag == "equal")
+        .collect();
+
+    previous
+        .diagnostics
+        .iter()
+        .filter_map(|diagnostic| {
+            let start = diagnostic.range.start.line as usize;
+            let end = diagnostic.range.end.line as usize;
+            let op = equal
+                .iter()
+                .find(|op| op.first_start <= start && end < op.first_end)?;
+            let shift = op.second_start as i64 - op.first_start as i64;
+            let new_start = (start as i64 + shift) as usize;
+            let new_end = (end as i64 + shift) as usize;
+            if new_start < region.1 && new_end >= region.0 {
+                return None;
+            }
+
+            let mut diagnostic = diagnostic.clone();
+            diagnostic.range = shift_range(diagnostic.range, shift);
+            if let Some(mut edit) = suggested_edit(&diagnostic) {
+                edit.range = shift_range(edit.range, shift);
+                diagnostic.data = serde_json::to_value(edit).ok();
+            }
+            Some(diagnostic)
+        })
+        .collect()
+}
+
+fn shift_range(range: Range, shift: i64) -> Range {
+    let shift = |position: Position| {
+        Position::new((position.line as i64 + shift) as u32, position.character)
+    };
+    Range::new(shift(range.start), shift(range.end))
+}
+
+/// The JSON array in a reply, tolerating prose or fences around it
+fn parse_findings(reply: &str) -> Result<Vec<Finding>> {
+    let array = reply
+        .find('[')
+        .zip(reply.rfind(']'))
+        .filter(|(start, end)| start < end)
+        .map(|(start, end)| &reply[start..=end])
+        .ok_or_else(|| TedError::Lsp("Review reply contains no JSON array".to_string()))?;
+    serde_json::from_str(array).map_err(|e| TedError::Lsp(format!("Invalid review reply: {}", e)))
+}
+
+/// A finding as a diagnostic, dropped when it lies outside the reviewed lines
+fn to_diagnostic(content: &str, region: (usize, usize), finding: Finding) -> Option<Diagnostic> {
+    let lines: Vec<&str> = content.lines().collect();
+    let first = finding.line.checked_sub(1)? as usize;
+    let last = finding
+        .end_line
+        .and_then(|line| line.checked_sub(1))
+        .map_or(first, |line| (line as usize).max(first));
+    if first < region.0 || first >= region.1.max(region.0 + 1) || last >= lines.len() {
+        return None;
+    }
+
+    let start_line = lines[first];
+    let indent = start_line.len() - start_line.trim_start().len();
+    let range = Range::new(
+        Position::new(
+            first as u32,
+            start_line[..indent].encode_utf16().count() as u32,
+        ),
+        Position::new(last as u32, lines[last].encode_utf16().count() as u32),
+    );
+    let data = finding.suggestion.map(|suggestion| {
+        let mut new_text = suggestion.trim_end_matches(['\n', '\r']).to_string();
+        let end = if last + 1 < lines.len() || content.ends_with('\n') {
+            if !new_text.is_empty() {
+                new_text.push('\n');
+            }
+            Position::new(last as u32 + 1, 0)
+        } else {
+            document_end(content)
+        };
+        let range = Range::new(Position::new(first as u32, 0), end);
+        serde_json::to_value(TextEdit { range, new_text }).unwrap_or_default()
+    });
+
+    Some(Diagnostic {
+        range,
+        severity: Some(severity(&finding.severity)),
+        source: Some(REVIEW_SOURCE.to_string()),
+        message: finding.message,
+        data,
+        ..Default::default()
+    })
+}
+
+fn severity(name: &str) -> DiagnosticSeverity {
+    match name.to_ascii_lowercase().as_str() {
+        "error" => DiagnosticSeverity::ERROR,
+        "info" | "information" => DiagnosticSeverity::INFORMATION,
+        "hint" => DiagnosticSeverity::HINT,
+        _ => DiagnosticSeverity::WARNING,
+    }
+}
+
+fn suggested_edit(diagnostic: &Diagnostic) -> Option<TextEdit> {
+    serde_json::from_value(diagnostic.data.clone()?).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::llm::MockProvider;
+
+    const CONTENT: &str =
+        "fn main() {\n    let x = 1;\n    let y = x / 0;\n    println!(\"{}\", y);\n}\n";
+
+    fn doc(content: &str) -> DocumentState {
+        DocumentState {
+            uri: Url::parse("file:///project/src/main.rs").unwrap(),
+            content: content.to_string(),
+            version: 1,
+            language_id: "rust".to_string(),
+        }
+    }
+
+    fn reviewer(responses: Vec<&str>) -> (Reviewer, Arc<MockProvider>) {
+        let provider = Arc::new(
+            MockProvider::new().with_responses(responses.into_iter().map(String::from).collect()),
+        );
+        let config = LspReviewConfig {
+            enabled: true,
+            min_interval_secs: 0,
+            ..Default::default()
+        };
+        let model = Arc::new(LspModel::with_provider(provider.clone(), "mock-model"));
+        (Reviewer::new(model, config), provider)
+    }
+
+    fn finding(line: u32, suggestion: Option<&str>) -> Finding {
+        Finding {
+            line,
+            end_line: None,
+            severity: "error".to_string(),
+            message: "Division by zero".to_string(),
+            suggestion: suggestion.map(String::from),
+        }
+    }
+
+    #[test]
+    fn test_parse_findings_tolerates_surrounding_text() {
+        let reply = "Here is my review:\n```json\n[{\"line\": 3, \"severity\": \"error\", \
+                     \"message\": \"Division by zero\", \"suggestion\": \"    let y = x;\"}]\n```";
+        let findings = parse_findings(reply).unwrap();
+        assert_eq!(findings, vec![finding(3, Some("    let y = x;"))]);
+
+        assert!(parse_findings("[]").unwrap().is_empty());
+        assert!(parse_findings("Looks good to me.").is_err());
+    }
+
+    #[test]
+    fn test_to_diagnostic_ranges_and_edit() {
+        let diagnostic =
+            to_diagnostic(CONTENT, (0, 5), finding(3, Some("    let y = x;\n"))).unwrap();
+        assert_eq!(
+            diagnostic.range,
+            Range::new(Position::new(2, 4), Position::new(2, 18))
+        );
+        assert_eq!(diagnostic.severity, Some(DiagnosticSeverity::ERROR));
+        assert_eq!(diagnostic.source.as_deref(), Some(REVIEW_SOURCE));
+
+        let edit = suggested_edit(&diagnostic).unwrap();
+        assert_eq!(
+            edit.range,
+            Range::new(Position::new(2, 0), Position::new(3, 0))
+        );
+        assert_eq!(edit.new_text, "    let y = x;\n");
+
+        // Outside the reviewed lines, or past the end of the document
+        assert!(to_diagnostic(CONTENT, (0, 2), finding(3, None)).is_none());
+        assert!(to_diagnostic(CONTENT, (0, 5), finding(9, None)).is_none());
+        assert!(to_diagnostic(CONTENT, (0, 5), finding(0, None)).is_none());
+        assert_eq!(severity("Info"), DiagnosticSeverity::INFORMATION);
+        assert_eq!(severity("nit"), DiagnosticSeverity::WARNING);
+    }
+
+    #[test]
+    fn test_edit_on_last_line_without_newline() {
+        let content = "let a = 1;\nlet b = a / 0;";
+        let diagnostic = to_diagnostic(content, (0, 2), finding(2, Some("let b = a;"))).unwrap();
+        let edit = suggested_edit(&diagnostic).unwrap();
+        assert_eq!(
+            edit.range,
+            Range::new(Position::new(1, 0), Position::new(1, 14))
+        );
+        assert_eq!(edit.new_text, "let b = a;");
+    }
+
+    #[test]
+    fn test_changed_region() {
+        assert_eq!(changed_region(CONTENT, CONTENT), None);
+
+        let edited = CONTENT.replace("x / 0", "x / 2");
+        assert_eq!(changed_region(CONTENT, &edited), Some((2, 3)));
+
+        let inserted = CONTENT.replace("fn main() {\n", "fn main() {\n    // setup\n");
+        assert_eq!(changed_region(CONTENT, &inserted), Some((1, 2)));
+
+        let deleted = CONTENT.replace("    let x = 1;\n", "");
+        assert_eq!(changed_region(CONTENT, &deleted), Some((1, 2)));
+    }
+
+    #[test]
+    fn test_carry_over_shifts_unchanged_findings() {
+        let diagnostic =
+            to_diagnostic(CONTENT, (0, 5), finding(3, Some("    let y = x;"))).unwrap();
+        let previous = Reviewed {
+            content: CONTENT.to_string(),
+            diagnostics: vec![diagnostic],
+        };
+
+        let inserted = CONTENT.replace("fn main() {\n", "fn main() {\n    // setup\n");
+        let carried = carry_over(&previous, &inserted, (1, 2));
+        assert_eq!(carried.len(), 1);
+        assert_eq!(carried[0].range.start.line, 3);
+        assert_eq!(suggested_edit(&carried[0]).unwrap().range.start.line, 3);
+
+        // Findings on lines that changed are re-reviewed, not carried over
+        let edited = CONTENT.replace("x / 0", "x / 2");
+        assert!(carry_over(&previous, &edited, (2, 3)).is_empty());
+    }
+
+    #[test]
+    fn test_rate_limiter() {
+        let a = Url::parse("file:///a.rs").unwrap();
+        let b = Url::parse("file:///b.rs").unwrap();
+        let start = Instant::now();
+        let mut limiter = RateLimiter::new(Duration::from_secs(30), 2);
+
+        assert!(limiter.try_acquire(&a, start));
+        assert!(!limiter.try_acquire(&a, start + Duration::from_secs(10)));
+        assert!(limiter.try_acquire(&b, start + Duration::from_secs(10)));
+        // Hourly budget spent
+        assert!(!limiter.try_acquire(&a, start + Duration::from_secs(60)));
+        assert!(limiter.try_acquire(&a, start + Duration::from_secs(3600)));
+    }
+
+    #[tokio::test]
+    async fn test_review_changed_region_and_cache() {
+        let (reviewer, provider) = reviewer(vec![
+            r#"[{"line": 3, "severity": "error", "message": "Division by zero", "suggestion": "    let y = x;"}]"#,
+        ]);
+        let original = doc("fn main() {\n    let x = 1;\n}\n");
+        reviewer.open(&original);
+
+        // Unchanged since open: nothing to review
+        assert_eq!(reviewer.review(&original, None).await.unwrap(), None);
+        assert_eq!(provider.call_count(), 0);
+
+        let saved = doc(CONTENT);
+        let diagnostics = reviewer.review(&saved, None).await.unwrap().unwrap();
+        assert_eq!(diagnostics.len(), 1);
+        let prompt = format!("{:?}", provider.last_request().unwrap().messages);
+        assert!(prompt.contains("Lines to review (3-4)"));
+
+        // Same content again comes from the cache
+        reviewer.close(&saved.uri);
+        let cached = reviewer.review(&saved, None).await.unwrap().unwrap();
+        assert_eq!(cached, diagnostics);
+        assert_eq!(provider.call_count(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_review_disabled_language() {
+        let (reviewer, provider) = reviewer(vec!["[]"]);
+        let mut markdown = doc("# Title\n");
+        markdown.language_id = "markdown".to_string();
+        let reviewer = Reviewer {
+            config: LspReviewConfig {
+                languages: HashMap::from([("markdown".to_string(), false)]),
+                ..reviewer.config.clone()
+            },
+            ..reviewer
+        };
+
+        assert_eq!(reviewer.review(&markdown, None).await.unwrap(), None);
+        assert_eq!(provider.call_count(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_quick_fixes_from_review() {
+        let (reviewer, _) = reviewer(vec![
+            r#"[{"line": 3, "severity": "warning", "message": "Division by zero", "suggestion": "    let y = x;"}]"#,
+        ]);
+        let saved = doc(CONTENT);
+        let diagnostics = reviewer.review(&saved, None).await.unwrap().unwrap();
+
+        // Clients that don't round-trip `data` still get the fix
+        let mut without_data = diagnostics[0].clone();
+        without_data.data = None;
+        let params = CodeActionParams {
+            text_document: TextDocumentIdentifier::new(saved.uri.clone()),
+            range: without_data.range,
+            context: CodeActionContext {
+                diagnostics: vec![without_data],
+                only: None,
+                trigger_kind: None,
+            },
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        };
+
+        let mut current = doc(CONTENT);
+        current.version = 7;
+        let actions = reviewer.quick_fixes(&params, &current);
+        assert_eq!(actions.len(), 1);
+        let CodeActionOrCommand::CodeAction(action) = &actions[0] else {
+            panic!("expected a code action");
+        };
+        assert_eq!(action.kind, Some(CodeActionKind::QUICKFIX));
+        let Some(DocumentChanges::Edits(changes)) = &action.edit.as_ref().unwrap().document_changes
+        else {
+            panic!("expected versioned document edits");
+        };
+        assert_eq!(changes[0].text_document.version, Some(7));
+        let OneOf::Left(edit) = &changes[0].edits[0] else {
+            panic!("expected a plain text edit");
+        };
+        assert_eq!(edit.new_text, "    let y = x;\n");
+
+        // Stale once the document changes
+        assert!(reviewer
+            .quick_fixes(&params, &doc("fn main() {}\n"))
+            .is_empty());
+    }
+}
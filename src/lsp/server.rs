@@ -5,15 +5,26 @@ use std::collections::HashMap;
 use std::path::PathBuf;
 use std::sync::Arc;
 
+use serde_json::Value;
 use tokio::sync::RwLock;
-use tower_lsp::jsonrpc::Result;
+use tower_lsp::jsonrpc::{Error, Result};
 use tower_lsp::lsp_types::*;
 use tower_lsp::{Client, LanguageServer};
 
 use super::capabilities::server_capabilities;
+use super::code_actions::{
+    code_actions, run_code_action, ActionOutcome, CodeActionArgs, RUN_CODE_ACTION_COMMAND,
+};
 use super::completion::provide_completions;
-use super::definition::provide_definition;
+use super::definition::{get_word_at_position, provide_definition};
 use super::hover::provide_hover;
+use super::inline_completion::{
+    fim_request, InlineCompleter, InlineCompletionItem, InlineCompletionList,
+    InlineCompletionParams, INLINE_COMPLETION_METHOD, MAX_RELATED_FILES,
+};
+use super::model::LspModel;
+use super::review::Reviewer;
+use super::symbols::SymbolIndex;
 
 /// Document state for an open file
 #[derive(Debug, Clone)]
@@ -29,14 +40,26 @@ pub struct TedLanguageServer {
     client: Client,
     workspace_root: Arc<RwLock<Option<PathBuf>>>,
     documents: Arc<RwLock<HashMap<Url, DocumentState>>>,
+    /// Indexer-backed symbols; the indexer isn't `Sync`, hence the mutex
+    symbols: Arc<tokio::sync::Mutex<SymbolIndex>>,
+    model: Arc<LspModel>,
+    inline: Arc<InlineCompleter>,
+    reviewer: Arc<Reviewer>,
 }
 
 impl TedLanguageServer {
     pub fn new(client: Client) -> Self {
+        let settings = crate::config::Settings::load().unwrap_or_default();
+        let review = settings.lsp.review.clone();
+        let model = Arc::new(LspModel::from_settings(settings));
         Self {
             client,
             workspace_root: Arc::new(RwLock::new(None)),
             documents: Arc::new(RwLock::new(HashMap::new())),
+            symbols: Arc::new(tokio::sync::Mutex::new(SymbolIndex::default())),
+            inline: Arc::new(InlineCompleter::new(model.clone())),
+            reviewer: Arc::new(Reviewer::new(model.clone(), review)),
+            model,
         }
     }
 
@@ -54,6 +77,77 @@ impl TedLanguageServer {
     pub async fn get_all_documents(&self) -> Vec<DocumentState> {
         self.documents.read().await.values().cloned().collect()
     }
+
+    /// Index the workspace in the background, then re-apply open documents
+    /// that changed while the scan ran.
+    async fn build_symbol_index(&self) {
+        let Some(root) = self.get_workspace_root().await else {
+            return;
+        };
+        let symbols = self.symbols.clone();
+        let documents = self.documents.clone();
+        tokio::spawn(async move {
+            let index = match tokio::task::spawn_blocking(move || SymbolIndex::build(&root)).await {
+                Ok(index) => index,
+                Err(e) => {
+                    tracing::warn!("Symbol indexing task failed: {}", e);
+                    return;
+                }
+            };
+            let mut symbols = symbols.lock().await;
+            *symbols = index;
+            for doc in documents.read().await.values() {
+                if let Ok(path) = doc.uri.to_file_path() {
+                    symbols.update(&path, &doc.content);
+                }
+            }
+            tracing::info!("Workspace symbol index ready");
+        });
+    }
+
+    /// Handle `textDocument/inlineCompletion`, registered as a custom method
+    pub async fn inline_completion(
+        &self,
+        params: InlineCompletionParams,
+    ) -> Result<Option<InlineCompletionList>> {
+        let uri = &params.text_document.uri;
+        let position = params.position;
+
+        let Some(ticket) = self.inline.begin(params.is_automatic()).await else {
+            return Ok(None);
+        };
+        let Some(doc) = self.get_document(uri).await else {
+            return Ok(None);
+        };
+        tracing::debug!("Inline completion at {:?}:{:?}", uri, position);
+
+        let related = match uri.to_file_path() {
+            Ok(path) if !self.inline.tier().await.single_file_mode() => self
+                .symbols
+                .lock()
+                .await
+                .related_files(&path, MAX_RELATED_FILES),
+            _ => Vec::new(),
+        };
+        let request = fim_request(&doc.content, position, related);
+
+        let Some(text) = self.inline.complete(ticket, request).await else {
+            return Ok(None);
+        };
+        Ok(Some(InlineCompletionList {
+            items: vec![InlineCompletionItem {
+                insert_text: text,
+                range: Some(Range::new(position, position)),
+            }],
+        }))
+    }
+
+    /// Re-index a document's current content for symbols and references
+    async fn update_symbols(&self, uri: &Url, content: &str) {
+        if let Ok(path) = uri.to_file_path() {
+            self.symbols.lock().await.update(&path, content);
+        }
+    }
 }
 
 #[tower_lsp::async_trait]
@@ -83,6 +177,17 @@ impl LanguageServer for TedLanguageServer {
         self.client
             .log_message(MessageType::INFO, "Ted LSP server initialized")
             .await;
+        self.build_symbol_index().await;
+
+        // Not in lsp-types 0.94's server capabilities, so offered dynamically
+        let registration = Registration {
+            id: "ted-inline-completion".to_string(),
+            method: INLINE_COMPLETION_METHOD.to_string(),
+            register_options: None,
+        };
+        if let Err(e) = self.client.register_capability(vec![registration]).await {
+            tracing::debug!("Client declined inline completion registration: {}", e);
+        }
     }
 
     async fn shutdown(&self) -> Result<()> {
@@ -101,6 +206,8 @@ impl LanguageServer for TedLanguageServer {
             language_id: doc.language_id,
         };
 
+        self.update_symbols(&doc.uri, &state.content).await;
+        self.reviewer.open(&state);
         self.documents.write().await.insert(doc.uri, state);
     }
 
@@ -110,6 +217,7 @@ impl LanguageServer for TedLanguageServer {
 
         if let Some(change) = params.content_changes.into_iter().last() {
             // For full document sync, we just replace the content
+            self.update_symbols(&uri, &change.text).await;
             let mut docs = self.documents.write().await;
             if let Some(state) = docs.get_mut(&uri) {
                 state.content = change.text;
@@ -119,16 +227,43 @@ impl LanguageServer for TedLanguageServer {
     }
 
     async fn did_close(&self, params: DidCloseTextDocumentParams) {
-        tracing::debug!("Document closed: {}", params.text_document.uri);
-        self.documents
-            .write()
-            .await
-            .remove(&params.text_document.uri);
+        let uri = params.text_document.uri;
+        tracing::debug!("Document closed: {}", uri);
+        let closed = self.documents.write().await.remove(&uri);
+        if let Ok(path) = uri.to_file_path() {
+            self.symbols.lock().await.close(&path);
+        }
+        self.reviewer.close(&uri);
+        if closed.is_some_and(|doc| self.reviewer.is_enabled_for(&doc.language_id)) {
+            self.client.publish_diagnostics(uri, Vec::new(), None).await;
+        }
     }
 
     async fn did_save(&self, params: DidSaveTextDocumentParams) {
-        tracing::debug!("Document saved: {}", params.text_document.uri);
-        // Could trigger diagnostics or other actions here
+        let uri = params.text_document.uri;
+        tracing::debug!("Document saved: {}", uri);
+        let Some(doc) = self.get_document(&uri).await else {
+            return;
+        };
+        if !self.reviewer.is_enabled_for(&doc.language_id) {
+            return;
+        }
+
+        // Reviews take seconds; don't hold up the notification queue
+        let reviewer = self.reviewer.clone();
+        let client = self.client.clone();
+        let workspace = self.get_workspace_root().await;
+        tokio::spawn(async move {
+            match reviewer.review(&doc, workspace.as_deref()).await {
+                Ok(Some(diagnostics)) => {
+                    client
+                        .publish_diagnostics(doc.uri, diagnostics, Some(doc.version))
+                        .await;
+                }
+                Ok(None) => {}
+                Err(e) => tracing::warn!("Review of {} failed: {}", doc.uri, e),
+            }
+        });
     }
 
     async fn completion(&self, params: CompletionParams) -> Result<Option<CompletionResponse>> {
@@ -195,6 +330,124 @@ impl LanguageServer for TedLanguageServer {
 
         Ok(None)
     }
+
+    async fn references(&self, params: ReferenceParams) -> Result<Option<Vec<Location>>> {
+        let uri = &params.text_document_position.text_document.uri;
+        let position = params.text_document_position.position;
+
+        tracing::debug!("References at {:?}:{:?}", uri, position);
+
+        let (Some(doc), Ok(path)) = (self.get_document(uri).await, uri.to_file_path()) else {
+            return Ok(None);
+        };
+        let Some(line) = doc.content.lines().nth(position.line as usize) else {
+            return Ok(None);
+        };
+        let word = get_word_at_position(line, position.character as usize);
+        if word.is_empty() {
+            return Ok(None);
+        }
+
+        let locations = self.symbols.lock().await.references(
+            &path,
+            &doc.content,
+            &word,
+            params.context.include_declaration,
+        );
+        Ok(Some(locations))
+    }
+
+    async fn document_symbol(
+        &self,
+        params: DocumentSymbolParams,
+    ) -> Result<Option<DocumentSymbolResponse>> {
+        let uri = &params.text_document.uri;
+        let (Some(doc), Ok(path)) = (self.get_document(uri).await, uri.to_file_path()) else {
+            return Ok(None);
+        };
+
+        let symbols = self
+            .symbols
+            .lock()
+            .await
+            .document_symbols(&path, &doc.content);
+        Ok(Some(DocumentSymbolResponse::Nested(symbols)))
+    }
+
+    async fn symbol(
+        &self,
+        params: WorkspaceSymbolParams,
+    ) -> Result<Option<Vec<SymbolInformation>>> {
+        let symbols = self.symbols.lock().await.workspace_symbols(&params.query);
+        Ok(Some(symbols))
+    }
+
+    async fn code_action(&self, params: CodeActionParams) -> Result<Option<CodeActionResponse>> {
+        let Some(doc) = self.get_document(&params.text_document.uri).await else {
+            return Ok(None);
+        };
+        let mut actions = self.reviewer.quick_fixes(&params, &doc);
+        actions.extend(code_actions(&params));
+        Ok(Some(actions))
+    }
+
+    async fn execute_command(&self, params: ExecuteCommandParams) -> Result<Option<Value>> {
+        if params.command != RUN_CODE_ACTION_COMMAND {
+            return Err(Error::invalid_params(format!(
+                "Unknown command: {}",
+                params.command
+            )));
+        }
+        let args: CodeActionArgs = params
+            .arguments
+            .into_iter()
+            .next()
+            .and_then(|args| serde_json::from_value(args).ok())
+            .ok_or_else(|| Error::invalid_params("Expected code action arguments"))?;
+        let doc = self
+            .get_document(&args.uri)
+            .await
+            .ok_or_else(|| Error::invalid_params(format!("Document not open: {}", args.uri)))?;
+
+        let workspace = self.get_workspace_root().await;
+        let outcome = run_code_action(
+            &self.client,
+            &self.model,
+            &doc,
+            &args,
+            workspace.as_deref(),
+            params.work_done_progress_params.work_done_token,
+        )
+        .await;
+
+        match outcome {
+            Ok(ActionOutcome::Edit(edit)) => {
+                let applied = match self.client.apply_edit(edit.clone()).await {
+                    Ok(response) => response.applied,
+                    Err(e) => {
+                        tracing::warn!("Failed to apply code action edit: {}", e);
+                        false
+                    }
+                };
+                Ok(Some(
+                    serde_json::json!({ "applied": applied, "edit": edit }),
+                ))
+            }
+            Ok(ActionOutcome::Explanation(text)) => {
+                self.client.show_message(MessageType::INFO, &text).await;
+                Ok(Some(serde_json::json!({ "explanation": text })))
+            }
+            Err(e) => {
+                tracing::warn!("Code action error: {}", e);
+                self.client
+                    .show_message(MessageType::ERROR, format!("Ted: {}", e))
+                    .await;
+                let mut error = Error::internal_error();
+                error.message = e.to_string().into();
+                Err(error)
+            }
+        }
+    }
 }
 
 #[cfg(test)]
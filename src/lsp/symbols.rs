@@ -0,0 +1,532 @@
+// SPDX-License-Identifier: AGPL-3.0-or-later
+// Copyright (C) 2025 Blackman Artificial Intelligence Technologies Inc.
+
+//! References, document symbols and workspace symbols
+//!
+//! Answered from the indexer: the exports its language parsers find are the
+//! symbols, and its dependency graph narrows a references search to the
+//! defining files and the files that (transitively) import them. Open
+//! documents are re-indexed on every change, so results follow unsaved edits.
+
+use std::collections::{BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+
+use tower_lsp::lsp_types::*;
+
+use super::definition::is_identifier_char;
+use crate::indexer::{ExportKind, ExportRef, Indexer, IndexerConfig, ParserRegistry};
+
+/// Most results returned for one `workspace/symbol` query
+const MAX_WORKSPACE_SYMBOLS: usize = 200;
+
+/// Symbols and dependency edges of one workspace
+#[derive(Default)]
+pub struct SymbolIndex {
+    /// `None` until a workspace has been scanned
+    indexer: Option<Indexer>,
+    parsers: ParserRegistry,
+    /// Parsed exports keyed by path relative to the workspace root
+    exports: HashMap<PathBuf, Vec<ExportRef>>,
+    /// Unsaved content of open documents, keyed like `exports`
+    open: HashMap<PathBuf, String>,
+}
+
+impl SymbolIndex {
+    /// Scan `root` and parse the exports of every indexed file.
+    ///
+    /// Blocking; a workspace that can't be indexed yields an empty index
+    /// that still serves open documents.
+    pub fn build(root: &Path) -> Self {
+        let mut index = Self::default();
+        let mut indexer = match Indexer::new(root, IndexerConfig::default()) {
+            Ok(indexer) => indexer,
+            Err(e) => {
+                tracing::warn!("Failed to index workspace for symbols: {}", e);
+                return index;
+            }
+        };
+        if let Err(e) = indexer.full_scan() {
+            tracing::warn!("Workspace symbol scan failed: {}", e);
+        }
+
+        let files: Vec<PathBuf> = indexer.index().files.keys().cloned().collect();
+        for relative in files {
+            if let Ok(content) = std::fs::read_to_string(indexer.root().join(&relative)) {
+                let exports = index.parsers.parse_exports(&relative, &content);
+                index.exports.insert(relative, exports);
+            }
+        }
+        index.indexer = Some(indexer);
+        index
+    }
+
+    /// Path relative to the workspace root, or `None` outside of it
+    fn relative(&self, path: &Path) -> Option<PathBuf> {
+        let root = self.indexer.as_ref()?.root();
+        if let Ok(relative) = path.strip_prefix(root) {
+            return Some(relative.to_path_buf());
+        }
+        // The indexer canonicalizes its root; editors may not
+        let canonical = path.canonicalize().ok()?;
+        canonical.strip_prefix(root).ok().map(Path::to_path_buf)
+    }
+
+    fn absolute(&self, relative: &Path) -> PathBuf {
+        match &self.indexer {
+            Some(indexer) => indexer.root().join(relative),
+            None => relative.to_path_buf(),
+        }
+    }
+
+    /// Re-index an open document from its current content.
+    pub fn update(&mut self, path: &Path, content: &str) {
+        let Some(relative) = self.relative(path) else {
+            return;
+        };
+        let Some(indexer) = self.indexer.as_mut() else {
+            return;
+        };
+        if !indexer.config().should_index(&relative) || indexer.tedignore().is_ignored(path) {
+            return;
+        }
+        if let Err(e) = indexer.update_file_content(path, content) {
+            tracing::debug!("Failed to re-index {}: {}", path.display(), e);
+        }
+        self.exports
+            .insert(relative.clone(), self.parsers.parse_exports(path, content));
+        self.open.insert(relative, content.to_string());
+    }
+
+    /// Forget an open document's unsaved content and re-index it from disk.
+    pub fn close(&mut self, path: &Path) {
+        let Some(relative) = self.relative(path) else {
+            return;
+        };
+        self.open.remove(&relative);
+        if let Some(indexer) = self.indexer.as_mut() {
+            if let Err(e) = indexer.refresh_file(path) {
+                tracing::debug!("Failed to re-index {}: {}", path.display(), e);
+            }
+        }
+        match std::fs::read_to_string(path) {
+            Ok(content) => {
+                let exports = self.parsers.parse_exports(path, &content);
+                self.exports.insert(relative, exports);
+            }
+            Err(_) => {
+                self.exports.remove(&relative);
+            }
+        }
+    }
+
+    /// Symbols defined in one document
+    pub fn document_symbols(&self, path: &Path, content: &str) -> Vec<DocumentSymbol> {
+        let lines: Vec<&str> = content.lines().collect();
+        let mut exports = self.parsers.parse_exports(path, content);
+        exports.sort_by_key(|export| export.line);
+        exports
+            .iter()
+            .filter(|export| export.kind != ExportKind::ReExport)
+            .filter_map(|export| {
+                let line = export.line.checked_sub(1)?;
+                let text = lines.get(line as usize)?;
+                let selection_range = name_range(text, line, &export.name);
+                #[allow(deprecated)]
+                Some(DocumentSymbol {
+                    name: export.name.clone(),
+                    detail: None,
+                    kind: symbol_kind(export.kind),
+                    tags: None,
+                    deprecated: None,
+                    range: Range {
+                        start: Position { line, character: 0 },
+                        end: Position {
+                            line,
+                            character: utf16_len(text),
+                        },
+                    },
+                    selection_range,
+                    children: None,
+                })
+            })
+            .collect()
+    }
+
+    /// Symbols across the workspace whose name contains `query`
+    /// (case-insensitive), best matches first.
+    pub fn workspace_symbols(&self, query: &str) -> Vec<SymbolInformation> {
+        let query = query.to_lowercase();
+        let mut matches: Vec<(u8, f64, &Path, &ExportRef)> = self
+            .exports
+            .iter()
+            .flat_map(|(path, exports)| exports.iter().map(move |e| (path.as_path(), e)))
+            .filter(|(_, export)| export.kind != ExportKind::ReExport)
+            .filter_map(|(path, export)| {
+                let name = export.name.to_lowercase();
+                let rank = if name == query {
+                    0
+                } else if name.starts_with(&query) {
+                    1
+                } else if name.contains(&query) {
+                    2
+                } else {
+                    return None;
+                };
+                let centrality = self
+                    .indexer
+                    .as_ref()
+                    .map(|indexer| indexer.graph().centrality(path))
+                    .unwrap_or(0.0);
+                Some((rank, centrality, path, export))
+            })
+            .collect();
+        // Exact and prefix matches first, then symbols of central files
+        matches.sort_by(|a, b| {
+            a.0.cmp(&b.0)
+                .then_with(|| b.1.total_cmp(&a.1))
+                .then_with(|| a.3.name.cmp(&b.3.name))
+                .then_with(|| a.2.cmp(b.2))
+        });
+
+        matches
+            .into_iter()
+            .take(MAX_WORKSPACE_SYMBOLS)
+            .filter_map(|(_, _, path, export)| {
+                let absolute = self.absolute(path);
+                let uri = Url::from_file_path(&absolute).ok()?;
+                let line = export.line.saturating_sub(1);
+                let range = self
+                    .content(path)
+                    .and_then(|content| {
+                        content
+                            .lines()
+                            .nth(line as usize)
+                            .map(|text| name_range(text, line, &export.name))
+                    })
+                    .unwrap_or_default();
+                #[allow(deprecated)]
+                Some(SymbolInformation {
+                    name: export.name.clone(),
+                    kind: symbol_kind(export.kind),
+                    tags: None,
+                    deprecated: None,
+                    location: Location { uri, range },
+                    container_name: Some(path.display().to_string()),
+                })
+            })
+            .collect()
+    }
+
+    /// Whole-word uses of `name`, searched in `path`, the files defining
+    /// `name` and the files that import those.
+    pub fn references(
+        &self,
+        path: &Path,
+        content: &str,
+        name: &str,
+        include_declaration: bool,
+    ) -> Vec<Location> {
+        let current = self.relative(path);
+        let definitions: Vec<(&Path, u32)> = self
+            .exports
+            .iter()
+            .flat_map(|(file, exports)| exports.iter().map(move |e| (file.as_path(), e)))
+            .filter(|(_, export)| export.kind != ExportKind::ReExport && export.name == name)
+            .map(|(file, export)| (file, export.line.saturating_sub(1)))
+            .collect();
+
+        let mut files: BTreeSet<PathBuf> = BTreeSet::new();
+        if let Some(indexer) = &self.indexer {
+            for (file, _) in &definitions {
+                files.insert(file.to_path_buf());
+                files.extend(indexer.dependents_of(file));
+            }
+        }
+        if let Some(current) = &current {
+            files.remove(current);
+        }
+
+        let mut locations = Vec::new();
+        if let Ok(uri) = Url::from_file_path(path) {
+            let declarations: Vec<u32> = definitions
+                .iter()
+                .filter(|(file, _)| current.as_deref() == Some(*file))
+                .map(|(_, line)| *line)
+                .collect();
+            locations.extend(find_uses(
+                &uri,
+                content,
+                name,
+                &declarations,
+                include_declaration,
+            ));
+        }
+        for file in files {
+            let Some(text) = self.content(&file) else {
+                continue;
+            };
+            let Ok(uri) = Url::from_file_path(self.absolute(&file)) else {
+                continue;
+            };
+            let declarations: Vec<u32> = definitions
+                .iter()
+                .filter(|(defined_in, _)| *defined_in == file.as_path())
+                .map(|(_, line)| *line)
+                .collect();
+            locations.extend(find_uses(
+                &uri,
+                &text,
+                name,
+                &declarations,
+                include_declaration,
+            ));
+        }
+        locations
+    }
+
+    /// The files most related to `path`, with their current content: what it
+    /// imports first, then what imports it, each ranked by retention score.
+    pub fn related_files(&self, path: &Path, limit: usize) -> Vec<(PathBuf, String)> {
+        let (Some(indexer), Some(relative)) = (&self.indexer, self.relative(path)) else {
+            return Vec::new();
+        };
+        let Some(node) = indexer.graph().get_node(&relative) else {
+            return Vec::new();
+        };
+        let retention = |file: &PathBuf| {
+            indexer
+                .index()
+                .get_file(file)
+                .map(|memory| memory.retention_score)
+                .unwrap_or(0.0)
+        };
+
+        let mut ranked: Vec<(u8, f64, &PathBuf)> = node
+            .dependencies
+            .iter()
+            .map(|file| (0, retention(file), file))
+            .chain(
+                node.dependents
+                    .iter()
+                    .map(|file| (1, retention(file), file)),
+            )
+            .collect();
+        ranked.sort_by(|a, b| a.0.cmp(&b.0).then_with(|| b.1.total_cmp(&a.1)));
+
+        let mut seen = BTreeSet::new();
+        ranked
+            .into_iter()
+            .filter(|(_, _, file)| **file != relative && seen.insert(*file))
+            .filter_map(|(_, _, file)| Some((file.clone(), self.content(file)?)))
+            .take(limit)
+            .collect()
+    }
+
+    /// Current content of a workspace file: the open buffer, else the disk
+    fn content(&self, relative: &Path) -> Option<String> {
+        self.open
+            .get(relative)
+            .cloned()
+            .or_else(|| std::fs::read_to_string(self.absolute(relative)).ok())
+    }
+}
+
+/// Whole-word occurrences of `name`, optionally skipping those on a
+/// declaration line
+fn find_uses(
+    uri: &Url,
+    content: &str,
+    name: &str,
+    declaration_lines: &[u32],
+    include_declaration: bool,
+) -> Vec<Location> {
+    let mut locations = Vec::new();
+    for (line, text) in content.lines().enumerate() {
+        let line = line as u32;
+        if !include_declaration && declaration_lines.contains(&line) {
+            continue;
+        }
+        for (start, _) in text.match_indices(name) {
+            let end = start + name.len();
+            let before = text[..start].chars().next_back();
+            let after = text[end..].chars().next();
+            if before.is_some_and(is_identifier_char) || after.is_some_and(is_identifier_char) {
+                continue;
+            }
+            let character = utf16_len(&text[..start]);
+            locations.push(Location {
+                uri: uri.clone(),
+                range: Range {
+                    start: Position { line, character },
+                    end: Position {
+                        line,
+                        character: character + utf16_len(name),
+                    },
+                },
+            });
+        }
+    }
+    locations
+}
+
+/// Range of the first whole-word `name` on a line, or the line start
+fn name_range(text: &str, line: u32, name: &str) -> Range {
+    let start = text
+        .match_indices(name)
+        .find(|(start, _)| {
+            let before = text[..*start].chars().next_back();
+            let after = text[start + name.len()..].chars().next();
+            !before.is_some_and(is_identifier_char) && !after.is_some_and(is_identifier_char)
+        })
+        .map(|(start, _)| utf16_len(&text[..start]))
+        .unwrap_or(0);
+    Range {
+        start: Position {
+            line,
+            character: start,
+        },
+        end: Position {
+            line,
+            character: start + utf16_len(name),
+        },
+    }
+}
+
+/// LSP positions count UTF-16 code units
+fn utf16_len(text: &str) -> u32 {
+    text.encode_utf16().count() as u32
+}
+
+fn symbol_kind(kind: ExportKind) -> SymbolKind {
+    match kind {
+        ExportKind::Function => SymbolKind::FUNCTION,
+        ExportKind::Type => SymbolKind::CLASS,
+        ExportKind::Constant => SymbolKind::CONSTANT,
+        ExportKind::Module => SymbolKind::MODULE,
+        ExportKind::ReExport | ExportKind::Default | ExportKind::Other => SymbolKind::VARIABLE,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn workspace() -> TempDir {
+        let temp = TempDir::new().unwrap();
+        std::fs::create_dir_all(temp.path().join("src")).unwrap();
+        std::fs::write(
+            temp.path().join("src/lib.rs"),
+            "pub mod utils;\n\nuse crate::utils::helper;\n\npub fn run() {\n    helper();\n}\n",
+        )
+        .unwrap();
+        std::fs::write(
+            temp.path().join("src/utils.rs"),
+            "pub fn helper() {}\n\npub struct HelperConfig;\n",
+        )
+        .unwrap();
+        std::fs::write(temp.path().join("src/other.rs"), "fn helper() {}\n").unwrap();
+        temp
+    }
+
+    fn root(temp: &TempDir) -> PathBuf {
+        temp.path().canonicalize().unwrap()
+    }
+
+    #[test]
+    fn test_document_symbols() {
+        let index = SymbolIndex::default();
+        let symbols = index.document_symbols(
+            Path::new("lib.rs"),
+            "pub struct Config;\n\npub fn load() -> Config {\n    Config\n}\n",
+        );
+        let names: Vec<_> = symbols.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, vec!["Config", "load"]);
+        let load = &symbols[1];
+        assert_eq!(load.kind, SymbolKind::FUNCTION);
+        assert_eq!(load.selection_range.start, Position::new(2, 7));
+        assert_eq!(load.selection_range.end, Position::new(2, 11));
+    }
+
+    #[test]
+    fn test_workspace_symbols_rank_exact_matches_first() {
+        let temp = workspace();
+        let index = SymbolIndex::build(temp.path());
+
+        let symbols = index.workspace_symbols("helper");
+        let names: Vec<_> = symbols.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, vec!["helper", "HelperConfig"]);
+        assert!(symbols[0].location.uri.path().ends_with("src/utils.rs"));
+        assert_eq!(symbols[0].location.range.start, Position::new(0, 7));
+        assert!(index.workspace_symbols("nothing_like_this").is_empty());
+    }
+
+    #[test]
+    fn test_references_follow_dependents() {
+        let temp = workspace();
+        let index = SymbolIndex::build(temp.path());
+        let utils = root(&temp).join("src/utils.rs");
+        let content = std::fs::read_to_string(&utils).unwrap();
+
+        let locations = index.references(&utils, &content, "helper", true);
+        let mut found: Vec<(String, u32)> = locations
+            .iter()
+            .map(|l| {
+                let path = l.uri.to_file_path().unwrap();
+                let file = path.file_name().unwrap().to_string_lossy().to_string();
+                (file, l.range.start.line)
+            })
+            .collect();
+        found.sort();
+        // other.rs has its own private `helper` and doesn't import utils.rs
+        assert_eq!(
+            found,
+            vec![
+                ("lib.rs".to_string(), 2),
+                ("lib.rs".to_string(), 5),
+                ("utils.rs".to_string(), 0),
+            ]
+        );
+
+        let without_declaration = index.references(&utils, &content, "helper", false);
+        assert_eq!(without_declaration.len(), 2);
+    }
+
+    #[test]
+    fn test_related_files_prefer_imports() {
+        let temp = workspace();
+        let index = SymbolIndex::build(temp.path());
+
+        let related = index.related_files(&root(&temp).join("src/lib.rs"), 3);
+        assert_eq!(related.len(), 1);
+        assert_eq!(related[0].0, PathBuf::from("src/utils.rs"));
+        assert!(related[0].1.contains("pub fn helper"));
+
+        let related = index.related_files(&root(&temp).join("src/utils.rs"), 3);
+        assert_eq!(related[0].0, PathBuf::from("src/lib.rs"));
+        assert!(index
+            .related_files(&root(&temp).join("src/other.rs"), 3)
+            .is_empty());
+    }
+
+    #[test]
+    fn test_update_reindexes_unsaved_content() {
+        let temp = workspace();
+        let mut index = SymbolIndex::build(temp.path());
+        let utils = root(&temp).join("src/utils.rs");
+
+        index.update(&utils, "pub fn renamed() {}\n");
+        assert!(index.workspace_symbols("helper").is_empty());
+        assert_eq!(index.workspace_symbols("renamed").len(), 1);
+
+        // lib.rs no longer imports utils.rs, so it drops out of the search
+        let lib = root(&temp).join("src/lib.rs");
+        index.update(&lib, "pub fn run() {}\n");
+        let content = "pub fn renamed() {}\n";
+        assert_eq!(index.references(&utils, content, "renamed", true).len(), 1);
+
+        // Closing falls back to the content on disk
+        index.close(&utils);
+        assert_eq!(index.workspace_symbols("helper").len(), 2);
+    }
+}
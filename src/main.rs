@@ -41,7 +41,7 @@ use ted::llm::message::{ContentBlock, MessageContent};
 use ted::plans::PlanStore;
 #[cfg(test)]
 use ted::tools::ToolResult;
-use ted::tools::{ToolContext, ToolExecutor};
+use ted::tools::{FileReadTracker, ToolContext, ToolExecutor};
 use ted::tui::chat::{run_chat_tui_loop, ChatTuiConfig};
 use ted::utils;
 
@@ -251,14 +251,18 @@ async fn run_chat(args: ChatArgs, mut settings: Settings, verbose: u8) -> Result
 
     // Use TUI or simple mode
     if !args.no_tui {
-        // TUI mode requires trust mode for tool permissions since interactive
-        // prompts don't work in raw terminal mode. We'll add a TUI permission
-        // dialog in the future, but for now auto-approve is necessary.
+        // The executor auto-approves in TUI mode since interactive prompts
+        // don't work in raw terminal mode. File edits are instead reviewed
+        // hunk by hunk in the TUI unless --trust is set.
         let tui_trust_mode = true;
 
         // Set environment variable to suppress agent output in TUI mode
         std::env::set_var("TED_TUI_MODE", "1");
 
+        // Questions from ask_user are answered in the TUI picker
+        let (question_tx, question_rx) = tokio::sync::mpsc::unbounded_channel();
+        let (tool_progress_tx, tool_progress_rx) = tokio::sync::mpsc::unbounded_channel();
+
         // Recreate tool executor with trust mode for TUI
         let tui_tool_context = ToolContext::new(
             working_directory.clone(),
@@ -266,8 +270,17 @@ async fn run_chat(args: ChatArgs, mut settings: Settings, verbose: u8) -> Result
             session_id.0,
             tui_trust_mode,
         )
-        .with_files_in_context(args.files_in_context.clone());
-        let mut tui_tool_executor = ToolExecutor::new(tui_tool_context, tui_trust_mode);
+        .with_files_in_context(args.files_in_context.clone())
+        .with_read_tracker(FileReadTracker::new())
+        .with_question_sender(question_tx)
+        .with_progress_sender(tool_progress_tx);
+        let mut tui_tool_executor = ToolExecutor::new(tui_tool_context, tui_trust_mode)
+            .with_plan_mode(tool_executor.plan_mode().clone());
+        tui_tool_executor
+            .attach_mcp_servers(tool_executor.mcp_servers().clone())
+            .await;
+        // Sampling approvals go to the TUI picker from here on
+        tui_tool_executor.enable_mcp_sampling(provider.clone(), model.to_string());
 
         // Re-register spawn_agent tool for TUI executor with progress tracking
         let agent_progress_tracker = tui_tool_executor
@@ -284,7 +297,7 @@ async fn run_chat(args: ChatArgs, mut settings: Settings, verbose: u8) -> Result
             provider_name: provider_name.clone(),
             model: model.clone(),
             caps: cap_names.clone(),
-            trust_mode: tui_trust_mode,
+            trust_mode: args.trust,
             stream_enabled: !args.no_stream,
         };
 
@@ -298,6 +311,8 @@ async fn run_chat(args: ChatArgs, mut settings: Settings, verbose: u8) -> Result
             history_store,
             session_info,
             agent_progress_tracker,
+            question_rx,
+            tool_progress_rx,
         )
         .await;
     }
@@ -315,10 +330,17 @@ async fn run_chat(args: ChatArgs, mut settings: Settings, verbose: u8) -> Result
         &merged_cap.source_caps,
     )?;
 
+    if tool_executor.plan_mode().is_enabled() {
+        let mut stdout = io::stdout();
+        stdout.execute(SetForegroundColor(Color::Yellow))?;
+        println!("Plan mode on - changes are disabled until you run /plan-mode approve\n");
+        stdout.execute(ResetColor)?;
+    }
+
     // Main chat loop (simple mode - used when --no-tui is set)
     loop {
         // Get user input
-        let input = read_user_input()?;
+        let mut input = read_user_input()?;
 
         // Check for shell command (starts with >)
         if input.trim().starts_with('>') {
@@ -854,6 +876,39 @@ async fn run_chat(args: ChatArgs, mut settings: Settings, verbose: u8) -> Result
             continue;
         }
 
+        // Check for /mcp command (external MCP server status)
+        if chat::input_parser::is_mcp_command(&trimmed) {
+            println!("\n{}\n", tool_executor.mcp_servers().format_status());
+            continue;
+        }
+
+        // Check for /plan-mode command (enter, leave or approve plan mode)
+        if let Some(args) = chat::input_parser::parse_plan_mode_command(&trimmed) {
+            match chat::slash_commands::execute_plan_mode(
+                &args,
+                tool_executor.plan_mode(),
+                &mut conversation,
+                session_id.0,
+            ) {
+                chat::slash_commands::SlashCommandResult::SendToLlm(message) => {
+                    let mut stdout = io::stdout();
+                    stdout.execute(SetForegroundColor(Color::Green))?;
+                    println!("\n✓ Plan approved. Plan mode off.\n");
+                    stdout.execute(ResetColor)?;
+                    input = message;
+                }
+                chat::slash_commands::SlashCommandResult::Message(message) => {
+                    println!("\n{}\n", message);
+                    continue;
+                }
+                chat::slash_commands::SlashCommandResult::Error(error) => {
+                    eprintln!("\n{}\n", error);
+                    continue;
+                }
+                chat::slash_commands::SlashCommandResult::SpawnAgent { .. } => continue,
+            }
+        }
+
         // Check for /model command (show or switch model)
         if trimmed == "/model" || trimmed == "/models" {
             println!("\nCurrent model: {}", model);
@@ -1220,6 +1275,20 @@ blocked_commands = []
             continue;
         }
 
+        // UserPromptSubmit hooks may block, rewrite or annotate the prompt
+        let prompt_hooks = tool_executor
+            .hooks()
+            .user_prompt_submit(tool_executor.context().session_id, &input)
+            .await;
+        if let Some(reason) = &prompt_hooks.block_reason {
+            let mut stdout = io::stdout();
+            stdout.execute(SetForegroundColor(Color::Red))?;
+            println!("\n✗ Prompt blocked by hook: {}\n", reason);
+            stdout.execute(ResetColor)?;
+            continue;
+        }
+        let input = prompt_hooks.apply_to_prompt(&input);
+
         // Store user message in context
         context_manager.store_message("user", &input, None).await?;
 
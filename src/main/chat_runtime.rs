@@ -24,6 +24,7 @@ use ted::llm::message::Conversation;
 use ted::llm::provider::LlmProvider;
 use ted::llm::providers::AnthropicProvider;
 use ted::skills::SkillRegistry;
+use ted::tools::plan_mode::PLAN_MODE_CONTEXT;
 use ted::tools::ToolExecutor;
 
 use super::chat_ui::{prompt_session_choice, resume_session};
@@ -286,7 +287,11 @@ pub(super) async fn initialize_chat_runtime(
                     provider_name = "anthropic".to_string();
                     settings.defaults.provider = "anthropic".to_string();
                     settings.save()?;
-                    Arc::new(AnthropicProvider::new(api_key))
+                    ProviderFactory::with_redaction(
+                        Arc::new(AnthropicProvider::new(api_key)),
+                        &provider_name,
+                        &settings,
+                    )
                 } else {
                     // No other provider available — run setup flow
                     settings = check_provider_configuration(&Settings::default(), "none")?;
@@ -424,6 +429,25 @@ pub(super) async fn initialize_chat_runtime(
             );
     }
 
+    // Plan mode keeps mutating tools disabled until /plan-mode approve
+    if args.plan {
+        tool_executor.plan_mode().set_enabled(true);
+        conversation.set_pinned_context(Some(PLAN_MODE_CONTEXT.to_string()));
+    }
+
+    // Mount tools from external MCP servers configured under mcpServers
+    tool_executor.connect_mcp_servers().await;
+    tool_executor.enable_mcp_sampling(provider.clone(), model.clone());
+    for status in tool_executor.mcp_servers().statuses() {
+        if status.state == ted::mcp::McpServerState::Failed {
+            eprintln!(
+                "Warning: MCP server '{}' failed to start: {}",
+                status.name,
+                status.last_error.as_deref().unwrap_or("unknown error")
+            );
+        }
+    }
+
     // Update session info
     session_info.project_root = project_root.clone();
     if !is_resumed {
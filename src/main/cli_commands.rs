@@ -454,6 +454,7 @@ pub(super) fn print_help() -> Result<()> {
     println!("  /switch    - Switch to a different session");
     println!("  /new       - Start a new session");
     println!("  /plans     - Browse and manage work plans");
+    println!("  /plan-mode - Explore read-only until you approve a plan (approve/off)");
     println!("  /clear     - Clear conversation context");
     println!("  /stats     - Show context/session statistics");
     println!("  /help      - Show this help message");
@@ -838,6 +839,7 @@ fn format_policy_source(source: &PolicySource) -> String {
     match source {
         PolicySource::User(path) => format!("user ({})", path.display()),
         PolicySource::Project(path) => format!("project ({})", path.display()),
+        PolicySource::Builtin => "built-in".to_string(),
     }
 }
 
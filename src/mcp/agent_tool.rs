@@ -0,0 +1,347 @@
+// SPDX-License-Identifier: AGPL-3.0-or-later
+// Copyright (C) 2025 Blackman Artificial Intelligence Technologies Inc.
+
+//! `ted_agent` MCP tool
+//!
+//! Hands a whole task to Ted: the tool runs the full agent loop with the
+//! configured provider and returns the agent's report, so any MCP host can
+//! use Ted as a sub-agent. Progress is reported through the tool context and
+//! surfaces as `notifications/progress` when the client asked for it.
+
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use serde_json::Value;
+use tokio::sync::OnceCell;
+
+use crate::agents::{
+    get_agent_type_names, is_valid_agent_type, AgentConfig, AgentContext, AgentProgressEvent,
+    AgentRunner, RunnerConfig,
+};
+use crate::config::Settings;
+use crate::error::{Result, TedError};
+use crate::llm::factory::ProviderFactory;
+use crate::llm::provider::{LlmProvider, ToolDefinition};
+use crate::tools::{PermissionRequest, SchemaBuilder, Tool, ToolContext, ToolResult};
+
+/// Name of the tool as seen by MCP clients
+pub const TED_AGENT_TOOL: &str = "ted_agent";
+
+/// Agent type used when the caller does not pick one
+const DEFAULT_AGENT_TYPE: &str = "implement";
+
+/// Runs Ted's agent loop on a delegated task
+pub struct TedAgentTool {
+    settings: Settings,
+    provider_name: String,
+    model: String,
+    working_dir: PathBuf,
+    /// Created on first use so the server starts without credentials
+    provider: OnceCell<Arc<dyn LlmProvider>>,
+}
+
+impl TedAgentTool {
+    /// Tool using the default provider and model from `settings`
+    pub fn from_settings(settings: Settings, working_dir: PathBuf) -> Self {
+        let provider_name = ProviderFactory::resolve_provider_name(None, &settings);
+        let model = ProviderFactory::default_model(&provider_name, &settings);
+        Self {
+            settings,
+            provider_name,
+            model,
+            working_dir,
+            provider: OnceCell::new(),
+        }
+    }
+
+    /// Tool using an already created provider
+    pub fn with_provider(
+        provider: Arc<dyn LlmProvider>,
+        model: impl Into<String>,
+        working_dir: PathBuf,
+    ) -> Self {
+        let settings = Settings::default();
+        Self {
+            provider_name: ProviderFactory::resolve_provider_name(None, &settings),
+            settings,
+            model: model.into(),
+            working_dir,
+            provider: OnceCell::new_with(Some(provider)),
+        }
+    }
+
+    async fn provider(&self) -> Result<Arc<dyn LlmProvider>> {
+        self.provider
+            .get_or_try_init(|| ProviderFactory::create(&self.provider_name, &self.settings, false))
+            .await
+            .cloned()
+    }
+
+    fn build_config(&self, input: &Value) -> std::result::Result<AgentConfig, String> {
+        let task = input["task"]
+            .as_str()
+            .map(str::trim)
+            .filter(|task| !task.is_empty())
+            .ok_or_else(|| "task is required".to_string())?;
+        let agent_type = input["agent_type"].as_str().unwrap_or(DEFAULT_AGENT_TYPE);
+        if !is_valid_agent_type(agent_type) {
+            return Err(format!(
+                "Invalid agent type '{}'. Available types: {}",
+                agent_type,
+                get_agent_type_names().join(", ")
+            ));
+        }
+
+        let mut config = AgentConfig::new(agent_type, task, self.working_dir.clone())
+            .with_model(self.model.clone());
+        if let Some(caps) = input["caps"].as_array() {
+            config = config.with_caps(
+                caps.iter()
+                    .filter_map(|cap| cap.as_str().map(String::from))
+                    .collect(),
+            );
+        }
+        if let Some(max) = input["max_iterations"].as_u64() {
+            config = config.with_max_iterations(max.clamp(1, u32::MAX as u64) as u32);
+        }
+        if let Some(budget) = input["token_budget"].as_u64() {
+            config = config.with_token_budget(budget.clamp(1, u32::MAX as u64) as u32);
+        }
+        Ok(config)
+    }
+}
+
+#[async_trait]
+impl Tool for TedAgentTool {
+    fn name(&self) -> &str {
+        TED_AGENT_TOOL
+    }
+
+    fn definition(&self) -> ToolDefinition {
+        let agent_types = get_agent_type_names().join(", ");
+        ToolDefinition {
+            name: TED_AGENT_TOOL.to_string(),
+            description: format!(
+                "Delegate a whole task to Ted. Ted plans, reads and edits files and runs \
+                 commands in its workspace until the task is done, then reports what it \
+                 did and which files changed. Agent types: {}",
+                agent_types
+            ),
+            input_schema: SchemaBuilder::new()
+                .string("task", "The task to accomplish. Be specific.", true)
+                .string(
+                    "agent_type",
+                    &format!(
+                        "Type of agent (default: {}). Options: {}",
+                        DEFAULT_AGENT_TYPE, agent_types
+                    ),
+                    false,
+                )
+                .array("caps", "Caps (personas) to load", "string", false)
+                .integer(
+                    "max_iterations",
+                    "Maximum iterations before stopping (default: from agent type)",
+                    false,
+                )
+                .integer(
+                    "token_budget",
+                    "Token budget for the whole run (default: 100000)",
+                    false,
+                )
+                .build(),
+        }
+    }
+
+    async fn execute(
+        &self,
+        tool_use_id: String,
+        input: Value,
+        context: &ToolContext,
+    ) -> Result<ToolResult> {
+        let config = match self.build_config(&input) {
+            Ok(config) => config,
+            Err(message) => return Ok(ToolResult::error(tool_use_id, message)),
+        };
+        let provider = match self.provider().await {
+            Ok(provider) => provider,
+            Err(e) => {
+                return Ok(ToolResult::error(
+                    tool_use_id,
+                    format!("No LLM provider available: {}", e),
+                ))
+            }
+        };
+
+        // Output on stderr would interleave with the host's logs
+        let runner = AgentRunner::with_config(
+            provider,
+            RunnerConfig {
+                quiet: true,
+                ..Default::default()
+            },
+        );
+        let (progress_tx, mut progress_rx) =
+            tokio::sync::mpsc::unbounded_channel::<AgentProgressEvent>();
+
+        let run = runner.run_with_progress(AgentContext::new(config), Some(progress_tx));
+        let forward = async {
+            let mut percent = None;
+            while let Some(event) = progress_rx.recv().await {
+                match &event {
+                    AgentProgressEvent::IterationStart {
+                        iteration,
+                        max_iterations,
+                    } if *max_iterations > 0 => {
+                        percent = Some((iteration * 100 / max_iterations).min(100) as u8);
+                    }
+                    // Duplicates of ToolStart/ToolComplete with full payloads
+                    AgentProgressEvent::ToolCallStarted { .. }
+                    | AgentProgressEvent::ToolCallCompleted { .. } => continue,
+                    _ => {}
+                }
+                context.emit_tool_progress(
+                    &tool_use_id,
+                    TED_AGENT_TOOL,
+                    event.status_text(),
+                    percent,
+                );
+            }
+        };
+        let (result, ()) = tokio::join!(run, forward);
+
+        match result {
+            Ok(result) if result.success => {
+                Ok(ToolResult::success(tool_use_id, result.format_for_parent()))
+            }
+            Ok(result) => Ok(ToolResult::error(tool_use_id, result.format_for_parent())),
+            Err(TedError::InvalidInput(message)) => Ok(ToolResult::error(tool_use_id, message)),
+            Err(e) => Ok(ToolResult::error(
+                tool_use_id,
+                format!("Agent execution failed: {}", e),
+            )),
+        }
+    }
+
+    fn permission_request(&self, input: &Value) -> Option<PermissionRequest> {
+        let task: String = input["task"]
+            .as_str()
+            .unwrap_or("unknown task")
+            .chars()
+            .take(80)
+            .collect();
+        Some(PermissionRequest {
+            tool_name: TED_AGENT_TOOL.to_string(),
+            action_description: format!("Run Ted agent: {}", task),
+            affected_paths: Vec::new(),
+            is_destructive: false,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::llm::MockProvider;
+    use crate::tools::{ToolOutput, ToolProgressEvent};
+    use tempfile::TempDir;
+
+    fn tool(temp: &TempDir, provider: MockProvider) -> TedAgentTool {
+        TedAgentTool::with_provider(Arc::new(provider), "mock-model", temp.path().to_path_buf())
+    }
+
+    fn context(temp: &TempDir) -> ToolContext {
+        ToolContext::new(
+            temp.path().to_path_buf(),
+            Some(temp.path().to_path_buf()),
+            uuid::Uuid::new_v4(),
+            false,
+        )
+    }
+
+    #[test]
+    fn test_definition() {
+        let temp = TempDir::new().unwrap();
+        let definition = tool(&temp, MockProvider::new()).definition();
+        assert_eq!(definition.name, "ted_agent");
+        let schema = serde_json::to_value(&definition.input_schema).unwrap();
+        assert_eq!(schema["required"], serde_json::json!(["task"]));
+        assert!(schema["properties"]["token_budget"].is_object());
+    }
+
+    #[test]
+    fn test_build_config() {
+        let temp = TempDir::new().unwrap();
+        let tool = tool(&temp, MockProvider::new());
+        let config = tool
+            .build_config(&serde_json::json!({
+                "task": "Add tests",
+                "agent_type": "explore",
+                "caps": ["rust-expert"],
+                "max_iterations": 5,
+                "token_budget": 20000
+            }))
+            .unwrap();
+        assert_eq!(config.agent_type, "explore");
+        assert_eq!(config.caps, vec!["rust-expert".to_string()]);
+        assert_eq!(config.max_iterations, 5);
+        assert_eq!(config.token_budget, 20000);
+        assert_eq!(config.model.as_deref(), Some("mock-model"));
+
+        let default = tool
+            .build_config(&serde_json::json!({"task": "Add tests"}))
+            .unwrap();
+        assert_eq!(default.agent_type, "implement");
+
+        assert!(tool.build_config(&serde_json::json!({})).is_err());
+        assert!(tool
+            .build_config(&serde_json::json!({"task": "x", "agent_type": "wizard"}))
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_execute_runs_agent_and_reports_progress() {
+        let temp = TempDir::new().unwrap();
+        let tool = tool(&temp, MockProvider::new().with_response("All done."));
+        let (progress_tx, mut progress_rx) =
+            tokio::sync::mpsc::unbounded_channel::<ToolProgressEvent>();
+        let context = context(&temp).with_progress_sender(progress_tx);
+
+        let result = tool
+            .execute(
+                "call-1".to_string(),
+                serde_json::json!({"task": "Say hi", "agent_type": "explore"}),
+                &context,
+            )
+            .await
+            .unwrap();
+
+        let ToolOutput::Success(text) = result.output else {
+            panic!("expected success, got {:?}", result.output);
+        };
+        assert!(text.contains("Completed"));
+        assert!(text.contains("All done."));
+
+        drop(context);
+        let mut events = Vec::new();
+        while let Some(event) = progress_rx.recv().await {
+            events.push(event);
+        }
+        assert!(events.iter().all(|e| e.tool_name == "ted_agent"));
+        assert!(events
+            .iter()
+            .any(|e| e.message.contains("Starting explore")));
+        assert!(events.iter().any(|e| e.message.starts_with("Done")));
+    }
+
+    #[tokio::test]
+    async fn test_execute_rejects_missing_task() {
+        let temp = TempDir::new().unwrap();
+        let tool = tool(&temp, MockProvider::new());
+        let result = tool
+            .execute("call-1".to_string(), serde_json::json!({}), &context(&temp))
+            .await
+            .unwrap();
+        assert!(matches!(result.output, ToolOutput::Error(ref e) if e.contains("task")));
+    }
+}
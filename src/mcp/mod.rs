@@ -1,14 +1,29 @@
 // SPDX-License-Identifier: AGPL-3.0-or-later
 // Copyright (C) 2025 Blackman Artificial Intelligence Technologies Inc.
 
-//! Model Context Protocol (MCP) server implementation
+//! Model Context Protocol (MCP) support
 //!
-//! Exposes Ted's tools to MCP-compatible clients like Claude Desktop
+//! - `server`: exposes Ted's tools, resources and prompts to MCP-compatible
+//!   clients like Claude Desktop, including `ted_agent` for delegating whole
+//!   tasks
+//! - `client`: mounts tools from external MCP servers configured in settings
+//! - `sampling`: serves their `sampling/createMessage` requests with Ted's
+//!   provider
 
+pub mod agent_tool;
+pub mod client;
+pub mod prompts;
 pub mod protocol;
+pub mod resources;
+pub mod sampling;
 pub mod server;
 pub mod transport;
 
+pub use agent_tool::TedAgentTool;
+pub use client::{McpClientManager, McpServerState, McpServerStatus};
+pub use prompts::PromptCatalog;
 pub use protocol::*;
+pub use resources::WorkspaceResources;
+pub use sampling::{McpSampler, SamplingUsage};
 pub use server::*;
 pub use transport::*;
@@ -0,0 +1,278 @@
+// SPDX-License-Identifier: AGPL-3.0-or-later
+// Copyright (C) 2025 Blackman Artificial Intelligence Technologies Inc.
+
+//! MCP prompts
+//!
+//! Caps are exposed as `cap:<name>` prompts carrying the cap's rendered
+//! persona, and custom commands from `.ted/commands/` as `command:<name>`
+//! prompts that describe the script for the client's model to run.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use super::protocol::{GetPromptResult, Prompt, PromptArgument, PromptMessage, ToolContent};
+use crate::caps::render::render_system_prompt;
+use crate::caps::{CapLoader, CapResolver};
+use crate::commands::{discover_commands_in, CustomCommand};
+use crate::config::Settings;
+use crate::error::{Result, TedError};
+
+const CAP_PREFIX: &str = "cap:";
+const COMMAND_PREFIX: &str = "command:";
+
+/// Longest script body included in a command prompt
+const MAX_SCRIPT_CHARS: usize = 8000;
+
+/// Caps and custom commands for one workspace
+#[derive(Clone)]
+pub struct PromptCatalog {
+    cap_loader: CapLoader,
+    user_commands_dir: PathBuf,
+    project_commands_dir: PathBuf,
+}
+
+impl PromptCatalog {
+    /// Catalog for `workspace`, using its `.ted/` plus the user's `~/.ted/`
+    pub fn new(workspace: &Path) -> Self {
+        let project_caps_dir = workspace.join(".ted").join("caps");
+        Self {
+            cap_loader: CapLoader::with_paths(
+                Some(project_caps_dir).filter(|dir| dir.exists()),
+                Settings::caps_dir(),
+            ),
+            user_commands_dir: Settings::commands_dir(),
+            project_commands_dir: workspace.join(".ted").join("commands"),
+        }
+    }
+
+    /// Catalog with explicit cap loader and command directories
+    pub fn with_paths(
+        cap_loader: CapLoader,
+        user_commands_dir: PathBuf,
+        project_commands_dir: PathBuf,
+    ) -> Self {
+        Self {
+            cap_loader,
+            user_commands_dir,
+            project_commands_dir,
+        }
+    }
+
+    fn commands(&self) -> Result<HashMap<String, CustomCommand>> {
+        discover_commands_in(&self.user_commands_dir, Some(&self.project_commands_dir))
+    }
+
+    /// Every prompt, caps first, each group sorted by name
+    pub fn list(&self) -> Result<Vec<Prompt>> {
+        let mut prompts: Vec<Prompt> = self
+            .cap_loader
+            .list_available()?
+            .into_iter()
+            .map(|(name, builtin)| {
+                let description = self
+                    .cap_loader
+                    .load(&name)
+                    .ok()
+                    .map(|cap| cap.description)
+                    .filter(|description| !description.is_empty())
+                    .unwrap_or_else(|| {
+                        if builtin {
+                            format!("Built-in {} persona", name)
+                        } else {
+                            format!("Custom {} persona", name)
+                        }
+                    });
+                Prompt {
+                    name: format!("{}{}", CAP_PREFIX, name),
+                    description: Some(description),
+                    arguments: vec![PromptArgument {
+                        name: "task".to_string(),
+                        description: Some("Task to work on with this persona".to_string()),
+                        required: false,
+                    }],
+                }
+            })
+            .collect();
+
+        let mut commands: Vec<CustomCommand> = self.commands()?.into_values().collect();
+        commands.sort_by(|a, b| a.name.cmp(&b.name));
+        prompts.extend(commands.into_iter().map(|command| Prompt {
+            name: format!("{}{}", COMMAND_PREFIX, command.name),
+            description: Some(format!(
+                "Ted custom command ({})",
+                if command.is_local { "project" } else { "user" }
+            )),
+            arguments: vec![PromptArgument {
+                name: "args".to_string(),
+                description: Some("Arguments to pass to the command".to_string()),
+                required: false,
+            }],
+        }));
+
+        Ok(prompts)
+    }
+
+    /// Render a prompt with its arguments
+    pub fn get(&self, name: &str, arguments: &HashMap<String, String>) -> Result<GetPromptResult> {
+        if let Some(cap_name) = name.strip_prefix(CAP_PREFIX) {
+            return self.cap_prompt(cap_name, arguments);
+        }
+        if let Some(command_name) = name.strip_prefix(COMMAND_PREFIX) {
+            return self.command_prompt(command_name, arguments);
+        }
+        Err(TedError::InvalidInput(format!("Unknown prompt: {}", name)))
+    }
+
+    fn cap_prompt(
+        &self,
+        name: &str,
+        arguments: &HashMap<String, String>,
+    ) -> Result<GetPromptResult> {
+        if !self.cap_loader.exists(name) {
+            return Err(TedError::InvalidInput(format!(
+                "Unknown prompt: cap:{}",
+                name
+            )));
+        }
+        let merged =
+            CapResolver::new(self.cap_loader.clone()).resolve_and_merge(&[name.to_string()])?;
+        let mut text = render_system_prompt(&merged);
+        if let Some(task) = arguments.get("task").filter(|task| !task.trim().is_empty()) {
+            text.push_str(&format!("\n\n# Task\n\n{}", task.trim()));
+        }
+
+        Ok(GetPromptResult {
+            description: Some(format!("Ted {} persona", name)),
+            messages: vec![user_message(text)],
+        })
+    }
+
+    fn command_prompt(
+        &self,
+        name: &str,
+        arguments: &HashMap<String, String>,
+    ) -> Result<GetPromptResult> {
+        let command = self
+            .commands()?
+            .remove(name)
+            .ok_or_else(|| TedError::InvalidInput(format!("Unknown prompt: command:{}", name)))?;
+        let script = std::fs::read_to_string(&command.path).unwrap_or_default();
+        let script: String = script.chars().take(MAX_SCRIPT_CHARS).collect();
+
+        let args = arguments
+            .get("args")
+            .map(|args| args.trim())
+            .filter(|args| !args.is_empty());
+        let mut text = format!(
+            "Run the Ted custom command `{}` from the workspace root:\n\n```sh\n{}{}\n```\n",
+            name,
+            command.path.display(),
+            args.map(|args| format!(" {}", args)).unwrap_or_default()
+        );
+        if !script.is_empty() {
+            text.push_str(&format!(
+                "\nThe command runs this script:\n\n```\n{}\n```\n",
+                script.trim_end()
+            ));
+        }
+
+        Ok(GetPromptResult {
+            description: Some(format!("Ted custom command {}", name)),
+            messages: vec![user_message(text)],
+        })
+    }
+}
+
+fn user_message(text: String) -> PromptMessage {
+    PromptMessage {
+        role: "user".to_string(),
+        content: ToolContent::Text { text },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn catalog(temp: &TempDir) -> PromptCatalog {
+        let caps_dir = temp.path().join("caps");
+        let commands_dir = temp.path().join("commands");
+        std::fs::create_dir_all(&caps_dir).unwrap();
+        std::fs::create_dir_all(&commands_dir).unwrap();
+        std::fs::write(
+            caps_dir.join("reviewer.toml"),
+            "name = \"reviewer\"\ndescription = \"Strict reviewer\"\nsystem_prompt = \"Review carefully.\"\n",
+        )
+        .unwrap();
+
+        let script = commands_dir.join("deploy.sh");
+        std::fs::write(&script, "#!/bin/sh\necho deploying\n").unwrap();
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+        }
+
+        PromptCatalog::with_paths(
+            CapLoader::with_paths(Some(caps_dir), temp.path().join("user-caps")),
+            temp.path().join("user-commands"),
+            commands_dir,
+        )
+    }
+
+    #[test]
+    fn test_list_includes_caps_and_commands() {
+        let temp = TempDir::new().unwrap();
+        let prompts = catalog(&temp).list().unwrap();
+
+        let reviewer = prompts.iter().find(|p| p.name == "cap:reviewer").unwrap();
+        assert_eq!(reviewer.description.as_deref(), Some("Strict reviewer"));
+        assert!(
+            prompts
+                .iter()
+                .filter(|p| p.name.starts_with("cap:"))
+                .count()
+                > 1
+        );
+        #[cfg(unix)]
+        assert!(prompts.iter().any(|p| p.name == "command:deploy"));
+    }
+
+    #[test]
+    fn test_get_cap_prompt_includes_persona_and_task() {
+        let temp = TempDir::new().unwrap();
+        let arguments = HashMap::from([("task".to_string(), "Review src/lib.rs".to_string())]);
+        let result = catalog(&temp).get("cap:reviewer", &arguments).unwrap();
+
+        let ToolContent::Text { text } = &result.messages[0].content else {
+            panic!("expected text content");
+        };
+        assert_eq!(result.messages[0].role, "user");
+        assert!(text.contains("Review carefully."));
+        assert!(text.ends_with("Review src/lib.rs"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_get_command_prompt_includes_script() {
+        let temp = TempDir::new().unwrap();
+        let arguments = HashMap::from([("args".to_string(), "staging".to_string())]);
+        let result = catalog(&temp).get("command:deploy", &arguments).unwrap();
+
+        let ToolContent::Text { text } = &result.messages[0].content else {
+            panic!("expected text content");
+        };
+        assert!(text.contains("deploy.sh staging"));
+        assert!(text.contains("echo deploying"));
+    }
+
+    #[test]
+    fn test_get_unknown_prompt() {
+        let temp = TempDir::new().unwrap();
+        let catalog = catalog(&temp);
+        assert!(catalog.get("cap:missing", &HashMap::new()).is_err());
+        assert!(catalog.get("command:missing", &HashMap::new()).is_err());
+        assert!(catalog.get("other", &HashMap::new()).is_err());
+    }
+}
@@ -8,9 +8,24 @@
 
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
-
-/// MCP protocol version
-pub const PROTOCOL_VERSION: &str = "2024-11-05";
+use std::collections::HashMap;
+
+/// Latest MCP protocol version, offered when the client asks for one we
+/// don't support
+pub const PROTOCOL_VERSION: &str = "2025-06-18";
+
+/// Protocol revisions the server can speak, newest first
+pub const SUPPORTED_PROTOCOL_VERSIONS: &[&str] = &["2025-06-18", "2025-03-26", "2024-11-05"];
+
+/// Version to answer `initialize` with: the client's if supported, otherwise
+/// the latest
+pub fn negotiate_protocol_version(requested: &str) -> &'static str {
+    SUPPORTED_PROTOCOL_VERSIONS
+        .iter()
+        .find(|version| **version == requested)
+        .copied()
+        .unwrap_or(PROTOCOL_VERSION)
+}
 
 /// JSON-RPC 2.0 request
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -95,17 +110,27 @@ pub struct ServerCapabilities {
     pub prompts: Option<PromptsCapability>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub resources: Option<ResourcesCapability>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub logging: Option<LoggingCapability>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ToolsCapability {
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(
+        rename = "listChanged",
+        alias = "list_changed",
+        skip_serializing_if = "Option::is_none"
+    )]
     pub list_changed: Option<bool>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PromptsCapability {
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(
+        rename = "listChanged",
+        alias = "list_changed",
+        skip_serializing_if = "Option::is_none"
+    )]
     pub list_changed: Option<bool>,
 }
 
@@ -113,10 +138,18 @@ pub struct PromptsCapability {
 pub struct ResourcesCapability {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub subscribe: Option<bool>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(
+        rename = "listChanged",
+        alias = "list_changed",
+        skip_serializing_if = "Option::is_none"
+    )]
     pub list_changed: Option<bool>,
 }
 
+/// Logging support (`logging/setLevel`); carries no options
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LoggingCapability {}
+
 /// MCP client capabilities
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ClientCapabilities {
@@ -128,7 +161,11 @@ pub struct ClientCapabilities {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RootsCapability {
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(
+        rename = "listChanged",
+        alias = "list_changed",
+        skip_serializing_if = "Option::is_none"
+    )]
     pub list_changed: Option<bool>,
 }
 
@@ -175,8 +212,11 @@ pub struct Tool {
 
 /// Tools list result
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct ToolsListResult {
     pub tools: Vec<Tool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<String>,
 }
 
 /// Tool call request params
@@ -191,7 +231,11 @@ pub struct CallToolParams {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CallToolResult {
     pub content: Vec<ToolContent>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(
+        rename = "isError",
+        alias = "is_error",
+        skip_serializing_if = "Option::is_none"
+    )]
     pub is_error: Option<bool>,
 }
 
@@ -202,7 +246,11 @@ pub enum ToolContent {
     #[serde(rename = "text")]
     Text { text: String },
     #[serde(rename = "image")]
-    Image { data: String, mime_type: String },
+    Image {
+        data: String,
+        #[serde(rename = "mimeType", alias = "mime_type")]
+        mime_type: String,
+    },
     #[serde(rename = "resource")]
     Resource { resource: ResourceContent },
 }
@@ -211,7 +259,11 @@ pub enum ToolContent {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ResourceContent {
     pub uri: String,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(
+        rename = "mimeType",
+        alias = "mime_type",
+        skip_serializing_if = "Option::is_none"
+    )]
     pub mime_type: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub text: Option<String>,
@@ -219,6 +271,159 @@ pub struct ResourceContent {
     pub blob: Option<String>,
 }
 
+/// Resource listed by `resources/list`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Resource {
+    pub uri: String,
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mime_type: Option<String>,
+}
+
+/// Resources list result
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResourcesListResult {
+    pub resources: Vec<Resource>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<String>,
+}
+
+/// Params naming a single resource (`resources/read`, `resources/subscribe`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResourceUriParams {
+    pub uri: String,
+}
+
+/// Resource read result
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReadResourceResult {
+    pub contents: Vec<ResourceContent>,
+}
+
+/// Prompt listed by `prompts/list`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Prompt {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub arguments: Vec<PromptArgument>,
+}
+
+/// Argument accepted by a prompt
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PromptArgument {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub required: bool,
+}
+
+/// Prompts list result
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PromptsListResult {
+    pub prompts: Vec<Prompt>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<String>,
+}
+
+/// Prompt get request params
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GetPromptParams {
+    pub name: String,
+    #[serde(default)]
+    pub arguments: Option<HashMap<String, String>>,
+}
+
+/// Prompt get result
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GetPromptResult {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub messages: Vec<PromptMessage>,
+}
+
+/// Message returned by `prompts/get`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PromptMessage {
+    pub role: String,
+    pub content: ToolContent,
+}
+
+/// Syslog severity used by `logging/setLevel` and `notifications/message`,
+/// ordered from least to most severe
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogLevel {
+    Debug,
+    Info,
+    Notice,
+    Warning,
+    Error,
+    Critical,
+    Alert,
+    Emergency,
+}
+
+/// `logging/setLevel` params
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SetLevelParams {
+    pub level: LogLevel,
+}
+
+/// `notifications/cancelled` params
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CancelledParams {
+    pub request_id: Value,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+/// Message in a `sampling/createMessage` request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SamplingMessage {
+    pub role: String,
+    pub content: ToolContent,
+}
+
+/// `sampling/createMessage` params, sent by a server to its client
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateMessageParams {
+    pub messages: Vec<SamplingMessage>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub system_prompt: Option<String>,
+    pub max_tokens: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f32>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub stop_sequences: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model_preferences: Option<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub include_context: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<Value>,
+}
+
+/// `sampling/createMessage` result
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateMessageResult {
+    pub role: String,
+    pub content: ToolContent,
+    pub model: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stop_reason: Option<String>,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -233,6 +438,74 @@ mod tests {
         assert!(PROTOCOL_VERSION.len() >= 10);
     }
 
+    #[test]
+    fn test_negotiate_protocol_version() {
+        assert_eq!(negotiate_protocol_version("2024-11-05"), "2024-11-05");
+        assert_eq!(negotiate_protocol_version("2025-03-26"), "2025-03-26");
+        assert_eq!(negotiate_protocol_version("2025-06-18"), "2025-06-18");
+        assert_eq!(negotiate_protocol_version("1999-01-01"), PROTOCOL_VERSION);
+        assert_eq!(SUPPORTED_PROTOCOL_VERSIONS[0], PROTOCOL_VERSION);
+    }
+
+    #[test]
+    fn test_log_level_order_and_names() {
+        assert!(LogLevel::Debug < LogLevel::Info);
+        assert!(LogLevel::Warning < LogLevel::Error);
+        assert!(LogLevel::Alert < LogLevel::Emergency);
+        let params: SetLevelParams = serde_json::from_str(r#"{"level": "warning"}"#).unwrap();
+        assert_eq!(params.level, LogLevel::Warning);
+        assert_eq!(
+            serde_json::to_value(LogLevel::Critical).unwrap(),
+            "critical"
+        );
+    }
+
+    #[test]
+    fn test_cancelled_params() {
+        let params: CancelledParams =
+            serde_json::from_str(r#"{"requestId": 7, "reason": "user"}"#).unwrap();
+        assert_eq!(params.request_id, 7);
+        assert_eq!(params.reason.as_deref(), Some("user"));
+    }
+
+    #[test]
+    fn test_create_message_params() {
+        let params: CreateMessageParams = serde_json::from_str(
+            r#"{
+                "messages": [{"role": "user", "content": {"type": "text", "text": "Hi"}}],
+                "systemPrompt": "Be brief",
+                "maxTokens": 100,
+                "modelPreferences": {"hints": [{"name": "claude"}]}
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(params.messages.len(), 1);
+        assert_eq!(params.system_prompt.as_deref(), Some("Be brief"));
+        assert_eq!(params.max_tokens, 100);
+        assert!(params.temperature.is_none());
+
+        let result = serde_json::to_value(CreateMessageResult {
+            role: "assistant".to_string(),
+            content: ToolContent::Text {
+                text: "Hello".to_string(),
+            },
+            model: "mock-model".to_string(),
+            stop_reason: Some("endTurn".to_string()),
+        })
+        .unwrap();
+        assert_eq!(result["stopReason"], "endTurn");
+        assert_eq!(result["content"]["type"], "text");
+    }
+
+    #[test]
+    fn test_capability_names_accept_snake_case() {
+        let caps: ToolsCapability = serde_json::from_str(r#"{"list_changed": true}"#).unwrap();
+        assert_eq!(caps.list_changed, Some(true));
+        let result: CallToolResult =
+            serde_json::from_str(r#"{"content": [], "is_error": true}"#).unwrap();
+        assert_eq!(result.is_error, Some(true));
+    }
+
     // ===== JsonRpcRequest Tests =====
 
     #[test]
@@ -409,6 +682,7 @@ mod tests {
             tools: None,
             prompts: None,
             resources: None,
+            logging: None,
         };
 
         let json = serde_json::to_string(&caps).unwrap();
@@ -424,11 +698,12 @@ mod tests {
             }),
             prompts: None,
             resources: None,
+            logging: None,
         };
 
         let json = serde_json::to_value(&caps).unwrap();
-        // list_changed is serialized as "list_changed" (snake_case by default)
-        assert!(json["tools"]["list_changed"].as_bool().unwrap());
+        // list_changed is serialized as "listChanged" per the spec
+        assert!(json["tools"]["listChanged"].as_bool().unwrap());
     }
 
     #[test]
@@ -444,6 +719,7 @@ mod tests {
                 subscribe: Some(true),
                 list_changed: Some(false),
             }),
+            logging: None,
         };
 
         let json = serde_json::to_value(&caps).unwrap();
@@ -475,7 +751,7 @@ mod tests {
         };
 
         let json = serde_json::to_value(&caps).unwrap();
-        assert!(json["roots"]["list_changed"].as_bool().unwrap());
+        assert!(json["roots"]["listChanged"].as_bool().unwrap());
     }
 
     // ===== ServerInfo and ClientInfo Tests =====
@@ -553,6 +829,7 @@ mod tests {
                 }),
                 prompts: None,
                 resources: None,
+                logging: None,
             },
             server_info: ServerInfo {
                 name: "ted".to_string(),
@@ -606,10 +883,14 @@ mod tests {
 
     #[test]
     fn test_tools_list_result_empty() {
-        let result = ToolsListResult { tools: vec![] };
+        let result = ToolsListResult {
+            tools: vec![],
+            next_cursor: None,
+        };
 
         let json = serde_json::to_value(&result).unwrap();
         assert!(json["tools"].as_array().unwrap().is_empty());
+        assert!(json.get("nextCursor").is_none());
     }
 
     #[test]
@@ -627,10 +908,12 @@ mod tests {
                     input_schema: serde_json::json!({}),
                 },
             ],
+            next_cursor: Some("2".to_string()),
         };
 
         let json = serde_json::to_value(&result).unwrap();
         assert_eq!(json["tools"].as_array().unwrap().len(), 2);
+        assert_eq!(json["nextCursor"], "2");
     }
 
     // ===== CallToolParams Tests =====
@@ -694,7 +977,7 @@ mod tests {
         };
 
         let json = serde_json::to_value(&result).unwrap();
-        assert!(json["is_error"].as_bool().unwrap());
+        assert!(json["isError"].as_bool().unwrap());
     }
 
     // ===== ToolContent Tests =====
@@ -719,7 +1002,7 @@ mod tests {
 
         let json = serde_json::to_value(&content).unwrap();
         assert_eq!(json["type"], "image");
-        assert_eq!(json["mime_type"], "image/png");
+        assert_eq!(json["mimeType"], "image/png");
     }
 
     #[test]
@@ -767,7 +1050,7 @@ mod tests {
 
         let json = serde_json::to_value(&resource).unwrap();
         assert_eq!(json["text"], "Hello");
-        assert_eq!(json["mime_type"], "text/plain");
+        assert_eq!(json["mimeType"], "text/plain");
     }
 
     #[test]
@@ -792,7 +1075,7 @@ mod tests {
         };
 
         let json = serde_json::to_value(&cap).unwrap();
-        assert!(json["list_changed"].as_bool().unwrap());
+        assert!(json["listChanged"].as_bool().unwrap());
     }
 
     #[test]
@@ -802,7 +1085,7 @@ mod tests {
         };
 
         let json = serde_json::to_value(&cap).unwrap();
-        assert!(!json["list_changed"].as_bool().unwrap());
+        assert!(!json["listChanged"].as_bool().unwrap());
     }
 
     #[test]
@@ -814,7 +1097,7 @@ mod tests {
 
         let json = serde_json::to_value(&cap).unwrap();
         assert!(json["subscribe"].as_bool().unwrap());
-        assert!(!json["list_changed"].as_bool().unwrap());
+        assert!(!json["listChanged"].as_bool().unwrap());
     }
 
     #[test]
@@ -824,7 +1107,7 @@ mod tests {
         };
 
         let json = serde_json::to_value(&cap).unwrap();
-        assert!(json["list_changed"].as_bool().unwrap());
+        assert!(json["listChanged"].as_bool().unwrap());
     }
 
     // ===== Clone and Debug Tests =====
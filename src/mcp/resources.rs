@@ -0,0 +1,543 @@
+// SPDX-License-Identifier: AGPL-3.0-or-later
+// Copyright (C) 2025 Blackman Artificial Intelligence Technologies Inc.
+
+//! MCP resources
+//!
+//! Exposes what Ted knows about a workspace as MCP resources:
+//! - `file:///<path>` - workspace files, honouring `.gitignore` and `.tedignore`
+//! - `ted://sessions/<id>` - past chat sessions in this workspace
+//! - `ted://beads/<id>` - beads tracked in `<workspace>/.beads`
+//! - `ted://plans/<id>` - plans for this workspace
+
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+
+use uuid::Uuid;
+
+use super::protocol::{Resource, ResourceContent};
+use crate::beads::{BeadStatus, BeadStore};
+use crate::config::Settings;
+use crate::context::chunk::ChunkContent;
+use crate::context::{ContextManager, SessionId};
+use crate::error::{Result, TedError};
+use crate::history::SessionInfo;
+use crate::plans::PlanInfo;
+use crate::tools::builtin::walk;
+use crate::tools::ToolContext;
+
+/// Most workspace files listed
+pub const MAX_FILE_RESOURCES: usize = 2000;
+
+/// Most past sessions listed
+const MAX_SESSION_RESOURCES: usize = 50;
+
+/// Largest file returned by `resources/read`
+const MAX_FILE_BYTES: u64 = 1024 * 1024;
+
+const SESSION_PREFIX: &str = "ted://sessions/";
+const BEAD_PREFIX: &str = "ted://beads/";
+const PLAN_PREFIX: &str = "ted://plans/";
+const FILE_PREFIX: &str = "file://";
+
+/// Resources for one workspace
+#[derive(Clone)]
+pub struct WorkspaceResources {
+    context: ToolContext,
+    history_path: PathBuf,
+    context_storage: PathBuf,
+    plans_dir: PathBuf,
+}
+
+impl WorkspaceResources {
+    /// Resources for the workspace of `context`, reading sessions and plans
+    /// from the default Ted home.
+    pub fn new(context: ToolContext) -> Self {
+        let context_storage = Settings::load()
+            .map(|settings| settings.context.storage_path)
+            .unwrap_or_else(|_| Settings::context_path());
+        Self {
+            context,
+            history_path: Settings::ted_home().join("history.json"),
+            context_storage,
+            plans_dir: crate::plans::plans_dir(),
+        }
+    }
+
+    /// Read sessions and plans from explicit locations
+    pub fn with_store_paths(
+        mut self,
+        history_path: PathBuf,
+        context_storage: PathBuf,
+        plans_dir: PathBuf,
+    ) -> Self {
+        self.history_path = history_path;
+        self.context_storage = context_storage;
+        self.plans_dir = plans_dir;
+        self
+    }
+
+    /// The workspace root
+    pub fn root(&self) -> &Path {
+        self.context
+            .project_root
+            .as_deref()
+            .unwrap_or(&self.context.working_directory)
+    }
+
+    /// Every resource, files first
+    pub fn list(&self) -> Result<Vec<Resource>> {
+        let mut resources = self.file_resources();
+        resources.extend(self.session_resources()?);
+        resources.extend(self.bead_resources()?);
+        resources.extend(self.plan_resources()?);
+        Ok(resources)
+    }
+
+    /// Read one resource by URI
+    pub async fn read(&self, uri: &str) -> Result<ResourceContent> {
+        if let Some(id) = uri.strip_prefix(SESSION_PREFIX) {
+            return self.read_session(uri, id).await;
+        }
+        if let Some(id) = uri.strip_prefix(BEAD_PREFIX) {
+            return self.read_bead(uri, id);
+        }
+        if let Some(id) = uri.strip_prefix(PLAN_PREFIX) {
+            return self.read_plan(uri, id);
+        }
+        if let Some(path) = uri.strip_prefix(FILE_PREFIX) {
+            return self.read_file(uri, Path::new(path));
+        }
+        Err(TedError::InvalidInput(format!("Unknown resource: {}", uri)))
+    }
+
+    /// Hash of a resource's current content, used to notify subscribers of
+    /// changes. `None` when the resource cannot be read.
+    pub async fn fingerprint(&self, uri: &str) -> Option<u64> {
+        let content = self.read(uri).await.ok()?;
+        let mut hasher = DefaultHasher::new();
+        content.text.hash(&mut hasher);
+        content.blob.hash(&mut hasher);
+        Some(hasher.finish())
+    }
+
+    fn file_resources(&self) -> Vec<Resource> {
+        let root = self.root();
+        walk::walk(root, &self.context, false, false, None)
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.file_type().is_some_and(|t| t.is_file()))
+            .take(MAX_FILE_RESOURCES)
+            .map(|entry| {
+                let path = entry.path();
+                let relative = path.strip_prefix(root).unwrap_or(path);
+                Resource {
+                    uri: format!("{}{}", FILE_PREFIX, path.display()),
+                    name: relative.display().to_string(),
+                    description: None,
+                    mime_type: Some(mime_type_for(path).to_string()),
+                }
+            })
+            .collect()
+    }
+
+    fn read_file(&self, uri: &str, path: &Path) -> Result<ResourceContent> {
+        let root = self.root().canonicalize()?;
+        let path = path
+            .canonicalize()
+            .map_err(|_| TedError::InvalidInput(format!("Resource not found: {}", uri)))?;
+        if !path.starts_with(&root) || !path.is_file() {
+            return Err(TedError::InvalidInput(format!(
+                "Resource not found: {}",
+                uri
+            )));
+        }
+        if let Some(message) = self.context.check_path_not_ignored(&path) {
+            return Err(TedError::PermissionDenied(message));
+        }
+        // Only files `resources/list` would offer can be read
+        if !walk::is_walked(&root, &path, &self.context) {
+            return Err(TedError::InvalidInput(format!(
+                "Resource not found: {}",
+                uri
+            )));
+        }
+        if std::fs::metadata(&path)?.len() > MAX_FILE_BYTES {
+            return Err(TedError::InvalidInput(format!(
+                "{} is larger than {} bytes",
+                uri, MAX_FILE_BYTES
+            )));
+        }
+        let text = String::from_utf8(std::fs::read(&path)?)
+            .map_err(|_| TedError::InvalidInput(format!("{} is not a text file", uri)))?;
+
+        Ok(ResourceContent {
+            uri: uri.to_string(),
+            mime_type: Some(mime_type_for(&path).to_string()),
+            text: Some(text),
+            blob: None,
+        })
+    }
+
+    /// Sessions started in this workspace, most recent first
+    fn sessions(&self) -> Result<Vec<SessionInfo>> {
+        if !self.history_path.exists() {
+            return Ok(Vec::new());
+        }
+        let content = std::fs::read_to_string(&self.history_path)?;
+        let mut sessions: Vec<SessionInfo> = serde_json::from_str(&content).unwrap_or_default();
+        let root = self.root();
+        sessions.retain(|session| {
+            session.project_root.as_deref() == Some(root)
+                || session.working_directory.starts_with(root)
+        });
+        sessions.sort_by_key(|session| std::cmp::Reverse(session.last_active));
+        sessions.truncate(MAX_SESSION_RESOURCES);
+        Ok(sessions)
+    }
+
+    fn session_resources(&self) -> Result<Vec<Resource>> {
+        Ok(self
+            .sessions()?
+            .into_iter()
+            .map(|session| Resource {
+                uri: format!("{}{}", SESSION_PREFIX, session.id),
+                name: session
+                    .summary
+                    .clone()
+                    .unwrap_or_else(|| format!("Session {}", &session.id.to_string()[..8])),
+                description: Some(format!(
+                    "Chat session from {} ({} messages)",
+                    session.last_active.format("%Y-%m-%d %H:%M"),
+                    session.message_count
+                )),
+                mime_type: Some("text/markdown".to_string()),
+            })
+            .collect())
+    }
+
+    async fn read_session(&self, uri: &str, id: &str) -> Result<ResourceContent> {
+        let not_found = || TedError::InvalidInput(format!("Resource not found: {}", uri));
+        let id = Uuid::parse_str(id).map_err(|_| not_found())?;
+        let session = self
+            .sessions()?
+            .into_iter()
+            .find(|session| session.id == id)
+            .ok_or_else(not_found)?;
+
+        let mut text = format!(
+            "# {}\n\n- Session: {}\n- Started: {}\n- Last active: {}\n- Messages: {}\n",
+            session.summary.as_deref().unwrap_or("Chat session"),
+            session.id,
+            session.started_at.to_rfc3339(),
+            session.last_active.to_rfc3339(),
+            session.message_count
+        );
+        if !session.caps.is_empty() {
+            text.push_str(&format!("- Caps: {}\n", session.caps.join(", ")));
+        }
+
+        // Only open the context store when it exists; opening creates it.
+        if self.context_storage.join(id.to_string()).exists() {
+            let manager = ContextManager::new(self.context_storage.clone(), SessionId(id)).await?;
+            let mut chunks = manager.get_all_chunks().await?;
+            chunks.sort_by_key(|chunk| chunk.sequence);
+            for chunk in chunks {
+                match chunk.content {
+                    ChunkContent::Message { role, content } => {
+                        text.push_str(&format!("\n## {}\n\n{}\n", role, content));
+                    }
+                    ChunkContent::Summary { text: summary, .. } => {
+                        text.push_str(&format!("\n## summary\n\n{}\n", summary));
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        Ok(ResourceContent {
+            uri: uri.to_string(),
+            mime_type: Some("text/markdown".to_string()),
+            text: Some(text),
+            blob: None,
+        })
+    }
+
+    fn bead_store(&self) -> Result<Option<BeadStore>> {
+        let beads_dir = self.root().join(".beads");
+        if !beads_dir.exists() {
+            return Ok(None);
+        }
+        BeadStore::new(beads_dir).map(Some)
+    }
+
+    fn bead_resources(&self) -> Result<Vec<Resource>> {
+        let Some(store) = self.bead_store()? else {
+            return Ok(Vec::new());
+        };
+        let mut beads = store.all();
+        beads.sort_by(|a, b| a.id.0.cmp(&b.id.0));
+        Ok(beads
+            .into_iter()
+            .map(|bead| Resource {
+                uri: format!("{}{}", BEAD_PREFIX, bead.id),
+                name: format!("{}: {}", bead.id, bead.title),
+                description: Some(format!("Bead ({})", bead_status_label(&bead.status))),
+                mime_type: Some("application/json".to_string()),
+            })
+            .collect())
+    }
+
+    fn read_bead(&self, uri: &str, id: &str) -> Result<ResourceContent> {
+        let bead = self
+            .bead_store()?
+            .and_then(|store| store.get(&id.into()))
+            .ok_or_else(|| TedError::InvalidInput(format!("Resource not found: {}", uri)))?;
+        Ok(ResourceContent {
+            uri: uri.to_string(),
+            mime_type: Some("application/json".to_string()),
+            text: Some(serde_json::to_string_pretty(&bead)?),
+            blob: None,
+        })
+    }
+
+    /// Plans for this workspace, plus plans not tied to any project
+    fn plans(&self) -> Result<Vec<PlanInfo>> {
+        let index_path = self.plans_dir.join("index.json");
+        if !index_path.exists() {
+            return Ok(Vec::new());
+        }
+        let content = std::fs::read_to_string(&index_path)?;
+        let mut plans: Vec<PlanInfo> = serde_json::from_str(&content).unwrap_or_default();
+        let root = self.root();
+        plans.retain(|plan| {
+            plan.project_path
+                .as_deref()
+                .is_none_or(|path| path.starts_with(root))
+        });
+        plans.sort_by_key(|plan| std::cmp::Reverse(plan.modified_at));
+        Ok(plans)
+    }
+
+    fn plan_resources(&self) -> Result<Vec<Resource>> {
+        Ok(self
+            .plans()?
+            .into_iter()
+            .map(|plan| Resource {
+                uri: format!("{}{}", PLAN_PREFIX, plan.id),
+                name: plan.title.clone(),
+                description: Some(format!(
+                    "Plan ({}, {}/{} tasks done)",
+                    plan.status.label(),
+                    plan.completed_count,
+                    plan.task_count
+                )),
+                mime_type: Some("text/markdown".to_string()),
+            })
+            .collect())
+    }
+
+    fn read_plan(&self, uri: &str, id: &str) -> Result<ResourceContent> {
+        let not_found = || TedError::InvalidInput(format!("Resource not found: {}", uri));
+        let id = Uuid::parse_str(id).map_err(|_| not_found())?;
+        if !self.plans()?.iter().any(|plan| plan.id == id) {
+            return Err(not_found());
+        }
+        let text = std::fs::read_to_string(self.plans_dir.join(format!("{}.md", id)))
+            .map_err(|_| not_found())?;
+        Ok(ResourceContent {
+            uri: uri.to_string(),
+            mime_type: Some("text/markdown".to_string()),
+            text: Some(text),
+            blob: None,
+        })
+    }
+}
+
+fn bead_status_label(status: &BeadStatus) -> &'static str {
+    match status {
+        BeadStatus::Pending => "pending",
+        BeadStatus::Ready => "ready",
+        BeadStatus::InProgress => "in progress",
+        BeadStatus::Blocked { .. } => "blocked",
+        BeadStatus::Done => "done",
+        BeadStatus::Cancelled { .. } => "cancelled",
+    }
+}
+
+/// MIME type from a file extension, defaulting to plain text
+fn mime_type_for(path: &Path) -> &'static str {
+    match path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| ext.to_ascii_lowercase())
+        .as_deref()
+    {
+        Some("md" | "markdown") => "text/markdown",
+        Some("rs") => "text/x-rust",
+        Some("py") => "text/x-python",
+        Some("js" | "mjs" | "cjs") => "text/javascript",
+        Some("ts" | "tsx") => "text/typescript",
+        Some("json") => "application/json",
+        Some("toml") => "application/toml",
+        Some("yaml" | "yml") => "application/yaml",
+        Some("html" | "htm") => "text/html",
+        Some("css") => "text/css",
+        Some("png") => "image/png",
+        Some("jpg" | "jpeg") => "image/jpeg",
+        Some("gif") => "image/gif",
+        Some("pdf") => "application/pdf",
+        _ => "text/plain",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::beads::Bead;
+    use tempfile::TempDir;
+
+    struct Fixture {
+        _temp: TempDir,
+        workspace: PathBuf,
+        resources: WorkspaceResources,
+        session_id: Uuid,
+        plan_id: Uuid,
+    }
+
+    fn fixture() -> Fixture {
+        let temp = TempDir::new().unwrap();
+        let workspace = temp.path().join("project");
+        let home = temp.path().join("home");
+        std::fs::create_dir_all(workspace.join("src")).unwrap();
+        std::fs::create_dir_all(home.join("plans")).unwrap();
+        std::fs::write(workspace.join("src/main.rs"), "fn main() {}").unwrap();
+        std::fs::write(workspace.join("secrets.env"), "TOKEN=1").unwrap();
+        std::fs::write(workspace.join(".tedignore"), "secrets.env\n").unwrap();
+        std::fs::write(workspace.join(".gitignore"), "build.log\n").unwrap();
+        std::fs::write(workspace.join("build.log"), "log").unwrap();
+
+        let mut session = SessionInfo::new(Uuid::new_v4(), workspace.clone());
+        session.set_summary("Fix the parser");
+        session.message_count = 2;
+        let other = SessionInfo::new(Uuid::new_v4(), temp.path().join("elsewhere"));
+        std::fs::write(
+            home.join("history.json"),
+            serde_json::to_string(&vec![session.clone(), other]).unwrap(),
+        )
+        .unwrap();
+
+        let mut plan = PlanInfo::new("Refactor parser");
+        plan.set_project_path(workspace.clone());
+        std::fs::write(
+            home.join("plans/index.json"),
+            serde_json::to_string(&vec![plan.clone()]).unwrap(),
+        )
+        .unwrap();
+        std::fs::write(
+            home.join(format!("plans/{}.md", plan.id)),
+            "# Refactor parser\n- [ ] Split lexer\n",
+        )
+        .unwrap();
+
+        let store = BeadStore::new(workspace.join(".beads")).unwrap();
+        store
+            .create(Bead::new("Write tests", "Cover the parser"))
+            .unwrap();
+
+        let context = ToolContext::new(
+            workspace.clone(),
+            Some(workspace.clone()),
+            Uuid::new_v4(),
+            false,
+        );
+        let resources = WorkspaceResources::new(context).with_store_paths(
+            home.join("history.json"),
+            home.join("context"),
+            home.join("plans"),
+        );
+
+        Fixture {
+            _temp: temp,
+            workspace,
+            resources,
+            session_id: session.id,
+            plan_id: plan.id,
+        }
+    }
+
+    #[test]
+    fn test_list_covers_files_sessions_beads_and_plans() {
+        let fixture = fixture();
+        let resources = fixture.resources.list().unwrap();
+        let names: Vec<&str> = resources.iter().map(|r| r.name.as_str()).collect();
+
+        assert!(names.contains(&"src/main.rs"));
+        assert!(!names.contains(&"secrets.env"));
+        assert!(!names.contains(&"build.log"));
+        assert!(names.contains(&"Fix the parser"));
+        assert!(names.contains(&"Refactor parser"));
+        assert!(names.iter().any(|name| name.ends_with(": Write tests")));
+        assert_eq!(
+            resources
+                .iter()
+                .filter(|r| r.uri.starts_with(SESSION_PREFIX))
+                .count(),
+            1
+        );
+    }
+
+    #[tokio::test]
+    async fn test_read_file_session_and_plan() {
+        let fixture = fixture();
+        let uri = format!("file://{}", fixture.workspace.join("src/main.rs").display());
+        let file = fixture.resources.read(&uri).await.unwrap();
+        assert_eq!(file.text.as_deref(), Some("fn main() {}"));
+        assert_eq!(file.mime_type.as_deref(), Some("text/x-rust"));
+
+        let session = fixture
+            .resources
+            .read(&format!("ted://sessions/{}", fixture.session_id))
+            .await
+            .unwrap();
+        assert!(session.text.unwrap().starts_with("# Fix the parser"));
+
+        let plan = fixture
+            .resources
+            .read(&format!("ted://plans/{}", fixture.plan_id))
+            .await
+            .unwrap();
+        assert!(plan.text.unwrap().contains("Split lexer"));
+    }
+
+    #[tokio::test]
+    async fn test_read_rejects_ignored_and_outside_files() {
+        let fixture = fixture();
+        let ignored = format!("file://{}", fixture.workspace.join("secrets.env").display());
+        assert!(matches!(
+            fixture.resources.read(&ignored).await,
+            Err(TedError::PermissionDenied(_))
+        ));
+        for unlisted in ["build.log", ".gitignore"] {
+            let uri = format!("file://{}", fixture.workspace.join(unlisted).display());
+            assert!(fixture.resources.read(&uri).await.is_err(), "{}", unlisted);
+        }
+
+        let outside = format!(
+            "file://{}",
+            fixture.workspace.join("../home/history.json").display()
+        );
+        assert!(fixture.resources.read(&outside).await.is_err());
+        assert!(fixture.resources.read("ted://unknown/1").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_fingerprint_changes_with_content() {
+        let fixture = fixture();
+        let path = fixture.workspace.join("src/main.rs");
+        let uri = format!("file://{}", path.display());
+        let before = fixture.resources.fingerprint(&uri).await.unwrap();
+        std::fs::write(&path, "fn main() { println!(); }").unwrap();
+        let after = fixture.resources.fingerprint(&uri).await.unwrap();
+        assert_ne!(before, after);
+    }
+}
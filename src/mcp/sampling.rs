@@ -0,0 +1,525 @@
+// SPDX-License-Identifier: AGPL-3.0-or-later
+// Copyright (C) 2025 Blackman Artificial Intelligence Technologies Inc.
+
+//! Sampling for external MCP servers
+//!
+//! Servers mounted by the [`McpClientManager`](super::McpClientManager) may
+//! send `sampling/createMessage` to run a completion through Ted's active
+//! provider, so their LLM-assisted features work without API keys of their
+//! own. The first request from each server in a session needs the user's
+//! approval, `maxTokens` is capped and each server has a token budget (see
+//! [`McpSamplingConfig`]). Every request is written to the sampling audit log.
+//!
+//! Model preferences from the server are ignored: completions always use the
+//! model of the current session.
+
+use std::sync::{Arc, Mutex, RwLock};
+
+use serde_json::Value;
+
+use super::client::mcp_tool_name;
+use super::protocol::{CreateMessageParams, CreateMessageResult, JsonRpcError, ToolContent};
+use crate::audit::{SamplingAuditEvent, SamplingAuditLog, SamplingDecision};
+use crate::config::McpSamplingConfig;
+use crate::llm::message::Message;
+use crate::llm::provider::{CompletionRequest, ContentBlockResponse, LlmProvider, StopReason};
+use crate::tools::{PermissionRequest, ToolContext};
+
+/// `maxTokens` cap when the server's settings don't set one
+pub const DEFAULT_MAX_TOKENS_PER_REQUEST: u32 = 4096;
+
+/// Per-session token budget when the server's settings don't set one
+pub const DEFAULT_MAX_TOTAL_TOKENS: u64 = 100_000;
+
+/// Error code the spec uses for requests the user rejected
+const USER_REJECTED: i32 = -1;
+
+/// Characters of the prompt shown when asking for approval
+const PROMPT_PREVIEW_CHARS: usize = 200;
+
+/// Runs sampling requests through an LLM provider
+pub struct McpSampler {
+    provider: Arc<dyn LlmProvider>,
+    model: String,
+    /// Used to ask the user for approval
+    context: ToolContext,
+    audit_log: SamplingAuditLog,
+}
+
+impl McpSampler {
+    pub fn new(
+        provider: Arc<dyn LlmProvider>,
+        model: impl Into<String>,
+        context: ToolContext,
+    ) -> Self {
+        Self {
+            provider,
+            model: model.into(),
+            context,
+            audit_log: SamplingAuditLog::default(),
+        }
+    }
+
+    /// Replace the default audit log
+    pub fn with_audit_log(mut self, audit_log: SamplingAuditLog) -> Self {
+        self.audit_log = audit_log;
+        self
+    }
+
+    fn record(&self, event: SamplingAuditEvent) {
+        if let Err(err) = self.audit_log.append(&event) {
+            tracing::warn!(
+                target: "ted.audit.sampling",
+                error = %err,
+                "Failed to append sampling audit event"
+            );
+        }
+    }
+
+    fn event(
+        &self,
+        server: &str,
+        decision: SamplingDecision,
+        max_tokens: u32,
+    ) -> SamplingAuditEvent {
+        SamplingAuditEvent::new(
+            server.to_string(),
+            self.provider.name().to_string(),
+            self.model.clone(),
+            decision,
+            max_tokens,
+        )
+    }
+}
+
+/// Sampler shared by every server, attachable after they connected
+pub(crate) type SamplerSlot = Arc<RwLock<Option<Arc<McpSampler>>>>;
+
+/// Sampling done by one server in this session
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SamplingUsage {
+    pub requests: u32,
+    pub tokens: u64,
+}
+
+#[derive(Default)]
+struct SamplingState {
+    approved: bool,
+    usage: SamplingUsage,
+}
+
+/// Sampling limits and usage of one configured server
+pub(crate) struct ServerSampling {
+    server: String,
+    config: McpSamplingConfig,
+    sampler: SamplerSlot,
+    state: Mutex<SamplingState>,
+}
+
+impl ServerSampling {
+    pub(crate) fn new(server: &str, config: McpSamplingConfig, sampler: SamplerSlot) -> Self {
+        Self {
+            server: server.to_string(),
+            config,
+            sampler,
+            state: Mutex::new(SamplingState::default()),
+        }
+    }
+
+    /// Whether the capability is advertised to the server
+    pub(crate) fn is_enabled(&self) -> bool {
+        !self.config.disabled
+    }
+
+    pub(crate) fn usage(&self) -> SamplingUsage {
+        self.state
+            .lock()
+            .map(|state| state.usage)
+            .unwrap_or_default()
+    }
+
+    fn max_tokens_per_request(&self) -> u32 {
+        self.config
+            .max_tokens_per_request
+            .unwrap_or(DEFAULT_MAX_TOKENS_PER_REQUEST)
+            .max(1)
+    }
+
+    fn max_total_tokens(&self) -> u64 {
+        self.config
+            .max_total_tokens
+            .unwrap_or(DEFAULT_MAX_TOTAL_TOKENS)
+    }
+
+    fn is_approved(&self) -> bool {
+        self.state
+            .lock()
+            .map(|state| state.approved)
+            .unwrap_or(false)
+    }
+
+    /// Handle a `sampling/createMessage` request, returning its result.
+    pub(crate) async fn create_message(
+        &self,
+        params: Option<Value>,
+    ) -> std::result::Result<Value, JsonRpcError> {
+        let sampler = self
+            .sampler
+            .read()
+            .ok()
+            .and_then(|sampler| sampler.clone())
+            .filter(|_| self.is_enabled())
+            .ok_or_else(|| error(-32601, "Sampling is not available"))?;
+
+        let params: CreateMessageParams = params
+            .and_then(|params| serde_json::from_value(params).ok())
+            .ok_or_else(JsonRpcError::invalid_params)?;
+        let messages = params
+            .messages
+            .iter()
+            .map(|message| match (message.role.as_str(), &message.content) {
+                ("user", ToolContent::Text { text }) => Ok(Message::user(text.clone())),
+                ("assistant", ToolContent::Text { text }) => Ok(Message::assistant(text.clone())),
+                ("user" | "assistant", _) => Err("Only text content is supported for sampling"),
+                _ => Err("Message role must be 'user' or 'assistant'"),
+            })
+            .collect::<std::result::Result<Vec<_>, _>>()
+            .map_err(|message| error(-32602, message))?;
+        if messages.is_empty() {
+            return Err(error(-32602, "messages must not be empty"));
+        }
+
+        let used = self.usage().tokens;
+        let limit = self.max_total_tokens();
+        let max_tokens = params
+            .max_tokens
+            .clamp(1, self.max_tokens_per_request())
+            .min(limit.saturating_sub(used).min(u32::MAX as u64) as u32);
+        if max_tokens == 0 {
+            let message = format!(
+                "Sampling token limit of {} reached for MCP server '{}'",
+                limit, self.server
+            );
+            let mut event = sampler.event(
+                &self.server,
+                SamplingDecision::LimitExceeded,
+                params.max_tokens,
+            );
+            event.note = Some(message.clone());
+            sampler.record(event);
+            return Err(error(-32603, message));
+        }
+
+        if !self.is_approved() {
+            let prompt: String = params
+                .messages
+                .iter()
+                .rev()
+                .find_map(|message| match &message.content {
+                    ToolContent::Text { text } if message.role == "user" => Some(text.as_str()),
+                    _ => None,
+                })
+                .unwrap_or_default()
+                .chars()
+                .take(PROMPT_PREVIEW_CHARS)
+                .collect();
+            let request = PermissionRequest {
+                tool_name: mcp_tool_name(&self.server, "sampling"),
+                action_description: format!(
+                    "MCP server '{}' wants to run completions with {} ({}), up to {} tokens each and {} in total this session. First prompt: {}",
+                    self.server,
+                    sampler.provider.name(),
+                    sampler.model,
+                    self.max_tokens_per_request(),
+                    limit,
+                    prompt
+                ),
+                affected_paths: Vec::new(),
+                is_destructive: false,
+            };
+            // The TUI runs tools in trust mode because it reviews edits itself,
+            // so an attached frontend is always asked, whatever the trust mode.
+            let mut context = sampler.context.clone();
+            if context.can_ask_user() {
+                context.trust_mode = false;
+            }
+            if !context.request_permission(&request).await {
+                sampler.record(sampler.event(&self.server, SamplingDecision::Denied, max_tokens));
+                return Err(error(USER_REJECTED, "User rejected sampling request"));
+            }
+            if let Ok(mut state) = self.state.lock() {
+                state.approved = true;
+            }
+        }
+
+        let mut request =
+            CompletionRequest::new(sampler.model.clone(), messages).with_max_tokens(max_tokens);
+        if let Some(system) = params.system_prompt.filter(|s| !s.trim().is_empty()) {
+            request = request.with_system(system);
+        }
+        if let Some(temperature) = params.temperature {
+            request = request.with_temperature(temperature);
+        }
+
+        let response = match sampler.provider.complete(request).await {
+            Ok(response) => response,
+            Err(err) => {
+                let mut event = sampler.event(&self.server, SamplingDecision::Failed, max_tokens);
+                event.note = Some(err.to_string());
+                sampler.record(event);
+                return Err(error(-32603, format!("Sampling failed: {}", err)));
+            }
+        };
+
+        let usage = &response.usage;
+        if let Ok(mut state) = self.state.lock() {
+            state.usage.requests += 1;
+            state.usage.tokens += usage.input_tokens as u64 + usage.output_tokens as u64;
+        }
+        let mut event = sampler.event(&self.server, SamplingDecision::Completed, max_tokens);
+        event.input_tokens = usage.input_tokens;
+        event.output_tokens = usage.output_tokens;
+        sampler.record(event);
+
+        let text = response
+            .content
+            .iter()
+            .filter_map(|block| match block {
+                ContentBlockResponse::Text { text } => Some(text.as_str()),
+                ContentBlockResponse::ToolUse { .. } => None,
+            })
+            .collect::<Vec<_>>()
+            .join("");
+        let result = CreateMessageResult {
+            role: "assistant".to_string(),
+            content: ToolContent::Text { text },
+            model: response.model,
+            stop_reason: response.stop_reason.map(|reason| {
+                match reason {
+                    StopReason::EndTurn => "endTurn",
+                    StopReason::MaxTokens => "maxTokens",
+                    StopReason::StopSequence => "stopSequence",
+                    StopReason::ToolUse => "toolUse",
+                }
+                .to_string()
+            }),
+        };
+        serde_json::to_value(result).map_err(|_| JsonRpcError::internal_error())
+    }
+}
+
+fn error(code: i32, message: impl Into<String>) -> JsonRpcError {
+    JsonRpcError {
+        code,
+        message: message.into(),
+        data: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::llm::MockProvider;
+    use crate::tools::PendingQuestion;
+    use tempfile::TempDir;
+
+    fn context(temp: &TempDir, trust_mode: bool) -> ToolContext {
+        ToolContext::new(
+            temp.path().to_path_buf(),
+            Some(temp.path().to_path_buf()),
+            uuid::Uuid::new_v4(),
+            trust_mode,
+        )
+    }
+
+    fn sampling(
+        temp: &TempDir,
+        provider: MockProvider,
+        context: ToolContext,
+        config: McpSamplingConfig,
+    ) -> ServerSampling {
+        let sampler = McpSampler::new(Arc::new(provider), "mock-model", context)
+            .with_audit_log(SamplingAuditLog::new(temp.path().join("sampling.jsonl")));
+        ServerSampling::new(
+            "docs",
+            config,
+            Arc::new(RwLock::new(Some(Arc::new(sampler)))),
+        )
+    }
+
+    fn params(max_tokens: u32) -> Option<Value> {
+        Some(serde_json::json!({
+            "messages": [{"role": "user", "content": {"type": "text", "text": "Summarize this"}}],
+            "systemPrompt": "Be brief",
+            "maxTokens": max_tokens
+        }))
+    }
+
+    #[tokio::test]
+    async fn test_create_message_runs_provider_and_records_usage() {
+        let temp = TempDir::new().unwrap();
+        let provider = MockProvider::new().with_response("A summary");
+        let sampling = sampling(
+            &temp,
+            provider.clone(),
+            context(&temp, true),
+            McpSamplingConfig {
+                max_tokens_per_request: Some(256),
+                ..Default::default()
+            },
+        );
+
+        let result = sampling.create_message(params(10_000)).await.unwrap();
+        assert_eq!(result["role"], "assistant");
+        assert_eq!(result["content"]["text"], "A summary");
+        assert_eq!(result["model"], "mock-model");
+        assert_eq!(result["stopReason"], "endTurn");
+
+        let request = provider.last_request().unwrap();
+        assert_eq!(request.max_tokens, 256);
+        assert_eq!(request.system.as_deref(), Some("Be brief"));
+        assert_eq!(sampling.usage().requests, 1);
+
+        let events = SamplingAuditLog::new(temp.path().join("sampling.jsonl"))
+            .read_recent(10)
+            .unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].server, "docs");
+        assert_eq!(events[0].decision, SamplingDecision::Completed);
+        assert_eq!(
+            sampling.usage().tokens,
+            (events[0].input_tokens + events[0].output_tokens) as u64
+        );
+    }
+
+    #[tokio::test]
+    async fn test_create_message_asks_once_per_server() {
+        let temp = TempDir::new().unwrap();
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel::<PendingQuestion>();
+        let questions = tokio::spawn(async move {
+            let mut asked = Vec::new();
+            while let Some(question) = rx.recv().await {
+                asked.push(question.question.question.clone());
+                question.answer(Some("Allow".to_string()));
+            }
+            asked
+        });
+        let sampling = sampling(
+            &temp,
+            MockProvider::new(),
+            context(&temp, false).with_question_sender(tx),
+            McpSamplingConfig::default(),
+        );
+
+        sampling.create_message(params(100)).await.unwrap();
+        sampling.create_message(params(100)).await.unwrap();
+        drop(sampling);
+
+        let asked = questions.await.unwrap();
+        assert_eq!(asked.len(), 1);
+        assert!(asked[0].contains("MCP server 'docs'"));
+        assert!(asked[0].contains("Summarize this"));
+    }
+
+    #[tokio::test]
+    async fn test_create_message_rejected_by_user() {
+        let temp = TempDir::new().unwrap();
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel::<PendingQuestion>();
+        tokio::spawn(async move {
+            while let Some(question) = rx.recv().await {
+                question.answer(Some("Deny".to_string()));
+            }
+        });
+        let provider = MockProvider::new();
+        let sampling = sampling(
+            &temp,
+            provider.clone(),
+            context(&temp, false).with_question_sender(tx),
+            McpSamplingConfig::default(),
+        );
+
+        let err = sampling.create_message(params(100)).await.unwrap_err();
+        assert_eq!(err.code, USER_REJECTED);
+        assert_eq!(provider.call_count(), 0);
+        let events = SamplingAuditLog::new(temp.path().join("sampling.jsonl"))
+            .read_recent(10)
+            .unwrap();
+        assert_eq!(events[0].decision, SamplingDecision::Denied);
+    }
+
+    #[tokio::test]
+    async fn test_create_message_asks_frontend_even_in_trust_mode() {
+        let temp = TempDir::new().unwrap();
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel::<PendingQuestion>();
+        tokio::spawn(async move {
+            while let Some(question) = rx.recv().await {
+                question.answer(Some("Deny".to_string()));
+            }
+        });
+        let provider = MockProvider::new();
+        let sampling = sampling(
+            &temp,
+            provider.clone(),
+            context(&temp, true).with_question_sender(tx),
+            McpSamplingConfig::default(),
+        );
+
+        let err = sampling.create_message(params(100)).await.unwrap_err();
+        assert_eq!(err.code, USER_REJECTED);
+        assert_eq!(provider.call_count(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_create_message_enforces_token_budget() {
+        let temp = TempDir::new().unwrap();
+        let sampling = sampling(
+            &temp,
+            MockProvider::new(),
+            context(&temp, true),
+            McpSamplingConfig {
+                max_total_tokens: Some(1),
+                ..Default::default()
+            },
+        );
+
+        sampling.create_message(params(100)).await.unwrap();
+        let err = sampling.create_message(params(100)).await.unwrap_err();
+        assert!(err.message.contains("token limit"));
+        let events = SamplingAuditLog::new(temp.path().join("sampling.jsonl"))
+            .read_recent(10)
+            .unwrap();
+        assert_eq!(events[1].decision, SamplingDecision::LimitExceeded);
+    }
+
+    #[tokio::test]
+    async fn test_create_message_rejects_invalid_requests() {
+        let temp = TempDir::new().unwrap();
+        let sampling = sampling(
+            &temp,
+            MockProvider::new(),
+            context(&temp, true),
+            McpSamplingConfig::default(),
+        );
+
+        let err = sampling.create_message(None).await.unwrap_err();
+        assert_eq!(err.code, -32602);
+        let image = serde_json::json!({
+            "messages": [{"role": "user", "content": {"type": "image", "data": "", "mimeType": "image/png"}}],
+            "maxTokens": 10
+        });
+        let err = sampling.create_message(Some(image)).await.unwrap_err();
+        assert!(err.message.contains("text content"));
+
+        let disabled = ServerSampling::new(
+            "off",
+            McpSamplingConfig {
+                disabled: true,
+                ..Default::default()
+            },
+            Arc::new(RwLock::new(None)),
+        );
+        assert!(!disabled.is_enabled());
+        let err = disabled.create_message(params(10)).await.unwrap_err();
+        assert_eq!(err.code, -32601);
+    }
+}
@@ -6,12 +6,14 @@
 use serde_json::Value;
 use std::collections::HashMap;
 use std::sync::Arc;
-use tokio::sync::RwLock;
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader};
+use tokio::sync::{broadcast, oneshot, Mutex, RwLock};
 
+use super::prompts::PromptCatalog;
 use super::protocol::*;
-use super::transport::StdioTransport;
+use super::resources::WorkspaceResources;
 use crate::error::Result;
-use crate::tools::{Tool as TedTool, ToolContext, ToolExecutor, ToolOutput};
+use crate::tools::{Tool as TedTool, ToolContext, ToolExecutor, ToolOutput, ToolProgressEvent};
 
 /// Adapter to expose Ted tools through MCP protocol
 struct TedToolAdapter {
@@ -47,29 +49,92 @@ impl TedToolAdapter {
 }
 
 /// MCP server state
+///
+/// Cloning is cheap and shares all state, so requests can be handled on
+/// separate tasks.
+#[derive(Clone)]
 pub struct McpServer {
     /// Available tools (wrapped in adapters)
     tools: Arc<RwLock<HashMap<String, TedToolAdapter>>>,
     /// Server initialized
     initialized: Arc<RwLock<bool>>,
-    /// Transport layer
-    transport: Arc<StdioTransport>,
+    /// Protocol version agreed during `initialize`
+    protocol_version: Arc<RwLock<String>>,
+    /// Lowest level sent as `notifications/message`; nothing until the
+    /// client calls `logging/setLevel`
+    log_level: Arc<RwLock<Option<LogLevel>>>,
+    /// Requests being handled, keyed by their JSON id, for cancellation
+    in_flight: Arc<Mutex<HashMap<String, oneshot::Sender<()>>>>,
+    /// Items returned per page by the list methods
+    page_size: usize,
     /// Tool executor (reserved for future use)
     #[allow(dead_code)]
     executor: Arc<ToolExecutor>,
+    /// Workspace files, sessions, beads and plans
+    resources: WorkspaceResources,
+    /// Caps and custom commands
+    prompts: PromptCatalog,
+    /// Subscribed resource URIs and the fingerprint last reported
+    subscriptions: Arc<RwLock<HashMap<String, Option<u64>>>>,
+    /// Server-initiated notifications, fanned out to the transport
+    notifications: broadcast::Sender<JsonRpcRequest>,
 }
 
+/// Items returned per page by `tools/list`, `resources/list` and `prompts/list`
+const LIST_PAGE_SIZE: usize = 500;
+
+/// Notifications buffered for slow readers before the oldest are dropped
+const NOTIFICATION_BUFFER: usize = 64;
+
+/// How often subscribed resources are checked for changes
+const SUBSCRIPTION_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_secs(2);
+
 impl McpServer {
     /// Create a new MCP server
     pub fn new(executor: ToolExecutor) -> Self {
+        let context = executor.context().clone();
+        let workspace = context
+            .project_root
+            .clone()
+            .unwrap_or_else(|| context.working_directory.clone());
         Self {
             tools: Arc::new(RwLock::new(HashMap::new())),
             initialized: Arc::new(RwLock::new(false)),
-            transport: Arc::new(StdioTransport::new()),
+            protocol_version: Arc::new(RwLock::new(PROTOCOL_VERSION.to_string())),
+            log_level: Arc::new(RwLock::new(None)),
+            in_flight: Arc::new(Mutex::new(HashMap::new())),
+            page_size: LIST_PAGE_SIZE,
             executor: Arc::new(executor),
+            resources: WorkspaceResources::new(context),
+            prompts: PromptCatalog::new(&workspace),
+            subscriptions: Arc::new(RwLock::new(HashMap::new())),
+            notifications: broadcast::channel(NOTIFICATION_BUFFER).0,
         }
     }
 
+    /// Replace the resources exposed by the server
+    pub fn with_resources(mut self, resources: WorkspaceResources) -> Self {
+        self.resources = resources;
+        self
+    }
+
+    /// Replace the prompts exposed by the server
+    pub fn with_prompts(mut self, prompts: PromptCatalog) -> Self {
+        self.prompts = prompts;
+        self
+    }
+
+    /// Set how many items the list methods return per page
+    pub fn with_page_size(mut self, page_size: usize) -> Self {
+        self.page_size = page_size.max(1);
+        self
+    }
+
+    /// Protocol version agreed with the client
+    pub async fn protocol_version(&self) -> String {
+        self.protocol_version.read().await.clone()
+    }
+
     /// Register a tool with the MCP server
     pub async fn register_tool(&self, tool: Arc<dyn TedTool>) {
         let adapter = TedToolAdapter::new(tool);
@@ -78,44 +143,276 @@ impl McpServer {
         tools.insert(name, adapter);
     }
 
-    /// Run the MCP server (main loop)
+    /// Run the MCP server on stdio until stdin closes
     pub async fn run(&self) -> Result<()> {
         tracing::info!("[MCP] Starting Model Context Protocol server");
+        self.serve(tokio::io::stdin(), tokio::io::stdout()).await
+    }
+
+    /// Serve newline-delimited JSON-RPC from `reader`, writing responses and
+    /// notifications to `writer`, until `reader` reaches EOF.
+    ///
+    /// Each message is handled on its own task so a long tool call can be
+    /// cancelled by a later `notifications/cancelled`.
+    pub async fn serve<R, W>(&self, reader: R, writer: W) -> Result<()>
+    where
+        R: AsyncRead + Unpin,
+        W: AsyncWrite + Unpin + Send + 'static,
+    {
+        let writer = Arc::new(Mutex::new(writer));
+        let mut outgoing = self.subscribe_notifications();
+        let watcher = self.spawn_subscription_watcher();
+        let notification_writer = Arc::clone(&writer);
+        let forwarder = tokio::spawn(async move {
+            loop {
+                match outgoing.recv().await {
+                    Ok(notification) => {
+                        if let Err(e) = write_line(&notification_writer, &notification).await {
+                            tracing::error!("[MCP] Failed to write notification: {}", e);
+                            return;
+                        }
+                    }
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        tracing::warn!("[MCP] Dropped {} notifications", skipped);
+                    }
+                    Err(broadcast::error::RecvError::Closed) => return,
+                }
+            }
+        });
 
-        loop {
-            // Read request from stdin
-            let request = match self.transport.read_request().await {
-                Ok(req) => req,
+        let mut lines = BufReader::new(reader).lines();
+        let mut handlers = tokio::task::JoinSet::new();
+        let result = loop {
+            let line = match lines.next_line().await {
+                Ok(Some(line)) => line,
+                Ok(None) => break Ok(()),
                 Err(e) => {
                     tracing::error!("[MCP] Failed to read request: {}", e);
-                    break;
+                    break Err(e.into());
                 }
             };
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let server = self.clone();
+            let writer = Arc::clone(&writer);
+            handlers.spawn(async move {
+                if let Some(reply) = server.handle_message(&line).await {
+                    if let Err(e) = write_line(&writer, &reply).await {
+                        tracing::error!("[MCP] Failed to write response: {}", e);
+                    }
+                }
+            });
+            // Reap finished handlers so the set doesn't grow unbounded
+            while handlers.try_join_next().is_some() {}
+        };
 
-            tracing::debug!("[MCP] Received request: {}", request.method);
+        while handlers.join_next().await.is_some() {}
+        watcher.abort();
+        forwarder.abort();
+        result
+    }
 
-            // Handle request
-            let response = self.handle_request(request).await;
+    /// Handle one raw JSON-RPC message (single or batch) and return the
+    /// reply, if any
+    pub(crate) async fn handle_message(&self, message: &str) -> Option<Value> {
+        let payload: Value = match serde_json::from_str(message) {
+            Ok(payload) => payload,
+            Err(e) => {
+                tracing::error!("[MCP] Failed to parse JSON-RPC message: {}", e);
+                return serde_json::to_value(Self::error_response(
+                    Some(Value::Null),
+                    JsonRpcError::parse_error(),
+                ))
+                .ok();
+            }
+        };
 
-            // Write response to stdout
-            if let Err(e) = self.transport.write_response(&response).await {
-                tracing::error!("[MCP] Failed to write response: {}", e);
-                break;
+        match payload {
+            Value::Array(messages) if messages.is_empty() => serde_json::to_value(
+                Self::error_response(Some(Value::Null), JsonRpcError::invalid_request()),
+            )
+            .ok(),
+            Value::Array(messages) => {
+                let mut replies = Vec::new();
+                for message in messages {
+                    if let Some(reply) = self.handle_value(message).await {
+                        replies.push(reply);
+                    }
+                }
+                (!replies.is_empty())
+                    .then(|| serde_json::to_value(replies).ok())
+                    .flatten()
             }
+            message => self
+                .handle_value(message)
+                .await
+                .and_then(|reply| serde_json::to_value(reply).ok()),
+        }
+    }
+
+    async fn handle_value(&self, message: Value) -> Option<JsonRpcResponse> {
+        // Responses to requests we never send are ignored
+        if message.get("method").is_none() {
+            tracing::debug!("[MCP] Ignoring message without a method");
+            return None;
+        }
+        let id = message.get("id").cloned();
+        match serde_json::from_value::<JsonRpcRequest>(message) {
+            Ok(request) => self.dispatch(request).await,
+            Err(_) => Some(Self::error_response(
+                Some(id.unwrap_or(Value::Null)),
+                JsonRpcError::invalid_request(),
+            )),
         }
+    }
+
+    /// Handle a request or notification.
+    ///
+    /// Returns no response for notifications and for requests cancelled by
+    /// `notifications/cancelled`.
+    pub async fn dispatch(&self, request: JsonRpcRequest) -> Option<JsonRpcResponse> {
+        let Some(id) = request.id.clone() else {
+            self.handle_notification(request).await;
+            return None;
+        };
 
-        Ok(())
+        tracing::debug!("[MCP] Received request: {}", request.method);
+
+        // `initialize` must not be cancelled
+        if request.method == "initialize" {
+            return Some(self.handle_request(request).await);
+        }
+
+        let key = id.to_string();
+        let (cancel, cancelled) = oneshot::channel();
+        self.in_flight.lock().await.insert(key.clone(), cancel);
+        let response = tokio::select! {
+            response = self.handle_request(request) => Some(response),
+            Ok(()) = cancelled => {
+                tracing::info!("[MCP] Request {} cancelled", key);
+                None
+            }
+        };
+        self.in_flight.lock().await.remove(&key);
+        response
+    }
+
+    /// Handle a notification from the client
+    async fn handle_notification(&self, request: JsonRpcRequest) {
+        match request.method.as_str() {
+            "notifications/initialized" | "initialized" => {
+                self.handle_initialized(request).await;
+            }
+            "notifications/cancelled" => {
+                let Some(params) = request
+                    .params
+                    .and_then(|p| serde_json::from_value::<CancelledParams>(p).ok())
+                else {
+                    return;
+                };
+                let key = params.request_id.to_string();
+                // Unknown ids are requests that already finished
+                if let Some(cancel) = self.in_flight.lock().await.remove(&key) {
+                    tracing::info!(
+                        "[MCP] Cancelling request {}: {}",
+                        key,
+                        params.reason.as_deref().unwrap_or("no reason given")
+                    );
+                    let _ = cancel.send(());
+                }
+            }
+            method => tracing::debug!("[MCP] Ignoring notification: {}", method),
+        }
+    }
+
+    /// Send `data` as a `notifications/message` log entry if the client asked
+    /// for messages at `level` or above
+    pub async fn log(&self, level: LogLevel, data: Value) {
+        let enabled = matches!(*self.log_level.read().await, Some(min) if level >= min);
+        if !enabled {
+            return;
+        }
+        let _ = self.notifications.send(JsonRpcRequest {
+            jsonrpc: "2.0".to_string(),
+            id: None,
+            method: "notifications/message".to_string(),
+            params: Some(serde_json::json!({
+                "level": level,
+                "logger": "ted",
+                "data": data,
+            })),
+        });
+    }
+
+    /// Receive notifications the server sends on its own, such as resource
+    /// updates and tool progress
+    pub(crate) fn subscribe_notifications(&self) -> broadcast::Receiver<JsonRpcRequest> {
+        self.notifications.subscribe()
+    }
+
+    /// Poll subscribed resources and publish `notifications/resources/updated`
+    /// when their content changes.
+    pub(crate) fn spawn_subscription_watcher(&self) -> tokio::task::JoinHandle<()> {
+        let notifications = self.notifications.clone();
+        let resources = self.resources.clone();
+        let subscriptions = Arc::clone(&self.subscriptions);
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(SUBSCRIPTION_POLL_INTERVAL);
+            loop {
+                interval.tick().await;
+                for uri in Self::changed_subscriptions(&resources, &subscriptions).await {
+                    let notification = JsonRpcRequest {
+                        jsonrpc: "2.0".to_string(),
+                        id: None,
+                        method: "notifications/resources/updated".to_string(),
+                        params: Some(serde_json::json!({ "uri": uri })),
+                    };
+                    // Nobody listening is fine; the update is simply not delivered.
+                    let _ = notifications.send(notification);
+                }
+            }
+        })
+    }
+
+    /// Subscribed URIs whose fingerprint changed since the last check
+    async fn changed_subscriptions(
+        resources: &WorkspaceResources,
+        subscriptions: &RwLock<HashMap<String, Option<u64>>>,
+    ) -> Vec<String> {
+        let uris: Vec<String> = subscriptions.read().await.keys().cloned().collect();
+        let mut changed = Vec::new();
+        for uri in uris {
+            let fingerprint = resources.fingerprint(&uri).await;
+            let mut subscriptions = subscriptions.write().await;
+            if let Some(last) = subscriptions.get_mut(&uri) {
+                if *last != fingerprint {
+                    *last = fingerprint;
+                    changed.push(uri);
+                }
+            }
+        }
+        changed
     }
 
     /// Handle a JSON-RPC request
-    async fn handle_request(&self, request: JsonRpcRequest) -> JsonRpcResponse {
+    pub(crate) async fn handle_request(&self, request: JsonRpcRequest) -> JsonRpcResponse {
         let method = &request.method;
 
         match method.as_str() {
             "initialize" => self.handle_initialize(request).await,
             "initialized" => self.handle_initialized(request).await,
+            "ping" => Self::success_response(request.id, serde_json::json!({})),
+            "logging/setLevel" => self.handle_set_level(request).await,
             "tools/list" => self.handle_tools_list(request).await,
             "tools/call" => self.handle_tools_call(request).await,
+            "resources/list" => self.handle_resources_list(request).await,
+            "resources/read" => self.handle_resources_read(request).await,
+            "resources/subscribe" => self.handle_resources_subscribe(request, true).await,
+            "resources/unsubscribe" => self.handle_resources_subscribe(request, false).await,
+            "prompts/list" => self.handle_prompts_list(request).await,
+            "prompts/get" => self.handle_prompts_get(request).await,
             _ => Self::error_response(request.id, JsonRpcError::method_not_found()),
         }
     }
@@ -134,20 +431,30 @@ impl McpServer {
             }
         };
 
+        let protocol_version = negotiate_protocol_version(&params.protocol_version);
         tracing::info!(
-            "[MCP] Initialize from client: {} v{}",
+            "[MCP] Initialize from client: {} v{} (protocol {}, requested {})",
             params.client_info.name,
-            params.client_info.version
+            params.client_info.version,
+            protocol_version,
+            params.protocol_version
         );
+        *self.protocol_version.write().await = protocol_version.to_string();
 
         let result = InitializeResult {
-            protocol_version: PROTOCOL_VERSION.to_string(),
+            protocol_version: protocol_version.to_string(),
             capabilities: ServerCapabilities {
                 tools: Some(ToolsCapability {
                     list_changed: Some(false),
                 }),
-                prompts: None,
-                resources: None,
+                prompts: Some(PromptsCapability {
+                    list_changed: Some(false),
+                }),
+                resources: Some(ResourcesCapability {
+                    subscribe: Some(true),
+                    list_changed: Some(false),
+                }),
+                logging: Some(LoggingCapability {}),
             },
             server_info: ServerInfo {
                 name: "ted".to_string(),
@@ -169,11 +476,21 @@ impl McpServer {
         Self::success_response(request.id, Value::Null)
     }
 
-    /// Handle tools/list request
+    /// Handle logging/setLevel request
+    async fn handle_set_level(&self, request: JsonRpcRequest) -> JsonRpcResponse {
+        let params: SetLevelParams = match request.params.clone().map(serde_json::from_value) {
+            Some(Ok(params)) => params,
+            _ => return Self::error_response(request.id, JsonRpcError::invalid_params()),
+        };
+        *self.log_level.write().await = Some(params.level);
+        Self::success_response(request.id, serde_json::json!({}))
+    }
+
+    /// Handle tools/list request, paged by `cursor`
     async fn handle_tools_list(&self, request: JsonRpcRequest) -> JsonRpcResponse {
         let tools = self.tools.read().await;
 
-        let tool_list: Vec<Tool> = tools
+        let mut tool_list: Vec<Tool> = tools
             .values()
             .map(|adapter| Tool {
                 name: adapter.name().to_string(),
@@ -181,12 +498,43 @@ impl McpServer {
                 input_schema: adapter.parameters(),
             })
             .collect();
+        drop(tools);
+        // Cursors are offsets, so the order must be stable
+        tool_list.sort_by(|a, b| a.name.cmp(&b.name));
 
-        let result = ToolsListResult { tools: tool_list };
+        let (tools, next_cursor) = match self.paginate(&request, tool_list) {
+            Ok(page) => page,
+            Err(e) => return Self::error_response(request.id, e),
+        };
+        let result = ToolsListResult { tools, next_cursor };
 
         Self::success_response(request.id, serde_json::to_value(result).unwrap())
     }
 
+    /// Take the page of `items` starting at the request's `cursor`, with the
+    /// cursor for the next page
+    fn paginate<T>(
+        &self,
+        request: &JsonRpcRequest,
+        items: Vec<T>,
+    ) -> std::result::Result<(Vec<T>, Option<String>), JsonRpcError> {
+        let offset = match request.params.as_ref().and_then(|p| p.get("cursor")) {
+            None | Some(Value::Null) => 0,
+            Some(cursor) => cursor
+                .as_str()
+                .and_then(|c| c.parse::<usize>().ok())
+                .ok_or_else(JsonRpcError::invalid_params)?,
+        };
+        let next_offset = offset.saturating_add(self.page_size);
+        let next_cursor = (next_offset < items.len()).then(|| next_offset.to_string());
+        let page = items
+            .into_iter()
+            .skip(offset)
+            .take(self.page_size)
+            .collect();
+        Ok((page, next_cursor))
+    }
+
     /// Handle tools/call request
     async fn handle_tools_call(&self, request: JsonRpcRequest) -> JsonRpcResponse {
         let params: CallToolParams = match request.params {
@@ -202,6 +550,11 @@ impl McpServer {
         };
 
         tracing::info!("[MCP] Calling tool: {}", params.name);
+        self.log(
+            LogLevel::Info,
+            Value::String(format!("Calling tool {}", params.name)),
+        )
+        .await;
 
         let tools = self.tools.read().await;
 
@@ -235,9 +588,47 @@ impl McpServer {
             .unwrap_or(Value::Object(serde_json::Map::new()));
         let tool_use_id = uuid::Uuid::new_v4().to_string();
 
-        let result = match tool.execute(tool_use_id, args, &context).await {
+        // Clients that pass `_meta.progressToken` get `notifications/progress`
+        let progress_token = request
+            .params
+            .as_ref()
+            .and_then(|p| p.get("_meta"))
+            .and_then(|meta| meta.get("progressToken"))
+            .cloned();
+        let (context, progress) = match progress_token {
+            Some(token) => {
+                let (sender, events) = tokio::sync::mpsc::unbounded_channel();
+                (
+                    context.with_progress_sender(sender),
+                    Some(self.spawn_progress_forwarder(token, events)),
+                )
+            }
+            None => (context, None),
+        };
+
+        if let Some(message) = Self::ignored_argument_path(&args, &context) {
+            let call_result = CallToolResult {
+                content: vec![ToolContent::Text { text: message }],
+                is_error: Some(true),
+            };
+            return Self::success_response(request.id, serde_json::to_value(call_result).unwrap());
+        }
+
+        let result = tool.execute(tool_use_id, args, &context).await;
+        // Dropping the context closes the progress channel
+        drop(context);
+        if let Some(progress) = progress {
+            let _ = progress.await;
+        }
+
+        let result = match result {
             Ok(result) => result,
             Err(e) => {
+                self.log(
+                    LogLevel::Error,
+                    Value::String(format!("Tool {} failed: {}", params.name, e)),
+                )
+                .await;
                 return Self::error_response(
                     request.id,
                     JsonRpcError {
@@ -262,6 +653,186 @@ impl McpServer {
         Self::success_response(request.id, serde_json::to_value(call_result).unwrap())
     }
 
+    /// Publish tool progress as `notifications/progress` for `token`
+    fn spawn_progress_forwarder(
+        &self,
+        token: Value,
+        mut events: tokio::sync::mpsc::UnboundedReceiver<ToolProgressEvent>,
+    ) -> tokio::task::JoinHandle<()> {
+        let notifications = self.notifications.clone();
+        tokio::spawn(async move {
+            // Progress must increase with every notification, and tools
+            // rarely know their total, so count the updates.
+            let mut progress: u64 = 0;
+            while let Some(event) = events.recv().await {
+                progress += 1;
+                let notification = JsonRpcRequest {
+                    jsonrpc: "2.0".to_string(),
+                    id: None,
+                    method: "notifications/progress".to_string(),
+                    params: Some(serde_json::json!({
+                        "progressToken": token,
+                        "progress": progress,
+                        "message": event.message,
+                    })),
+                };
+                let _ = notifications.send(notification);
+            }
+        })
+    }
+
+    /// Handle resources/list request, paged by `cursor`
+    async fn handle_resources_list(&self, request: JsonRpcRequest) -> JsonRpcResponse {
+        let resources = match self.resources.list() {
+            Ok(resources) => resources,
+            Err(e) => return Self::error_response(request.id, Self::resource_error(e)),
+        };
+        let (resources, next_cursor) = match self.paginate(&request, resources) {
+            Ok(page) => page,
+            Err(e) => return Self::error_response(request.id, e),
+        };
+        let result = ResourcesListResult {
+            resources,
+            next_cursor,
+        };
+
+        Self::success_response(request.id, serde_json::to_value(result).unwrap())
+    }
+
+    /// Handle resources/read request
+    async fn handle_resources_read(&self, request: JsonRpcRequest) -> JsonRpcResponse {
+        let Some(params) = Self::uri_params(&request) else {
+            return Self::error_response(request.id, JsonRpcError::invalid_params());
+        };
+
+        match self.resources.read(&params.uri).await {
+            Ok(content) => {
+                let result = ReadResourceResult {
+                    contents: vec![content],
+                };
+                Self::success_response(request.id, serde_json::to_value(result).unwrap())
+            }
+            Err(e) => Self::error_response(request.id, Self::resource_error(e)),
+        }
+    }
+
+    /// Handle resources/subscribe and resources/unsubscribe requests
+    async fn handle_resources_subscribe(
+        &self,
+        request: JsonRpcRequest,
+        subscribe: bool,
+    ) -> JsonRpcResponse {
+        let Some(params) = Self::uri_params(&request) else {
+            return Self::error_response(request.id, JsonRpcError::invalid_params());
+        };
+
+        if subscribe {
+            let fingerprint = self.resources.fingerprint(&params.uri).await;
+            if fingerprint.is_none() {
+                return Self::error_response(
+                    request.id,
+                    JsonRpcError {
+                        code: -32002,
+                        message: format!("Resource not found: {}", params.uri),
+                        data: None,
+                    },
+                );
+            }
+            self.subscriptions
+                .write()
+                .await
+                .insert(params.uri, fingerprint);
+        } else {
+            self.subscriptions.write().await.remove(&params.uri);
+        }
+
+        Self::success_response(request.id, serde_json::json!({}))
+    }
+
+    /// Handle prompts/list request, paged by `cursor`
+    async fn handle_prompts_list(&self, request: JsonRpcRequest) -> JsonRpcResponse {
+        match self.prompts.list() {
+            Ok(prompts) => match self.paginate(&request, prompts) {
+                Ok((prompts, next_cursor)) => Self::success_response(
+                    request.id,
+                    serde_json::to_value(PromptsListResult {
+                        prompts,
+                        next_cursor,
+                    })
+                    .unwrap(),
+                ),
+                Err(e) => Self::error_response(request.id, e),
+            },
+            Err(e) => Self::error_response(
+                request.id,
+                JsonRpcError {
+                    code: -32603,
+                    message: format!("Failed to list prompts: {}", e),
+                    data: None,
+                },
+            ),
+        }
+    }
+
+    /// Handle prompts/get request
+    async fn handle_prompts_get(&self, request: JsonRpcRequest) -> JsonRpcResponse {
+        let params: GetPromptParams = match request.params.clone().map(serde_json::from_value) {
+            Some(Ok(params)) => params,
+            _ => return Self::error_response(request.id, JsonRpcError::invalid_params()),
+        };
+
+        match self
+            .prompts
+            .get(&params.name, &params.arguments.unwrap_or_default())
+        {
+            Ok(result) => Self::success_response(request.id, serde_json::to_value(result).unwrap()),
+            Err(e) => Self::error_response(
+                request.id,
+                JsonRpcError {
+                    code: -32602,
+                    message: e.to_string(),
+                    data: None,
+                },
+            ),
+        }
+    }
+
+    fn uri_params(request: &JsonRpcRequest) -> Option<ResourceUriParams> {
+        serde_json::from_value(request.params.clone()?).ok()
+    }
+
+    /// Map a resource failure to a JSON-RPC error; unknown and unreadable
+    /// resources use the spec's "resource not found" code.
+    fn resource_error(error: crate::error::TedError) -> JsonRpcError {
+        let code = match error {
+            crate::error::TedError::InvalidInput(_)
+            | crate::error::TedError::PermissionDenied(_) => -32002,
+            _ => -32603,
+        };
+        JsonRpcError {
+            code,
+            message: error.to_string(),
+            data: None,
+        }
+    }
+
+    /// Reject calls whose path arguments point at `.tedignore`d files, so the
+    /// rules hold even for tools that do not check paths themselves.
+    fn ignored_argument_path(args: &Value, context: &ToolContext) -> Option<String> {
+        ["path", "file_path", "file", "directory", "dir"]
+            .iter()
+            .filter_map(|key| args.get(*key).and_then(Value::as_str))
+            .find_map(|path| {
+                let path = std::path::Path::new(path);
+                let full = if path.is_absolute() {
+                    path.to_path_buf()
+                } else {
+                    context.working_directory.join(path)
+                };
+                context.check_path_not_ignored(&full)
+            })
+    }
+
     /// Create a success response
     fn success_response(id: Option<Value>, result: Value) -> JsonRpcResponse {
         JsonRpcResponse {
@@ -283,6 +854,20 @@ impl McpServer {
     }
 }
 
+/// Write `message` as one line of JSON
+async fn write_line<W, T>(writer: &Mutex<W>, message: &T) -> Result<()>
+where
+    W: AsyncWrite + Unpin,
+    T: serde::Serialize,
+{
+    let mut line = serde_json::to_vec(message)?;
+    line.push(b'\n');
+    let mut writer = writer.lock().await;
+    writer.write_all(&line).await?;
+    writer.flush().await?;
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -990,7 +1575,7 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn test_server_transport_creation() {
+    async fn test_server_notification_channel_creation() {
         use std::env;
         let context = crate::tools::ToolContext::new(
             env::current_dir().unwrap(),
@@ -1001,8 +1586,10 @@ mod tests {
         let executor = ToolExecutor::new(context, false);
         let server = McpServer::new(executor);
 
-        // Transport should be created
-        assert!(Arc::strong_count(&server.transport) >= 1);
+        // Nobody listens for notifications until a transport subscribes
+        assert_eq!(server.notifications.receiver_count(), 0);
+        let _receiver = server.subscribe_notifications();
+        assert_eq!(server.notifications.receiver_count(), 1);
     }
 
     // ===== Handle Tools Call Execution Tests =====
@@ -1213,10 +1800,10 @@ mod tests {
 
         let response = server.handle_request(request).await;
 
-        // Error output is still a success response, but with is_error flag
+        // Error output is still a success response, but with isError flag
         assert!(response.error.is_none());
         let result = response.result.unwrap();
-        assert_eq!(result["is_error"], true);
+        assert_eq!(result["isError"], true);
     }
 
     #[tokio::test]
@@ -1294,7 +1881,7 @@ mod tests {
         };
 
         let json = serde_json::to_value(&result).unwrap();
-        assert_eq!(json["is_error"], true);
+        assert_eq!(json["isError"], true);
     }
 
     // ===== ToolContent Tests =====
@@ -1372,4 +1959,479 @@ mod tests {
         let call_response = server.handle_request(call_request).await;
         assert!(call_response.error.is_none());
     }
+
+    #[test]
+    fn test_ignored_argument_path() {
+        let temp = tempfile::TempDir::new().unwrap();
+        std::fs::write(temp.path().join(".tedignore"), "secrets/\n").unwrap();
+        let context = ToolContext::new(
+            temp.path().to_path_buf(),
+            Some(temp.path().to_path_buf()),
+            uuid::Uuid::new_v4(),
+            false,
+        );
+
+        let message = McpServer::ignored_argument_path(
+            &serde_json::json!({"path": "secrets/prod.env"}),
+            &context,
+        )
+        .unwrap();
+        assert!(message.contains("Path is ignored by .tedignore"));
+        assert!(McpServer::ignored_argument_path(
+            &serde_json::json!({"path": "src/main.rs"}),
+            &context
+        )
+        .is_none());
+    }
+    // ===== Resources and Prompts Tests =====
+
+    fn workspace_server(temp: &tempfile::TempDir) -> McpServer {
+        let workspace = temp.path().join("project");
+        let home = temp.path().join("home");
+        std::fs::create_dir_all(workspace.join(".ted/commands")).unwrap();
+        std::fs::write(workspace.join("README.md"), "# Project").unwrap();
+        let context = ToolContext::new(
+            workspace.clone(),
+            Some(workspace.clone()),
+            uuid::Uuid::new_v4(),
+            false,
+        );
+        let resources = WorkspaceResources::new(context.clone()).with_store_paths(
+            home.join("history.json"),
+            home.join("context"),
+            home.join("plans"),
+        );
+        let prompts = PromptCatalog::with_paths(
+            crate::caps::CapLoader::with_paths(None, home.join("caps")),
+            home.join("commands"),
+            workspace.join(".ted/commands"),
+        );
+        McpServer::new(ToolExecutor::new(context, false))
+            .with_resources(resources)
+            .with_prompts(prompts)
+    }
+
+    fn request(method: &str, params: Option<Value>) -> JsonRpcRequest {
+        JsonRpcRequest {
+            jsonrpc: "2.0".to_string(),
+            id: Some(Value::Number(1.into())),
+            method: method.to_string(),
+            params,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_initialize_advertises_resources_and_prompts() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let server = workspace_server(&temp);
+        let response = server
+            .handle_request(request(
+                "initialize",
+                Some(serde_json::json!({
+                    "protocolVersion": "2024-11-05",
+                    "capabilities": {},
+                    "clientInfo": {"name": "TestClient", "version": "1.0"}
+                })),
+            ))
+            .await;
+
+        let capabilities = &response.result.unwrap()["capabilities"];
+        assert_eq!(capabilities["resources"]["subscribe"], true);
+        assert!(capabilities["prompts"].is_object());
+    }
+
+    #[tokio::test]
+    async fn test_resources_list_and_read() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let server = workspace_server(&temp);
+
+        let response = server.handle_request(request("resources/list", None)).await;
+        let result = response.result.unwrap();
+        let readme = result["resources"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|r| r["name"] == "README.md")
+            .unwrap()
+            .clone();
+        assert_eq!(readme["mimeType"], "text/markdown");
+        assert!(result.get("nextCursor").is_none());
+
+        let response = server
+            .handle_request(request(
+                "resources/read",
+                Some(serde_json::json!({"uri": readme["uri"]})),
+            ))
+            .await;
+        let result = response.result.unwrap();
+        assert_eq!(result["contents"][0]["text"], "# Project");
+    }
+
+    #[tokio::test]
+    async fn test_resources_read_unknown_uri() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let server = workspace_server(&temp);
+
+        let response = server
+            .handle_request(request(
+                "resources/read",
+                Some(serde_json::json!({"uri": "ted://plans/not-a-plan"})),
+            ))
+            .await;
+        assert_eq!(response.error.unwrap().code, -32002);
+
+        let response = server.handle_request(request("resources/read", None)).await;
+        assert_eq!(response.error.unwrap().code, -32602);
+    }
+
+    #[tokio::test]
+    async fn test_resources_subscribe_reports_changes() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let server = workspace_server(&temp);
+        let path = temp.path().join("project/README.md");
+        let uri = format!("file://{}", path.display());
+
+        let response = server
+            .handle_request(request(
+                "resources/subscribe",
+                Some(serde_json::json!({"uri": uri})),
+            ))
+            .await;
+        assert!(response.error.is_none());
+        assert!(
+            McpServer::changed_subscriptions(&server.resources, &server.subscriptions)
+                .await
+                .is_empty()
+        );
+
+        std::fs::write(&path, "# Changed").unwrap();
+        assert_eq!(
+            McpServer::changed_subscriptions(&server.resources, &server.subscriptions).await,
+            vec![uri.clone()]
+        );
+
+        server
+            .handle_request(request(
+                "resources/unsubscribe",
+                Some(serde_json::json!({"uri": uri})),
+            ))
+            .await;
+        assert!(server.subscriptions.read().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_prompts_list_and_get() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let server = workspace_server(&temp);
+
+        let response = server.handle_request(request("prompts/list", None)).await;
+        let prompts = response.result.unwrap()["prompts"].clone();
+        let name = prompts[0]["name"].as_str().unwrap().to_string();
+        assert!(name.starts_with("cap:"));
+
+        let response = server
+            .handle_request(request(
+                "prompts/get",
+                Some(serde_json::json!({"name": name, "arguments": {"task": "Ship it"}})),
+            ))
+            .await;
+        let result = response.result.unwrap();
+        assert_eq!(result["messages"][0]["role"], "user");
+        assert!(result["messages"][0]["content"]["text"]
+            .as_str()
+            .unwrap()
+            .ends_with("Ship it"));
+
+        let response = server
+            .handle_request(request(
+                "prompts/get",
+                Some(serde_json::json!({"name": "cap:missing"})),
+            ))
+            .await;
+        assert_eq!(response.error.unwrap().code, -32602);
+    }
+
+    #[tokio::test]
+    async fn test_ted_agent_call_sends_progress_notifications() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let server = workspace_server(&temp);
+        server
+            .register_tool(Arc::new(crate::mcp::TedAgentTool::with_provider(
+                Arc::new(crate::llm::MockProvider::new().with_response("Finished the task.")),
+                "mock-model",
+                temp.path().join("project"),
+            )))
+            .await;
+        let mut notifications = server.subscribe_notifications();
+
+        let response = server
+            .handle_request(request(
+                "tools/call",
+                Some(serde_json::json!({
+                    "name": "ted_agent",
+                    "arguments": {"task": "Summarize the project", "agent_type": "explore"},
+                    "_meta": {"progressToken": "agent-1"}
+                })),
+            ))
+            .await;
+        let result = response.result.unwrap();
+        assert!(result.get("isError").is_none());
+        assert!(result["content"][0]["text"]
+            .as_str()
+            .unwrap()
+            .contains("Finished the task."));
+
+        let mut progress = Vec::new();
+        while let Ok(notification) = notifications.try_recv() {
+            assert_eq!(notification.method, "notifications/progress");
+            let params = notification.params.unwrap();
+            assert_eq!(params["progressToken"], "agent-1");
+            progress.push(params["progress"].as_u64().unwrap());
+        }
+        assert!(progress.len() >= 2);
+        assert!(progress.windows(2).all(|pair| pair[0] < pair[1]));
+    }
+
+    #[tokio::test]
+    async fn test_tools_call_without_progress_token_sends_no_progress() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let server = workspace_server(&temp);
+        server
+            .register_tool(Arc::new(crate::mcp::TedAgentTool::with_provider(
+                Arc::new(crate::llm::MockProvider::new().with_response("Done.")),
+                "mock-model",
+                temp.path().join("project"),
+            )))
+            .await;
+        let mut notifications = server.subscribe_notifications();
+
+        server
+            .handle_request(request(
+                "tools/call",
+                Some(
+                    serde_json::json!({"name": "ted_agent", "arguments": {"task": "Look around"}}),
+                ),
+            ))
+            .await;
+        assert!(notifications.try_recv().is_err());
+    }
+
+    struct SlowTool;
+
+    #[async_trait::async_trait]
+    impl crate::tools::Tool for SlowTool {
+        fn name(&self) -> &str {
+            "slow"
+        }
+
+        fn definition(&self) -> crate::llm::ToolDefinition {
+            crate::llm::ToolDefinition {
+                name: "slow".to_string(),
+                description: "Never finishes on its own".to_string(),
+                input_schema: crate::llm::ToolInputSchema {
+                    schema_type: "object".to_string(),
+                    properties: serde_json::json!({}),
+                    required: vec![],
+                },
+            }
+        }
+
+        fn permission_request(
+            &self,
+            _input: &serde_json::Value,
+        ) -> Option<crate::tools::PermissionRequest> {
+            None
+        }
+
+        async fn execute(
+            &self,
+            tool_use_id: String,
+            _args: serde_json::Value,
+            _context: &crate::tools::ToolContext,
+        ) -> crate::error::Result<crate::tools::ToolResult> {
+            tokio::time::sleep(std::time::Duration::from_secs(3600)).await;
+            Ok(crate::tools::ToolResult::success(tool_use_id, "late"))
+        }
+    }
+
+    fn initialize_request(version: &str) -> JsonRpcRequest {
+        request(
+            "initialize",
+            Some(serde_json::json!({
+                "protocolVersion": version,
+                "capabilities": {},
+                "clientInfo": {"name": "TestClient", "version": "1.0"}
+            })),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_initialize_negotiates_protocol_version() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let server = workspace_server(&temp);
+
+        let response = server
+            .handle_request(initialize_request("2024-11-05"))
+            .await;
+        let result = response.result.unwrap();
+        assert_eq!(result["protocolVersion"], "2024-11-05");
+        assert!(result["capabilities"]["logging"].is_object());
+        assert_eq!(server.protocol_version().await, "2024-11-05");
+
+        let response = server
+            .handle_request(initialize_request("2030-01-01"))
+            .await;
+        assert_eq!(
+            response.result.unwrap()["protocolVersion"],
+            PROTOCOL_VERSION
+        );
+        assert_eq!(server.protocol_version().await, PROTOCOL_VERSION);
+    }
+
+    #[tokio::test]
+    async fn test_ping() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let response = workspace_server(&temp)
+            .handle_request(request("ping", None))
+            .await;
+        assert_eq!(response.result, Some(serde_json::json!({})));
+    }
+
+    #[tokio::test]
+    async fn test_tools_list_paginates_in_name_order() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let server = workspace_server(&temp).with_page_size(1);
+        server.register_tool(Arc::new(MockSuccessTool)).await;
+        server.register_tool(Arc::new(MockErrorTool)).await;
+
+        let first = server
+            .handle_request(request("tools/list", None))
+            .await
+            .result
+            .unwrap();
+        assert_eq!(first["tools"][0]["name"], "mock_error");
+        assert_eq!(first["nextCursor"], "1");
+
+        let second = server
+            .handle_request(request(
+                "tools/list",
+                Some(serde_json::json!({"cursor": "1"})),
+            ))
+            .await
+            .result
+            .unwrap();
+        assert_eq!(second["tools"][0]["name"], "mock_success");
+        assert!(second.get("nextCursor").is_none());
+
+        let invalid = server
+            .handle_request(request(
+                "tools/list",
+                Some(serde_json::json!({"cursor": "abc"})),
+            ))
+            .await;
+        assert_eq!(invalid.error.unwrap().code, -32602);
+    }
+
+    #[tokio::test]
+    async fn test_set_level_filters_log_messages() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let server = workspace_server(&temp);
+        let mut notifications = server.subscribe_notifications();
+
+        server.log(LogLevel::Error, Value::from("before")).await;
+        assert!(notifications.try_recv().is_err());
+
+        let response = server
+            .handle_request(request(
+                "logging/setLevel",
+                Some(serde_json::json!({"level": "warning"})),
+            ))
+            .await;
+        assert!(response.error.is_none());
+
+        server.log(LogLevel::Info, Value::from("too low")).await;
+        server.log(LogLevel::Error, Value::from("reported")).await;
+        let message = notifications.try_recv().unwrap();
+        assert_eq!(message.method, "notifications/message");
+        let params = message.params.unwrap();
+        assert_eq!(params["level"], "error");
+        assert_eq!(params["data"], "reported");
+        assert!(notifications.try_recv().is_err());
+
+        let invalid = server
+            .handle_request(request(
+                "logging/setLevel",
+                Some(serde_json::json!({"level": "verbose"})),
+            ))
+            .await;
+        assert_eq!(invalid.error.unwrap().code, -32602);
+    }
+
+    #[tokio::test]
+    async fn test_dispatch_cancels_in_flight_request() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let server = workspace_server(&temp);
+        server.register_tool(Arc::new(SlowTool)).await;
+
+        let call = JsonRpcRequest {
+            jsonrpc: "2.0".to_string(),
+            id: Some(Value::from("slow-1")),
+            method: "tools/call".to_string(),
+            params: Some(serde_json::json!({"name": "slow"})),
+        };
+        let running = tokio::spawn({
+            let server = server.clone();
+            async move { server.dispatch(call).await }
+        });
+        while server.in_flight.lock().await.is_empty() {
+            tokio::task::yield_now().await;
+        }
+
+        let cancel = JsonRpcRequest {
+            jsonrpc: "2.0".to_string(),
+            id: None,
+            method: "notifications/cancelled".to_string(),
+            params: Some(serde_json::json!({"requestId": "slow-1", "reason": "test"})),
+        };
+        assert!(server.dispatch(cancel).await.is_none());
+
+        let response = tokio::time::timeout(std::time::Duration::from_secs(5), running)
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(response.is_none());
+        assert!(server.in_flight.lock().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_handle_message_batches_and_errors() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let server = workspace_server(&temp);
+
+        let parse_error = server.handle_message("{not json").await.unwrap();
+        assert_eq!(parse_error["error"]["code"], -32700);
+        assert!(parse_error["id"].is_null());
+
+        let empty_batch = server.handle_message("[]").await.unwrap();
+        assert_eq!(empty_batch["error"]["code"], -32600);
+
+        let notification = server
+            .handle_message(r#"{"jsonrpc":"2.0","method":"notifications/initialized"}"#)
+            .await;
+        assert!(notification.is_none());
+        assert!(*server.initialized.read().await);
+
+        let batch = server
+            .handle_message(
+                r#"[{"jsonrpc":"2.0","id":1,"method":"ping"},
+                    {"jsonrpc":"2.0","method":"notifications/initialized"},
+                    {"jsonrpc":"2.0","id":2,"method":"nope"}]"#,
+            )
+            .await
+            .unwrap();
+        let replies = batch.as_array().unwrap();
+        assert_eq!(replies.len(), 2);
+        assert_eq!(replies[0]["id"], 1);
+        assert_eq!(replies[1]["error"]["code"], -32601);
+    }
 }
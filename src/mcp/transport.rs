@@ -3,7 +3,10 @@
 
 //! MCP transport layer - stdio-based communication
 //!
-//! MCP servers communicate via stdio (standard input/output) using JSON-RPC 2.0
+//! MCP servers communicate via stdio (standard input/output) using JSON-RPC 2.0.
+//! The Streamable HTTP transport lives in [`http`].
+
+pub mod http;
 
 use std::io::{self, BufRead, Write};
 use std::sync::Arc;
@@ -62,6 +65,17 @@ impl StdioTransport {
 
         Ok(())
     }
+
+    /// Write a JSON-RPC notification (a request without an id) to stdout
+    pub async fn write_notification(&self, notification: &JsonRpcRequest) -> Result<()> {
+        let json = serde_json::to_string(notification)?;
+
+        let mut stdout = self.stdout.lock().await;
+        writeln!(stdout, "{}", json)?;
+        stdout.flush()?;
+
+        Ok(())
+    }
 }
 
 impl Default for StdioTransport {
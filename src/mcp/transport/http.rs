@@ -0,0 +1,913 @@
+// SPDX-License-Identifier: AGPL-3.0-or-later
+// Copyright (C) 2025 Blackman Artificial Intelligence Technologies Inc.
+
+//! MCP Streamable HTTP transport
+//!
+//! Serves a single endpoint, [`MCP_ENDPOINT`]:
+//! - `POST` carries client messages. Requests are answered with a JSON body,
+//!   or with an SSE stream when the client only accepts `text/event-stream`.
+//! - `GET` opens an SSE stream for server notifications.
+//! - `DELETE` ends a session.
+//!
+//! `initialize` starts a session and returns its id in the `Mcp-Session-Id`
+//! header; every later request must send it back. Each session gets its own
+//! [`McpServer`], so several clients can share one long-running Ted. Sessions
+//! close after [`HttpTransportConfig::idle_timeout`] without requests or an
+//! open event stream, and at most [`HttpTransportConfig::max_sessions`] can be
+//! open at once.
+
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use bytes::Bytes;
+use futures::future::BoxFuture;
+use futures::StreamExt;
+use http_body_util::combinators::BoxBody;
+use http_body_util::{BodyExt, Empty, Full, Limited, StreamBody};
+use hyper::body::{Body, Frame};
+use hyper::header::{HeaderValue, ACCEPT, ALLOW, AUTHORIZATION, CONTENT_TYPE, ORIGIN};
+use hyper::{Method, Request, Response, StatusCode};
+use hyper_util::rt::TokioIo;
+use serde_json::Value;
+use tokio::net::TcpListener;
+use tokio::sync::{broadcast, RwLock};
+
+use crate::error::Result;
+use crate::mcp::protocol::{
+    JsonRpcError, JsonRpcRequest, JsonRpcResponse, SUPPORTED_PROTOCOL_VERSIONS,
+};
+use crate::mcp::server::McpServer;
+
+/// Path of the MCP endpoint
+pub const MCP_ENDPOINT: &str = "/mcp";
+
+/// Header carrying the session id
+pub const SESSION_HEADER: &str = "mcp-session-id";
+
+/// Header carrying the negotiated protocol version (2025-06-18 and later)
+pub const PROTOCOL_VERSION_HEADER: &str = "mcp-protocol-version";
+
+/// Largest accepted request body
+const MAX_BODY_BYTES: usize = 4 * 1024 * 1024;
+
+/// Interval between keep-alive comments on idle SSE streams
+const KEEP_ALIVE_INTERVAL: Duration = Duration::from_secs(15);
+
+/// Default time a session may go unused before it is closed
+const DEFAULT_IDLE_TIMEOUT: Duration = Duration::from_secs(30 * 60);
+
+/// Default limit on concurrently open sessions
+const DEFAULT_MAX_SESSIONS: usize = 32;
+
+/// Longest wait between sweeps for idle sessions
+const MAX_REAP_INTERVAL: Duration = Duration::from_secs(60);
+
+type HttpBody = BoxBody<Bytes, Infallible>;
+
+/// Builds the server for a new session
+pub type ServerFactory = Arc<dyn Fn() -> BoxFuture<'static, McpServer> + Send + Sync>;
+
+/// Options for the HTTP transport
+#[derive(Debug, Clone)]
+pub struct HttpTransportConfig {
+    /// Require `Authorization: Bearer <token>` on every request
+    pub bearer_token: Option<String>,
+    /// Close sessions that see no requests and have no open event stream
+    /// for this long
+    pub idle_timeout: Duration,
+    /// Refuse `initialize` once this many sessions are open
+    pub max_sessions: usize,
+}
+
+impl Default for HttpTransportConfig {
+    fn default() -> Self {
+        Self {
+            bearer_token: None,
+            idle_timeout: DEFAULT_IDLE_TIMEOUT,
+            max_sessions: DEFAULT_MAX_SESSIONS,
+        }
+    }
+}
+
+struct HttpSession {
+    server: McpServer,
+    watcher: tokio::task::JoinHandle<()>,
+    last_used: Arc<Mutex<Instant>>,
+}
+
+impl HttpSession {
+    fn touch(&self) {
+        touch(&self.last_used);
+    }
+
+    fn idle_for(&self) -> Duration {
+        self.last_used
+            .lock()
+            .map(|last_used| last_used.elapsed())
+            .unwrap_or_default()
+    }
+}
+
+fn touch(last_used: &Mutex<Instant>) {
+    if let Ok(mut last_used) = last_used.lock() {
+        *last_used = Instant::now();
+    }
+}
+
+impl Drop for HttpSession {
+    fn drop(&mut self) {
+        self.watcher.abort();
+    }
+}
+
+/// Streamable HTTP transport serving MCP sessions
+#[derive(Clone)]
+pub struct HttpTransport {
+    config: HttpTransportConfig,
+    factory: ServerFactory,
+    sessions: Arc<RwLock<HashMap<String, Arc<HttpSession>>>>,
+}
+
+impl HttpTransport {
+    /// Create a transport that builds one server per session with `factory`
+    pub fn new(factory: ServerFactory, config: HttpTransportConfig) -> Self {
+        Self {
+            config,
+            factory,
+            sessions: Arc::new(RwLock::new(HashMap::new())),
+        }
+    }
+
+    /// Number of open sessions
+    pub async fn session_count(&self) -> usize {
+        self.sessions.read().await.len()
+    }
+
+    /// Accept connections until the listener fails
+    pub async fn serve(self, listener: TcpListener) -> Result<()> {
+        let reaper = self.spawn_reaper();
+        loop {
+            let (stream, peer) = match listener.accept().await {
+                Ok(accepted) => accepted,
+                Err(e) => {
+                    reaper.abort();
+                    return Err(e.into());
+                }
+            };
+            let transport = self.clone();
+            tokio::spawn(async move {
+                let service = hyper::service::service_fn(move |request| {
+                    let transport = transport.clone();
+                    async move { Ok::<_, Infallible>(transport.handle(request).await) }
+                });
+                if let Err(e) = hyper::server::conn::http1::Builder::new()
+                    .serve_connection(TokioIo::new(stream), service)
+                    .await
+                {
+                    tracing::debug!("[MCP] HTTP connection from {} ended: {}", peer, e);
+                }
+            });
+        }
+    }
+
+    /// Periodically close sessions that have been idle too long
+    fn spawn_reaper(&self) -> tokio::task::JoinHandle<()> {
+        let transport = self.clone();
+        let period =
+            (self.config.idle_timeout / 2).clamp(Duration::from_secs(1), MAX_REAP_INTERVAL);
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(period);
+            loop {
+                interval.tick().await;
+                transport.reap_idle_sessions().await;
+            }
+        })
+    }
+
+    /// Close sessions idle for longer than the configured timeout
+    async fn reap_idle_sessions(&self) {
+        let timeout = self.config.idle_timeout;
+        self.sessions.write().await.retain(|id, session| {
+            let keep = session.idle_for() <= timeout;
+            if !keep {
+                tracing::info!("[MCP] Closed idle HTTP session {}", id);
+            }
+            keep
+        });
+    }
+
+    /// Route one HTTP request
+    pub async fn handle<B>(&self, request: Request<B>) -> Response<HttpBody>
+    where
+        B: Body<Data = Bytes>,
+        B::Error: std::error::Error + Send + Sync + 'static,
+    {
+        if request.uri().path() != MCP_ENDPOINT {
+            return status_response(StatusCode::NOT_FOUND, "Not found");
+        }
+        if !self.origin_allowed(&request) {
+            return status_response(StatusCode::FORBIDDEN, "Origin not allowed");
+        }
+        if !self.authorized(&request) {
+            let mut response = status_response(StatusCode::UNAUTHORIZED, "Unauthorized");
+            response
+                .headers_mut()
+                .insert("www-authenticate", HeaderValue::from_static("Bearer"));
+            return response;
+        }
+        if let Some(version) = request.headers().get(PROTOCOL_VERSION_HEADER) {
+            let supported = version
+                .to_str()
+                .is_ok_and(|version| SUPPORTED_PROTOCOL_VERSIONS.contains(&version));
+            if !supported {
+                return jsonrpc_error_response(
+                    StatusCode::BAD_REQUEST,
+                    "Unsupported MCP-Protocol-Version",
+                );
+            }
+        }
+
+        match *request.method() {
+            Method::POST => self.handle_post(request).await,
+            Method::GET => self.handle_get(&request).await,
+            Method::DELETE => self.handle_delete(&request).await,
+            _ => {
+                let mut response =
+                    status_response(StatusCode::METHOD_NOT_ALLOWED, "Method not allowed");
+                response
+                    .headers_mut()
+                    .insert(ALLOW, HeaderValue::from_static("GET, POST, DELETE"));
+                response
+            }
+        }
+    }
+
+    /// Browsers may only reach the endpoint from a local page, unless a
+    /// bearer token is required anyway. Guards against DNS rebinding.
+    fn origin_allowed<B>(&self, request: &Request<B>) -> bool {
+        if self.config.bearer_token.is_some() {
+            return true;
+        }
+        let Some(origin) = request.headers().get(ORIGIN).and_then(|v| v.to_str().ok()) else {
+            return true;
+        };
+        let host = origin
+            .split_once("://")
+            .map(|(_, rest)| rest)
+            .unwrap_or(origin);
+        let host = host.split('/').next().unwrap_or(host);
+        let host = if host.starts_with('[') {
+            host.split(']').next().map(|h| &h[1..]).unwrap_or(host)
+        } else {
+            host.split(':').next().unwrap_or(host)
+        };
+        matches!(host, "localhost" | "127.0.0.1" | "::1")
+    }
+
+    fn authorized<B>(&self, request: &Request<B>) -> bool {
+        let Some(expected) = self.config.bearer_token.as_deref() else {
+            return true;
+        };
+        request
+            .headers()
+            .get(AUTHORIZATION)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.strip_prefix("Bearer "))
+            .is_some_and(|token| constant_time_eq(token.trim().as_bytes(), expected.as_bytes()))
+    }
+
+    async fn session<B>(
+        &self,
+        request: &Request<B>,
+    ) -> std::result::Result<Arc<HttpSession>, Response<HttpBody>> {
+        let Some(id) = request
+            .headers()
+            .get(SESSION_HEADER)
+            .and_then(|v| v.to_str().ok())
+        else {
+            return Err(jsonrpc_error_response(
+                StatusCode::BAD_REQUEST,
+                "Missing Mcp-Session-Id header",
+            ));
+        };
+        let session = self.sessions.read().await.get(id).cloned();
+        match session {
+            Some(session) if session.idle_for() <= self.config.idle_timeout => {
+                session.touch();
+                Ok(session)
+            }
+            Some(_) => {
+                self.reap_idle_sessions().await;
+                Err(jsonrpc_error_response(
+                    StatusCode::NOT_FOUND,
+                    "Session not found",
+                ))
+            }
+            None => Err(jsonrpc_error_response(
+                StatusCode::NOT_FOUND,
+                "Session not found",
+            )),
+        }
+    }
+
+    /// Start a session, or `None` when the session limit is reached
+    async fn start_session(&self) -> Option<(String, Arc<HttpSession>)> {
+        self.reap_idle_sessions().await;
+        if self.sessions.read().await.len() >= self.config.max_sessions {
+            return None;
+        }
+        let server = (self.factory)().await;
+        let watcher = server.spawn_subscription_watcher();
+        let id = uuid::Uuid::new_v4().to_string();
+        let session = Arc::new(HttpSession {
+            server,
+            watcher,
+            last_used: Arc::new(Mutex::new(Instant::now())),
+        });
+        let mut sessions = self.sessions.write().await;
+        if sessions.len() >= self.config.max_sessions {
+            return None;
+        }
+        sessions.insert(id.clone(), Arc::clone(&session));
+        tracing::info!("[MCP] Started HTTP session {}", id);
+        Some((id, session))
+    }
+
+    async fn handle_post<B>(&self, request: Request<B>) -> Response<HttpBody>
+    where
+        B: Body<Data = Bytes>,
+        B::Error: std::error::Error + Send + Sync + 'static,
+    {
+        let stream_response = accepts_only_event_stream(&request);
+        let existing = match request.headers().get(SESSION_HEADER) {
+            Some(_) => match self.session(&request).await {
+                Ok(session) => Some(session),
+                Err(response) => return response,
+            },
+            None => None,
+        };
+
+        let body = match Limited::new(request.into_body(), MAX_BODY_BYTES)
+            .collect()
+            .await
+        {
+            Ok(body) => body.to_bytes(),
+            Err(_) => return status_response(StatusCode::PAYLOAD_TOO_LARGE, "Body too large"),
+        };
+        let Ok(payload) = serde_json::from_slice::<Value>(&body) else {
+            return json_response(
+                StatusCode::BAD_REQUEST,
+                &error_message(None, JsonRpcError::parse_error()),
+            );
+        };
+        let (messages, batch) = match payload {
+            Value::Array(messages) if !messages.is_empty() => (messages, true),
+            Value::Array(_) => {
+                return json_response(
+                    StatusCode::BAD_REQUEST,
+                    &error_message(None, JsonRpcError::invalid_request()),
+                )
+            }
+            message => (vec![message], false),
+        };
+
+        let initializing = messages
+            .iter()
+            .any(|m| m.get("method").and_then(Value::as_str) == Some("initialize"));
+        let (session_id, session) = match existing {
+            Some(session) => (None, session),
+            None if initializing => match self.start_session().await {
+                Some((id, session)) => (Some(id), session),
+                None => {
+                    tracing::warn!(
+                        "[MCP] Refused HTTP session: {} sessions already open",
+                        self.config.max_sessions
+                    );
+                    return jsonrpc_error_response(
+                        StatusCode::SERVICE_UNAVAILABLE,
+                        "Too many open sessions",
+                    );
+                }
+            },
+            None => {
+                return jsonrpc_error_response(
+                    StatusCode::BAD_REQUEST,
+                    "Missing Mcp-Session-Id header",
+                )
+            }
+        };
+
+        let mut responses = Vec::new();
+        for message in messages {
+            // Client responses (no method) need no reply
+            if message.get("method").is_none() {
+                continue;
+            }
+            let id = message.get("id").cloned();
+            match serde_json::from_value::<JsonRpcRequest>(message) {
+                // Notifications and cancelled requests get no response
+                Ok(request) => responses.extend(session.server.dispatch(request).await),
+                Err(_) => responses.push(error_message(id, JsonRpcError::invalid_request())),
+            }
+        }
+
+        let mut response = if responses.is_empty() {
+            empty_response(StatusCode::ACCEPTED)
+        } else {
+            let body = if batch {
+                serde_json::to_value(&responses).unwrap_or(Value::Null)
+            } else {
+                serde_json::to_value(&responses[0]).unwrap_or(Value::Null)
+            };
+            if stream_response {
+                sse_response(full_body(sse_event(&body)))
+            } else {
+                json_response(StatusCode::OK, &body)
+            }
+        };
+        if let Some(id) = session_id.and_then(|id| HeaderValue::from_str(&id).ok()) {
+            response.headers_mut().insert(SESSION_HEADER, id);
+        }
+        response
+    }
+
+    async fn handle_get<B>(&self, request: &Request<B>) -> Response<HttpBody> {
+        let accepts_stream = request
+            .headers()
+            .get(ACCEPT)
+            .and_then(|v| v.to_str().ok())
+            .is_some_and(|accept| accept.contains("text/event-stream"));
+        if !accepts_stream {
+            return status_response(StatusCode::NOT_ACCEPTABLE, "Accept text/event-stream");
+        }
+        let session = match self.session(request).await {
+            Ok(session) => session,
+            Err(response) => return response,
+        };
+
+        let mut notifications = session.server.subscribe_notifications();
+        // An open stream keeps the session alive.
+        let last_used = Arc::clone(&session.last_used);
+        // Only the receiver is kept, so ending the session closes the stream.
+        drop(session);
+        let stream = async_stream::stream! {
+            loop {
+                touch(&last_used);
+                tokio::select! {
+                    notification = notifications.recv() => match notification {
+                        Ok(notification) => {
+                            let value = serde_json::to_value(&notification).unwrap_or(Value::Null);
+                            yield Bytes::from(sse_event(&value));
+                        }
+                        Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                            tracing::warn!("[MCP] SSE stream dropped {} notifications", skipped);
+                        }
+                        Err(broadcast::error::RecvError::Closed) => break,
+                    },
+                    _ = tokio::time::sleep(KEEP_ALIVE_INTERVAL) => {
+                        yield Bytes::from_static(b": keep-alive\n\n");
+                    }
+                }
+            }
+        };
+        sse_response(BodyExt::boxed(StreamBody::new(
+            stream.map(|bytes| Ok::<_, Infallible>(Frame::data(bytes))),
+        )))
+    }
+
+    async fn handle_delete<B>(&self, request: &Request<B>) -> Response<HttpBody> {
+        let Some(id) = request
+            .headers()
+            .get(SESSION_HEADER)
+            .and_then(|v| v.to_str().ok())
+        else {
+            return jsonrpc_error_response(
+                StatusCode::BAD_REQUEST,
+                "Missing Mcp-Session-Id header",
+            );
+        };
+        match self.sessions.write().await.remove(id) {
+            Some(_) => {
+                tracing::info!("[MCP] Closed HTTP session {}", id);
+                empty_response(StatusCode::NO_CONTENT)
+            }
+            None => jsonrpc_error_response(StatusCode::NOT_FOUND, "Session not found"),
+        }
+    }
+}
+
+/// Whether the client asked for SSE but not JSON
+fn accepts_only_event_stream<B>(request: &Request<B>) -> bool {
+    let accept = request
+        .headers()
+        .get(ACCEPT)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or_default();
+    accept.contains("text/event-stream")
+        && !accept.contains("application/json")
+        && !accept.contains("*/*")
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+fn error_message(id: Option<Value>, error: JsonRpcError) -> JsonRpcResponse {
+    JsonRpcResponse {
+        jsonrpc: "2.0".to_string(),
+        id,
+        result: None,
+        error: Some(error),
+    }
+}
+
+fn sse_event(message: &Value) -> String {
+    format!("event: message\ndata: {}\n\n", message)
+}
+
+fn full_body(body: impl Into<Bytes>) -> HttpBody {
+    BodyExt::boxed(Full::new(body.into()))
+}
+
+fn empty_response(status: StatusCode) -> Response<HttpBody> {
+    let mut response = Response::new(BodyExt::boxed(Empty::new()));
+    *response.status_mut() = status;
+    response
+}
+
+fn status_response(status: StatusCode, message: &'static str) -> Response<HttpBody> {
+    let mut response = Response::new(full_body(message));
+    *response.status_mut() = status;
+    response
+        .headers_mut()
+        .insert(CONTENT_TYPE, HeaderValue::from_static("text/plain"));
+    response
+}
+
+fn json_response(status: StatusCode, body: &impl serde::Serialize) -> Response<HttpBody> {
+    let mut response = Response::new(full_body(serde_json::to_vec(body).unwrap_or_default()));
+    *response.status_mut() = status;
+    response
+        .headers_mut()
+        .insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+    response
+}
+
+fn jsonrpc_error_response(status: StatusCode, message: &str) -> Response<HttpBody> {
+    json_response(
+        status,
+        &error_message(
+            None,
+            JsonRpcError {
+                code: -32600,
+                message: message.to_string(),
+                data: None,
+            },
+        ),
+    )
+}
+
+fn sse_response(body: HttpBody) -> Response<HttpBody> {
+    let mut response = Response::new(body);
+    let headers = response.headers_mut();
+    headers.insert(CONTENT_TYPE, HeaderValue::from_static("text/event-stream"));
+    headers.insert("cache-control", HeaderValue::from_static("no-cache"));
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mcp::resources::WorkspaceResources;
+    use crate::tools::{ToolContext, ToolExecutor, ToolRegistry};
+    use std::path::PathBuf;
+    use tempfile::TempDir;
+
+    async fn start(temp: &TempDir, bearer_token: Option<&str>) -> String {
+        start_with(
+            temp,
+            HttpTransportConfig {
+                bearer_token: bearer_token.map(str::to_string),
+                ..Default::default()
+            },
+        )
+        .await
+    }
+
+    async fn start_with(temp: &TempDir, config: HttpTransportConfig) -> String {
+        let workspace = temp.path().join("project");
+        let home = temp.path().join("home");
+        std::fs::create_dir_all(&workspace).unwrap();
+        std::fs::write(workspace.join("notes.md"), "v1").unwrap();
+
+        let factory: ServerFactory = Arc::new(move || {
+            let workspace: PathBuf = workspace.clone();
+            let home = home.clone();
+            Box::pin(async move {
+                let context = ToolContext::new(
+                    workspace.clone(),
+                    Some(workspace),
+                    uuid::Uuid::new_v4(),
+                    false,
+                );
+                let resources = WorkspaceResources::new(context.clone()).with_store_paths(
+                    home.join("history.json"),
+                    home.join("context"),
+                    home.join("plans"),
+                );
+                let server =
+                    McpServer::new(ToolExecutor::new(context, false)).with_resources(resources);
+                let registry = ToolRegistry::with_builtins();
+                if let Some(tool) = registry.get("file_read") {
+                    server.register_tool(tool.clone()).await;
+                }
+                server
+            })
+        });
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}{}", listener.local_addr().unwrap(), MCP_ENDPOINT);
+        let transport = HttpTransport::new(factory, config);
+        tokio::spawn(transport.serve(listener));
+        url
+    }
+
+    fn initialize() -> Value {
+        serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "initialize",
+            "params": {
+                "protocolVersion": "2024-11-05",
+                "capabilities": {},
+                "clientInfo": {"name": "test", "version": "1.0"}
+            }
+        })
+    }
+
+    async fn open_session(client: &reqwest::Client, url: &str) -> String {
+        let response = client
+            .post(url)
+            .header(ACCEPT, "application/json, text/event-stream")
+            .json(&initialize())
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), 200);
+        let session = response.headers()[SESSION_HEADER]
+            .to_str()
+            .unwrap()
+            .to_string();
+        let body: Value = response.json().await.unwrap();
+        assert_eq!(body["result"]["serverInfo"]["name"], "ted");
+        session
+    }
+
+    #[tokio::test]
+    async fn test_session_lifecycle() {
+        let temp = TempDir::new().unwrap();
+        let url = start(&temp, None).await;
+        let client = reqwest::Client::new();
+        let session = open_session(&client, &url).await;
+
+        let notification = client
+            .post(&url)
+            .header(SESSION_HEADER, &session)
+            .json(&serde_json::json!({"jsonrpc": "2.0", "method": "notifications/initialized"}))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(notification.status(), 202);
+
+        let tools: Value = client
+            .post(&url)
+            .header(SESSION_HEADER, &session)
+            .json(&serde_json::json!({"jsonrpc": "2.0", "id": 2, "method": "tools/list"}))
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert_eq!(tools["result"]["tools"][0]["name"], "file_read");
+
+        let batch: Value = client
+            .post(&url)
+            .header(SESSION_HEADER, &session)
+            .json(&serde_json::json!([
+                {"jsonrpc": "2.0", "id": 3, "method": "tools/list"},
+                {"jsonrpc": "2.0", "id": 4, "method": "prompts/list"}
+            ]))
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert_eq!(batch.as_array().unwrap().len(), 2);
+
+        let deleted = client
+            .delete(&url)
+            .header(SESSION_HEADER, &session)
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(deleted.status(), 204);
+
+        let after_delete = client
+            .post(&url)
+            .header(SESSION_HEADER, &session)
+            .json(&serde_json::json!({"jsonrpc": "2.0", "id": 5, "method": "tools/list"}))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(after_delete.status(), 404);
+    }
+
+    #[tokio::test]
+    async fn test_requests_need_a_session() {
+        let temp = TempDir::new().unwrap();
+        let url = start(&temp, None).await;
+        let client = reqwest::Client::new();
+
+        let missing = client
+            .post(&url)
+            .json(&serde_json::json!({"jsonrpc": "2.0", "id": 1, "method": "tools/list"}))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(missing.status(), 400);
+
+        let unknown = client
+            .post(&url)
+            .header(SESSION_HEADER, "nope")
+            .json(&serde_json::json!({"jsonrpc": "2.0", "id": 1, "method": "tools/list"}))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(unknown.status(), 404);
+
+        let unsupported = client
+            .post(&url)
+            .header(PROTOCOL_VERSION_HEADER, "1999-01-01")
+            .json(&initialize())
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(unsupported.status(), 400);
+
+        let wrong_path = client
+            .post(url.replace(MCP_ENDPOINT, "/other"))
+            .json(&initialize())
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(wrong_path.status(), 404);
+    }
+
+    #[tokio::test]
+    async fn test_sessions_are_independent() {
+        let temp = TempDir::new().unwrap();
+        let url = start(&temp, None).await;
+        let client = reqwest::Client::new();
+
+        let first = open_session(&client, &url).await;
+        let second = open_session(&client, &url).await;
+        assert_ne!(first, second);
+    }
+
+    #[tokio::test]
+    async fn test_session_limit_and_idle_timeout() {
+        let temp = TempDir::new().unwrap();
+        let url = start_with(
+            &temp,
+            HttpTransportConfig {
+                idle_timeout: Duration::from_millis(300),
+                max_sessions: 1,
+                ..Default::default()
+            },
+        )
+        .await;
+        let client = reqwest::Client::new();
+        let session = open_session(&client, &url).await;
+
+        let refused = client.post(&url).json(&initialize()).send().await.unwrap();
+        assert_eq!(refused.status(), 503);
+
+        tokio::time::sleep(Duration::from_millis(500)).await;
+        let expired = client
+            .post(&url)
+            .header(SESSION_HEADER, &session)
+            .json(&serde_json::json!({"jsonrpc": "2.0", "id": 2, "method": "tools/list"}))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(expired.status(), 404);
+
+        // The expired session no longer counts against the limit
+        open_session(&client, &url).await;
+    }
+
+    #[tokio::test]
+    async fn test_post_answers_with_sse_when_requested() {
+        let temp = TempDir::new().unwrap();
+        let url = start(&temp, None).await;
+        let client = reqwest::Client::new();
+
+        let response = client
+            .post(&url)
+            .header(ACCEPT, "text/event-stream")
+            .json(&initialize())
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.headers()[CONTENT_TYPE], "text/event-stream");
+        let body = response.text().await.unwrap();
+        let data = body
+            .lines()
+            .find_map(|line| line.strip_prefix("data: "))
+            .unwrap();
+        let message: Value = serde_json::from_str(data).unwrap();
+        assert_eq!(message["id"], 1);
+    }
+
+    #[tokio::test]
+    async fn test_get_stream_delivers_resource_updates() {
+        let temp = TempDir::new().unwrap();
+        let url = start(&temp, None).await;
+        let client = reqwest::Client::new();
+        let session = open_session(&client, &url).await;
+
+        let uri = format!("file://{}", temp.path().join("project/notes.md").display());
+        let subscribed = client
+            .post(&url)
+            .header(SESSION_HEADER, &session)
+            .json(&serde_json::json!({
+                "jsonrpc": "2.0", "id": 2, "method": "resources/subscribe",
+                "params": {"uri": uri}
+            }))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(subscribed.status(), 200);
+
+        let mut stream = client
+            .get(&url)
+            .header(ACCEPT, "text/event-stream")
+            .header(SESSION_HEADER, &session)
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(stream.status(), 200);
+
+        std::fs::write(temp.path().join("project/notes.md"), "v2").unwrap();
+        let chunk = tokio::time::timeout(Duration::from_secs(10), stream.chunk())
+            .await
+            .unwrap()
+            .unwrap()
+            .unwrap();
+        let text = String::from_utf8_lossy(&chunk);
+        assert!(text.contains("notifications/resources/updated"));
+        assert!(text.contains("notes.md"));
+    }
+
+    #[tokio::test]
+    async fn test_bearer_token_and_origin() {
+        let temp = TempDir::new().unwrap();
+        let url = start(&temp, Some("secret")).await;
+        let client = reqwest::Client::new();
+
+        let unauthorized = client.post(&url).json(&initialize()).send().await.unwrap();
+        assert_eq!(unauthorized.status(), 401);
+
+        let authorized = client
+            .post(&url)
+            .bearer_auth("secret")
+            .json(&initialize())
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(authorized.status(), 200);
+
+        let open_url = start(&temp, None).await;
+        let foreign = client
+            .post(&open_url)
+            .header(ORIGIN, "https://evil.example")
+            .json(&initialize())
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(foreign.status(), 403);
+
+        let local = client
+            .post(&open_url)
+            .header(ORIGIN, "http://localhost:3000")
+            .json(&initialize())
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(local.status(), 200);
+    }
+}
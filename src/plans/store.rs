@@ -395,6 +395,14 @@ impl PlanStore {
         }
     }
 
+    /// Get the most recently modified plan linked to a session
+    pub fn latest_for_session(&self, session_id: Uuid) -> Option<&PlanInfo> {
+        self.plans
+            .iter()
+            .filter(|p| p.session_id == Some(session_id))
+            .max_by_key(|p| p.modified_at)
+    }
+
     /// Get the active plan (most recently modified active plan)
     pub fn get_active(&self) -> Option<&PlanInfo> {
         self.plans
@@ -595,6 +603,30 @@ mod tests {
         assert_eq!(store.get_active().unwrap().id, plan.info.id);
     }
 
+    #[test]
+    fn test_store_latest_for_session() {
+        let (_dir, mut store) = create_test_store();
+        let session_id = Uuid::new_v4();
+
+        let first = store.create("First", "- [ ] A").unwrap();
+        let other = store.create("Other session", "- [ ] B").unwrap();
+        store.link_session(first.info.id, session_id).unwrap();
+        store.link_session(other.info.id, Uuid::new_v4()).unwrap();
+        assert_eq!(
+            store.latest_for_session(session_id).unwrap().id,
+            first.info.id
+        );
+
+        std::thread::sleep(std::time::Duration::from_millis(10));
+        let second = store.create("Second", "- [ ] C").unwrap();
+        store.link_session(second.info.id, session_id).unwrap();
+        assert_eq!(
+            store.latest_for_session(session_id).unwrap().id,
+            second.info.id
+        );
+        assert!(store.latest_for_session(Uuid::new_v4()).is_none());
+    }
+
     #[test]
     fn test_count_tasks_with_subtasks() {
         let tasks = vec![
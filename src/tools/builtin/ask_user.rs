@@ -0,0 +1,332 @@
+// SPDX-License-Identifier: AGPL-3.0-or-later
+// Copyright (C) 2025 Blackman Artificial Intelligence Technologies Inc.
+
+//! Ask user tool
+//!
+//! Lets the model ask the user a structured clarifying question, optionally
+//! with multiple-choice options. Execution blocks until the frontend (the TUI
+//! picker, or the host of an embedded `question` event) returns an answer.
+//! Without an interactive frontend the tool falls back to the question's
+//! `default`, or fails so the model proceeds on its own judgement.
+
+use async_trait::async_trait;
+use serde_json::Value;
+
+use crate::error::{Result, TedError};
+use crate::llm::provider::ToolDefinition;
+use crate::tools::{PermissionRequest, SchemaBuilder, Tool, ToolContext, ToolResult, UserQuestion};
+
+/// Tool for asking the user clarifying questions
+pub struct AskUserTool;
+
+impl AskUserTool {
+    fn parse_question(tool_use_id: &str, input: &Value) -> Result<UserQuestion> {
+        let question = input["question"]
+            .as_str()
+            .map(str::trim)
+            .filter(|q| !q.is_empty())
+            .ok_or_else(|| TedError::InvalidInput("question is required".to_string()))?;
+
+        let options: Vec<String> = input["options"]
+            .as_array()
+            .map(|values| {
+                values
+                    .iter()
+                    .filter_map(|v| v.as_str())
+                    .map(|v| v.trim().to_string())
+                    .filter(|v| !v.is_empty())
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        // Free text defaults to on when there is nothing to pick from.
+        let allow_free_text = input["allow_free_text"]
+            .as_bool()
+            .unwrap_or(options.is_empty());
+
+        let default = input["default"]
+            .as_str()
+            .map(|d| d.trim().to_string())
+            .filter(|d| !d.is_empty());
+
+        Ok(UserQuestion {
+            id: tool_use_id.to_string(),
+            question: question.to_string(),
+            options,
+            allow_free_text,
+            default,
+        })
+    }
+}
+
+/// Normalize an answer against the question's options.
+///
+/// Accepts an option's text (case-insensitively) or its 1-based number.
+/// Returns `None` if the answer is not acceptable for the question.
+pub fn resolve_answer(question: &UserQuestion, answer: &str) -> Option<String> {
+    let answer = answer.trim();
+    if answer.is_empty() {
+        return None;
+    }
+
+    if let Some(option) = question
+        .options
+        .iter()
+        .find(|o| o.eq_ignore_ascii_case(answer))
+    {
+        return Some(option.clone());
+    }
+
+    if let Ok(n) = answer.parse::<usize>() {
+        if let Some(option) = n.checked_sub(1).and_then(|i| question.options.get(i)) {
+            return Some(option.clone());
+        }
+    }
+
+    if question.allow_free_text || question.options.is_empty() {
+        Some(answer.to_string())
+    } else {
+        None
+    }
+}
+
+#[async_trait]
+impl Tool for AskUserTool {
+    fn name(&self) -> &str {
+        "ask_user"
+    }
+
+    fn definition(&self) -> ToolDefinition {
+        ToolDefinition {
+            name: "ask_user".to_string(),
+            description: "Ask the user a clarifying question and wait for the answer. Use this only when a decision genuinely needs the user's input (ambiguous requirements, choosing between approaches with real trade-offs). Provide 'options' for multiple choice; set 'allow_free_text' to also accept a typed answer. In non-interactive runs the 'default' is used, or the tool fails and you should proceed with your best judgement.".to_string(),
+            input_schema: SchemaBuilder::new()
+                .string("question", "The question to ask the user", true)
+                .array(
+                    "options",
+                    "Choices to offer the user (omit for a free-text question)",
+                    "string",
+                    false,
+                )
+                .boolean(
+                    "allow_free_text",
+                    "Whether the user may type an answer other than the options (default: true without options, false with options)",
+                    false,
+                )
+                .string(
+                    "default",
+                    "Answer to use when no interactive user is available",
+                    false,
+                )
+                .build(),
+        }
+    }
+
+    async fn execute(
+        &self,
+        tool_use_id: String,
+        input: Value,
+        context: &ToolContext,
+    ) -> Result<ToolResult> {
+        let question = Self::parse_question(&tool_use_id, &input)?;
+
+        if !context.can_ask_user() {
+            return Ok(match question.default {
+                Some(default) => ToolResult::success(
+                    tool_use_id,
+                    format!(
+                        "No interactive user is available; using the default answer: {}",
+                        default
+                    ),
+                ),
+                None => ToolResult::error(
+                    tool_use_id,
+                    "No interactive user is available to answer questions in this mode. Proceed with your best judgement and state any assumptions in your response.",
+                ),
+            });
+        }
+
+        let default = question.default.clone();
+        let pending = question.clone();
+        match context.ask_user(pending).await {
+            Some(answer) => match resolve_answer(&question, &answer) {
+                Some(answer) => Ok(ToolResult::success(
+                    tool_use_id,
+                    format!("User answered: {}", answer),
+                )),
+                None => Ok(ToolResult::error(
+                    tool_use_id,
+                    format!(
+                        "User answer '{}' is not one of the options: {}",
+                        answer.trim(),
+                        question.options.join(", ")
+                    ),
+                )),
+            },
+            None => Ok(match default {
+                Some(default) => ToolResult::success(
+                    tool_use_id,
+                    format!(
+                        "User dismissed the question; using the default answer: {}",
+                        default
+                    ),
+                ),
+                None => ToolResult::error(
+                    tool_use_id,
+                    "User dismissed the question without answering.",
+                ),
+            }),
+        }
+    }
+
+    fn permission_request(&self, input: &Value) -> Option<PermissionRequest> {
+        let question = input["question"].as_str().unwrap_or("unknown");
+        Some(PermissionRequest {
+            tool_name: "ask_user".to_string(),
+            action_description: format!("Ask user: {}", question),
+            affected_paths: Vec::new(),
+            is_destructive: false,
+        })
+    }
+
+    fn requires_permission(&self) -> bool {
+        false // Only talks to the user
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::sync::mpsc;
+    use uuid::Uuid;
+
+    fn create_test_context() -> ToolContext {
+        let dir = std::env::temp_dir();
+        ToolContext::new(dir.clone(), Some(dir), Uuid::new_v4(), true)
+    }
+
+    fn question(options: &[&str], allow_free_text: bool) -> UserQuestion {
+        UserQuestion {
+            id: "q".to_string(),
+            question: "Which?".to_string(),
+            options: options.iter().map(|o| o.to_string()).collect(),
+            allow_free_text,
+            default: None,
+        }
+    }
+
+    #[test]
+    fn test_tool_definition() {
+        let tool = AskUserTool;
+        assert_eq!(tool.name(), "ask_user");
+        let def = tool.definition();
+        assert_eq!(def.name, "ask_user");
+        assert_eq!(def.input_schema.required, vec!["question".to_string()]);
+        assert!(!tool.requires_permission());
+    }
+
+    #[test]
+    fn test_parse_question_defaults() {
+        let free =
+            AskUserTool::parse_question("id", &serde_json::json!({"question": "Name?"})).unwrap();
+        assert!(free.options.is_empty());
+        assert!(free.allow_free_text);
+
+        let choice = AskUserTool::parse_question(
+            "id",
+            &serde_json::json!({"question": "DB?", "options": ["sqlite", "postgres"]}),
+        )
+        .unwrap();
+        assert_eq!(choice.options, vec!["sqlite", "postgres"]);
+        assert!(!choice.allow_free_text);
+
+        assert!(AskUserTool::parse_question("id", &serde_json::json!({"question": " "})).is_err());
+    }
+
+    #[test]
+    fn test_resolve_answer() {
+        let q = question(&["sqlite", "postgres"], false);
+        assert_eq!(resolve_answer(&q, "Postgres").as_deref(), Some("postgres"));
+        assert_eq!(resolve_answer(&q, "1").as_deref(), Some("sqlite"));
+        assert_eq!(resolve_answer(&q, "mysql"), None);
+        assert_eq!(resolve_answer(&q, "  "), None);
+
+        let q = question(&["sqlite"], true);
+        assert_eq!(resolve_answer(&q, "mysql").as_deref(), Some("mysql"));
+    }
+
+    #[tokio::test]
+    async fn test_headless_without_default_fails() {
+        let result = AskUserTool
+            .execute(
+                "t1".to_string(),
+                serde_json::json!({"question": "Proceed?"}),
+                &create_test_context(),
+            )
+            .await
+            .unwrap();
+        assert!(result.is_error());
+        assert!(result.output_text().contains("No interactive user"));
+    }
+
+    #[tokio::test]
+    async fn test_headless_uses_default() {
+        let result = AskUserTool
+            .execute(
+                "t1".to_string(),
+                serde_json::json!({"question": "Proceed?", "options": ["yes", "no"], "default": "yes"}),
+                &create_test_context(),
+            )
+            .await
+            .unwrap();
+        assert!(!result.is_error());
+        assert!(result.output_text().contains("default answer: yes"));
+    }
+
+    #[tokio::test]
+    async fn test_blocks_until_answered() {
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let context = create_test_context().with_question_sender(tx);
+
+        let responder = tokio::spawn(async move {
+            let pending = rx.recv().await.unwrap();
+            assert_eq!(pending.question.id, "t1");
+            assert_eq!(pending.question.options, vec!["sqlite", "postgres"]);
+            pending.answer(Some("2".to_string()));
+        });
+
+        let result = AskUserTool
+            .execute(
+                "t1".to_string(),
+                serde_json::json!({"question": "DB?", "options": ["sqlite", "postgres"]}),
+                &context,
+            )
+            .await
+            .unwrap();
+        responder.await.unwrap();
+
+        assert!(!result.is_error());
+        assert_eq!(result.output_text(), "User answered: postgres");
+    }
+
+    #[tokio::test]
+    async fn test_dismissed_question() {
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let context = create_test_context().with_question_sender(tx);
+
+        tokio::spawn(async move {
+            rx.recv().await.unwrap().answer(None);
+        });
+
+        let result = AskUserTool
+            .execute(
+                "t1".to_string(),
+                serde_json::json!({"question": "Proceed?"}),
+                &context,
+            )
+            .await
+            .unwrap();
+        assert!(result.is_error());
+        assert!(result.output_text().contains("dismissed"));
+    }
+}
@@ -0,0 +1,906 @@
+// SPDX-License-Identifier: AGPL-3.0-or-later
+// Copyright (C) 2025 Blackman Artificial Intelligence Technologies Inc.
+
+//! Symbol-aware code search tool
+//!
+//! Gives the model structural navigation over the project without reading
+//! whole files: symbol definitions, a file's exports, its dependents and
+//! dependencies from the indexer's [`DependencyGraph`], and semantic search
+//! over symbol-level chunks through [`Indexer::hybrid_search`].
+//!
+//! The index is built on first use and refreshed incrementally on later
+//! calls, so results follow edits made during the session.
+//!
+//! [`DependencyGraph`]: crate::indexer::DependencyGraph
+
+use async_trait::async_trait;
+use regex::Regex;
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+use tokio::sync::Mutex;
+use uuid::Uuid;
+
+use crate::embeddings::EmbeddingGenerator;
+use crate::error::{Result, TedError};
+use crate::indexer::{
+    ChunkMemory, CodeChunk, ExportKind, ExportRef, Indexer, IndexerConfig, SourceLocation,
+    SymbolType,
+};
+use crate::llm::provider::ToolDefinition;
+use crate::tools::{PermissionRequest, SchemaBuilder, Tool, ToolContext, ToolResult};
+
+const DEFAULT_LIMIT: usize = 10;
+const MAX_LIMIT: usize = 50;
+/// Longest chunk built for a single symbol
+const MAX_CHUNK_LINES: usize = 60;
+/// Lines of source shown under each definition or search hit
+const SNIPPET_LINES: usize = 6;
+/// Upper bound on chunks embedded for semantic search
+const MAX_EMBEDDED_CHUNKS: usize = 5000;
+const EMBED_BATCH_SIZE: usize = 32;
+/// Weight of semantic similarity versus usage importance in hybrid search
+const SEMANTIC_WEIGHT: f32 = 0.8;
+
+/// Tool for structural and semantic code navigation
+pub struct CodeSearchTool {
+    state: Mutex<Option<CodeIndex>>,
+    embedder: Option<EmbeddingGenerator>,
+}
+
+impl CodeSearchTool {
+    pub fn new() -> Self {
+        Self {
+            state: Mutex::new(None),
+            embedder: Some(EmbeddingGenerator::new()),
+        }
+    }
+
+    /// Create the tool without an embedding model; semantic queries fall
+    /// back to keyword ranking.
+    pub fn without_embeddings() -> Self {
+        Self {
+            state: Mutex::new(None),
+            embedder: None,
+        }
+    }
+}
+
+impl Default for CodeSearchTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl Tool for CodeSearchTool {
+    fn name(&self) -> &str {
+        "code_search"
+    }
+
+    fn definition(&self) -> ToolDefinition {
+        ToolDefinition {
+            name: "code_search".to_string(),
+            description: "Navigate the codebase structurally instead of reading whole files. Modes: 'definition' finds where a symbol is defined, 'exports' lists a file's public symbols, 'dependents' lists files that import a file, 'dependencies' lists files a file imports, 'semantic' finds code related to a natural-language query.".to_string(),
+            input_schema: SchemaBuilder::new()
+                .string_enum(
+                    "mode",
+                    "What to search for",
+                    &["definition", "exports", "dependents", "dependencies", "semantic"],
+                    true,
+                )
+                .string("symbol", "Symbol name (definition mode)", false)
+                .string("file", "File path (exports, dependents and dependencies modes)", false)
+                .string("query", "Natural-language description of the code (semantic mode)", false)
+                .integer("limit", "Maximum results (default: 10)", false)
+                .build(),
+        }
+    }
+
+    async fn execute(
+        &self,
+        tool_use_id: String,
+        input: Value,
+        context: &ToolContext,
+    ) -> Result<ToolResult> {
+        let mode = input["mode"].as_str().unwrap_or("definition").to_string();
+        let limit = input["limit"]
+            .as_u64()
+            .map(|l| l as usize)
+            .unwrap_or(DEFAULT_LIMIT)
+            .clamp(1, MAX_LIMIT);
+        let required = |name: &str| -> std::result::Result<String, String> {
+            input[name]
+                .as_str()
+                .filter(|s| !s.trim().is_empty())
+                .map(|s| s.trim().to_string())
+                .ok_or_else(|| format!("'{}' is required for {} mode", name, mode))
+        };
+        let argument = match mode.as_str() {
+            "definition" => required("symbol"),
+            "exports" | "dependents" | "dependencies" => required("file"),
+            "semantic" => required("query"),
+            other => Err(format!("Unknown mode '{}'", other)),
+        };
+        let argument = match argument {
+            Ok(argument) => argument,
+            Err(message) => return Ok(ToolResult::error(tool_use_id, message)),
+        };
+
+        let root = context
+            .project_root
+            .clone()
+            .unwrap_or_else(|| context.working_directory.clone());
+
+        let mut state = self.state.lock().await;
+        let previous = state.take();
+        let index = tokio::task::spawn_blocking(move || CodeIndex::refreshed(previous, &root))
+            .await
+            .map_err(|e| TedError::ToolExecution(e.to_string()))?;
+        let mut index = match index {
+            Ok(index) => index,
+            Err(e) => {
+                return Ok(ToolResult::error(
+                    tool_use_id,
+                    format!("Failed to index the project: {}", e),
+                ))
+            }
+        };
+
+        let output = match mode.as_str() {
+            "definition" => Ok(index.find_definitions(&argument, limit, context)),
+            "exports" => resolve_file(&argument, context, &index)
+                .and_then(|relative| index.exports_of(&relative)),
+            "dependents" | "dependencies" => resolve_file(&argument, context, &index)
+                .and_then(|relative| index.related_files(&relative, &mode, context)),
+            _ => Ok(self
+                .semantic_search(&mut index, &argument, limit, &tool_use_id, context)
+                .await),
+        };
+        *state = Some(index);
+
+        Ok(match output {
+            Ok(text) => ToolResult::success(tool_use_id, text),
+            Err(message) => ToolResult::error(tool_use_id, message),
+        })
+    }
+
+    fn permission_request(&self, _input: &Value) -> Option<PermissionRequest> {
+        None
+    }
+
+    fn requires_permission(&self) -> bool {
+        false
+    }
+}
+
+impl CodeSearchTool {
+    async fn semantic_search(
+        &self,
+        index: &mut CodeIndex,
+        query: &str,
+        limit: usize,
+        tool_use_id: &str,
+        context: &ToolContext,
+    ) -> String {
+        let Some(embedder) = &self.embedder else {
+            return index.keyword_search(query, limit, None, context);
+        };
+
+        let ranked = match index.embed_pending(embedder, tool_use_id, context).await {
+            Ok(()) => embedder.embed(query).await.map(|query_embedding| {
+                index
+                    .indexer
+                    .hybrid_search(&query_embedding, limit, SEMANTIC_WEIGHT)
+                    .into_iter()
+                    .filter(|r| index.is_chunk_visible(r.id, context))
+                    .map(|r| (r.id, r.score))
+                    .collect::<Vec<_>>()
+            }),
+            Err(e) => Err(e),
+        };
+
+        match ranked {
+            Ok(ranked) if !ranked.is_empty() => index.format_hits(query, "semantic", &ranked),
+            Ok(_) => index.keyword_search(query, limit, None, context),
+            Err(e) => index.keyword_search(
+                query,
+                limit,
+                Some(&format!("embeddings unavailable: {}", e)),
+                context,
+            ),
+        }
+    }
+}
+
+/// The project index plus the symbol tables built on top of it
+struct CodeIndex {
+    indexer: Indexer,
+    /// Exported symbols per file (relative path)
+    exports: HashMap<PathBuf, Vec<ExportRef>>,
+    /// Symbol chunks per file, for semantic search
+    chunks: HashMap<PathBuf, Vec<Uuid>>,
+    /// Chunks that already have an embedding in the vector index
+    embedded: HashSet<Uuid>,
+    /// When the index was last brought up to date
+    refreshed_at: SystemTime,
+}
+
+impl CodeIndex {
+    /// Build the index for `root`, or bring an existing one up to date.
+    fn refreshed(previous: Option<Self>, root: &Path) -> Result<Self> {
+        let canonical_root = root.canonicalize().unwrap_or_else(|_| root.to_path_buf());
+        if let Some(mut index) = previous.filter(|i| i.indexer.root() == canonical_root) {
+            let started = SystemTime::now();
+            for path in index.indexer.refresh_changed(index.refreshed_at)? {
+                index.reload_file(&path);
+            }
+            index.refreshed_at = started;
+            return Ok(index);
+        }
+
+        let started = SystemTime::now();
+        let mut indexer = Indexer::new(root, IndexerConfig::default())?;
+        indexer.full_scan()?;
+        let files: Vec<PathBuf> = indexer.index().files.keys().cloned().collect();
+        let mut index = Self {
+            indexer,
+            exports: HashMap::new(),
+            chunks: HashMap::new(),
+            embedded: HashSet::new(),
+            refreshed_at: started,
+        };
+        for path in files {
+            index.reload_file(&path);
+        }
+        Ok(index)
+    }
+
+    /// Re-parse one file's exports and rebuild its symbol chunks.
+    fn reload_file(&mut self, relative: &Path) {
+        for id in self.chunks.remove(relative).unwrap_or_default() {
+            self.indexer.index_mut().remove_chunk(id);
+            self.indexer.remove_chunk_embedding(&id);
+            self.embedded.remove(&id);
+        }
+        self.exports.remove(relative);
+
+        let full = self.indexer.root().join(relative);
+        let Ok(content) = std::fs::read_to_string(&full) else {
+            return;
+        };
+        let mut exports = self.indexer.parsers().parse_exports(&full, &content);
+        exports.sort_by_key(|e| e.line);
+        exports.dedup_by(|a, b| a.line == b.line && a.name == b.name);
+
+        let lines: Vec<&str> = content.lines().collect();
+        let mut ids = Vec::new();
+        for (i, export) in exports.iter().enumerate() {
+            let start = export.line.max(1) as usize;
+            let next = exports
+                .get(i + 1)
+                .map(|e| e.line as usize)
+                .filter(|next| *next > start)
+                .unwrap_or(lines.len() + 1);
+            let end = (next - 1).min(start + MAX_CHUNK_LINES - 1).min(lines.len());
+            if start > end {
+                continue;
+            }
+            let chunk = CodeChunk::with_symbol(
+                lines[start - 1..end].join("\n"),
+                SourceLocation::new(relative.to_path_buf(), start as u32, end as u32),
+                export.name.clone(),
+                symbol_type(export.kind),
+            );
+            ids.push(chunk.id);
+            self.indexer
+                .index_mut()
+                .upsert_chunk_memory(ChunkMemory::new(chunk.id));
+            self.indexer.index_mut().upsert_chunk(chunk);
+        }
+
+        self.chunks.insert(relative.to_path_buf(), ids);
+        self.exports.insert(relative.to_path_buf(), exports);
+    }
+
+    fn find_definitions(&self, symbol: &str, limit: usize, context: &ToolContext) -> String {
+        let mut hits: Vec<(PathBuf, u32, String)> = Vec::new();
+
+        // Exported symbols first: exact, then case-insensitive
+        for exact in [true, false] {
+            for (path, exports) in self.sorted_exports(context) {
+                for export in exports {
+                    let matches = if exact {
+                        export.name == symbol
+                    } else {
+                        export.name.eq_ignore_ascii_case(symbol) && export.name != symbol
+                    };
+                    if matches {
+                        hits.push((path.clone(), export.line, kind_label(export.kind)));
+                    }
+                }
+            }
+            if !hits.is_empty() {
+                break;
+            }
+        }
+
+        // Fall back to private definitions the export parsers skip
+        if hits.is_empty() {
+            if let Some(pattern) = definition_pattern(symbol) {
+                for path in self.sorted_paths(context) {
+                    let Ok(content) = std::fs::read_to_string(self.indexer.root().join(path))
+                    else {
+                        continue;
+                    };
+                    for (i, line) in content.lines().enumerate() {
+                        if pattern.is_match(line) {
+                            hits.push((path.clone(), i as u32 + 1, "definition".to_string()));
+                        }
+                    }
+                }
+            }
+        }
+
+        if hits.is_empty() {
+            return format!(
+                "No definition of '{}' found in {} indexed files. Try grep for usages or semantic mode.",
+                symbol,
+                self.exports.len()
+            );
+        }
+
+        let total = hits.len();
+        let mut out = format!("Definitions of '{}':\n", symbol);
+        for (path, line, kind) in hits.into_iter().take(limit) {
+            out.push_str(&format!("\n{}:{} ({})\n", path.display(), line, kind));
+            out.push_str(&self.snippet(&path, line));
+        }
+        if total > limit {
+            out.push_str(&format!("\n({} more not shown)\n", total - limit));
+        }
+        out
+    }
+
+    fn exports_of(&self, relative: &Path) -> std::result::Result<String, String> {
+        let exports = match self.exports.get(relative) {
+            Some(exports) => exports.clone(),
+            None => {
+                let full = self.indexer.root().join(relative);
+                let content = std::fs::read_to_string(&full)
+                    .map_err(|_| format!("File not found: {}", relative.display()))?;
+                self.indexer.parsers().parse_exports(&full, &content)
+            }
+        };
+        if exports.is_empty() {
+            return Ok(format!("{} has no exported symbols", relative.display()));
+        }
+
+        let mut out = format!("Exports of {} ({}):\n", relative.display(), exports.len());
+        for export in exports {
+            out.push_str(&format!(
+                "  {:>5}  {:<10} {}\n",
+                export.line,
+                kind_label(export.kind),
+                export.name
+            ));
+        }
+        Ok(out)
+    }
+
+    fn related_files(
+        &self,
+        relative: &Path,
+        mode: &str,
+        context: &ToolContext,
+    ) -> std::result::Result<String, String> {
+        let Some(node) = self.indexer.graph().get_node(relative) else {
+            return if self.indexer.root().join(relative).exists() {
+                Ok(format!(
+                    "{} has no resolved imports or importers",
+                    relative.display()
+                ))
+            } else {
+                Err(format!("File not found: {}", relative.display()))
+            };
+        };
+
+        let (direct, transitive, label) = if mode == "dependents" {
+            (
+                node.dependents.clone(),
+                self.indexer.dependents_of(relative),
+                "Files that depend on",
+            )
+        } else {
+            (
+                node.dependencies.clone(),
+                self.indexer.dependencies_of(relative),
+                "Files depended on by",
+            )
+        };
+
+        let mut direct: Vec<PathBuf> = direct
+            .into_iter()
+            .filter(|p| self.is_visible(p, context))
+            .collect();
+        direct.sort();
+        let mut indirect: Vec<PathBuf> = transitive
+            .into_iter()
+            .filter(|p| p != relative && !direct.contains(p) && self.is_visible(p, context))
+            .collect();
+        indirect.sort();
+
+        if direct.is_empty() && indirect.is_empty() {
+            return Ok(format!("No {} found for {}", mode, relative.display()));
+        }
+        let mut out = format!("{} {}:\n", label, relative.display());
+        out.push_str(&format!("Direct ({}):\n", direct.len()));
+        for path in &direct {
+            out.push_str(&format!("  {}\n", path.display()));
+        }
+        if !indirect.is_empty() {
+            out.push_str(&format!("Indirect ({}):\n", indirect.len()));
+            for path in &indirect {
+                out.push_str(&format!("  {}\n", path.display()));
+            }
+        }
+        Ok(out)
+    }
+
+    /// Embed chunks that don't have an embedding yet.
+    async fn embed_pending(
+        &mut self,
+        embedder: &EmbeddingGenerator,
+        tool_use_id: &str,
+        context: &ToolContext,
+    ) -> Result<()> {
+        self.indexer.enable_semantic_search(embedder.dimension());
+        let pending: Vec<(Uuid, String)> = self
+            .chunks
+            .iter()
+            .filter(|(path, _)| self.is_visible(path, context))
+            .flat_map(|(_, ids)| ids)
+            .filter(|id| !self.embedded.contains(id))
+            .take(MAX_EMBEDDED_CHUNKS.saturating_sub(self.embedded.len()))
+            .filter_map(|id| {
+                let chunk = self.indexer.index().get_chunk(*id)?;
+                let text = format!(
+                    "{} {}\n{}",
+                    chunk.source.file_path.display(),
+                    chunk.symbol_name.as_deref().unwrap_or_default(),
+                    chunk.content
+                );
+                Some((*id, text))
+            })
+            .collect();
+
+        let total = pending.len();
+        for (batch_index, batch) in pending.chunks(EMBED_BATCH_SIZE).enumerate() {
+            let done = batch_index * EMBED_BATCH_SIZE;
+            context.emit_tool_progress(
+                tool_use_id,
+                "code_search",
+                format!("Embedding code chunks ({}/{})", done, total),
+                Some((done * 100 / total) as u8),
+            );
+            let texts: Vec<String> = batch.iter().map(|(_, text)| text.clone()).collect();
+            let embeddings = embedder.embed_batch(&texts).await?;
+            for ((id, _), embedding) in batch.iter().zip(embeddings) {
+                self.indexer.add_chunk_embedding(*id, embedding);
+                self.embedded.insert(*id);
+            }
+        }
+        Ok(())
+    }
+
+    /// Rank chunks by how many query terms they contain.
+    fn keyword_search(
+        &self,
+        query: &str,
+        limit: usize,
+        note: Option<&str>,
+        context: &ToolContext,
+    ) -> String {
+        let terms: Vec<String> = query
+            .split(|c: char| !c.is_alphanumeric() && c != '_')
+            .filter(|t| t.len() >= 2)
+            .map(|t| t.to_lowercase())
+            .collect();
+
+        let mut ranked: Vec<(Uuid, f32)> = self
+            .chunks
+            .iter()
+            .filter(|(path, _)| self.is_visible(path, context))
+            .flat_map(|(_, ids)| ids)
+            .filter_map(|id| {
+                let chunk = self.indexer.index().get_chunk(*id)?;
+                let content = chunk.content.to_lowercase();
+                let symbol = chunk
+                    .symbol_name
+                    .as_deref()
+                    .unwrap_or_default()
+                    .to_lowercase();
+                let score: f32 = terms
+                    .iter()
+                    .map(|term| {
+                        let in_content = content.matches(term.as_str()).count().min(5) as f32;
+                        let in_symbol = if symbol.contains(term.as_str()) {
+                            3.0
+                        } else {
+                            0.0
+                        };
+                        in_content + in_symbol
+                    })
+                    .sum();
+                (score > 0.0).then_some((*id, score))
+            })
+            .collect();
+        ranked.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+        ranked.truncate(limit);
+
+        let method = match note {
+            Some(note) => format!("keyword; {}", note),
+            None => "keyword".to_string(),
+        };
+        if ranked.is_empty() {
+            return format!("No code matching '{}' ({})", query, method);
+        }
+        self.format_hits(query, &method, &ranked)
+    }
+
+    fn format_hits(&self, query: &str, method: &str, ranked: &[(Uuid, f32)]) -> String {
+        let mut out = format!("Results for '{}' ({}):\n", query, method);
+        for (rank, (id, score)) in ranked.iter().enumerate() {
+            let Some(chunk) = self.indexer.index().get_chunk(*id) else {
+                continue;
+            };
+            out.push_str(&format!(
+                "\n{}. {}:{}-{} {} (score {:.2})\n",
+                rank + 1,
+                chunk.source.file_path.display(),
+                chunk.source.start_line,
+                chunk.source.end_line,
+                chunk.symbol_name.as_deref().unwrap_or_default(),
+                score
+            ));
+            out.push_str(&indent_snippet(&chunk.content));
+        }
+        out
+    }
+
+    fn snippet(&self, relative: &Path, line: u32) -> String {
+        std::fs::read_to_string(self.indexer.root().join(relative))
+            .map(|content| {
+                let text: Vec<&str> = content
+                    .lines()
+                    .skip(line.saturating_sub(1) as usize)
+                    .take(SNIPPET_LINES)
+                    .collect();
+                indent_snippet(&text.join("\n"))
+            })
+            .unwrap_or_default()
+    }
+
+    /// Whether an indexed file may be shown, i.e. is not in `.tedignore`.
+    fn is_visible(&self, relative: &Path, context: &ToolContext) -> bool {
+        !context.is_path_ignored(&self.indexer.root().join(relative))
+    }
+
+    fn is_chunk_visible(&self, id: Uuid, context: &ToolContext) -> bool {
+        self.indexer
+            .index()
+            .get_chunk(id)
+            .is_some_and(|chunk| self.is_visible(&chunk.source.file_path, context))
+    }
+
+    fn sorted_paths(&self, context: &ToolContext) -> Vec<&PathBuf> {
+        let mut paths: Vec<&PathBuf> = self
+            .exports
+            .keys()
+            .filter(|path| self.is_visible(path, context))
+            .collect();
+        paths.sort();
+        paths
+    }
+
+    fn sorted_exports(&self, context: &ToolContext) -> Vec<(&PathBuf, &Vec<ExportRef>)> {
+        let mut entries: Vec<_> = self
+            .exports
+            .iter()
+            .filter(|(path, _)| self.is_visible(path, context))
+            .collect();
+        entries.sort_by_key(|(path, _)| *path);
+        entries
+    }
+}
+
+/// Resolve a user-supplied path to one relative to the index root.
+///
+/// Paths outside the project or hidden by `.tedignore` are rejected.
+fn resolve_file(
+    file: &str,
+    context: &ToolContext,
+    index: &CodeIndex,
+) -> std::result::Result<PathBuf, String> {
+    let full = if Path::new(file).is_absolute() {
+        PathBuf::from(file)
+    } else {
+        let cwd = &context.working_directory;
+        cwd.canonicalize()
+            .unwrap_or_else(|_| cwd.clone())
+            .join(file)
+    };
+    let full = full.canonicalize().unwrap_or(full);
+    let relative = full
+        .strip_prefix(index.indexer.root())
+        .ok()
+        .filter(|relative| {
+            relative
+                .components()
+                .all(|c| matches!(c, std::path::Component::Normal(_)))
+        })
+        .ok_or_else(|| format!("Path outside project: {}", file))?;
+    if let Some(message) = context.check_path_not_ignored(&full) {
+        return Err(message);
+    }
+    Ok(relative.to_path_buf())
+}
+
+/// Regex for common definition forms across the supported languages.
+fn definition_pattern(symbol: &str) -> Option<Regex> {
+    let name = regex::escape(symbol);
+    Regex::new(&format!(
+        r"\b(?:fn|def|class|struct|enum|trait|type|interface|function|const|static|let|var|mod|impl)\s+{name}\b|\bfunc\s+(?:\([^)]*\)\s*)?{name}\b",
+    ))
+    .ok()
+}
+
+fn indent_snippet(text: &str) -> String {
+    let mut out = String::new();
+    for line in text.lines().take(SNIPPET_LINES) {
+        out.push_str("    ");
+        out.push_str(line);
+        out.push('\n');
+    }
+    out
+}
+
+fn symbol_type(kind: ExportKind) -> SymbolType {
+    match kind {
+        ExportKind::Function => SymbolType::Function,
+        ExportKind::Type => SymbolType::Struct,
+        ExportKind::Constant => SymbolType::Constant,
+        ExportKind::Module => SymbolType::Module,
+        _ => SymbolType::Unknown,
+    }
+}
+
+fn kind_label(kind: ExportKind) -> String {
+    match kind {
+        ExportKind::Function => "function",
+        ExportKind::Type => "type",
+        ExportKind::Constant => "constant",
+        ExportKind::Module => "module",
+        ExportKind::ReExport => "re-export",
+        ExportKind::Default => "default",
+        ExportKind::Other => "other",
+    }
+    .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    fn project() -> TempDir {
+        let temp = TempDir::new().unwrap();
+        std::fs::create_dir_all(temp.path().join("src")).unwrap();
+        std::fs::write(
+            temp.path().join("src/lib.rs"),
+            "pub mod config;\npub mod server;\n",
+        )
+        .unwrap();
+        std::fs::write(
+            temp.path().join("src/config.rs"),
+            "/// Settings\npub struct Config {\n    pub port: u16,\n}\n\npub fn load_config(path: &str) -> Config {\n    parse_toml(path)\n}\n\nfn parse_toml(_path: &str) -> Config {\n    Config { port: 80 }\n}\n",
+        )
+        .unwrap();
+        std::fs::write(
+            temp.path().join("src/server.rs"),
+            "use crate::config::Config;\n\npub fn start_server(config: Config) {\n    println!(\"listening on {}\", config.port);\n}\n",
+        )
+        .unwrap();
+        temp
+    }
+
+    fn context(dir: &TempDir) -> ToolContext {
+        ToolContext::new(
+            dir.path().to_path_buf(),
+            Some(dir.path().to_path_buf()),
+            Uuid::new_v4(),
+            true,
+        )
+    }
+
+    async fn run(tool: &CodeSearchTool, ctx: &ToolContext, input: Value) -> ToolResult {
+        tool.execute("t".to_string(), input, ctx).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_definition_of_exported_and_private_symbols() {
+        let dir = project();
+        let ctx = context(&dir);
+        let tool = CodeSearchTool::without_embeddings();
+
+        let result = run(
+            &tool,
+            &ctx,
+            json!({"mode": "definition", "symbol": "Config"}),
+        )
+        .await;
+        assert!(!result.is_error(), "{}", result.output_text());
+        assert!(result.output_text().contains("src/config.rs:2 (type)"));
+        assert!(result.output_text().contains("pub struct Config {"));
+
+        let result = run(
+            &tool,
+            &ctx,
+            json!({"mode": "definition", "symbol": "parse_toml"}),
+        )
+        .await;
+        assert!(result
+            .output_text()
+            .contains("src/config.rs:10 (definition)"));
+
+        let result = run(
+            &tool,
+            &ctx,
+            json!({"mode": "definition", "symbol": "missing"}),
+        )
+        .await;
+        assert!(result.output_text().contains("No definition of 'missing'"));
+    }
+
+    #[tokio::test]
+    async fn test_exports_and_dependents() {
+        let dir = project();
+        let ctx = context(&dir);
+        let tool = CodeSearchTool::without_embeddings();
+
+        let result = run(
+            &tool,
+            &ctx,
+            json!({"mode": "exports", "file": "src/config.rs"}),
+        )
+        .await;
+        let output = result.output_text();
+        assert!(output.contains("Exports of src/config.rs (2)"));
+        assert!(output.contains("load_config"));
+        assert!(!output.contains("parse_toml"));
+
+        let result = run(
+            &tool,
+            &ctx,
+            json!({"mode": "dependents", "file": "src/config.rs"}),
+        )
+        .await;
+        assert!(!result.is_error(), "{}", result.output_text());
+        assert!(result.output_text().contains("src/server.rs"));
+
+        let result = run(
+            &tool,
+            &ctx,
+            json!({"mode": "dependencies", "file": "src/server.rs"}),
+        )
+        .await;
+        assert!(result.output_text().contains("src/config.rs"));
+    }
+
+    #[tokio::test]
+    async fn test_hides_ignored_and_outside_files() {
+        let dir = project();
+        std::fs::write(dir.path().join(".tedignore"), "src/config.rs\n").unwrap();
+        let ctx = context(&dir);
+        let tool = CodeSearchTool::without_embeddings();
+
+        let result = run(
+            &tool,
+            &ctx,
+            json!({"mode": "definition", "symbol": "Config"}),
+        )
+        .await;
+        assert!(!result.output_text().contains("src/config.rs"));
+
+        let result = run(
+            &tool,
+            &ctx,
+            json!({"mode": "exports", "file": "src/config.rs"}),
+        )
+        .await;
+        assert!(result.is_error());
+        assert!(result.output_text().contains(".tedignore"));
+
+        let result = run(
+            &tool,
+            &ctx,
+            json!({"mode": "dependencies", "file": "src/server.rs"}),
+        )
+        .await;
+        assert!(!result.output_text().contains("src/config.rs"));
+
+        let result = run(
+            &tool,
+            &ctx,
+            json!({"mode": "semantic", "query": "load config"}),
+        )
+        .await;
+        assert!(!result.output_text().contains("load_config"));
+
+        let outside = TempDir::new().unwrap();
+        std::fs::write(outside.path().join("other.rs"), "pub fn other() {}\n").unwrap();
+        for file in [
+            outside.path().join("other.rs").display().to_string(),
+            "../other.rs".to_string(),
+        ] {
+            let result = run(&tool, &ctx, json!({"mode": "exports", "file": file})).await;
+            assert!(result.is_error());
+            assert!(result.output_text().contains("Path outside project"));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_semantic_falls_back_to_keywords_and_sees_edits() {
+        let dir = project();
+        let ctx = context(&dir);
+        let tool = CodeSearchTool::without_embeddings();
+
+        let result = run(
+            &tool,
+            &ctx,
+            json!({"mode": "semantic", "query": "where is the server started"}),
+        )
+        .await;
+        assert!(result.output_text().contains("(keyword)"));
+        assert!(result.output_text().contains("start_server"));
+
+        std::fs::write(
+            dir.path().join("src/metrics.rs"),
+            "pub fn record_latency(ms: u64) {}\n",
+        )
+        .unwrap();
+        let result = run(
+            &tool,
+            &ctx,
+            json!({"mode": "definition", "symbol": "record_latency"}),
+        )
+        .await;
+        assert!(result.output_text().contains("src/metrics.rs:1"));
+    }
+
+    #[tokio::test]
+    async fn test_missing_arguments() {
+        let dir = project();
+        let result = run(
+            &CodeSearchTool::without_embeddings(),
+            &context(&dir),
+            json!({"mode": "exports"}),
+        )
+        .await;
+        assert!(result.is_error());
+        assert!(result.output_text().contains("'file' is required"));
+    }
+
+    #[test]
+    fn test_definition_pattern_matches_languages() {
+        let pattern = definition_pattern("handle").unwrap();
+        assert!(pattern.is_match("fn handle(x: u8) {"));
+        assert!(pattern.is_match("    def handle(self):"));
+        assert!(pattern.is_match("func (s *Server) handle(w http.ResponseWriter) {"));
+        assert!(pattern.is_match("const handle = () => {}"));
+        assert!(!pattern.is_match("handle(x);"));
+        assert!(!pattern.is_match("fn handler() {}"));
+    }
+}
@@ -5,6 +5,8 @@
 //!
 //! Provides tools for initializing, migrating, querying, and seeding SQLite databases
 //! using Prisma ORM. Supports SQLite by default with PostgreSQL as an upgrade path.
+//! The registered `database_query` tool is the native one in [`super::sqlite`],
+//! which falls back to [`DatabaseQueryTool`] for non-SQLite Prisma projects.
 
 use async_trait::async_trait;
 use serde_json::Value;
@@ -451,6 +453,9 @@ impl Tool for DatabaseQueryTool {
         {
             // Find the database file
             let db_path = context.working_directory.join("prisma/dev.db");
+            if let Some(message) = context.check_path_not_ignored(&db_path) {
+                return Ok(ToolResult::error(tool_use_id, message));
+            }
             if !db_path.exists() {
                 return Ok(ToolResult::error(
                     tool_use_id,
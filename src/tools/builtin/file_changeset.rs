@@ -185,6 +185,10 @@ impl Tool for FileChangeSetTool {
                 context.working_directory.join(path)
             };
 
+            if let Some(message) = context.check_path_not_ignored(&full_path) {
+                return Ok(ToolResult::error(&tool_use_id, message));
+            }
+
             // Basic validation
             if !full_path.starts_with(&context.working_directory) {
                 if let Some(project_root) = &context.project_root {
@@ -196,6 +200,46 @@ impl Tool for FileChangeSetTool {
                     }
                 }
             }
+
+            // Notebooks are changed cell by cell, as in file_write
+            let is_notebook = super::file_read::is_notebook(&full_path);
+            match op {
+                FileOperation::Edit { .. } | FileOperation::Write { .. }
+                    if is_notebook && full_path.exists() =>
+                {
+                    return Ok(ToolResult::error(
+                        &tool_use_id,
+                        format!(
+                            "{} is a Jupyter notebook. Use notebook_edit to change its cells; rewriting the raw JSON can corrupt it.",
+                            path
+                        ),
+                    ));
+                }
+                FileOperation::Write { content, .. }
+                    if is_notebook
+                        && !serde_json::from_str::<Value>(content)
+                            .is_ok_and(|notebook| notebook["cells"].is_array()) =>
+                {
+                    return Ok(ToolResult::error(
+                        &tool_use_id,
+                        format!(
+                            "{} must be notebook JSON with a \"cells\" array. Create an empty notebook, then add cells with notebook_edit.",
+                            path
+                        ),
+                    ));
+                }
+                _ => {}
+            }
+
+            // Edits and overwrites must be based on what's on disk now
+            if matches!(op, FileOperation::Edit { .. } | FileOperation::Write { .. }) {
+                if let Ok(current) = std::fs::read_to_string(&full_path) {
+                    if let Some(message) = context.check_read_is_current(&full_path, path, &current)
+                    {
+                        return Ok(ToolResult::error(&tool_use_id, message));
+                    }
+                }
+            }
         }
 
         // Generate preview of changes
@@ -338,6 +382,102 @@ mod tests {
         assert!(tool_result.output_text().contains("Incremental mode"));
     }
 
+    #[tokio::test]
+    async fn test_execute_rejects_edits_to_unread_or_stale_files() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let file_path = temp_dir.path().join("lib.rs");
+        std::fs::write(&file_path, "fn old() {}").unwrap();
+
+        let tracker = crate::tools::FileReadTracker::new();
+        let context = ToolContext::new(
+            temp_dir.path().to_path_buf(),
+            None,
+            uuid::Uuid::new_v4(),
+            false,
+        )
+        .with_read_tracker(tracker.clone());
+        let input = json!({
+            "id": "rename",
+            "description": "Rename old",
+            "operations": [
+                {
+                    "type": "edit",
+                    "path": "lib.rs",
+                    "old_string": "fn old()",
+                    "new_string": "fn new()"
+                },
+                {
+                    "type": "write",
+                    "path": "new_module.rs",
+                    "content": "// new"
+                }
+            ]
+        });
+
+        let result = FileChangeSetTool
+            .execute("test".to_string(), input.clone(), &context)
+            .await
+            .unwrap();
+        assert!(result.is_error());
+        assert!(result.output_text().contains("has not been read yet"));
+
+        tracker.record(&file_path, "fn old() {}");
+        std::fs::write(&file_path, "fn old() { changed() }").unwrap();
+        let result = FileChangeSetTool
+            .execute("test".to_string(), input.clone(), &context)
+            .await
+            .unwrap();
+        assert!(result.is_error());
+        assert!(result.output_text().contains("changed on disk"));
+
+        tracker.record(&file_path, "fn old() { changed() }");
+        let result = FileChangeSetTool
+            .execute("test".to_string(), input, &context)
+            .await
+            .unwrap();
+        assert!(!result.is_error());
+    }
+
+    #[tokio::test]
+    async fn test_execute_guards_notebooks() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        std::fs::write(temp_dir.path().join("analysis.ipynb"), "{\"cells\": []}").unwrap();
+        let context = ToolContext::new(
+            temp_dir.path().to_path_buf(),
+            None,
+            uuid::Uuid::new_v4(),
+            false,
+        );
+        let run = |operation: Value| {
+            let context = context.clone();
+            async move {
+                let input = json!({
+                    "id": "nb",
+                    "description": "Touch a notebook",
+                    "operations": [operation]
+                });
+                FileChangeSetTool
+                    .execute("test".to_string(), input, &context)
+                    .await
+                    .unwrap()
+            }
+        };
+
+        for operation in [
+            json!({"type": "edit", "path": "analysis.ipynb", "old_string": "[]", "new_string": "[1]"}),
+            json!({"type": "write", "path": "analysis.ipynb", "content": "{\"cells\": [1]}"}),
+            json!({"type": "write", "path": "new.ipynb", "content": "print('hi')"}),
+        ] {
+            let result = run(operation).await;
+            assert!(result.is_error());
+            assert!(result.output_text().contains("notebook_edit"));
+        }
+
+        let result =
+            run(json!({"type": "write", "path": "new.ipynb", "content": "{\"cells\": []}"})).await;
+        assert!(!result.is_error(), "{}", result.output_text());
+    }
+
     // ===== Tool Definition Tests =====
 
     #[test]
@@ -81,6 +81,20 @@ impl Tool for FileEditTool {
             context.working_directory.join(path_str)
         };
 
+        if let Some(message) = context.check_path_not_ignored(&path) {
+            return Ok(ToolResult::error(tool_use_id, message));
+        }
+
+        if super::file_read::is_notebook(&path) {
+            return Ok(ToolResult::error(
+                tool_use_id,
+                format!(
+                    "{} is a Jupyter notebook. Use notebook_edit to change its cells; editing the raw JSON can corrupt it.",
+                    path.display()
+                ),
+            ));
+        }
+
         // Check if file exists
         if !path.exists() {
             return Ok(ToolResult::error(
@@ -100,6 +114,11 @@ impl Tool for FileEditTool {
             }
         };
 
+        // Refuse to edit content the model has not seen
+        if let Some(message) = context.check_read_is_current(&path, path_str, &content) {
+            return Ok(ToolResult::error(tool_use_id, message));
+        }
+
         // Check how many occurrences exist
         let occurrences = content.matches(old_string).count();
 
@@ -135,6 +154,7 @@ impl Tool for FileEditTool {
             Ok(_) => {
                 // Emit recall event for memory tracking
                 context.emit_file_edit(&path);
+                context.record_file_content(&path, &new_content);
 
                 let lines_before = content.lines().count();
                 let lines_after = new_content.lines().count();
@@ -183,6 +203,8 @@ impl Tool for FileEditTool {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::tools::builtin::FileReadTool;
+    use crate::tools::FileReadTracker;
     use tempfile::TempDir;
     use uuid::Uuid;
 
@@ -229,6 +251,34 @@ mod tests {
         assert!(request.is_destructive);
     }
 
+    #[tokio::test]
+    async fn test_edit_refuses_notebooks() {
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = temp_dir.path().join("nb.ipynb");
+        std::fs::write(&file_path, "{\"cells\": []}").unwrap();
+
+        let context = create_test_context(&temp_dir);
+        let result = FileEditTool
+            .execute(
+                "test-id".to_string(),
+                serde_json::json!({
+                    "path": "nb.ipynb",
+                    "old_string": "cells",
+                    "new_string": "cellz"
+                }),
+                &context,
+            )
+            .await
+            .unwrap();
+
+        assert!(result.is_error());
+        assert!(result.output_text().contains("notebook_edit"));
+        assert_eq!(
+            std::fs::read_to_string(&file_path).unwrap(),
+            "{\"cells\": []}"
+        );
+    }
+
     #[tokio::test]
     async fn test_edit_single_occurrence() {
         let temp_dir = TempDir::new().unwrap();
@@ -495,6 +545,132 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[tokio::test]
+    async fn test_edit_rejected_when_file_never_read() {
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = temp_dir.path().join("tracked.txt");
+        std::fs::write(&file_path, "Hello World").unwrap();
+
+        let tool = FileEditTool;
+        let context = create_test_context(&temp_dir).with_read_tracker(FileReadTracker::new());
+
+        let result = tool
+            .execute(
+                "test-id".to_string(),
+                serde_json::json!({
+                    "path": "tracked.txt",
+                    "old_string": "World",
+                    "new_string": "Rust"
+                }),
+                &context,
+            )
+            .await
+            .unwrap();
+
+        assert!(result.is_error());
+        assert!(result.output_text().contains("has not been read"));
+        assert_eq!(std::fs::read_to_string(&file_path).unwrap(), "Hello World");
+    }
+
+    #[tokio::test]
+    async fn test_edit_rejected_when_file_changed_since_read() {
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = temp_dir.path().join("tracked.txt");
+        std::fs::write(&file_path, "Hello World").unwrap();
+
+        let context = create_test_context(&temp_dir).with_read_tracker(FileReadTracker::new());
+        FileReadTool
+            .execute(
+                "read-id".to_string(),
+                serde_json::json!({"path": "tracked.txt"}),
+                &context,
+            )
+            .await
+            .unwrap();
+
+        // Simulate a formatter or the user touching the file
+        std::fs::write(&file_path, "Hello World\n// formatted").unwrap();
+
+        let result = FileEditTool
+            .execute(
+                "test-id".to_string(),
+                serde_json::json!({
+                    "path": "tracked.txt",
+                    "old_string": "World",
+                    "new_string": "Rust"
+                }),
+                &context,
+            )
+            .await
+            .unwrap();
+
+        assert!(result.is_error());
+        assert!(result.output_text().contains("changed on disk"));
+        assert!(result.output_text().contains("re-read"));
+    }
+
+    #[tokio::test]
+    async fn test_edit_after_read_and_consecutive_edits() {
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = temp_dir.path().join("tracked.txt");
+        std::fs::write(&file_path, "one two").unwrap();
+
+        let context = create_test_context(&temp_dir).with_read_tracker(FileReadTracker::new());
+        FileReadTool
+            .execute(
+                "read-id".to_string(),
+                serde_json::json!({"path": "tracked.txt"}),
+                &context,
+            )
+            .await
+            .unwrap();
+
+        for (old, new) in [("one", "1"), ("two", "2")] {
+            let result = FileEditTool
+                .execute(
+                    "test-id".to_string(),
+                    serde_json::json!({
+                        "path": "tracked.txt",
+                        "old_string": old,
+                        "new_string": new
+                    }),
+                    &context,
+                )
+                .await
+                .unwrap();
+            assert!(!result.is_error(), "{}", result.output_text());
+        }
+
+        assert_eq!(std::fs::read_to_string(&file_path).unwrap(), "1 2");
+    }
+
+    #[tokio::test]
+    async fn test_edit_allowed_for_file_in_context_without_read() {
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = temp_dir.path().join("ctx.txt");
+        std::fs::write(&file_path, "Hello World").unwrap();
+
+        let context = create_test_context(&temp_dir)
+            .with_files_in_context(vec!["ctx.txt".to_string()])
+            .with_read_tracker(FileReadTracker::new());
+
+        let result = FileEditTool
+            .execute(
+                "test-id".to_string(),
+                serde_json::json!({
+                    "path": "ctx.txt",
+                    "old_string": "World",
+                    "new_string": "Rust"
+                }),
+                &context,
+            )
+            .await
+            .unwrap();
+
+        assert!(!result.is_error());
+        assert_eq!(std::fs::read_to_string(&file_path).unwrap(), "Hello Rust");
+    }
+
     #[tokio::test]
     async fn test_edit_alternative_param_names() {
         // Test flexible parameter names for better model compatibility
@@ -71,6 +71,10 @@ impl Tool for FileReadTool {
             context.working_directory.join(path_str)
         };
 
+        if let Some(message) = context.check_path_not_ignored(&path) {
+            return Ok(ToolResult::error(tool_use_id, message));
+        }
+
         // Check if file exists
         if !path.exists() {
             return Ok(ToolResult::error(
@@ -92,6 +96,17 @@ impl Tool for FileReadTool {
             Ok(content) => {
                 // Emit recall event for memory tracking
                 context.emit_file_read(&path);
+                context.record_file_content(&path, &content);
+
+                // Notebooks are shown cell by cell instead of as raw JSON
+                if is_notebook(&path) {
+                    if let Some(rendered) = super::render_notebook_content(&path, &content) {
+                        return Ok(ToolResult::success(
+                            tool_use_id,
+                            format!("{}\nUse notebook_edit to change cells.", rendered),
+                        ));
+                    }
+                }
 
                 let lines: Vec<&str> = content.lines().collect();
                 let start = (offset.saturating_sub(1)).min(lines.len());
@@ -146,6 +161,12 @@ impl Tool for FileReadTool {
     }
 }
 
+/// Whether `path` is a Jupyter notebook
+pub(crate) fn is_notebook(path: &std::path::Path) -> bool {
+    path.extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("ipynb"))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -191,6 +212,40 @@ mod tests {
         assert!(!request.is_destructive);
     }
 
+    #[tokio::test]
+    async fn test_read_notebook_shows_cells() {
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = temp_dir.path().join("nb.ipynb");
+        let notebook = serde_json::json!({
+            "cells": [{
+                "cell_type": "code", "id": "c1", "metadata": {}, "execution_count": null,
+                "outputs": [{"output_type": "display_data", "metadata": {},
+                             "data": {"image/png": "AAAAbase64"}}],
+                "source": ["print(1)"]
+            }],
+            "metadata": {}, "nbformat": 4, "nbformat_minor": 5
+        });
+        std::fs::write(&file_path, notebook.to_string()).unwrap();
+
+        let context = create_test_context(&temp_dir);
+        let result = FileReadTool
+            .execute(
+                "test-id".to_string(),
+                serde_json::json!({"path": "nb.ipynb"}),
+                &context,
+            )
+            .await
+            .unwrap();
+
+        assert!(!result.is_error());
+        assert!(result.output_text().contains("id=c1"));
+        assert!(result.output_text().contains("print(1)"));
+        assert!(!result.output_text().contains("AAAAbase64"));
+        assert!(result
+            .output_text()
+            .ends_with("\nUse notebook_edit to change cells."));
+    }
+
     #[tokio::test]
     async fn test_read_existing_file() {
         let temp_dir = TempDir::new().unwrap();
@@ -339,4 +394,30 @@ mod tests {
         assert!(!result.is_error());
         assert!(result.output_text().contains("truncated"));
     }
+
+    #[tokio::test]
+    async fn test_read_tedignored_file() {
+        let temp_dir = TempDir::new().unwrap();
+        std::fs::write(temp_dir.path().join(".tedignore"), "secrets/\n").unwrap();
+        std::fs::create_dir_all(temp_dir.path().join("secrets")).unwrap();
+        std::fs::write(temp_dir.path().join("secrets/prod.env"), "TOKEN=abc").unwrap();
+
+        let tool = FileReadTool;
+        let context = create_test_context(&temp_dir);
+
+        let result = tool
+            .execute(
+                "test-id".to_string(),
+                serde_json::json!({"path": "secrets/prod.env"}),
+                &context,
+            )
+            .await
+            .unwrap();
+
+        assert!(result.is_error());
+        assert!(result
+            .output_text()
+            .contains("Path is ignored by .tedignore"));
+        assert!(!result.output_text().contains("TOKEN"));
+    }
 }
@@ -3,17 +3,18 @@
 
 //! File write tool
 //!
-//! Writes content to a new file.
+//! Writes content to a new file, or replaces a file the model has read.
 
 use async_trait::async_trait;
 use serde_json::Value;
+use std::io::Write;
 use std::path::PathBuf;
 
 use crate::error::Result;
 use crate::llm::provider::ToolDefinition;
 use crate::tools::{PermissionRequest, SchemaBuilder, Tool, ToolContext, ToolResult};
 
-/// Tool for writing new files
+/// Tool for writing new files and replacing read ones
 pub struct FileWriteTool;
 
 #[async_trait]
@@ -25,7 +26,7 @@ impl Tool for FileWriteTool {
     fn definition(&self) -> ToolDefinition {
         ToolDefinition {
             name: "file_write".to_string(),
-            description: "Write content to a new file. Will create parent directories if needed. Fails if file already exists (use file_edit to modify existing files).".to_string(),
+            description: "Write content to a file. Will create parent directories if needed. An existing file is replaced only if it has been read with file_read and not changed since; prefer file_edit for small changes, and use notebook_edit for Jupyter notebooks.".to_string(),
             input_schema: SchemaBuilder::new()
                 .string("path", "The path where the file should be created", true)
                 .string("content", "The content to write to the file", true)
@@ -63,15 +64,48 @@ impl Tool for FileWriteTool {
             context.working_directory.join(path_str)
         };
 
-        // Check if file already exists
-        if path.exists() {
-            return Ok(ToolResult::error(
-                tool_use_id,
-                format!(
-                    "File already exists: {}. Use file_edit to modify existing files.",
-                    path.display()
-                ),
-            ));
+        if let Some(message) = context.check_path_not_ignored(&path) {
+            return Ok(ToolResult::error(tool_use_id, message));
+        }
+
+        if super::file_read::is_notebook(&path) {
+            if path.exists() {
+                return Ok(ToolResult::error(
+                    tool_use_id,
+                    format!(
+                        "{} is a Jupyter notebook. Use notebook_edit to change its cells; rewriting the raw JSON can corrupt it.",
+                        path.display()
+                    ),
+                ));
+            }
+            let is_notebook_json = serde_json::from_str::<Value>(content)
+                .is_ok_and(|notebook| notebook["cells"].is_array());
+            if !is_notebook_json {
+                return Ok(ToolResult::error(
+                    tool_use_id,
+                    format!(
+                        "{} must be notebook JSON with a \"cells\" array. Create an empty notebook, then add cells with notebook_edit.",
+                        path.display()
+                    ),
+                ));
+            }
+        }
+
+        // Only replace an existing file whose current content the model has read
+        let exists = path.exists();
+        if exists {
+            let current = match std::fs::read_to_string(&path) {
+                Ok(current) => current,
+                Err(e) => {
+                    return Ok(ToolResult::error(
+                        tool_use_id,
+                        format!("Failed to read existing file: {}", e),
+                    ))
+                }
+            };
+            if let Some(message) = context.check_read_is_current(&path, path_str, &current) {
+                return Ok(ToolResult::error(tool_use_id, message));
+            }
         }
 
         // Create parent directories if needed
@@ -86,17 +120,26 @@ impl Tool for FileWriteTool {
             }
         }
 
-        // Write the file
-        match std::fs::write(&path, content) {
+        // Write the file. A new file is created exclusively so one that
+        // appeared since the check above is not clobbered unread.
+        let written = std::fs::OpenOptions::new()
+            .write(true)
+            .truncate(exists)
+            .create_new(!exists)
+            .open(&path)
+            .and_then(|mut file| file.write_all(content.as_bytes()));
+        match written {
             Ok(_) => {
                 // Emit recall event for memory tracking
                 context.emit_file_write(&path);
+                context.record_file_content(&path, content);
 
                 let line_count = content.lines().count();
                 Ok(ToolResult::success(
                     tool_use_id,
                     format!(
-                        "Successfully created {} ({} lines, {} bytes)",
+                        "Successfully {} {} ({} lines, {} bytes)",
+                        if exists { "replaced" } else { "created" },
                         path.display(),
                         line_count,
                         content.len()
@@ -114,7 +157,7 @@ impl Tool for FileWriteTool {
         let path = input["path"].as_str().unwrap_or("unknown");
         Some(PermissionRequest {
             tool_name: "file_write".to_string(),
-            action_description: format!("Create new file: {}", path),
+            action_description: format!("Write file: {}", path),
             affected_paths: vec![path.to_string()],
             is_destructive: false,
         })
@@ -199,28 +242,135 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn test_write_existing_file_fails() {
+    async fn test_write_replaces_file_after_current_read() {
         let temp_dir = TempDir::new().unwrap();
         let file_path = temp_dir.path().join("existing.txt");
         std::fs::write(&file_path, "existing content").unwrap();
 
-        let tool = FileWriteTool;
+        let tracker = crate::tools::FileReadTracker::new();
+        tracker.record(&file_path, "existing content");
+        let context = create_test_context(&temp_dir).with_read_tracker(tracker);
+        let result = FileWriteTool
+            .execute(
+                "test-id".to_string(),
+                serde_json::json!({"path": "existing.txt", "content": "new content"}),
+                &context,
+            )
+            .await
+            .unwrap();
+
+        assert!(!result.is_error(), "{}", result.output_text());
+        assert!(result.output_text().contains("Successfully replaced"));
+        assert_eq!(std::fs::read_to_string(&file_path).unwrap(), "new content");
+
+        // The write counts as a read, so a follow-up write is allowed too
+        let result = FileWriteTool
+            .execute(
+                "test-id".to_string(),
+                serde_json::json!({"path": "existing.txt", "content": "short"}),
+                &context,
+            )
+            .await
+            .unwrap();
+        assert!(!result.is_error(), "{}", result.output_text());
+        assert_eq!(std::fs::read_to_string(&file_path).unwrap(), "short");
+    }
+
+    #[tokio::test]
+    async fn test_write_over_unread_file_asks_for_read() {
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = temp_dir.path().join("existing.txt");
+        std::fs::write(&file_path, "existing content").unwrap();
+
+        let context =
+            create_test_context(&temp_dir).with_read_tracker(crate::tools::FileReadTracker::new());
+        let result = FileWriteTool
+            .execute(
+                "test-id".to_string(),
+                serde_json::json!({"path": "existing.txt", "content": "new content"}),
+                &context,
+            )
+            .await
+            .unwrap();
+
+        assert!(result.is_error());
+        assert!(result.output_text().contains("has not been read yet"));
+        assert_eq!(
+            std::fs::read_to_string(&file_path).unwrap(),
+            "existing content"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_write_over_stale_file_asks_for_reread() {
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = temp_dir.path().join("existing.txt");
+        std::fs::write(&file_path, "changed by the user").unwrap();
+
+        let tracker = crate::tools::FileReadTracker::new();
+        tracker.record(&file_path, "what the model read");
+        let context = create_test_context(&temp_dir).with_read_tracker(tracker);
+        let result = FileWriteTool
+            .execute(
+                "test-id".to_string(),
+                serde_json::json!({"path": "existing.txt", "content": "new content"}),
+                &context,
+            )
+            .await
+            .unwrap();
+
+        assert!(result.is_error());
+        assert!(result.output_text().contains("changed on disk"));
+        assert_eq!(
+            std::fs::read_to_string(&file_path).unwrap(),
+            "changed by the user"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_write_guards_notebooks() {
+        let temp_dir = TempDir::new().unwrap();
+        let existing = temp_dir.path().join("existing.ipynb");
+        std::fs::write(&existing, "{\"cells\": []}").unwrap();
         let context = create_test_context(&temp_dir);
 
-        let result = tool
+        let result = FileWriteTool
             .execute(
                 "test-id".to_string(),
-                serde_json::json!({
-                    "path": file_path.to_string_lossy().to_string(),
-                    "content": "new content"
-                }),
+                serde_json::json!({"path": "existing.ipynb", "content": "{\"cells\": [1]}"}),
                 &context,
             )
             .await
             .unwrap();
+        assert!(result.is_error());
+        assert!(result.output_text().contains("notebook_edit"));
+        assert_eq!(
+            std::fs::read_to_string(&existing).unwrap(),
+            "{\"cells\": []}"
+        );
 
+        let result = FileWriteTool
+            .execute(
+                "test-id".to_string(),
+                serde_json::json!({"path": "new.ipynb", "content": "print('hi')"}),
+                &context,
+            )
+            .await
+            .unwrap();
         assert!(result.is_error());
-        assert!(result.output_text().contains("already exists"));
+        assert!(result.output_text().contains("notebook_edit"));
+        assert!(!temp_dir.path().join("new.ipynb").exists());
+
+        let notebook = r#"{"cells": [], "metadata": {}, "nbformat": 4, "nbformat_minor": 5}"#;
+        let result = FileWriteTool
+            .execute(
+                "test-id".to_string(),
+                serde_json::json!({"path": "new.ipynb", "content": notebook}),
+                &context,
+            )
+            .await
+            .unwrap();
+        assert!(!result.is_error());
     }
 
     #[tokio::test]
@@ -332,4 +482,53 @@ mod tests {
         assert!(!result.is_error());
         assert!(result.output_text().contains("3 lines"));
     }
+
+    #[tokio::test]
+    async fn test_write_records_content_for_follow_up_edits() {
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = temp_dir.path().join("tracked.txt");
+
+        let tracker = crate::tools::FileReadTracker::new();
+        let context = create_test_context(&temp_dir).with_read_tracker(tracker.clone());
+
+        let result = FileWriteTool
+            .execute(
+                "test-id".to_string(),
+                serde_json::json!({
+                    "path": "tracked.txt",
+                    "content": "fresh"
+                }),
+                &context,
+            )
+            .await
+            .unwrap();
+
+        assert!(!result.is_error());
+        assert_eq!(
+            tracker.check(&file_path, "fresh"),
+            crate::tools::ReadCheck::Fresh
+        );
+    }
+
+    #[tokio::test]
+    async fn test_write_tedignored_path() {
+        let temp_dir = TempDir::new().unwrap();
+        std::fs::write(temp_dir.path().join(".tedignore"), "*.pem\n").unwrap();
+
+        let tool = FileWriteTool;
+        let context = create_test_context(&temp_dir);
+
+        let result = tool
+            .execute(
+                "test-id".to_string(),
+                serde_json::json!({"path": "keys/server.pem", "content": "x"}),
+                &context,
+            )
+            .await
+            .unwrap();
+
+        assert!(result.is_error());
+        assert!(result.output_text().contains("Path is ignored"));
+        assert!(!temp_dir.path().join("keys/server.pem").exists());
+    }
 }
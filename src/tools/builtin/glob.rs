@@ -6,14 +6,23 @@
 //! Finds files matching a glob pattern.
 
 use async_trait::async_trait;
-use glob::glob as glob_match;
+use glob::{MatchOptions, Pattern};
 use serde_json::Value;
-use std::path::PathBuf;
+use std::path::{Component, Path, PathBuf};
+use std::time::SystemTime;
 
+use super::walk;
 use crate::error::Result;
 use crate::llm::provider::ToolDefinition;
 use crate::tools::{PermissionRequest, SchemaBuilder, Tool, ToolContext, ToolResult};
 
+/// `*` and `?` stop at directory separators, as in shell globs
+const MATCH_OPTIONS: MatchOptions = MatchOptions {
+    case_sensitive: true,
+    require_literal_separator: true,
+    require_literal_leading_dot: false,
+};
+
 /// Tool for finding files by glob pattern
 pub struct GlobTool;
 
@@ -26,10 +35,13 @@ impl Tool for GlobTool {
     fn definition(&self) -> ToolDefinition {
         ToolDefinition {
             name: "glob".to_string(),
-            description: "Find files matching a glob pattern (e.g., '**/*.rs', 'src/**/*.ts'). Returns matching file paths.".to_string(),
+            description: "Find files matching a glob pattern (e.g., '**/*.rs', 'src/**/*.ts'). Returns matching file paths. Files excluded by .gitignore/.ignore and dependency/build directories are skipped unless include_ignored is true.".to_string(),
             input_schema: SchemaBuilder::new()
                 .string("pattern", "Glob pattern to match (e.g., '**/*.rs', 'src/**/*.ts')", true)
                 .string("path", "Base directory to search in (default: working directory)", false)
+                .string("type", "Only return these file types, comma-separated ripgrep names (e.g., 'rust', 'ts,js'); prefix with ! to exclude", false)
+                .string_enum("sort", "'path' sorts alphabetically, 'mtime' puts the most recently modified first (default: path)", &["path", "mtime"], false)
+                .boolean("include_ignored", "Also return hidden files and files excluded by .gitignore/.ignore and dependency/build directories (default: false)", false)
                 .integer("limit", "Maximum number of results (default: 100)", false)
                 .build(),
         }
@@ -56,6 +68,16 @@ impl Tool for GlobTool {
             .or_else(|| input["max"].as_u64())
             .or_else(|| input["count"].as_u64())
             .unwrap_or(100) as usize;
+        let include_ignored = input["include_ignored"].as_bool().unwrap_or(false);
+        let sort_by_mtime = input["sort"].as_str() == Some("mtime");
+
+        let types = match input["type"].as_str().filter(|t| !t.trim().is_empty()) {
+            Some(spec) => match walk::file_types(spec) {
+                Ok(types) => Some(types),
+                Err(e) => return Ok(ToolResult::error(tool_use_id, e)),
+            },
+            None => None,
+        };
 
         // Resolve base path with flexible parameter names
         let path_str = input["path"]
@@ -73,13 +95,14 @@ impl Tool for GlobTool {
             context.working_directory.clone()
         };
 
-        // Build full pattern
-        let full_pattern = base_path.join(pattern);
-        let pattern_str = full_pattern.to_string_lossy();
+        if let Some(message) = context.check_path_not_ignored(&base_path) {
+            return Ok(ToolResult::error(tool_use_id, message));
+        }
 
-        // Execute glob
-        let entries = match glob_match(&pattern_str) {
-            Ok(paths) => paths,
+        // Walk only below the pattern's literal directory prefix
+        let (walk_root, relative_pattern) = split_literal_prefix(&base_path, pattern);
+        let matcher = match Pattern::new(&relative_pattern) {
+            Ok(matcher) => matcher,
             Err(e) => {
                 return Ok(ToolResult::error(
                     tool_use_id,
@@ -88,34 +111,67 @@ impl Tool for GlobTool {
             }
         };
 
-        let mut results: Vec<String> = Vec::new();
-        let mut count = 0;
+        let mut found: Vec<(PathBuf, Option<SystemTime>)> = Vec::new();
         let mut errors = 0;
 
-        for entry in entries {
-            match entry {
-                Ok(path) => {
-                    // Make path relative to working directory if possible
-                    let display_path = path
-                        .strip_prefix(&context.working_directory)
-                        .map(|p| p.to_path_buf())
-                        .unwrap_or(path);
-
-                    results.push(display_path.to_string_lossy().to_string());
-                    count += 1;
-
-                    if count >= limit {
-                        break;
+        if relative_pattern.is_empty() {
+            // The pattern names a single path without wildcards
+            if walk_root.exists() && !context.is_path_ignored(&walk_root) {
+                let modified = std::fs::metadata(&walk_root)
+                    .and_then(|m| m.modified())
+                    .ok();
+                found.push((walk_root.clone(), modified));
+            }
+        } else if walk_root.is_dir() {
+            let include_hidden = walk::names_hidden(pattern);
+            for entry in walk::walk(&walk_root, context, include_ignored, include_hidden, types) {
+                let entry = match entry {
+                    Ok(entry) => entry,
+                    Err(_) => {
+                        errors += 1;
+                        continue;
                     }
+                };
+                if entry.depth() == 0 {
+                    continue;
                 }
-                Err(_) => {
-                    errors += 1;
+                let Ok(relative) = entry.path().strip_prefix(&walk_root) else {
+                    continue;
+                };
+                if !matcher.matches_path_with(relative, MATCH_OPTIONS) {
+                    continue;
                 }
+                let modified = if sort_by_mtime {
+                    entry.metadata().ok().and_then(|m| m.modified().ok())
+                } else {
+                    None
+                };
+                found.push((entry.into_path(), modified));
             }
         }
 
-        // Sort results for consistent output
-        results.sort();
+        if sort_by_mtime {
+            // Newest first; ties fall back to path order
+            found.sort_by(|(a_path, a_time), (b_path, b_time)| {
+                b_time.cmp(a_time).then_with(|| a_path.cmp(b_path))
+            });
+        } else {
+            found.sort();
+        }
+        let total = found.len();
+        found.truncate(limit);
+
+        let results: Vec<String> = found
+            .into_iter()
+            .map(|(path, _)| {
+                // Make path relative to working directory if possible
+                path.strip_prefix(&context.working_directory)
+                    .map(|p| p.to_path_buf())
+                    .unwrap_or(path)
+                    .to_string_lossy()
+                    .to_string()
+            })
+            .collect();
 
         // Emit recall event for memory tracking with found file paths
         if !results.is_empty() {
@@ -130,12 +186,15 @@ impl Tool for GlobTool {
             pattern
         ));
 
-        if count >= limit {
+        if total > limit {
             output.push_str(&format!(" (limited to {})", limit));
         }
         if errors > 0 {
             output.push_str(&format!(" ({} paths had errors)", errors));
         }
+        if sort_by_mtime {
+            output.push_str(" (newest first)");
+        }
         output.push_str(":\n\n");
 
         for path in &results {
@@ -161,6 +220,32 @@ impl Tool for GlobTool {
     }
 }
 
+/// Split `pattern` into the directory its leading literal components name
+/// (joined onto `base`) and the remaining pattern relative to it.
+fn split_literal_prefix(base: &Path, pattern: &str) -> (PathBuf, String) {
+    let mut root = base.to_path_buf();
+    let mut components = Path::new(pattern).components().peekable();
+    while let Some(component) = components.peek() {
+        let literal = match component {
+            Component::Normal(name) => {
+                let name = name.to_string_lossy();
+                !name.contains(['*', '?', '[', '{'])
+            }
+            _ => true,
+        };
+        if !literal {
+            break;
+        }
+        match component {
+            Component::CurDir => {}
+            other => root.push(other.as_os_str()),
+        }
+        components.next();
+    }
+    let rest: PathBuf = components.collect();
+    (root, rest.to_string_lossy().replace('\\', "/"))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -376,4 +461,179 @@ mod tests {
         assert!(apple_pos < middle_pos);
         assert!(middle_pos < zebra_pos);
     }
+
+    #[tokio::test]
+    async fn test_glob_hides_tedignored_files() {
+        let temp_dir = TempDir::new().unwrap();
+        std::fs::write(temp_dir.path().join(".tedignore"), "dumps/\n").unwrap();
+        std::fs::create_dir_all(temp_dir.path().join("dumps")).unwrap();
+        std::fs::write(temp_dir.path().join("dumps/customers.csv"), "a,b").unwrap();
+        std::fs::write(temp_dir.path().join("report.csv"), "a,b").unwrap();
+
+        let tool = GlobTool;
+        let context = create_test_context(&temp_dir);
+
+        let result = tool
+            .execute(
+                "test-id".to_string(),
+                serde_json::json!({"pattern": "**/*.csv"}),
+                &context,
+            )
+            .await
+            .unwrap();
+        assert!(!result.is_error());
+        assert!(result.output_text().contains("report.csv"));
+        assert!(!result.output_text().contains("customers.csv"));
+
+        let result = tool
+            .execute(
+                "test-id".to_string(),
+                serde_json::json!({"pattern": "*", "path": "dumps"}),
+                &context,
+            )
+            .await
+            .unwrap();
+        assert!(result.is_error());
+        assert!(result.output_text().contains("Path is ignored"));
+    }
+
+    #[tokio::test]
+    async fn test_glob_respects_gitignore_and_types() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        std::fs::create_dir_all(root.join("dist")).unwrap();
+        std::fs::write(root.join(".gitignore"), "*.gen.ts\n").unwrap();
+        std::fs::write(root.join("src/app.ts"), "").unwrap();
+        std::fs::write(root.join("src/api.gen.ts"), "").unwrap();
+        std::fs::write(root.join("src/main.rs"), "").unwrap();
+        std::fs::write(root.join("dist/app.ts"), "").unwrap();
+
+        let tool = GlobTool;
+        let context = create_test_context(&temp_dir);
+        let run = |input: serde_json::Value| tool.execute("test-id".to_string(), input, &context);
+
+        let output = run(serde_json::json!({"pattern": "**/*.ts"}))
+            .await
+            .unwrap()
+            .output_text()
+            .to_string();
+        assert!(output.contains("Found 1 files"), "{}", output);
+        assert!(output.contains("src/app.ts"));
+
+        let output = run(serde_json::json!({"pattern": "**/*.ts", "include_ignored": true}))
+            .await
+            .unwrap()
+            .output_text()
+            .to_string();
+        assert!(output.contains("Found 3 files"), "{}", output);
+
+        let output = run(serde_json::json!({"pattern": "src/*", "type": "rust"}))
+            .await
+            .unwrap()
+            .output_text()
+            .to_string();
+        assert!(output.contains("Found 1 files"), "{}", output);
+        assert!(output.contains("src/main.rs"));
+    }
+
+    #[tokio::test]
+    async fn test_glob_finds_hidden_files_when_asked() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        std::fs::create_dir_all(root.join("web")).unwrap();
+        std::fs::create_dir_all(root.join(".github/workflows")).unwrap();
+        std::fs::write(root.join("web/.eslintrc.json"), "{}").unwrap();
+        std::fs::write(root.join(".github/workflows/ci.yml"), "").unwrap();
+        std::fs::write(root.join(".env.example"), "").unwrap();
+
+        let tool = GlobTool;
+        let context = create_test_context(&temp_dir);
+        let run = |input: serde_json::Value| tool.execute("test-id".to_string(), input, &context);
+
+        let output = run(serde_json::json!({"pattern": "**/.eslintrc*"}))
+            .await
+            .unwrap()
+            .output_text()
+            .to_string();
+        assert!(output.contains("Found 1 files"), "{}", output);
+        assert!(output.contains("web/.eslintrc.json"));
+
+        let output = run(serde_json::json!({"pattern": ".github/**/*.yml"}))
+            .await
+            .unwrap()
+            .output_text()
+            .to_string();
+        assert!(output.contains("Found 1 files"), "{}", output);
+
+        let output = run(serde_json::json!({"pattern": "**/*"}))
+            .await
+            .unwrap()
+            .output_text()
+            .to_string();
+        assert!(!output.contains(".env.example"), "{}", output);
+
+        let output = run(serde_json::json!({"pattern": "**/*", "include_ignored": true}))
+            .await
+            .unwrap()
+            .output_text()
+            .to_string();
+        assert!(output.contains(".env.example"), "{}", output);
+        assert!(output.contains("web/.eslintrc.json"));
+        assert!(output.contains("ci.yml"));
+    }
+
+    #[tokio::test]
+    async fn test_glob_sort_by_mtime() {
+        let temp_dir = TempDir::new().unwrap();
+        let old = temp_dir.path().join("a_old.txt");
+        let new = temp_dir.path().join("b_new.txt");
+        std::fs::write(&old, "").unwrap();
+        std::fs::write(&new, "").unwrap();
+        let day_ago = std::time::SystemTime::now() - std::time::Duration::from_secs(86_400);
+        std::fs::File::options()
+            .write(true)
+            .open(&old)
+            .unwrap()
+            .set_modified(day_ago)
+            .unwrap();
+        std::fs::File::options()
+            .write(true)
+            .open(&new)
+            .unwrap()
+            .set_modified(std::time::SystemTime::now())
+            .unwrap();
+
+        let tool = GlobTool;
+        let context = create_test_context(&temp_dir);
+        let result = tool
+            .execute(
+                "test-id".to_string(),
+                serde_json::json!({"pattern": "*.txt", "sort": "mtime"}),
+                &context,
+            )
+            .await
+            .unwrap();
+
+        let output = result.output_text();
+        assert!(output.contains("(newest first)"));
+        assert!(output.find("b_new.txt").unwrap() < output.find("a_old.txt").unwrap());
+    }
+
+    #[test]
+    fn test_split_literal_prefix() {
+        let base = Path::new("/repo");
+        assert_eq!(
+            split_literal_prefix(base, "src/**/*.ts"),
+            (PathBuf::from("/repo/src"), "**/*.ts".to_string())
+        );
+        assert_eq!(
+            split_literal_prefix(base, "**/*.rs"),
+            (PathBuf::from("/repo"), "**/*.rs".to_string())
+        );
+        assert_eq!(
+            split_literal_prefix(base, "./Cargo.toml"),
+            (PathBuf::from("/repo/Cargo.toml"), String::new())
+        );
+    }
 }
@@ -6,15 +6,20 @@
 //! Searches for patterns in files.
 
 use async_trait::async_trait;
-use regex::Regex;
+use regex::{Regex, RegexBuilder};
 use serde_json::Value;
 use std::path::PathBuf;
-use walkdir::WalkDir;
 
+use super::walk;
 use crate::error::Result;
 use crate::llm::provider::ToolDefinition;
 use crate::tools::{PermissionRequest, SchemaBuilder, Tool, ToolContext, ToolResult};
 
+const OUTPUT_MODES: &[&str] = &["content", "files_with_matches", "count"];
+
+/// Lines shown for a single multiline match before it is elided
+const MAX_MATCH_LINES: usize = 20;
+
 /// Tool for searching file contents
 pub struct GrepTool;
 
@@ -27,14 +32,18 @@ impl Tool for GrepTool {
     fn definition(&self) -> ToolDefinition {
         ToolDefinition {
             name: "grep".to_string(),
-            description: "Search for a pattern in files. Returns matching lines with file paths and line numbers.".to_string(),
+            description: "Search for a pattern in files. Returns matching lines with file paths and line numbers, or per-file counts or file names. Files excluded by .gitignore/.ignore and dependency/build directories are skipped unless include_ignored is true.".to_string(),
             input_schema: SchemaBuilder::new()
                 .string("pattern", "Regex pattern to search for", true)
                 .string("path", "File or directory to search in (default: working directory)", false)
                 .string("glob", "Optional glob filter for files (e.g., '*.rs')", false)
+                .string("type", "Only search these file types, comma-separated ripgrep names (e.g., 'rust', 'ts,js', 'py'); prefix with ! to exclude", false)
                 .boolean("case_insensitive", "Case insensitive search (default: false)", false)
+                .boolean("multiline", "Let the pattern match across lines; ^ and $ match at line boundaries, use (?s) for . to match newlines (default: false)", false)
+                .string_enum("output_mode", "'content' shows matching lines, 'files_with_matches' lists files, 'count' shows matches per file (default: content)", OUTPUT_MODES, false)
+                .boolean("include_ignored", "Also search hidden files and files excluded by .gitignore/.ignore and dependency/build directories (default: false)", false)
                 .integer("context_lines", "Lines of context around matches (default: 0)", false)
-                .integer("limit", "Maximum number of matches (default: 50)", false)
+                .integer("limit", "Maximum number of matches, or files in files_with_matches/count mode (default: 50)", false)
                 .build(),
         }
     }
@@ -50,17 +59,39 @@ impl Tool for GrepTool {
         })?;
 
         let case_insensitive = input["case_insensitive"].as_bool().unwrap_or(false);
+        let multiline = input["multiline"].as_bool().unwrap_or(false);
+        let include_ignored = input["include_ignored"].as_bool().unwrap_or(false);
         let context_lines = input["context_lines"].as_u64().unwrap_or(0) as usize;
         let limit = input["limit"].as_u64().unwrap_or(50) as usize;
         let glob_filter = input["glob"].as_str();
 
-        // Build regex
-        let regex = if case_insensitive {
-            Regex::new(&format!("(?i){}", pattern_str))
-        } else {
-            Regex::new(pattern_str)
+        let output_mode = input["output_mode"].as_str().unwrap_or("content");
+        if !OUTPUT_MODES.contains(&output_mode) {
+            return Ok(ToolResult::error(
+                tool_use_id,
+                format!(
+                    "Invalid output_mode '{}'; expected one of: {}",
+                    output_mode,
+                    OUTPUT_MODES.join(", ")
+                ),
+            ));
+        }
+        let list_files = output_mode != "content";
+
+        let types = match input["type"].as_str().filter(|t| !t.trim().is_empty()) {
+            Some(spec) => match walk::file_types(spec) {
+                Ok(types) => Some(types),
+                Err(e) => return Ok(ToolResult::error(tool_use_id, e)),
+            },
+            None => None,
         };
 
+        // Build regex
+        let regex = RegexBuilder::new(pattern_str)
+            .case_insensitive(case_insensitive)
+            .multi_line(multiline)
+            .build();
+
         let regex = match regex {
             Ok(r) => r,
             Err(e) => {
@@ -82,107 +113,154 @@ impl Tool for GrepTool {
             context.working_directory.clone()
         };
 
+        if let Some(message) = context.check_path_not_ignored(&search_path) {
+            return Ok(ToolResult::error(tool_use_id, message));
+        }
+
         // Build glob pattern for filtering
         let glob_pattern = glob_filter.and_then(|g| glob::Pattern::new(g).ok());
 
+        // An explicitly named file is searched even if ignore files exclude it
+        let files: Box<dyn Iterator<Item = PathBuf>> = if search_path.is_file() {
+            Box::new(std::iter::once(search_path.clone()))
+        } else {
+            Box::new(
+                walk::walk(&search_path, context, include_ignored, false, types)
+                    .filter_map(|e| e.ok())
+                    .filter(|e| e.file_type().is_some_and(|t| t.is_file()))
+                    .map(|e| e.into_path()),
+            )
+        };
+
         let mut matches: Vec<SearchMatch> = Vec::new();
+        let mut file_counts: Vec<(PathBuf, usize)> = Vec::new();
         let mut files_searched = 0;
-        let mut files_with_matches = 0;
-
-        // Walk directory or search single file
-        if search_path.is_file() {
-            if let Some(file_matches) =
-                search_file(&search_path, &regex, context_lines, limit - matches.len())
-            {
-                if !file_matches.is_empty() {
-                    files_with_matches += 1;
-                    matches.extend(file_matches);
-                }
-            }
-            files_searched = 1;
-        } else {
-            for entry in WalkDir::new(&search_path)
-                .follow_links(true)
-                .into_iter()
-                .filter_map(|e| e.ok())
-                .filter(|e| e.file_type().is_file())
-            {
-                let path = entry.path();
-
-                // Apply glob filter if provided
-                if let Some(ref pattern) = glob_pattern {
-                    if let Some(file_name) = path.file_name() {
-                        if !pattern.matches(&file_name.to_string_lossy()) {
-                            continue;
-                        }
+
+        for path in files {
+            // Apply glob filter if provided
+            if let Some(ref pattern) = glob_pattern {
+                if let Some(file_name) = path.file_name() {
+                    if !pattern.matches(&file_name.to_string_lossy()) {
+                        continue;
                     }
                 }
+            }
 
-                // Skip binary files and hidden directories
-                if is_likely_binary(path) || is_hidden_or_ignored(path) {
-                    continue;
-                }
+            if path != search_path && is_likely_binary(&path) {
+                continue;
+            }
 
-                files_searched += 1;
+            files_searched += 1;
 
-                if let Some(file_matches) =
-                    search_file(path, &regex, context_lines, limit - matches.len())
-                {
-                    if !file_matches.is_empty() {
-                        files_with_matches += 1;
-                        matches.extend(file_matches);
-                    }
-                }
+            let remaining = if list_files {
+                usize::MAX
+            } else {
+                limit - matches.len()
+            };
+            let file_matches = if multiline {
+                search_file_multiline(&path, &regex, remaining)
+            } else {
+                search_file(&path, &regex, context_lines, remaining)
+            };
 
-                if matches.len() >= limit {
-                    break;
+            if let Some(file_matches) = file_matches.filter(|m| !m.is_empty()) {
+                file_counts.push((path, file_matches.len()));
+                if !list_files {
+                    matches.extend(file_matches);
                 }
             }
+
+            let reached = if list_files {
+                file_counts.len()
+            } else {
+                matches.len()
+            };
+            if reached >= limit {
+                break;
+            }
         }
 
         // Emit recall event for memory tracking with unique file paths
-        if !matches.is_empty() {
-            let unique_paths: Vec<PathBuf> = matches
-                .iter()
-                .map(|m| m.path.clone())
-                .collect::<std::collections::HashSet<_>>()
-                .into_iter()
-                .collect();
-            context.emit_search_match(unique_paths);
+        if !file_counts.is_empty() {
+            context.emit_search_match(file_counts.iter().map(|(p, _)| p.clone()).collect());
         }
 
+        let display = |path: &PathBuf| {
+            path.strip_prefix(&context.working_directory)
+                .unwrap_or(path)
+                .to_string_lossy()
+                .to_string()
+        };
+        let total_matches: usize = file_counts.iter().map(|(_, n)| n).sum();
+
         // Format output
         let mut output = String::new();
-        output.push_str(&format!(
-            "Found {} matches in {} files (searched {} files):\n\n",
-            matches.len(),
-            files_with_matches,
-            files_searched
-        ));
-
-        let mut current_file: Option<String> = None;
-        for m in &matches {
-            // Show file header when file changes
-            let file_display = m
-                .path
-                .strip_prefix(&context.working_directory)
-                .unwrap_or(&m.path)
-                .to_string_lossy()
-                .to_string();
+        if output_mode == "files_with_matches" {
+            output.push_str(&format!(
+                "Found matches in {} files (searched {} files):\n\n",
+                file_counts.len(),
+                files_searched
+            ));
+            for (path, _) in &file_counts {
+                output.push_str(&display(path));
+                output.push('\n');
+            }
+        } else if output_mode == "count" {
+            output.push_str(&format!(
+                "Found {} matches in {} files (searched {} files):\n\n",
+                total_matches,
+                file_counts.len(),
+                files_searched
+            ));
+            for (path, count) in &file_counts {
+                output.push_str(&format!("{}: {}\n", display(path), count));
+            }
+        } else {
+            output.push_str(&format!(
+                "Found {} matches in {} files (searched {} files):\n\n",
+                matches.len(),
+                file_counts.len(),
+                files_searched
+            ));
+
+            let mut current_file: Option<String> = None;
+            for m in &matches {
+                // Show file header when file changes
+                let file_display = display(&m.path);
+
+                if current_file.as_ref() != Some(&file_display) {
+                    if current_file.is_some() {
+                        output.push('\n');
+                    }
+                    output.push_str(&format!("{}:\n", file_display));
+                    current_file = Some(file_display);
+                }
 
-            if current_file.as_ref() != Some(&file_display) {
-                if current_file.is_some() {
-                    output.push('\n');
+                let lines: Vec<&str> = m.line.split('\n').collect();
+                for (offset, line) in lines.iter().take(MAX_MATCH_LINES).enumerate() {
+                    output.push_str(&format!(
+                        "  {:>5}: {}\n",
+                        m.line_number + offset,
+                        line.trim()
+                    ));
+                }
+                if lines.len() > MAX_MATCH_LINES {
+                    output.push_str(&format!(
+                        "         ... ({} more lines)\n",
+                        lines.len() - MAX_MATCH_LINES
+                    ));
                 }
-                output.push_str(&format!("{}:\n", file_display));
-                current_file = Some(file_display);
             }
-
-            output.push_str(&format!("  {:>5}: {}\n", m.line_number, m.line.trim()));
         }
 
-        if matches.len() >= limit {
-            output.push_str(&format!("\n(limited to {} matches)\n", limit));
+        let reached = if list_files {
+            file_counts.len()
+        } else {
+            matches.len()
+        };
+        if reached >= limit {
+            let unit = if list_files { "files" } else { "matches" };
+            output.push_str(&format!("\n(limited to {} {})\n", limit, unit));
         }
 
         Ok(ToolResult::success(tool_use_id, output))
@@ -207,6 +285,7 @@ impl Tool for GrepTool {
 struct SearchMatch {
     path: PathBuf,
     line_number: usize,
+    /// Matched line, or every line a multiline match spans
     line: String,
 }
 
@@ -236,6 +315,55 @@ fn search_file(
     Some(matches)
 }
 
+/// Search the whole file so matches can span lines. Each match reports the
+/// line it starts on and the full text of the lines it covers.
+fn search_file_multiline(
+    path: &std::path::Path,
+    regex: &Regex,
+    limit: usize,
+) -> Option<Vec<SearchMatch>> {
+    let content = std::fs::read_to_string(path).ok()?;
+    let line_starts: Vec<usize> = std::iter::once(0)
+        .chain(content.match_indices('\n').map(|(i, _)| i + 1))
+        .collect();
+    let line_index = |offset: usize| line_starts.partition_point(|&start| start <= offset) - 1;
+
+    let mut matches: Vec<SearchMatch> = Vec::new();
+    for found in regex.find_iter(&content) {
+        let first = line_index(found.start());
+        // A match ending in a newline doesn't cover the following line
+        let last_offset = if found.end() > found.start() && content[..found.end()].ends_with('\n') {
+            found.end() - 1
+        } else {
+            found.end()
+        };
+        let last = line_index(last_offset).max(first);
+
+        if matches.last().is_some_and(|m| m.line_number == first + 1) {
+            continue;
+        }
+
+        let text_start = line_starts[first];
+        let text_end = line_starts
+            .get(last + 1)
+            .map(|&next| next - 1)
+            .unwrap_or(content.len());
+        matches.push(SearchMatch {
+            path: path.to_path_buf(),
+            line_number: first + 1,
+            line: content[text_start..text_end]
+                .trim_end_matches('\r')
+                .to_string(),
+        });
+
+        if matches.len() >= limit {
+            break;
+        }
+    }
+
+    Some(matches)
+}
+
 fn is_likely_binary(path: &std::path::Path) -> bool {
     if let Some(ext) = path.extension() {
         let ext = ext.to_string_lossy().to_lowercase();
@@ -285,32 +413,6 @@ fn is_likely_binary(path: &std::path::Path) -> bool {
     }
 }
 
-fn is_hidden_or_ignored(path: &std::path::Path) -> bool {
-    for component in path.components() {
-        if let std::path::Component::Normal(name) = component {
-            let name = name.to_string_lossy();
-            if name.starts_with('.') {
-                return true;
-            }
-            if matches!(
-                name.as_ref(),
-                "node_modules"
-                    | "target"
-                    | "__pycache__"
-                    | "venv"
-                    | ".venv"
-                    | "dist"
-                    | "build"
-                    | "vendor"
-                    | ".git"
-            ) {
-                return true;
-            }
-        }
-    }
-    false
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -633,81 +735,6 @@ mod tests {
         assert!(!is_likely_binary(std::path::Path::new("/src/main.rs")));
     }
 
-    #[test]
-    fn test_is_hidden_or_ignored() {
-        assert!(is_hidden_or_ignored(std::path::Path::new(
-            ".hidden/file.txt"
-        )));
-        assert!(is_hidden_or_ignored(std::path::Path::new(
-            "node_modules/pkg/file.js"
-        )));
-        assert!(is_hidden_or_ignored(std::path::Path::new(
-            "target/debug/ted"
-        )));
-        assert!(is_hidden_or_ignored(std::path::Path::new(".git/config")));
-        assert!(!is_hidden_or_ignored(std::path::Path::new("src/main.rs")));
-        assert!(!is_hidden_or_ignored(std::path::Path::new("lib/utils.rs")));
-    }
-
-    #[test]
-    fn test_is_hidden_or_ignored_all_directories() {
-        // Test all ignored directory names
-        assert!(is_hidden_or_ignored(std::path::Path::new(
-            "__pycache__/cache.pyc"
-        )));
-        assert!(is_hidden_or_ignored(std::path::Path::new(
-            "venv/bin/python"
-        )));
-        assert!(is_hidden_or_ignored(std::path::Path::new(".venv/lib/site")));
-        assert!(is_hidden_or_ignored(std::path::Path::new("dist/bundle.js")));
-        assert!(is_hidden_or_ignored(std::path::Path::new(
-            "build/output.js"
-        )));
-        assert!(is_hidden_or_ignored(std::path::Path::new(
-            "vendor/pkg/mod.go"
-        )));
-    }
-
-    #[test]
-    fn test_is_hidden_or_ignored_nested_paths() {
-        // Hidden dirs deep in path
-        assert!(is_hidden_or_ignored(std::path::Path::new(
-            "src/components/.hidden/file.ts"
-        )));
-        // Ignored dirs deep in path
-        assert!(is_hidden_or_ignored(std::path::Path::new(
-            "packages/app/node_modules/react/index.js"
-        )));
-        assert!(is_hidden_or_ignored(std::path::Path::new(
-            "crates/lib/target/release/binary"
-        )));
-    }
-
-    #[test]
-    fn test_is_hidden_or_ignored_hidden_files() {
-        // Files starting with dot are also considered hidden
-        // because the function checks all path components
-        assert!(is_hidden_or_ignored(std::path::Path::new("src/.gitignore")));
-        assert!(is_hidden_or_ignored(std::path::Path::new("pkg/.eslintrc")));
-        assert!(is_hidden_or_ignored(std::path::Path::new(".env")));
-        // But regular files are not hidden
-        assert!(!is_hidden_or_ignored(std::path::Path::new("src/gitignore")));
-    }
-
-    #[test]
-    fn test_is_hidden_or_ignored_similar_names() {
-        // Names that contain but aren't exactly the ignored names
-        assert!(!is_hidden_or_ignored(std::path::Path::new(
-            "not_node_modules/file.js"
-        )));
-        assert!(!is_hidden_or_ignored(std::path::Path::new(
-            "target_practice/file.rs"
-        )));
-        assert!(!is_hidden_or_ignored(std::path::Path::new(
-            "my_dist/file.js"
-        )));
-    }
-
     #[test]
     fn test_search_file() {
         let temp_dir = TempDir::new().unwrap();
@@ -840,4 +867,169 @@ mod tests {
         assert_eq!(matches.len(), 1);
         assert_eq!(matches[0].path, file_path);
     }
+
+    #[tokio::test]
+    async fn test_grep_skips_tedignored_files() {
+        // Default temp dirs start with '.', which the walker treats as hidden
+        let temp_dir = tempfile::Builder::new()
+            .prefix("ted-grep")
+            .tempdir()
+            .unwrap();
+        std::fs::write(temp_dir.path().join(".tedignore"), "secrets/\n").unwrap();
+        std::fs::create_dir_all(temp_dir.path().join("secrets")).unwrap();
+        std::fs::create_dir_all(temp_dir.path().join("src")).unwrap();
+        std::fs::write(temp_dir.path().join("secrets/keys.txt"), "api_key here").unwrap();
+        std::fs::write(temp_dir.path().join("src/lib.rs"), "// api_key usage").unwrap();
+
+        let tool = GrepTool;
+        let context = create_test_context(&temp_dir);
+
+        let result = tool
+            .execute(
+                "test-id".to_string(),
+                serde_json::json!({"pattern": "api_key"}),
+                &context,
+            )
+            .await
+            .unwrap();
+        assert!(!result.is_error());
+        assert!(result.output_text().contains("lib.rs"));
+        assert!(!result.output_text().contains("keys.txt"));
+
+        let result = tool
+            .execute(
+                "test-id".to_string(),
+                serde_json::json!({"pattern": "api_key", "path": "secrets/keys.txt"}),
+                &context,
+            )
+            .await
+            .unwrap();
+        assert!(result.is_error());
+    }
+
+    fn ignore_project() -> TempDir {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        std::fs::create_dir_all(root.join("generated")).unwrap();
+        std::fs::write(root.join(".gitignore"), "generated/\n").unwrap();
+        std::fs::write(root.join("src/lib.rs"), "// TODO: one\n// TODO: two\n").unwrap();
+        std::fs::write(root.join("src/app.py"), "# TODO: three\n").unwrap();
+        std::fs::write(root.join("generated/out.rs"), "// TODO: generated\n").unwrap();
+        temp_dir
+    }
+
+    async fn grep(temp_dir: &TempDir, input: serde_json::Value) -> ToolResult {
+        GrepTool
+            .execute("test-id".to_string(), input, &create_test_context(temp_dir))
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_grep_respects_gitignore_with_opt_out() {
+        let temp_dir = ignore_project();
+
+        let result = grep(&temp_dir, serde_json::json!({"pattern": "TODO"})).await;
+        assert!(result.output_text().contains("Found 3 matches in 2 files"));
+        assert!(!result.output_text().contains("generated"));
+
+        let result = grep(
+            &temp_dir,
+            serde_json::json!({"pattern": "TODO", "include_ignored": true}),
+        )
+        .await;
+        assert!(result.output_text().contains("Found 4 matches in 3 files"));
+        assert!(result.output_text().contains("generated/out.rs"));
+    }
+
+    #[tokio::test]
+    async fn test_grep_type_filter() {
+        let temp_dir = ignore_project();
+
+        let result = grep(
+            &temp_dir,
+            serde_json::json!({"pattern": "TODO", "type": "py"}),
+        )
+        .await;
+        assert!(result.output_text().contains("Found 1 matches in 1 files"));
+        assert!(result.output_text().contains("src/app.py"));
+
+        let result = grep(
+            &temp_dir,
+            serde_json::json!({"pattern": "TODO", "type": "cobolx"}),
+        )
+        .await;
+        assert!(result.is_error());
+        assert!(result.output_text().contains("cobolx"));
+    }
+
+    #[tokio::test]
+    async fn test_grep_output_modes() {
+        let temp_dir = ignore_project();
+
+        let result = grep(
+            &temp_dir,
+            serde_json::json!({"pattern": "TODO", "output_mode": "count"}),
+        )
+        .await;
+        let output = result.output_text();
+        assert!(output.contains("src/lib.rs: 2\n"));
+        assert!(output.contains("src/app.py: 1\n"));
+
+        let result = grep(
+            &temp_dir,
+            serde_json::json!({"pattern": "TODO", "output_mode": "files_with_matches", "limit": 1}),
+        )
+        .await;
+        let output = result.output_text();
+        assert!(output.starts_with("Found matches in 1 files"));
+        assert!(output.contains("(limited to 1 files)"));
+        assert!(!output.contains("TODO"));
+    }
+
+    #[tokio::test]
+    async fn test_grep_multiline() {
+        let temp_dir = TempDir::new().unwrap();
+        std::fs::write(
+            temp_dir.path().join("lib.rs"),
+            "#[derive(Debug)]\npub struct Config {\n    port: u16,\n}\n",
+        )
+        .unwrap();
+
+        let result = grep(
+            &temp_dir,
+            serde_json::json!({"pattern": r"derive\(Debug\)\]\s*pub struct \w+", "multiline": true}),
+        )
+        .await;
+        let output = result.output_text();
+        assert!(output.contains("Found 1 matches"), "{}", output);
+        assert!(output.contains("      1: #[derive(Debug)]\n      2: pub struct Config {"));
+
+        let result = grep(
+            &temp_dir,
+            serde_json::json!({"pattern": r"derive\(Debug\)\]\s*pub struct"}),
+        )
+        .await;
+        assert!(result.output_text().contains("Found 0 matches"));
+    }
+
+    #[test]
+    fn test_search_file_multiline_line_numbers() {
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = temp_dir.path().join("test.txt");
+        std::fs::write(&file_path, "a\nstart\nmiddle\nend\nstart\nend\n").unwrap();
+
+        let regex = RegexBuilder::new(r"(?s)start.*?end\n")
+            .multi_line(true)
+            .build()
+            .unwrap();
+        let matches = search_file_multiline(&file_path, &regex, 100).unwrap();
+
+        assert_eq!(matches.len(), 2);
+        assert_eq!(matches[0].line_number, 2);
+        assert_eq!(matches[0].line, "start\nmiddle\nend");
+        assert_eq!(matches[1].line_number, 5);
+        assert_eq!(matches[1].line, "start\nend");
+    }
 }
@@ -0,0 +1,553 @@
+// SPDX-License-Identifier: AGPL-3.0-or-later
+// Copyright (C) 2025 Blackman Artificial Intelligence Technologies Inc.
+
+//! HTTP request tool
+//!
+//! Sends a single HTTP request and returns the status line, response headers
+//! and body, with JSON pretty-printed and large bodies truncated. Meant for
+//! exercising APIs under development; requests to hosts other than localhost
+//! are denied unless a permission policy `hosts` rule allows them.
+
+use async_trait::async_trait;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue, CONTENT_TYPE};
+use reqwest::Method;
+use serde_json::Value;
+use std::fmt::Write as _;
+use std::time::{Duration, Instant};
+
+use crate::error::Result;
+use crate::llm::provider::ToolDefinition;
+use crate::tools::{
+    is_local_host, PermissionPolicy, PermissionRequest, PolicyEffect, SchemaBuilder, Tool,
+    ToolContext, ToolResult,
+};
+
+const METHODS: &[&str] = &["GET", "POST", "PUT", "PATCH", "DELETE", "HEAD", "OPTIONS"];
+
+const DEFAULT_TIMEOUT_SECS: u64 = 30;
+const MAX_TIMEOUT_SECS: u64 = 300;
+
+/// Response bodies beyond this many bytes are truncated
+const MAX_BODY_BYTES: usize = 64 * 1024;
+
+/// Tool for sending HTTP requests
+pub struct HttpRequestTool;
+
+#[async_trait]
+impl Tool for HttpRequestTool {
+    fn name(&self) -> &str {
+        "http_request"
+    }
+
+    fn definition(&self) -> ToolDefinition {
+        ToolDefinition {
+            name: "http_request".to_string(),
+            description: "Send an HTTP request and return the status, response headers and body (JSON is pretty-printed, bodies over 64 KB are truncated). Use this instead of curl to test APIs. Only localhost is reachable unless the permission policy allows other hosts. Redirects are not followed.".to_string(),
+            input_schema: SchemaBuilder::new()
+                .string("url", "Full URL including scheme, e.g. http://localhost:3000/api/users", true)
+                .string_enum("method", "HTTP method (default: GET)", METHODS, false)
+                .string_map("headers", "Request headers as name/value pairs", false)
+                .string("body", "Request body. JSON bodies get Content-Type: application/json unless set in headers", false)
+                .integer("timeout_secs", "Timeout in seconds (default: 30, max: 300)", false)
+                .build(),
+        }
+    }
+
+    async fn execute(
+        &self,
+        tool_use_id: String,
+        input: Value,
+        context: &ToolContext,
+    ) -> Result<ToolResult> {
+        let url = input["url"].as_str().unwrap_or_default().trim();
+        let url = match reqwest::Url::parse(url) {
+            Ok(url) if matches!(url.scheme(), "http" | "https") => url,
+            Ok(url) => {
+                return Ok(ToolResult::error(
+                    tool_use_id,
+                    format!(
+                        "Unsupported URL scheme '{}'; use http or https",
+                        url.scheme()
+                    ),
+                ))
+            }
+            Err(e) => {
+                return Ok(ToolResult::error(
+                    tool_use_id,
+                    format!("Invalid URL '{}': {}", url, e),
+                ))
+            }
+        };
+
+        let method_name = request_method(&input);
+        let Ok(method) = Method::from_bytes(method_name.as_bytes()) else {
+            return Ok(ToolResult::error(
+                tool_use_id,
+                format!("Invalid HTTP method '{}'", method_name),
+            ));
+        };
+
+        if let Some(reason) = host_denial(&url, &method_name, context) {
+            return Ok(ToolResult::error(
+                tool_use_id,
+                format!("Request to {} denied: {}", url, reason),
+            ));
+        }
+
+        let mut headers = HeaderMap::new();
+        if let Some(map) = input["headers"].as_object() {
+            for (name, value) in map {
+                let value = match value {
+                    Value::String(s) => s.clone(),
+                    other => other.to_string(),
+                };
+                let (Ok(name), Ok(value)) = (
+                    HeaderName::from_bytes(name.as_bytes()),
+                    HeaderValue::from_str(&value),
+                ) else {
+                    return Ok(ToolResult::error(
+                        tool_use_id,
+                        format!("Invalid header '{}'", name),
+                    ));
+                };
+                headers.insert(name, value);
+            }
+        }
+
+        let body = input["body"].as_str().map(str::to_string);
+        if let Some(body) = &body {
+            if !headers.contains_key(CONTENT_TYPE)
+                && serde_json::from_str::<Value>(body).is_ok_and(|v| v.is_object() || v.is_array())
+            {
+                headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+            }
+        }
+
+        let timeout = input["timeout_secs"]
+            .as_u64()
+            .unwrap_or(DEFAULT_TIMEOUT_SECS)
+            .clamp(1, MAX_TIMEOUT_SECS);
+
+        // Redirects are returned rather than followed so a local endpoint
+        // cannot bounce the request to a host the policy never saw.
+        let client = match reqwest::Client::builder()
+            .redirect(reqwest::redirect::Policy::none())
+            .timeout(Duration::from_secs(timeout))
+            .build()
+        {
+            Ok(client) => client,
+            Err(e) => {
+                return Ok(ToolResult::error(
+                    tool_use_id,
+                    format!("Failed to create HTTP client: {}", e),
+                ))
+            }
+        };
+
+        let mut request = client.request(method.clone(), url.clone()).headers(headers);
+        if let Some(body) = body {
+            request = request.body(body);
+        }
+
+        let started = Instant::now();
+        let mut response = match request.send().await {
+            Ok(response) => response,
+            Err(e) => {
+                let reason = if e.is_timeout() {
+                    format!("timed out after {}s", timeout)
+                } else if e.is_connect() {
+                    "connection failed (is the server running?)".to_string()
+                } else {
+                    e.to_string()
+                };
+                return Ok(ToolResult::error(
+                    tool_use_id,
+                    format!("{} {} failed: {}", method, url, reason),
+                ));
+            }
+        };
+
+        // Stop reading once the cap is exceeded so streaming endpoints
+        // don't hold the call open until the timeout.
+        let content_length = response.content_length();
+        let mut body = Vec::new();
+        let mut truncated = false;
+        while !truncated {
+            match response.chunk().await {
+                Ok(Some(chunk)) => {
+                    let room = MAX_BODY_BYTES - body.len();
+                    truncated = chunk.len() > room;
+                    body.extend_from_slice(&chunk[..chunk.len().min(room)]);
+                }
+                Ok(None) => break,
+                Err(e) => {
+                    return Ok(ToolResult::error(
+                        tool_use_id,
+                        format!("Failed to read response body: {}", e),
+                    ))
+                }
+            }
+        }
+        let elapsed = started.elapsed();
+
+        let status = response.status();
+        let mut output = format!(
+            "{} {} ({} ms)\n",
+            status.as_u16(),
+            status.canonical_reason().unwrap_or(""),
+            elapsed.as_millis()
+        );
+        for (name, value) in response.headers() {
+            let _ = writeln!(
+                output,
+                "{}: {}",
+                name,
+                String::from_utf8_lossy(value.as_bytes())
+            );
+        }
+
+        let content_type = response
+            .headers()
+            .get(CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or_default();
+        if !body.is_empty() {
+            output.push('\n');
+            output.push_str(&format_body(&body, content_type, truncated));
+        }
+        if truncated {
+            let _ = match content_length {
+                Some(total) => write!(
+                    output,
+                    "\n[body truncated: showing {} of {} bytes]",
+                    body.len(),
+                    total
+                ),
+                None => write!(
+                    output,
+                    "\n[body truncated: showing first {} bytes]",
+                    body.len()
+                ),
+            };
+        }
+
+        Ok(ToolResult::success(tool_use_id, output))
+    }
+
+    fn permission_request(&self, input: &Value) -> Option<PermissionRequest> {
+        let method = request_method(input);
+        let url = input["url"].as_str().unwrap_or("unknown").trim();
+        Some(PermissionRequest {
+            tool_name: "http_request".to_string(),
+            action_description: format!("Request: {} {}", method, url),
+            affected_paths: vec![],
+            is_destructive: method == "DELETE",
+        })
+    }
+
+    fn requires_permission(&self) -> bool {
+        true
+    }
+}
+
+fn request_method(input: &Value) -> String {
+    input["method"]
+        .as_str()
+        .map(|m| m.trim().to_ascii_uppercase())
+        .filter(|m| !m.is_empty())
+        .unwrap_or_else(|| "GET".to_string())
+}
+
+/// Why a request to `url` is not allowed, if it isn't.
+///
+/// `ToolExecutor` applies the same policy before asking the user, but
+/// sub-agents and MCP `tools/call` run tools directly, so the host
+/// restriction is enforced here too. A policy file that fails to load
+/// leaves only localhost reachable.
+fn host_denial(url: &reqwest::Url, method: &str, context: &ToolContext) -> Option<String> {
+    let host = url
+        .host_str()?
+        .trim_start_matches('[')
+        .trim_end_matches(']');
+    if is_local_host(&host.to_ascii_lowercase()) {
+        return None;
+    }
+    let policy = PermissionPolicy::load_for_workspace(
+        &context.working_directory,
+        context.project_root.as_deref(),
+    )
+    .unwrap_or_else(|e| {
+        tracing::warn!("Failed to load permissions policy for http_request: {}", e);
+        PermissionPolicy::default()
+    });
+    let matched = policy.evaluate(
+        "http_request",
+        &format!("Request: {} {}", method, url),
+        &[],
+        method == "DELETE",
+    )?;
+    (matched.effect == PolicyEffect::Deny).then(|| {
+        matched
+            .reason
+            .unwrap_or_else(|| "denied by permission policy".to_string())
+    })
+}
+
+/// Render a response body, pretty-printing complete JSON documents.
+fn format_body(body: &[u8], content_type: &str, truncated: bool) -> String {
+    let Ok(text) = std::str::from_utf8(body) else {
+        // A cut may split a multi-byte character; keep the valid prefix.
+        let valid = match std::str::from_utf8(body) {
+            Err(e) if truncated && e.error_len().is_none() => e.valid_up_to(),
+            _ => {
+                return format!(
+                    "[binary body, {} bytes{}]",
+                    body.len(),
+                    if content_type.is_empty() {
+                        String::new()
+                    } else {
+                        format!(", {}", content_type)
+                    }
+                )
+            }
+        };
+        return String::from_utf8_lossy(&body[..valid]).into_owned();
+    };
+
+    if !truncated {
+        let looks_json = content_type.contains("json")
+            || text.trim_start().starts_with('{')
+            || text.trim_start().starts_with('[');
+        if looks_json {
+            if let Ok(value) = serde_json::from_str::<Value>(text) {
+                if let Ok(pretty) = serde_json::to_string_pretty(&value) {
+                    return pretty;
+                }
+            }
+        }
+    }
+    text.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use tempfile::TempDir;
+    use uuid::Uuid;
+    use wiremock::matchers::{body_string, header, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn context(dir: &TempDir) -> ToolContext {
+        ToolContext::new(dir.path().to_path_buf(), None, Uuid::new_v4(), true)
+    }
+
+    #[tokio::test]
+    async fn test_get_pretty_prints_json() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/users"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("x-request-id", "abc")
+                    .set_body_json(json!({"users": [{"id": 1, "name": "Ada"}]})),
+            )
+            .mount(&server)
+            .await;
+
+        let dir = TempDir::new().unwrap();
+        let result = HttpRequestTool
+            .execute(
+                "t".to_string(),
+                json!({"url": format!("{}/api/users", server.uri())}),
+                &context(&dir),
+            )
+            .await
+            .unwrap();
+
+        assert!(!result.is_error(), "{}", result.output_text());
+        let output = result.output_text();
+        assert!(output.starts_with("200 OK"));
+        assert!(output.contains("x-request-id: abc"));
+        assert!(output.contains("\"users\": [\n"));
+        assert!(output.contains("\"name\": \"Ada\""));
+    }
+
+    #[tokio::test]
+    async fn test_post_sends_headers_and_json_body() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/items"))
+            .and(header("authorization", "Bearer token"))
+            .and(header("content-type", "application/json"))
+            .and(body_string("{\"name\":\"widget\"}"))
+            .respond_with(ResponseTemplate::new(201).set_body_string("created"))
+            .mount(&server)
+            .await;
+
+        let dir = TempDir::new().unwrap();
+        let result = HttpRequestTool
+            .execute(
+                "t".to_string(),
+                json!({
+                    "url": format!("{}/items", server.uri()),
+                    "method": "post",
+                    "headers": {"Authorization": "Bearer token"},
+                    "body": "{\"name\":\"widget\"}"
+                }),
+                &context(&dir),
+            )
+            .await
+            .unwrap();
+
+        let output = result.output_text();
+        assert!(output.starts_with("201 Created"), "{}", output);
+        assert!(output.ends_with("\ncreated"));
+    }
+
+    #[tokio::test]
+    async fn test_large_bodies_are_truncated_and_redirects_not_followed() {
+        let server = MockServer::start().await;
+        Mock::given(path("/big"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_string("x".repeat(MAX_BODY_BYTES + 10)),
+            )
+            .mount(&server)
+            .await;
+        Mock::given(path("/moved"))
+            .respond_with(
+                ResponseTemplate::new(302).insert_header("location", "https://example.com/"),
+            )
+            .mount(&server)
+            .await;
+
+        let dir = TempDir::new().unwrap();
+        let ctx = context(&dir);
+        let result = HttpRequestTool
+            .execute(
+                "t".to_string(),
+                json!({"url": format!("{}/big", server.uri())}),
+                &ctx,
+            )
+            .await
+            .unwrap();
+        assert!(result.output_text().contains(&format!(
+            "[body truncated: showing {} of {} bytes]",
+            MAX_BODY_BYTES,
+            MAX_BODY_BYTES + 10
+        )));
+
+        let result = HttpRequestTool
+            .execute(
+                "t".to_string(),
+                json!({"url": format!("{}/moved", server.uri())}),
+                &ctx,
+            )
+            .await
+            .unwrap();
+        assert!(result.output_text().starts_with("302 Found"));
+        assert!(result
+            .output_text()
+            .contains("location: https://example.com/"));
+    }
+
+    #[tokio::test]
+    async fn test_invalid_url_and_connection_errors() {
+        let dir = TempDir::new().unwrap();
+        let ctx = context(&dir);
+
+        let result = HttpRequestTool
+            .execute(
+                "t".to_string(),
+                json!({"url": "ftp://localhost/file"}),
+                &ctx,
+            )
+            .await
+            .unwrap();
+        assert!(result.is_error());
+        assert!(result
+            .output_text()
+            .contains("Unsupported URL scheme 'ftp'"));
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        drop(listener);
+        let result = HttpRequestTool
+            .execute(
+                "t".to_string(),
+                json!({"url": format!("http://127.0.0.1:{}/", port)}),
+                &ctx,
+            )
+            .await
+            .unwrap();
+        assert!(result.is_error());
+        assert!(result.output_text().contains("connection failed"));
+    }
+
+    #[tokio::test]
+    async fn test_external_hosts_denied_without_policy_rule() {
+        let dir = TempDir::new().unwrap();
+        for url in ["http://example.com/", "https://10.0.0.5:8443/admin"] {
+            let result = HttpRequestTool
+                .execute("t".to_string(), json!({ "url": url }), &context(&dir))
+                .await
+                .unwrap();
+            assert!(result.is_error());
+            assert!(
+                result.output_text().contains("only localhost is allowed"),
+                "{}",
+                result.output_text()
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_external_host_allowed_by_project_policy() {
+        let dir = TempDir::new().unwrap();
+        std::fs::create_dir_all(dir.path().join(".ted")).unwrap();
+        std::fs::write(
+            dir.path().join(".ted").join("permissions.toml"),
+            "[[rules]]\neffect = \"allow\"\ntools = [\"http_request\"]\nhosts = [\"*.invalid\"]\n",
+        )
+        .unwrap();
+
+        let result = HttpRequestTool
+            .execute(
+                "t".to_string(),
+                json!({"url": "http://ted-test.invalid/", "timeout_secs": 2}),
+                &context(&dir),
+            )
+            .await
+            .unwrap();
+        // Past the policy check; the name just doesn't resolve
+        assert!(
+            !result.output_text().contains("denied"),
+            "{}",
+            result.output_text()
+        );
+        assert!(result
+            .output_text()
+            .contains("GET http://ted-test.invalid/ failed"));
+    }
+
+    #[test]
+    fn test_permission_request_describes_request() {
+        let request = HttpRequestTool
+            .permission_request(
+                &json!({"url": "http://localhost:3000/users/1", "method": "delete"}),
+            )
+            .unwrap();
+        assert_eq!(
+            request.action_description,
+            "Request: DELETE http://localhost:3000/users/1"
+        );
+        assert!(request.is_destructive);
+    }
+
+    #[test]
+    fn test_format_body_keeps_valid_prefix_of_truncated_utf8() {
+        let body = "héllo".as_bytes();
+        assert_eq!(format_body(&body[..2], "text/plain", true), "h");
+        assert!(format_body(&[0xff, 0xfe, 0x00], "image/png", false).starts_with("[binary body"));
+    }
+}
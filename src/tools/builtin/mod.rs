@@ -3,7 +3,9 @@
 
 //! Built-in tools for Ted
 
+mod ask_user;
 mod beads;
+mod code_search;
 mod database;
 mod file_changeset;
 mod file_edit;
@@ -11,11 +13,17 @@ mod file_read;
 mod file_write;
 mod glob;
 mod grep;
+mod http_request;
+mod notebook;
 mod plan;
 mod shell;
 mod spawn_agent;
+mod sqlite;
+pub(crate) mod walk;
 
+pub use ask_user::{resolve_answer, AskUserTool};
 pub use beads::{BeadsAddTool, BeadsListTool, BeadsStatusTool};
+pub use code_search::CodeSearchTool;
 pub use database::{DatabaseInitTool, DatabaseMigrateTool, DatabaseQueryTool, DatabaseSeedTool};
 pub use file_changeset::FileChangeSetTool;
 pub use file_edit::FileEditTool;
@@ -23,9 +31,14 @@ pub use file_read::FileReadTool;
 pub use file_write::FileWriteTool;
 pub use glob::GlobTool;
 pub use grep::GrepTool;
+pub use http_request::HttpRequestTool;
+pub use notebook::{
+    find_notebook_cell, render_notebook_content, CellInfo, NotebookEditTool, NotebookReadTool,
+};
 pub use plan::PlanUpdateTool;
 pub use shell::ShellTool;
 pub use spawn_agent::{
     new_progress_tracker, AgentConversationEntry, AgentProgressState, ProgressTracker,
     SpawnAgentTool, ToolCallEntryStatus,
 };
+pub use sqlite::{SqliteExecuteTool, SqliteQueryTool, SqliteSchemaTool};
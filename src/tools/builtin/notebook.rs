@@ -0,0 +1,918 @@
+// SPDX-License-Identifier: AGPL-3.0-or-later
+// Copyright (C) 2025 Blackman Artificial Intelligence Technologies Inc.
+
+//! Jupyter notebook tools
+//!
+//! `.ipynb` files are JSON documents whose outputs (tracebacks, base64
+//! images) dwarf the code. These tools show cells with truncated outputs and
+//! edit whole cells by id, writing the notebook back in Jupyter's own layout
+//! (sorted keys, one-space indent) so it stays valid and diffs stay small.
+
+use async_trait::async_trait;
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+use std::path::{Path, PathBuf};
+
+use crate::error::{Result, TedError};
+use crate::llm::provider::ToolDefinition;
+use crate::tools::{PermissionRequest, SchemaBuilder, Tool, ToolContext, ToolResult};
+
+/// Maximum lines shown per cell output
+const MAX_OUTPUT_LINES: usize = 20;
+/// Maximum characters shown per cell output
+const MAX_OUTPUT_CHARS: usize = 2000;
+
+const CELL_TYPES: &[&str] = &["code", "markdown", "raw"];
+
+/// Tool for reading notebooks cell by cell
+pub struct NotebookReadTool;
+
+#[async_trait]
+impl Tool for NotebookReadTool {
+    fn name(&self) -> &str {
+        "notebook_read"
+    }
+
+    fn definition(&self) -> ToolDefinition {
+        ToolDefinition {
+            name: "notebook_read".to_string(),
+            description: "Read a Jupyter notebook (.ipynb) as a list of cells with their ids, types, source and truncated outputs. Images and other binary outputs are omitted. Use the cell ids with notebook_edit.".to_string(),
+            input_schema: SchemaBuilder::new()
+                .string("path", "Path to the .ipynb file", true)
+                .string("cell_id", "Only show this cell (id, or 0-based index)", false)
+                .boolean("include_outputs", "Show cell outputs (default: true)", false)
+                .build(),
+        }
+    }
+
+    async fn execute(
+        &self,
+        tool_use_id: String,
+        input: Value,
+        context: &ToolContext,
+    ) -> Result<ToolResult> {
+        let path_str = input["path"]
+            .as_str()
+            .ok_or_else(|| TedError::InvalidInput("path is required".to_string()))?;
+        let path = resolve_path(path_str, context);
+        if let Some(message) = context.check_path_not_ignored(&path) {
+            return Ok(ToolResult::error(tool_use_id, message));
+        }
+
+        let (content, notebook) = match load_notebook(&path) {
+            Ok(loaded) => loaded,
+            Err(message) => return Ok(ToolResult::error(tool_use_id, message)),
+        };
+        context.emit_file_read(&path);
+        context.record_file_content(&path, &content);
+
+        let include_outputs = input["include_outputs"].as_bool().unwrap_or(true);
+        match render_notebook(&path, &notebook, include_outputs, input["cell_id"].as_str()) {
+            Ok(rendered) => Ok(ToolResult::success(tool_use_id, rendered)),
+            Err(message) => Ok(ToolResult::error(tool_use_id, message)),
+        }
+    }
+
+    fn permission_request(&self, _input: &Value) -> Option<PermissionRequest> {
+        None
+    }
+
+    fn requires_permission(&self) -> bool {
+        false
+    }
+}
+
+/// Tool for editing, inserting, deleting and moving notebook cells
+pub struct NotebookEditTool;
+
+#[async_trait]
+impl Tool for NotebookEditTool {
+    fn name(&self) -> &str {
+        "notebook_edit"
+    }
+
+    fn definition(&self) -> ToolDefinition {
+        ToolDefinition {
+            name: "notebook_edit".to_string(),
+            description: "Change a Jupyter notebook (.ipynb) one cell at a time. 'edit' replaces a cell's source (and clears its outputs), 'insert' adds a new cell at index (default: end), 'delete' removes a cell and 'move' moves a cell to index. Read the notebook with notebook_read first to get cell ids.".to_string(),
+            input_schema: SchemaBuilder::new()
+                .string("path", "Path to the .ipynb file", true)
+                .string_enum(
+                    "operation",
+                    "What to do with the cell",
+                    &["edit", "insert", "delete", "move"],
+                    true,
+                )
+                .string("cell_id", "Target cell id, or 0-based index (required except for insert)", false)
+                .string("source", "New cell source (required for edit and insert)", false)
+                .string_enum(
+                    "cell_type",
+                    "Cell type for insert (default: code), or to convert a cell on edit",
+                    CELL_TYPES,
+                    false,
+                )
+                .integer("index", "0-based position for insert and move", false)
+                .build(),
+        }
+    }
+
+    async fn execute(
+        &self,
+        tool_use_id: String,
+        input: Value,
+        context: &ToolContext,
+    ) -> Result<ToolResult> {
+        let path_str = input["path"]
+            .as_str()
+            .ok_or_else(|| TedError::InvalidInput("path is required".to_string()))?;
+        let edit = match CellEdit::from_input(&input) {
+            Ok(edit) => edit,
+            Err(message) => return Ok(ToolResult::error(tool_use_id, message)),
+        };
+        let path = resolve_path(path_str, context);
+        if let Some(message) = context.check_path_not_ignored(&path) {
+            return Ok(ToolResult::error(tool_use_id, message));
+        }
+
+        let (content, mut notebook) = match load_notebook(&path) {
+            Ok(loaded) => loaded,
+            Err(message) => return Ok(ToolResult::error(tool_use_id, message)),
+        };
+        if let Some(message) = context.check_read_is_current(&path, path_str, &content) {
+            return Ok(ToolResult::error(tool_use_id, message));
+        }
+
+        let change = match apply_cell_edit(&mut notebook, &edit) {
+            Ok(change) => change,
+            Err(message) => return Ok(ToolResult::error(tool_use_id, message)),
+        };
+
+        let new_content = serialize_notebook(&notebook);
+        if let Err(e) = std::fs::write(&path, &new_content) {
+            return Ok(ToolResult::error(
+                tool_use_id,
+                format!("Failed to write {}: {}", path.display(), e),
+            ));
+        }
+        context.emit_file_edit(&path);
+        context.record_file_content(&path, &new_content);
+
+        Ok(ToolResult::success(
+            tool_use_id,
+            format!(
+                "{} {} cell {} (index {}) in {}",
+                change.operation.past_tense(),
+                change.cell_type,
+                change.cell_id,
+                change.index,
+                path.display()
+            ),
+        ))
+    }
+
+    fn permission_request(&self, input: &Value) -> Option<PermissionRequest> {
+        let path = input["path"].as_str().unwrap_or("unknown");
+        let operation = input["operation"].as_str().unwrap_or("edit");
+        let cell = input["cell_id"].as_str().unwrap_or("new cell");
+        Some(PermissionRequest {
+            tool_name: "notebook_edit".to_string(),
+            action_description: format!("{} notebook cell {} in {}", operation, cell, path),
+            affected_paths: vec![path.to_string()],
+            is_destructive: operation == "delete",
+        })
+    }
+
+    fn requires_permission(&self) -> bool {
+        true
+    }
+}
+
+/// Kind of cell change
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CellOperation {
+    Edit,
+    Insert,
+    Delete,
+    Move,
+}
+
+impl CellOperation {
+    pub fn parse(operation: &str) -> Option<Self> {
+        match operation {
+            "edit" => Some(Self::Edit),
+            "insert" => Some(Self::Insert),
+            "delete" => Some(Self::Delete),
+            "move" => Some(Self::Move),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Edit => "edit",
+            Self::Insert => "insert",
+            Self::Delete => "delete",
+            Self::Move => "move",
+        }
+    }
+
+    fn past_tense(self) -> &'static str {
+        match self {
+            Self::Edit => "Edited",
+            Self::Insert => "Inserted",
+            Self::Delete => "Deleted",
+            Self::Move => "Moved",
+        }
+    }
+}
+
+/// A requested change to one cell
+#[derive(Debug, Clone)]
+struct CellEdit {
+    operation: CellOperation,
+    cell_ref: Option<String>,
+    source: Option<String>,
+    cell_type: Option<String>,
+    index: Option<usize>,
+}
+
+impl CellEdit {
+    fn from_input(input: &Value) -> std::result::Result<Self, String> {
+        let operation = input["operation"].as_str().unwrap_or("edit");
+        let operation = CellOperation::parse(operation).ok_or_else(|| {
+            format!(
+                "Unknown operation '{}'. Use edit, insert, delete or move.",
+                operation
+            )
+        })?;
+        let edit = Self {
+            operation,
+            cell_ref: input["cell_id"].as_str().map(str::to_string),
+            source: input["source"].as_str().map(str::to_string),
+            cell_type: input["cell_type"].as_str().map(str::to_string),
+            index: input["index"].as_u64().map(|i| i as usize),
+        };
+
+        if operation != CellOperation::Insert && edit.cell_ref.is_none() {
+            return Err(format!("cell_id is required for {}", operation.as_str()));
+        }
+        if matches!(operation, CellOperation::Edit | CellOperation::Insert) && edit.source.is_none()
+        {
+            return Err(format!("source is required for {}", operation.as_str()));
+        }
+        if operation == CellOperation::Move && edit.index.is_none() {
+            return Err("index is required for move".to_string());
+        }
+        if let Some(cell_type) = &edit.cell_type {
+            if !CELL_TYPES.contains(&cell_type.as_str()) {
+                return Err(format!(
+                    "Unknown cell_type '{}'. Use code, markdown or raw.",
+                    cell_type
+                ));
+            }
+        }
+        Ok(edit)
+    }
+}
+
+/// The cell a change applied to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CellChange {
+    pub operation: CellOperation,
+    pub cell_id: String,
+    pub cell_type: String,
+    pub index: usize,
+}
+
+/// Summary of a cell for previews, looked up by id or index.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CellInfo {
+    pub cell_id: String,
+    pub cell_type: String,
+    pub index: usize,
+    pub source: String,
+}
+
+/// Look up a cell in the notebook at `path` without modifying it.
+pub fn find_notebook_cell(path: &Path, cell_ref: &str) -> Option<CellInfo> {
+    let (_, notebook) = load_notebook(path).ok()?;
+    let cells = notebook["cells"].as_array()?;
+    let index = find_cell(cells, cell_ref)?;
+    let cell = &cells[index];
+    Some(CellInfo {
+        cell_id: cell_label(cell, index),
+        cell_type: cell["cell_type"].as_str().unwrap_or("code").to_string(),
+        index,
+        source: cell_source(cell),
+    })
+}
+
+fn resolve_path(path_str: &str, context: &ToolContext) -> PathBuf {
+    if Path::new(path_str).is_absolute() {
+        PathBuf::from(path_str)
+    } else {
+        context.working_directory.join(path_str)
+    }
+}
+
+/// Read and sanity-check a notebook, returning the raw text and parsed JSON.
+fn load_notebook(path: &Path) -> std::result::Result<(String, Value), String> {
+    if !path.is_file() {
+        return Err(format!("Notebook not found: {}", path.display()));
+    }
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    let notebook: Value = serde_json::from_str(&content)
+        .map_err(|e| format!("{} is not valid notebook JSON: {}", path.display(), e))?;
+    if !notebook["cells"].is_array() {
+        return Err(format!("{} has no cells array", path.display()));
+    }
+    if notebook["nbformat"].as_u64() != Some(4) {
+        return Err(format!(
+            "{} uses nbformat {}; only nbformat 4 notebooks are supported",
+            path.display(),
+            notebook["nbformat"]
+        ));
+    }
+    Ok((content, notebook))
+}
+
+/// Serialize like Jupyter: sorted keys, one-space indent, trailing newline.
+fn serialize_notebook(notebook: &Value) -> String {
+    let mut buffer = Vec::new();
+    let formatter = serde_json::ser::PrettyFormatter::with_indent(b" ");
+    let mut serializer = serde_json::Serializer::with_formatter(&mut buffer, formatter);
+    notebook
+        .serialize(&mut serializer)
+        .expect("serializing a JSON value cannot fail");
+    let mut text = String::from_utf8(buffer).expect("serde_json writes UTF-8");
+    text.push('\n');
+    text
+}
+
+/// Cell source as a single string (notebooks store either a string or lines).
+fn cell_source(cell: &Value) -> String {
+    match &cell["source"] {
+        Value::String(text) => text.clone(),
+        Value::Array(lines) => lines.iter().filter_map(|l| l.as_str()).collect(),
+        _ => String::new(),
+    }
+}
+
+/// Source in Jupyter's list-of-lines form.
+fn source_lines(text: &str) -> Value {
+    Value::Array(
+        text.split_inclusive('\n')
+            .map(|line| Value::String(line.to_string()))
+            .collect(),
+    )
+}
+
+/// The id shown for a cell; pre-4.5 notebooks have no ids, so use the index.
+fn cell_label(cell: &Value, index: usize) -> String {
+    cell["id"]
+        .as_str()
+        .map(str::to_string)
+        .unwrap_or_else(|| index.to_string())
+}
+
+fn find_cell(cells: &[Value], cell_ref: &str) -> Option<usize> {
+    cells
+        .iter()
+        .position(|cell| cell["id"].as_str() == Some(cell_ref))
+        .or_else(|| {
+            cell_ref
+                .trim_start_matches('#')
+                .parse::<usize>()
+                .ok()
+                .filter(|i| *i < cells.len())
+        })
+}
+
+fn supports_cell_ids(notebook: &Value) -> bool {
+    notebook["nbformat_minor"].as_u64().unwrap_or(0) >= 5
+}
+
+fn new_cell_id(cells: &[Value]) -> String {
+    loop {
+        let id = uuid::Uuid::new_v4().simple().to_string()[..8].to_string();
+        if !cells.iter().any(|cell| cell["id"].as_str() == Some(&id)) {
+            return id;
+        }
+    }
+}
+
+/// Build a cell with the fields nbformat requires for its type.
+fn new_cell(cell_type: &str, source: &str, id: Option<String>) -> Value {
+    let mut cell = Map::new();
+    cell.insert("cell_type".to_string(), json!(cell_type));
+    cell.insert("metadata".to_string(), json!({}));
+    cell.insert("source".to_string(), source_lines(source));
+    if cell_type == "code" {
+        cell.insert("execution_count".to_string(), Value::Null);
+        cell.insert("outputs".to_string(), json!([]));
+    }
+    if let Some(id) = id {
+        cell.insert("id".to_string(), json!(id));
+    }
+    Value::Object(cell)
+}
+
+fn apply_cell_edit(
+    notebook: &mut Value,
+    edit: &CellEdit,
+) -> std::result::Result<CellChange, String> {
+    let with_ids = supports_cell_ids(notebook);
+    let cells = notebook["cells"]
+        .as_array_mut()
+        .ok_or_else(|| "Notebook has no cells array".to_string())?;
+
+    let target = match &edit.cell_ref {
+        Some(cell_ref) if edit.operation != CellOperation::Insert => {
+            Some(find_cell(cells, cell_ref).ok_or_else(|| {
+                let ids: Vec<String> = cells
+                    .iter()
+                    .enumerate()
+                    .map(|(i, cell)| cell_label(cell, i))
+                    .collect();
+                format!(
+                    "No cell with id '{}'. Available cells: {}",
+                    cell_ref,
+                    ids.join(", ")
+                )
+            })?)
+        }
+        _ => None,
+    };
+
+    let change = |cells: &[Value], index: usize| CellChange {
+        operation: edit.operation,
+        cell_id: cell_label(&cells[index], index),
+        cell_type: cells[index]["cell_type"]
+            .as_str()
+            .unwrap_or("code")
+            .to_string(),
+        index,
+    };
+
+    match edit.operation {
+        CellOperation::Edit => {
+            let index = target.expect("edit requires a cell");
+            let source = edit.source.as_deref().unwrap_or_default();
+            let cell = &cells[index];
+            let cell_type = edit
+                .cell_type
+                .clone()
+                .or_else(|| cell["cell_type"].as_str().map(str::to_string))
+                .unwrap_or_else(|| "code".to_string());
+            let mut replacement =
+                new_cell(&cell_type, source, cell["id"].as_str().map(str::to_string));
+            // Keep metadata (tags, collapsed state) and markdown attachments
+            replacement["metadata"] = cell["metadata"].clone();
+            if cell_type != "code" {
+                if let Some(attachments) = cell.get("attachments") {
+                    replacement["attachments"] = attachments.clone();
+                }
+            }
+            cells[index] = replacement;
+            Ok(change(cells, index))
+        }
+        CellOperation::Insert => {
+            let index = edit.index.unwrap_or(cells.len()).min(cells.len());
+            let cell_type = edit.cell_type.as_deref().unwrap_or("code");
+            let id = with_ids.then(|| new_cell_id(cells));
+            let source = edit.source.as_deref().unwrap_or_default();
+            cells.insert(index, new_cell(cell_type, source, id));
+            Ok(change(cells, index))
+        }
+        CellOperation::Delete => {
+            let index = target.expect("delete requires a cell");
+            let removed = change(cells, index);
+            cells.remove(index);
+            Ok(removed)
+        }
+        CellOperation::Move => {
+            let from = target.expect("move requires a cell");
+            let to = edit.index.unwrap_or(from).min(cells.len() - 1);
+            let cell = cells.remove(from);
+            cells.insert(to, cell);
+            Ok(change(cells, to))
+        }
+    }
+}
+
+/// Render a notebook for the model.
+fn render_notebook(
+    path: &Path,
+    notebook: &Value,
+    include_outputs: bool,
+    only_cell: Option<&str>,
+) -> std::result::Result<String, String> {
+    let cells = notebook["cells"].as_array().cloned().unwrap_or_default();
+    let language = notebook["metadata"]["kernelspec"]["language"]
+        .as_str()
+        .or_else(|| notebook["metadata"]["language_info"]["name"].as_str())
+        .unwrap_or("unknown");
+
+    let selected: Vec<usize> =
+        match only_cell {
+            Some(cell_ref) => vec![find_cell(&cells, cell_ref)
+                .ok_or_else(|| format!("No cell with id '{}'", cell_ref))?],
+            None => (0..cells.len()).collect(),
+        };
+
+    let mut out = format!(
+        "Notebook: {} ({} cells, language: {})\n",
+        path.display(),
+        cells.len(),
+        language
+    );
+    for index in selected {
+        let cell = &cells[index];
+        let cell_type = cell["cell_type"].as_str().unwrap_or("code");
+        out.push_str(&format!(
+            "\n── cell {} [{}] id={}",
+            index,
+            cell_type,
+            cell_label(cell, index)
+        ));
+        if let Some(count) = cell["execution_count"].as_u64() {
+            out.push_str(&format!(" (executed {})", count));
+        }
+        out.push('\n');
+        let source = cell_source(cell);
+        out.push_str(&source);
+        if !source.ends_with('\n') {
+            out.push('\n');
+        }
+
+        if include_outputs {
+            let outputs = cell["outputs"].as_array().cloned().unwrap_or_default();
+            if !outputs.is_empty() {
+                out.push_str("── outputs\n");
+                for output in &outputs {
+                    out.push_str(&render_output(output));
+                }
+            }
+        }
+    }
+    Ok(out)
+}
+
+fn render_output(output: &Value) -> String {
+    let text = match output["output_type"].as_str() {
+        Some("stream") => {
+            let name = output["name"].as_str().unwrap_or("stdout");
+            format!("[{}]\n{}", name, multiline_text(&output["text"]))
+        }
+        Some("execute_result") | Some("display_data") => {
+            let data = output["data"].as_object().cloned().unwrap_or_default();
+            let mut parts = Vec::new();
+            if let Some(text) = data.get("text/plain") {
+                parts.push(multiline_text(text));
+            }
+            for mime in data.keys().filter(|m| *m != "text/plain") {
+                parts.push(format!("[{} output omitted]", mime));
+            }
+            parts.join("\n")
+        }
+        Some("error") => {
+            let traceback: Vec<String> = output["traceback"]
+                .as_array()
+                .map(|lines| {
+                    lines
+                        .iter()
+                        .filter_map(|l| l.as_str())
+                        .map(strip_ansi)
+                        .collect()
+                })
+                .unwrap_or_default();
+            format!(
+                "[error] {}: {}\n{}",
+                output["ename"].as_str().unwrap_or("Error"),
+                output["evalue"].as_str().unwrap_or(""),
+                traceback.join("\n")
+            )
+        }
+        _ => "[unknown output]".to_string(),
+    };
+    let mut text = truncate_output(&text);
+    if !text.ends_with('\n') {
+        text.push('\n');
+    }
+    text
+}
+
+fn multiline_text(value: &Value) -> String {
+    match value {
+        Value::String(text) => text.clone(),
+        Value::Array(lines) => lines.iter().filter_map(|l| l.as_str()).collect(),
+        _ => String::new(),
+    }
+}
+
+fn truncate_output(text: &str) -> String {
+    let lines: Vec<&str> = text.lines().collect();
+    let mut kept = lines[..lines.len().min(MAX_OUTPUT_LINES)].join("\n");
+    if kept.chars().count() > MAX_OUTPUT_CHARS {
+        kept = kept.chars().take(MAX_OUTPUT_CHARS).collect();
+    }
+    if kept.len() < text.trim_end().len() {
+        kept.push_str(&format!(
+            "\n… (output truncated, {} lines total)",
+            lines.len()
+        ));
+    }
+    kept
+}
+
+/// Remove ANSI colour codes from tracebacks.
+fn strip_ansi(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c == '\u{1b}' && chars.peek() == Some(&'[') {
+            chars.next();
+            for c in chars.by_ref() {
+                if c.is_ascii_alphabetic() {
+                    break;
+                }
+            }
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+/// Render a notebook for `file_read`, if `content` parses as one.
+pub fn render_notebook_content(path: &Path, content: &str) -> Option<String> {
+    let notebook: Value = serde_json::from_str(content).ok()?;
+    if !notebook["cells"].is_array() {
+        return None;
+    }
+    render_notebook(path, &notebook, true, None).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn sample_notebook() -> Value {
+        json!({
+            "cells": [
+                {
+                    "cell_type": "markdown",
+                    "id": "intro",
+                    "metadata": {},
+                    "source": ["# Title\n", "Some text"]
+                },
+                {
+                    "cell_type": "code",
+                    "execution_count": 3,
+                    "id": "load",
+                    "metadata": {"tags": ["setup"]},
+                    "outputs": [
+                        {"name": "stdout", "output_type": "stream", "text": ["loaded\n"]},
+                        {
+                            "data": {"image/png": "iVBORw0KGgo...", "text/plain": ["<Figure>"]},
+                            "metadata": {},
+                            "output_type": "display_data"
+                        },
+                        {
+                            "ename": "ValueError",
+                            "evalue": "bad",
+                            "output_type": "error",
+                            "traceback": ["\u{1b}[0;31mValueError\u{1b}[0m: bad"]
+                        }
+                    ],
+                    "source": "import pandas as pd\ndf = pd.read_csv('x.csv')"
+                }
+            ],
+            "metadata": {"kernelspec": {"language": "python", "name": "python3"}},
+            "nbformat": 4,
+            "nbformat_minor": 5
+        })
+    }
+
+    fn write_notebook(dir: &TempDir) -> PathBuf {
+        let path = dir.path().join("analysis.ipynb");
+        std::fs::write(&path, serialize_notebook(&sample_notebook())).unwrap();
+        path
+    }
+
+    fn context(dir: &TempDir) -> ToolContext {
+        ToolContext::new(
+            dir.path().to_path_buf(),
+            Some(dir.path().to_path_buf()),
+            uuid::Uuid::new_v4(),
+            true,
+        )
+    }
+
+    fn edit(operation: CellOperation) -> CellEdit {
+        CellEdit {
+            operation,
+            cell_ref: None,
+            source: None,
+            cell_type: None,
+            index: None,
+        }
+    }
+
+    #[test]
+    fn test_render_shows_cells_and_omits_binary_outputs() {
+        let rendered =
+            render_notebook(Path::new("a.ipynb"), &sample_notebook(), true, None).unwrap();
+        assert!(rendered.contains("2 cells, language: python"));
+        assert!(rendered.contains("── cell 1 [code] id=load (executed 3)"));
+        assert!(rendered.contains("df = pd.read_csv('x.csv')"));
+        assert!(rendered.contains("[stdout]\nloaded"));
+        assert!(rendered.contains("[image/png output omitted]"));
+        assert!(!rendered.contains("iVBORw0KGgo"));
+        assert!(rendered.contains("[error] ValueError: bad\nValueError: bad"));
+
+        let without = render_notebook(
+            Path::new("a.ipynb"),
+            &sample_notebook(),
+            false,
+            Some("intro"),
+        )
+        .unwrap();
+        assert!(without.contains("# Title"));
+        assert!(!without.contains("load"));
+    }
+
+    #[test]
+    fn test_truncate_long_outputs() {
+        let text: String = (0..100).map(|i| format!("line {}\n", i)).collect();
+        let truncated = truncate_output(&text);
+        assert!(truncated.contains("line 19"));
+        assert!(!truncated.contains("line 20"));
+        assert!(truncated.contains("100 lines total"));
+    }
+
+    #[test]
+    fn test_edit_clears_outputs_and_keeps_metadata() {
+        let mut notebook = sample_notebook();
+        let change = apply_cell_edit(
+            &mut notebook,
+            &CellEdit {
+                cell_ref: Some("load".to_string()),
+                source: Some("x = 1\ny = 2".to_string()),
+                ..edit(CellOperation::Edit)
+            },
+        )
+        .unwrap();
+
+        assert_eq!(change.index, 1);
+        let cell = &notebook["cells"][1];
+        assert_eq!(cell["source"], json!(["x = 1\n", "y = 2"]));
+        assert_eq!(cell["outputs"], json!([]));
+        assert_eq!(cell["execution_count"], Value::Null);
+        assert_eq!(cell["metadata"]["tags"], json!(["setup"]));
+        assert_eq!(cell["id"], "load");
+    }
+
+    #[test]
+    fn test_insert_move_and_delete() {
+        let mut notebook = sample_notebook();
+        let inserted = apply_cell_edit(
+            &mut notebook,
+            &CellEdit {
+                source: Some("print('hi')".to_string()),
+                index: Some(1),
+                ..edit(CellOperation::Insert)
+            },
+        )
+        .unwrap();
+        assert_eq!(inserted.index, 1);
+        assert_eq!(inserted.cell_type, "code");
+        assert_eq!(inserted.cell_id.len(), 8);
+        assert_eq!(notebook["cells"][1]["outputs"], json!([]));
+
+        let moved = apply_cell_edit(
+            &mut notebook,
+            &CellEdit {
+                cell_ref: Some("intro".to_string()),
+                index: Some(99),
+                ..edit(CellOperation::Move)
+            },
+        )
+        .unwrap();
+        assert_eq!(moved.index, 2);
+        assert_eq!(notebook["cells"][2]["id"], "intro");
+
+        apply_cell_edit(
+            &mut notebook,
+            &CellEdit {
+                cell_ref: Some("load".to_string()),
+                ..edit(CellOperation::Delete)
+            },
+        )
+        .unwrap();
+        let ids: Vec<&str> = notebook["cells"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter_map(|c| c["id"].as_str())
+            .collect();
+        assert_eq!(ids, vec![inserted.cell_id.as_str(), "intro"]);
+    }
+
+    #[test]
+    fn test_unknown_cell_lists_available_ids() {
+        let mut notebook = sample_notebook();
+        let err = apply_cell_edit(
+            &mut notebook,
+            &CellEdit {
+                cell_ref: Some("nope".to_string()),
+                ..edit(CellOperation::Delete)
+            },
+        )
+        .unwrap_err();
+        assert!(err.contains("Available cells: intro, load"));
+    }
+
+    #[test]
+    fn test_input_requirements() {
+        assert!(
+            CellEdit::from_input(&json!({"operation": "edit", "cell_id": "a"}))
+                .unwrap_err()
+                .contains("source is required")
+        );
+        assert!(
+            CellEdit::from_input(&json!({"operation": "move", "cell_id": "a"}))
+                .unwrap_err()
+                .contains("index is required")
+        );
+        assert!(CellEdit::from_input(&json!({"operation": "insert", "source": "x"})).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_edit_tool_round_trip_stays_valid() {
+        let dir = TempDir::new().unwrap();
+        let path = write_notebook(&dir);
+        let ctx = context(&dir);
+
+        let result = NotebookEditTool
+            .execute(
+                "t".to_string(),
+                json!({
+                    "path": "analysis.ipynb",
+                    "operation": "insert",
+                    "cell_type": "markdown",
+                    "source": "## Results"
+                }),
+                &ctx,
+            )
+            .await
+            .unwrap();
+        assert!(!result.is_error(), "{}", result.output_text());
+        assert!(result.output_text().contains("Inserted markdown cell"));
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert!(content.starts_with("{\n \"cells\": ["));
+        assert!(content.ends_with("}\n"));
+        let notebook: Value = serde_json::from_str(&content).unwrap();
+        assert_eq!(notebook["nbformat"], 4);
+        assert_eq!(notebook["cells"][2]["cell_type"], "markdown");
+        assert!(notebook["cells"][2].get("outputs").is_none());
+
+        let info = find_notebook_cell(&path, "load").unwrap();
+        assert_eq!(info.index, 1);
+        assert!(info.source.starts_with("import pandas"));
+
+        let read = NotebookReadTool
+            .execute(
+                "t".to_string(),
+                json!({"path": "analysis.ipynb", "include_outputs": false}),
+                &ctx,
+            )
+            .await
+            .unwrap();
+        assert!(read.output_text().contains("## Results"));
+    }
+
+    #[tokio::test]
+    async fn test_rejects_non_notebooks() {
+        let dir = TempDir::new().unwrap();
+        std::fs::write(
+            dir.path().join("bad.ipynb"),
+            "{\"nbformat\": 3, \"cells\": []}",
+        )
+        .unwrap();
+
+        let result = NotebookReadTool
+            .execute(
+                "t".to_string(),
+                json!({"path": "bad.ipynb"}),
+                &context(&dir),
+            )
+            .await
+            .unwrap();
+        assert!(result.is_error());
+        assert!(result.output_text().contains("only nbformat 4"));
+    }
+}
@@ -159,6 +159,24 @@ impl ShellTool {
             .any(|indicator| lower.contains(indicator))
     }
 
+    /// Check if every command in a pipeline or list only reads.
+    ///
+    /// This is an allowlist used by plan mode: anything it cannot classify,
+    /// including output redirection and command substitution, counts as
+    /// mutating.
+    pub(crate) fn is_read_only_command(command: &str) -> bool {
+        let mut command = command.to_string();
+        for redirect in ["2>&1", "&>/dev/null", "2>/dev/null", ">/dev/null"] {
+            command = command.replace(redirect, " ");
+        }
+        match split_command_list(&command) {
+            Some(segments) => {
+                !segments.is_empty() && segments.iter().all(|words| is_read_only_invocation(words))
+            }
+            None => false,
+        }
+    }
+
     /// Expand a shell path token to an absolute path when possible.
     fn expand_path_token(token: &str) -> Option<PathBuf> {
         if token == "~" || token.starts_with("~/") {
@@ -244,6 +262,442 @@ impl ShellTool {
 
         false
     }
+
+    /// Find an existing path named in the command that `.tedignore` hides.
+    ///
+    /// This is best effort: it catches direct references such as
+    /// `cat secrets/prod.env` but not paths assembled at runtime.
+    fn referenced_ignored_path(command: &str, context: &ToolContext) -> Option<PathBuf> {
+        if context.tedignore().is_empty() {
+            return None;
+        }
+
+        command
+            .split(|ch: char| {
+                ch.is_whitespace() || [';', '|', '&', '(', ')', '<', '>'].contains(&ch)
+            })
+            .map(|token| token.trim_matches('"').trim_matches('\'').trim_matches('`'))
+            .map(|token| match token.split_once('=') {
+                Some((_, value)) if token.starts_with('-') => value,
+                _ => token,
+            })
+            .filter(|token| !token.is_empty() && !token.starts_with('-'))
+            .filter_map(|token| {
+                if token.starts_with('~') || token.starts_with('/') {
+                    Self::expand_path_token(token)
+                } else {
+                    Some(context.working_directory.join(token))
+                }
+            })
+            .find(|path| path.exists() && context.is_path_ignored(path))
+    }
+}
+
+/// Programs that only read, subject to the per-program checks in
+/// [`is_read_only_invocation`]
+const READ_ONLY_PROGRAMS: &[&str] = &[
+    "ls",
+    "cat",
+    "head",
+    "tail",
+    "wc",
+    "grep",
+    "egrep",
+    "fgrep",
+    "rg",
+    "ag",
+    "find",
+    "fd",
+    "tree",
+    "pwd",
+    "echo",
+    "printf",
+    "which",
+    "whereis",
+    "type",
+    "file",
+    "stat",
+    "du",
+    "df",
+    "sort",
+    "uniq",
+    "cut",
+    "tr",
+    "diff",
+    "cmp",
+    "comm",
+    "basename",
+    "dirname",
+    "realpath",
+    "readlink",
+    "printenv",
+    "date",
+    "uname",
+    "whoami",
+    "id",
+    "hostname",
+    "nl",
+    "column",
+    "jq",
+    "awk",
+    "sed",
+    "true",
+    "false",
+    "test",
+    "xxd",
+    "od",
+    "hexdump",
+    "strings",
+    "md5sum",
+    "sha1sum",
+    "sha256sum",
+    "ps",
+    "tokei",
+    "cloc",
+    "git",
+    "cargo",
+    "npm",
+];
+
+/// Environment variables that may prefix a read-only command
+const READ_ONLY_ENV_VARS: &[&str] = &[
+    "RUST_LOG",
+    "RUST_BACKTRACE",
+    "NO_COLOR",
+    "CLICOLOR",
+    "TERM",
+    "LANG",
+    "LC_ALL",
+    "TZ",
+    "COLUMNS",
+];
+
+/// Split a command line into the words of each command in its pipelines and
+/// lists. Returns `None` for constructs that can't be classified safely:
+/// output redirection, command substitution, background jobs or unbalanced
+/// quotes.
+fn split_command_list(command: &str) -> Option<Vec<Vec<String>>> {
+    let mut segments = Vec::new();
+    let mut words: Vec<String> = Vec::new();
+    let mut word = String::new();
+    let mut in_word = false;
+    let mut quote: Option<char> = None;
+    let mut chars = command.chars().peekable();
+
+    let finish_word = |words: &mut Vec<String>, word: &mut String, in_word: &mut bool| {
+        if *in_word {
+            words.push(std::mem::take(word));
+            *in_word = false;
+        }
+    };
+
+    while let Some(c) = chars.next() {
+        match quote {
+            Some('\'') => {
+                if c == '\'' {
+                    quote = None;
+                } else {
+                    word.push(c);
+                }
+                continue;
+            }
+            Some(_) => match c {
+                '"' => quote = None,
+                '`' => return None,
+                '$' if chars.peek() == Some(&'(') => return None,
+                '\\' => {
+                    if let Some(next) = chars.next() {
+                        word.push(next);
+                    }
+                }
+                _ => word.push(c),
+            },
+            None => match c {
+                '\'' | '"' => {
+                    quote = Some(c);
+                    in_word = true;
+                }
+                '\\' => {
+                    if let Some(next) = chars.next() {
+                        word.push(next);
+                        in_word = true;
+                    }
+                }
+                '`' | '>' => return None,
+                '$' | '<' if chars.peek() == Some(&'(') => return None,
+                '&' => {
+                    if chars.next() != Some('&') {
+                        return None;
+                    }
+                    finish_word(&mut words, &mut word, &mut in_word);
+                    segments.push(std::mem::take(&mut words));
+                }
+                '|' | ';' | '\n' => {
+                    if c == '|' && chars.peek() == Some(&'|') {
+                        chars.next();
+                    }
+                    finish_word(&mut words, &mut word, &mut in_word);
+                    segments.push(std::mem::take(&mut words));
+                }
+                c if c.is_whitespace() => finish_word(&mut words, &mut word, &mut in_word),
+                _ => {
+                    word.push(c);
+                    in_word = true;
+                }
+            },
+        }
+    }
+    if quote.is_some() {
+        return None;
+    }
+    finish_word(&mut words, &mut word, &mut in_word);
+    segments.push(words);
+    segments.retain(|words| !words.is_empty());
+    Some(segments)
+}
+
+/// Whether a single command (already split into words) only reads
+fn is_read_only_invocation(words: &[String]) -> bool {
+    // Leading VAR=value assignments only affect this command, but many
+    // programs run commands named in their environment (GIT_EXTERNAL_DIFF,
+    // PAGER, LD_PRELOAD), so only known-harmless variables are allowed.
+    let mut words: Vec<&str> = words.iter().map(String::as_str).collect();
+    while let Some((name, _)) = words.first().and_then(|w| w.split_once('=')) {
+        if name.is_empty() || name.starts_with('-') {
+            break;
+        }
+        if !READ_ONLY_ENV_VARS.contains(&name) {
+            return false;
+        }
+        words.remove(0);
+    }
+    let Some((&program, args)) = words.split_first() else {
+        return true;
+    };
+    let program = program.rsplit('/').next().unwrap_or(program);
+    if !READ_ONLY_PROGRAMS.contains(&program) {
+        return false;
+    }
+    let has = |flags: &[&str]| args.iter().any(|a| flags.contains(a));
+    match program {
+        "find" => !has(&[
+            "-delete", "-exec", "-execdir", "-ok", "-okdir", "-fprint", "-fprint0", "-fprintf",
+            "-fls",
+        ]),
+        "fd" => !has(&["-x", "--exec", "-X", "--exec-batch"]),
+        "sed" => is_read_only_sed(args),
+        "awk" => !args
+            .iter()
+            .any(|a| a.contains("system(") || a.contains('>') || a.replace("||", "").contains('|')),
+        "rg" => !args
+            .iter()
+            .any(|a| *a == "--pre" || a.starts_with("--pre=")),
+        "tree" => !args.iter().any(|a| a.starts_with("-o")),
+        "xxd" => {
+            // `xxd infile outfile` writes the dump to outfile.
+            const VALUE_FLAGS: &[&str] = &[
+                "-c",
+                "-cols",
+                "-g",
+                "-groupsize",
+                "-l",
+                "-len",
+                "-o",
+                "-offset",
+                "-s",
+                "-seek",
+                "-n",
+                "-name",
+            ];
+            let mut positional = 0;
+            let mut iter = args.iter();
+            while let Some(arg) = iter.next() {
+                if VALUE_FLAGS.contains(arg) {
+                    iter.next();
+                } else if *arg == "-" || !arg.starts_with('-') {
+                    positional += 1;
+                }
+            }
+            positional < 2
+        }
+        "sort" => !args
+            .iter()
+            .any(|a| a.starts_with("-o") || a.starts_with("--output")),
+        "git" => is_read_only_git(args),
+        "cargo" => matches!(
+            args.first().copied(),
+            Some(
+                "tree"
+                    | "metadata"
+                    | "search"
+                    | "locate-project"
+                    | "pkgid"
+                    | "verify-project"
+                    | "help"
+                    | "--version"
+                    | "-V"
+                    | "--list"
+            )
+        ),
+        "npm" => matches!(
+            args.first().copied(),
+            Some(
+                "ls" | "list"
+                    | "view"
+                    | "info"
+                    | "outdated"
+                    | "explain"
+                    | "why"
+                    | "help"
+                    | "--version"
+                    | "-v"
+            )
+        ),
+        _ => true,
+    }
+}
+
+fn is_read_only_git(args: &[&str]) -> bool {
+    let args: Vec<&str> = args
+        .iter()
+        .copied()
+        .skip_while(|a| matches!(*a, "--no-pager" | "-P" | "--no-optional-locks"))
+        .collect();
+    let Some((&subcommand, rest)) = args.split_first() else {
+        return true;
+    };
+    let has = |flags: &[&str]| rest.iter().any(|a| flags.contains(a));
+    match subcommand {
+        "status" | "log" | "diff" | "show" | "blame" | "ls-files" | "ls-tree" | "rev-parse"
+        | "describe" | "shortlog" | "grep" | "cat-file" | "rev-list" | "merge-base"
+        | "show-ref" | "whatchanged" | "--version" | "help" => !rest.iter().any(|a| {
+            a.starts_with("--output")
+                || (subcommand == "grep"
+                    && (a.starts_with("-O") || a.starts_with("--open-files-in-pager")))
+        }),
+        "branch" => rest.iter().all(|a| {
+            matches!(
+                *a,
+                "-a" | "-r"
+                    | "-v"
+                    | "-vv"
+                    | "--all"
+                    | "--remotes"
+                    | "--list"
+                    | "-l"
+                    | "--verbose"
+                    | "--show-current"
+                    | "--merged"
+                    | "--no-merged"
+            )
+        }),
+        "tag" => rest.is_empty() || has(&["-l", "--list"]),
+        "remote" => matches!(
+            rest.first().copied(),
+            None | Some("-v" | "show" | "get-url")
+        ),
+        "stash" => matches!(rest.first().copied(), Some("list" | "show")),
+        "config" => has(&["--get", "--get-all", "--get-regexp", "--list", "-l"]),
+        _ => false,
+    }
+}
+
+/// Whether a `sed` invocation neither edits in place nor runs a script that
+/// writes files (`w`/`W`, the `s///w` flag) or executes commands (`e`).
+/// Scripts read from a file can't be inspected and count as mutating.
+fn is_read_only_sed(args: &[&str]) -> bool {
+    let mut scripts = Vec::new();
+    let mut positional = Vec::new();
+    let mut iter = args.iter();
+    while let Some(&arg) = iter.next() {
+        if let Some(long) = arg.strip_prefix("--") {
+            if long.starts_with("in-place") || long.starts_with("file") {
+                return false;
+            }
+            if let Some(script) = long.strip_prefix("expression=") {
+                scripts.push(script);
+            } else if long == "expression" {
+                scripts.extend(iter.next().copied());
+            }
+        } else if let Some(flags) = arg.strip_prefix('-').filter(|f| !f.is_empty()) {
+            for (i, flag) in flags.char_indices() {
+                match flag {
+                    'i' | 'f' => return false,
+                    'e' => {
+                        let rest = &flags[i + 1..];
+                        if rest.is_empty() {
+                            scripts.extend(iter.next().copied());
+                        } else {
+                            scripts.push(rest);
+                        }
+                        break;
+                    }
+                    'l' => {
+                        if flags[i + 1..].is_empty() {
+                            iter.next();
+                        }
+                        break;
+                    }
+                    _ => {}
+                }
+            }
+        } else {
+            positional.push(arg);
+        }
+    }
+    if scripts.is_empty() {
+        scripts.extend(positional.first().copied());
+    }
+    !scripts.iter().any(|script| sed_script_writes(script))
+}
+
+/// Scan a sed script for commands that write files or run shell commands.
+fn sed_script_writes(script: &str) -> bool {
+    let mut chars = script.chars().peekable();
+    // Skip to the closing delimiter, honouring backslash escapes.
+    let skip_field = |chars: &mut std::iter::Peekable<std::str::Chars>, delim: char| {
+        while let Some(c) = chars.next() {
+            if c == '\\' {
+                chars.next();
+            } else if c == delim {
+                break;
+            }
+        }
+    };
+    let skip_line = |chars: &mut std::iter::Peekable<std::str::Chars>| {
+        for c in chars.by_ref() {
+            if c == '\n' {
+                break;
+            }
+        }
+    };
+    while let Some(c) = chars.next() {
+        match c {
+            'w' | 'W' | 'e' => return true,
+            '/' => skip_field(&mut chars, '/'),
+            's' | 'y' => {
+                let Some(delim) = chars.next() else {
+                    return false;
+                };
+                skip_field(&mut chars, delim);
+                skip_field(&mut chars, delim);
+                if c == 's' {
+                    while let Some(&flag) = chars.peek() {
+                        if matches!(flag, 'w' | 'e') {
+                            return true;
+                        }
+                        if matches!(flag, ';' | '\n' | '}') {
+                            break;
+                        }
+                        chars.next();
+                    }
+                }
+            }
+            'a' | 'i' | 'c' | 'r' | 'R' | 'b' | 't' | 'T' | ':' => skip_line(&mut chars),
+            _ => {}
+        }
+    }
+    false
 }
 
 impl Default for ShellTool {
@@ -314,6 +768,12 @@ impl Tool for ShellTool {
             ));
         }
 
+        if let Some(path) = Self::referenced_ignored_path(command, context) {
+            if let Some(message) = context.check_path_not_ignored(&path) {
+                return Ok(ToolResult::error(tool_use_id, message));
+            }
+        }
+
         // Note: We previously blocked echo commands that looked like user communication,
         // but this caused more issues than it solved. The model sometimes uses echo
         // for status messages, and blocking them confused the model's flow.
@@ -606,6 +1066,74 @@ mod tests {
         assert!(!tool.violates_workspace_boundary(&command, &context));
     }
 
+    #[test]
+    fn test_is_read_only_command_allows_exploration() {
+        for command in [
+            "ls -la src",
+            "cat Cargo.toml | head -20",
+            "grep -rn \"foo|bar\" src 2>/dev/null",
+            "find . -name '*.rs' | wc -l",
+            "git status && git log --oneline -5",
+            "git --no-pager diff HEAD~1",
+            "git branch -a",
+            "RUST_LOG=debug cargo tree",
+            "sed -n '1,20p' src/main.rs",
+            "sed -n '/error/p' src/main.rs",
+            "sed 's/foo/bar/g' src/main.rs",
+            "awk '$1 == 1 || $2 == 2' data.txt",
+            "rg --pre-glob '*.gz' foo",
+            "xxd -l 64 target.bin",
+            "wc -l < src/main.rs",
+        ] {
+            assert!(ShellTool::is_read_only_command(command), "{}", command);
+        }
+    }
+
+    #[test]
+    fn test_is_read_only_command_rejects_mutation() {
+        for command in [
+            "rm -rf build",
+            "echo hi > out.txt",
+            "cat a >> b",
+            "ls; touch x",
+            "sed -i 's/a/b/' file",
+            "find . -name '*.tmp' -delete",
+            "git commit -m wip",
+            "git branch feature",
+            "git stash",
+            "cargo build",
+            "npm install",
+            "echo $(rm x)",
+            "echo `rm x`",
+            "sleep 10 &",
+            "python script.py",
+            "awk '{ system(\"rm x\") }' f",
+            "awk '{ print | \"sh\" }' f",
+            "sed -n 'w out.txt' f",
+            "sed 's/a/b/w out.txt' f",
+            "sed -e '1e rm x' f",
+            "sed -ni 'p' f",
+            "rg --pre ./run.sh foo",
+            "rg --pre=./run.sh foo",
+            "tree -o out.txt",
+            "xxd in.bin out.hex",
+            "git diff --output=patch.diff",
+            "git log --output=log.txt",
+            "git grep -O foo",
+            "git grep --open-files-in-pager=vim foo",
+            "GIT_EXTERNAL_DIFF='rm -rf src;' git diff",
+            "GIT_CONFIG_COUNT=1 GIT_CONFIG_KEY_0=core.fsmonitor GIT_CONFIG_VALUE_0='rm x' git status",
+            "PAGER='rm x' git log",
+            "GIT_PAGER=./evil.sh git show",
+            "LD_PRELOAD=./evil.so ls",
+            "RUST_LOG=debug LD_PRELOAD=./evil.so ls",
+            "echo 'unterminated",
+            "",
+        ] {
+            assert!(!ShellTool::is_read_only_command(command), "{}", command);
+        }
+    }
+
     #[test]
     fn test_is_mutating_command_find_delete() {
         assert!(ShellTool::is_mutating_command(
@@ -899,4 +1427,17 @@ mod tests {
         assert!(output.contains("line2"));
         assert!(output.contains("line3"));
     }
+
+    #[test]
+    fn test_referenced_ignored_path() {
+        let temp_dir = TempDir::new().unwrap();
+        std::fs::write(temp_dir.path().join(".tedignore"), "secrets/\n").unwrap();
+        std::fs::create_dir_all(temp_dir.path().join("secrets")).unwrap();
+        std::fs::write(temp_dir.path().join("secrets/prod.env"), "X=1").unwrap();
+        let context = create_test_context(&temp_dir);
+
+        assert!(ShellTool::referenced_ignored_path("cat secrets/prod.env", &context).is_some());
+        assert!(ShellTool::referenced_ignored_path("ls -la 'secrets'", &context).is_some());
+        assert!(ShellTool::referenced_ignored_path("cargo test", &context).is_none());
+    }
 }
@@ -0,0 +1,860 @@
+// SPDX-License-Identifier: AGPL-3.0-or-later
+// Copyright (C) 2025 Blackman Artificial Intelligence Technologies Inc.
+
+//! Native SQLite tools
+//!
+//! Inspect, query and modify SQLite databases in the workspace with the
+//! embedded driver, without Node, Prisma or the `sqlite3` CLI. When no SQLite
+//! file exists and the project uses Prisma with another provider, queries
+//! fall back to the Prisma tooling in [`super::database`].
+
+use async_trait::async_trait;
+use rusqlite::types::ValueRef;
+use rusqlite::{Connection, OpenFlags};
+use serde_json::{json, Value};
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+
+use crate::error::{Result, TedError};
+use crate::llm::provider::ToolDefinition;
+use crate::tools::{PermissionRequest, SchemaBuilder, Tool, ToolContext, ToolResult};
+
+use super::database::DatabaseQueryTool as PrismaQueryTool;
+
+/// File extensions recognised as SQLite databases during discovery
+const SQLITE_EXTENSIONS: &[&str] = &["sqlite", "sqlite3", "db", "db3"];
+
+/// Directories never searched for databases
+const SKIPPED_DIRS: &[&str] = &[".git", "node_modules", "target", "vendor", ".venv", "venv"];
+
+/// How deep below the working directory to look for databases
+const DISCOVERY_DEPTH: usize = 4;
+
+const DEFAULT_ROW_LIMIT: usize = 100;
+const MAX_ROW_LIMIT: usize = 1000;
+const MAX_CELL_WIDTH: usize = 60;
+
+/// Which database a call operates on
+#[derive(Debug, Clone, PartialEq)]
+enum DatabaseTarget {
+    Sqlite(PathBuf),
+    /// No SQLite file; the project has a non-SQLite Prisma schema
+    Prisma(PathBuf),
+}
+
+fn database_param(builder: SchemaBuilder) -> SchemaBuilder {
+    builder.string(
+        "database",
+        "Path to the SQLite file (.sqlite, .sqlite3, .db). Default: the only SQLite database in the workspace",
+        false,
+    )
+}
+
+/// Tool for introspecting a SQLite database's tables, columns, indexes and foreign keys
+pub struct SqliteSchemaTool;
+
+#[async_trait]
+impl Tool for SqliteSchemaTool {
+    fn name(&self) -> &str {
+        "database_schema"
+    }
+
+    fn definition(&self) -> ToolDefinition {
+        ToolDefinition {
+            name: "database_schema".to_string(),
+            description: "Show the schema of a SQLite database: tables and views with their columns, primary keys, indexes and foreign keys. Use this before writing queries.".to_string(),
+            input_schema: database_param(SchemaBuilder::new())
+                .string("table", "Only show this table", false)
+                .build(),
+        }
+    }
+
+    async fn execute(
+        &self,
+        tool_use_id: String,
+        input: Value,
+        context: &ToolContext,
+    ) -> Result<ToolResult> {
+        let target = match resolve_database(&input, context) {
+            Ok(target) => target,
+            Err(message) => return Ok(ToolResult::error(tool_use_id, message)),
+        };
+
+        let path = match target {
+            DatabaseTarget::Sqlite(path) => path,
+            DatabaseTarget::Prisma(schema_path) => {
+                return Ok(match std::fs::read_to_string(&schema_path) {
+                    Ok(schema) => ToolResult::success(
+                        tool_use_id,
+                        format!(
+                            "No SQLite database found. Prisma schema ({}):\n\n{}",
+                            schema_path.display(),
+                            schema
+                        ),
+                    ),
+                    Err(e) => ToolResult::error(
+                        tool_use_id,
+                        format!("Could not read {}: {}", schema_path.display(), e),
+                    ),
+                });
+            }
+        };
+
+        let table = input["table"].as_str().map(str::to_string);
+        let display_path = path.clone();
+        let result = tokio::task::spawn_blocking(move || {
+            let conn = open(&path, true)?;
+            describe_schema(&conn, table.as_deref())
+        })
+        .await
+        .map_err(|e| TedError::ToolExecution(e.to_string()))?;
+
+        Ok(match result {
+            Ok(schema) => ToolResult::success(
+                tool_use_id,
+                format!("Database: {}\n\n{}", display_path.display(), schema),
+            ),
+            Err(e) => ToolResult::error(tool_use_id, e.to_string()),
+        })
+    }
+
+    fn permission_request(&self, _input: &Value) -> Option<PermissionRequest> {
+        None
+    }
+
+    fn requires_permission(&self) -> bool {
+        false
+    }
+}
+
+/// Tool for read-only SQL queries
+pub struct SqliteQueryTool;
+
+#[async_trait]
+impl Tool for SqliteQueryTool {
+    fn name(&self) -> &str {
+        "database_query"
+    }
+
+    fn definition(&self) -> ToolDefinition {
+        ToolDefinition {
+            name: "database_query".to_string(),
+            description: "Run a read-only SQL query (SELECT, WITH, EXPLAIN, read-only PRAGMA) against a SQLite database and return the rows as a table. Use database_execute for statements that modify data or schema.".to_string(),
+            input_schema: database_param(
+                SchemaBuilder::new().string("query", "A single read-only SQL statement", true),
+            )
+            .integer(
+                "limit",
+                &format!(
+                    "Maximum rows to return (default: {}, max: {})",
+                    DEFAULT_ROW_LIMIT, MAX_ROW_LIMIT
+                ),
+                false,
+            )
+            .build(),
+        }
+    }
+
+    async fn execute(
+        &self,
+        tool_use_id: String,
+        input: Value,
+        context: &ToolContext,
+    ) -> Result<ToolResult> {
+        let query = input["query"]
+            .as_str()
+            .ok_or_else(|| TedError::InvalidInput("query is required".to_string()))?
+            .to_string();
+        let limit = input["limit"]
+            .as_u64()
+            .map(|l| l as usize)
+            .unwrap_or(DEFAULT_ROW_LIMIT)
+            .clamp(1, MAX_ROW_LIMIT);
+
+        let path = match resolve_database(&input, context) {
+            Ok(DatabaseTarget::Sqlite(path)) => path,
+            Ok(DatabaseTarget::Prisma(_)) => {
+                return PrismaQueryTool
+                    .execute(
+                        tool_use_id,
+                        json!({ "query": query, "allow_write": false }),
+                        context,
+                    )
+                    .await;
+            }
+            Err(message) => return Ok(ToolResult::error(tool_use_id, message)),
+        };
+
+        let result = tokio::task::spawn_blocking(move || {
+            let conn = open(&path, true)?;
+            run_query(&conn, &query, limit)
+        })
+        .await
+        .map_err(|e| TedError::ToolExecution(e.to_string()))?;
+
+        Ok(match result {
+            Ok(table) => ToolResult::success(tool_use_id, table),
+            Err(e) => ToolResult::error(tool_use_id, e.to_string()),
+        })
+    }
+
+    fn permission_request(&self, _input: &Value) -> Option<PermissionRequest> {
+        None
+    }
+
+    fn requires_permission(&self) -> bool {
+        false // The connection is opened read-only
+    }
+}
+
+/// Tool for SQL statements that modify a SQLite database
+pub struct SqliteExecuteTool;
+
+#[async_trait]
+impl Tool for SqliteExecuteTool {
+    fn name(&self) -> &str {
+        "database_execute"
+    }
+
+    fn definition(&self) -> ToolDefinition {
+        ToolDefinition {
+            name: "database_execute".to_string(),
+            description: "Execute SQL that modifies a SQLite database (INSERT, UPDATE, DELETE, CREATE, ALTER, DROP). Multiple statements separated by semicolons run in one transaction and are rolled back if any fails. Requires permission.".to_string(),
+            input_schema: database_param(
+                SchemaBuilder::new().string("sql", "SQL statement(s) to execute", true),
+            )
+            .build(),
+        }
+    }
+
+    async fn execute(
+        &self,
+        tool_use_id: String,
+        input: Value,
+        context: &ToolContext,
+    ) -> Result<ToolResult> {
+        let sql = input["sql"]
+            .as_str()
+            .ok_or_else(|| TedError::InvalidInput("sql is required".to_string()))?
+            .to_string();
+
+        let path = match resolve_database(&input, context) {
+            Ok(DatabaseTarget::Sqlite(path)) => path,
+            Ok(DatabaseTarget::Prisma(_)) => {
+                return PrismaQueryTool
+                    .execute(
+                        tool_use_id,
+                        json!({ "query": sql, "allow_write": true }),
+                        context,
+                    )
+                    .await;
+            }
+            Err(message) => return Ok(ToolResult::error(tool_use_id, message)),
+        };
+
+        let display_path = path.clone();
+        let result = tokio::task::spawn_blocking(move || {
+            let mut conn = open(&path, false)?;
+            run_statements(&mut conn, &sql)
+        })
+        .await
+        .map_err(|e| TedError::ToolExecution(e.to_string()))?;
+
+        Ok(match result {
+            Ok(changed) => {
+                context.emit_file_write(&display_path);
+                ToolResult::success(
+                    tool_use_id,
+                    format!(
+                        "Executed on {}: {} row{} changed",
+                        display_path.display(),
+                        changed,
+                        if changed == 1 { "" } else { "s" }
+                    ),
+                )
+            }
+            Err(e) => ToolResult::error(
+                tool_use_id,
+                format!("{} (transaction rolled back, no changes made)", e),
+            ),
+        })
+    }
+
+    fn permission_request(&self, input: &Value) -> Option<PermissionRequest> {
+        let sql = input["sql"].as_str().unwrap_or("");
+        let preview: String = sql.chars().take(100).collect();
+        Some(PermissionRequest {
+            tool_name: "database_execute".to_string(),
+            action_description: format!("Execute SQL: {}", preview),
+            affected_paths: input["database"]
+                .as_str()
+                .map(|p| vec![p.to_string()])
+                .unwrap_or_default(),
+            is_destructive: true,
+        })
+    }
+
+    fn requires_permission(&self) -> bool {
+        true
+    }
+}
+
+/// Pick the database for a call from the `database` input or by discovery.
+fn resolve_database(
+    input: &Value,
+    context: &ToolContext,
+) -> std::result::Result<DatabaseTarget, String> {
+    if let Some(path_str) = input["database"].as_str() {
+        let path = if Path::new(path_str).is_absolute() {
+            PathBuf::from(path_str)
+        } else {
+            context.working_directory.join(path_str)
+        };
+        if let Some(message) = context.check_path_not_ignored(&path) {
+            return Err(message);
+        }
+        if !path.is_file() {
+            return Err(format!("Database file not found: {}", path.display()));
+        }
+        if !is_sqlite_file(&path) {
+            return Err(format!("Not a SQLite database: {}", path.display()));
+        }
+        return Ok(DatabaseTarget::Sqlite(path));
+    }
+
+    let mut found = discover_databases(&context.working_directory, context);
+    match found.len() {
+        1 => Ok(DatabaseTarget::Sqlite(found.remove(0))),
+        0 => {
+            let prisma_schema = context.working_directory.join("prisma/schema.prisma");
+            let uses_other_provider = std::fs::read_to_string(&prisma_schema)
+                .map(|schema| !schema.contains("\"sqlite\"") && !schema.contains("'sqlite'"))
+                .unwrap_or(false);
+            if uses_other_provider {
+                Ok(DatabaseTarget::Prisma(prisma_schema))
+            } else {
+                Err("No SQLite database found in the workspace. Pass `database` with the path to a .sqlite or .db file.".to_string())
+            }
+        }
+        _ => {
+            let listed: Vec<String> = found
+                .iter()
+                .take(10)
+                .map(|p| {
+                    p.strip_prefix(&context.working_directory)
+                        .unwrap_or(p)
+                        .display()
+                        .to_string()
+                })
+                .collect();
+            Err(format!(
+                "Found {} SQLite databases; pass `database` to choose one:\n  {}",
+                found.len(),
+                listed.join("\n  ")
+            ))
+        }
+    }
+}
+
+/// Find SQLite files under `root`, skipping dependency and VCS directories.
+fn discover_databases(root: &Path, context: &ToolContext) -> Vec<PathBuf> {
+    let mut found: Vec<PathBuf> = walkdir::WalkDir::new(root)
+        .max_depth(DISCOVERY_DEPTH)
+        .into_iter()
+        .filter_entry(|entry| {
+            entry.depth() == 0
+                || !(entry.file_type().is_dir()
+                    && SKIPPED_DIRS.contains(&entry.file_name().to_string_lossy().as_ref()))
+        })
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().is_file())
+        .map(|entry| entry.into_path())
+        .filter(|path| {
+            path.extension()
+                .and_then(|e| e.to_str())
+                .is_some_and(|e| SQLITE_EXTENSIONS.contains(&e.to_ascii_lowercase().as_str()))
+        })
+        .filter(|path| !context.is_path_ignored(path) && is_sqlite_file(path))
+        .collect();
+    found.sort();
+    found
+}
+
+/// Check for the SQLite header (empty files are valid new databases).
+fn is_sqlite_file(path: &Path) -> bool {
+    use std::io::Read;
+
+    let Ok(mut file) = std::fs::File::open(path) else {
+        return false;
+    };
+    let mut header = [0u8; 16];
+    match file.read(&mut header) {
+        Ok(0) => true,
+        Ok(16) => &header == b"SQLite format 3\0",
+        _ => false,
+    }
+}
+
+fn open(path: &Path, read_only: bool) -> Result<Connection> {
+    let flags = if read_only {
+        OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX
+    } else {
+        OpenFlags::SQLITE_OPEN_READ_WRITE | OpenFlags::SQLITE_OPEN_NO_MUTEX
+    };
+    let conn = Connection::open_with_flags(path, flags).map_err(|e| {
+        TedError::ToolExecution(format!("Could not open {}: {}", path.display(), e))
+    })?;
+    conn.busy_timeout(std::time::Duration::from_secs(5))
+        .map_err(sql_error)?;
+    Ok(conn)
+}
+
+fn sql_error(e: rusqlite::Error) -> TedError {
+    TedError::ToolExecution(format!("SQL error: {}", e))
+}
+
+fn describe_schema(conn: &Connection, only_table: Option<&str>) -> Result<String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT name, type FROM sqlite_master \
+             WHERE type IN ('table', 'view') AND name NOT LIKE 'sqlite_%' ORDER BY type, name",
+        )
+        .map_err(sql_error)?;
+    let objects: Vec<(String, String)> = stmt
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+        .map_err(sql_error)?
+        .collect::<rusqlite::Result<_>>()
+        .map_err(sql_error)?;
+
+    let objects: Vec<_> = objects
+        .into_iter()
+        .filter(|(name, _)| only_table.is_none_or(|t| t.eq_ignore_ascii_case(name)))
+        .collect();
+    if objects.is_empty() {
+        return Ok(match only_table {
+            Some(table) => format!("No table or view named '{}'", table),
+            None => "(empty database: no tables)".to_string(),
+        });
+    }
+
+    let mut out = String::new();
+    for (name, kind) in objects {
+        let _ = writeln!(
+            out,
+            "{} {}",
+            if kind == "view" { "View" } else { "Table" },
+            name
+        );
+        describe_columns(conn, &name, &mut out)?;
+        if kind == "table" {
+            describe_indexes(conn, &name, &mut out)?;
+            describe_foreign_keys(conn, &name, &mut out)?;
+        }
+        out.push('\n');
+    }
+    Ok(out.trim_end().to_string())
+}
+
+fn describe_columns(conn: &Connection, table: &str, out: &mut String) -> Result<()> {
+    let mut stmt = conn
+        .prepare("SELECT name, type, \"notnull\", dflt_value, pk FROM pragma_table_info(?1)")
+        .map_err(sql_error)?;
+    let columns: Vec<(String, String, bool, Option<String>, i64)> = stmt
+        .query_map([table], |row| {
+            Ok((
+                row.get(0)?,
+                row.get(1)?,
+                row.get(2)?,
+                row.get(3)?,
+                row.get(4)?,
+            ))
+        })
+        .map_err(sql_error)?
+        .collect::<rusqlite::Result<_>>()
+        .map_err(sql_error)?;
+
+    let name_width = columns.iter().map(|c| c.0.len()).max().unwrap_or(0);
+    let type_width = columns.iter().map(|c| c.1.len()).max().unwrap_or(0);
+    for (name, column_type, not_null, default, pk) in columns {
+        let mut line = format!(
+            "  {:<name_width$}  {:<type_width$}",
+            name,
+            column_type,
+            name_width = name_width,
+            type_width = type_width
+        );
+        if pk > 0 {
+            line.push_str("  PRIMARY KEY");
+        }
+        if not_null {
+            line.push_str("  NOT NULL");
+        }
+        if let Some(default) = default {
+            let _ = write!(line, "  DEFAULT {}", default);
+        }
+        let _ = writeln!(out, "{}", line.trim_end());
+    }
+    Ok(())
+}
+
+fn describe_indexes(conn: &Connection, table: &str, out: &mut String) -> Result<()> {
+    let mut stmt = conn
+        .prepare("SELECT name, \"unique\" FROM pragma_index_list(?1) ORDER BY name")
+        .map_err(sql_error)?;
+    let indexes: Vec<(String, bool)> = stmt
+        .query_map([table], |row| Ok((row.get(0)?, row.get(1)?)))
+        .map_err(sql_error)?
+        .collect::<rusqlite::Result<_>>()
+        .map_err(sql_error)?;
+    if indexes.is_empty() {
+        return Ok(());
+    }
+
+    out.push_str("  Indexes:\n");
+    let mut columns_stmt = conn
+        .prepare("SELECT name FROM pragma_index_info(?1) ORDER BY seqno")
+        .map_err(sql_error)?;
+    for (name, unique) in indexes {
+        let columns: Vec<Option<String>> = columns_stmt
+            .query_map([&name], |row| row.get(0))
+            .map_err(sql_error)?
+            .collect::<rusqlite::Result<_>>()
+            .map_err(sql_error)?;
+        let columns: Vec<String> = columns
+            .into_iter()
+            .map(|c| c.unwrap_or_else(|| "<expr>".to_string()))
+            .collect();
+        let _ = writeln!(
+            out,
+            "    {} ({}){}",
+            name,
+            columns.join(", "),
+            if unique { " UNIQUE" } else { "" }
+        );
+    }
+    Ok(())
+}
+
+fn describe_foreign_keys(conn: &Connection, table: &str, out: &mut String) -> Result<()> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT \"from\", \"table\", \"to\", on_update, on_delete \
+             FROM pragma_foreign_key_list(?1) ORDER BY id, seq",
+        )
+        .map_err(sql_error)?;
+    let keys: Vec<(String, String, Option<String>, String, String)> = stmt
+        .query_map([table], |row| {
+            Ok((
+                row.get(0)?,
+                row.get(1)?,
+                row.get(2)?,
+                row.get(3)?,
+                row.get(4)?,
+            ))
+        })
+        .map_err(sql_error)?
+        .collect::<rusqlite::Result<_>>()
+        .map_err(sql_error)?;
+    if keys.is_empty() {
+        return Ok(());
+    }
+
+    out.push_str("  Foreign keys:\n");
+    for (from, parent, to, on_update, on_delete) in keys {
+        let mut line = format!(
+            "    {} -> {}({})",
+            from,
+            parent,
+            to.unwrap_or_else(|| "<primary key>".to_string())
+        );
+        if on_delete != "NO ACTION" {
+            let _ = write!(line, " ON DELETE {}", on_delete);
+        }
+        if on_update != "NO ACTION" {
+            let _ = write!(line, " ON UPDATE {}", on_update);
+        }
+        let _ = writeln!(out, "{}", line);
+    }
+    Ok(())
+}
+
+fn run_query(conn: &Connection, query: &str, limit: usize) -> Result<String> {
+    let mut stmt = conn.prepare(query).map_err(sql_error)?;
+    if !stmt.readonly() {
+        return Err(TedError::ToolExecution(
+            "database_query only runs read-only statements. Use database_execute to modify the database.".to_string(),
+        ));
+    }
+
+    let headers: Vec<String> = stmt.column_names().iter().map(|c| c.to_string()).collect();
+    let column_count = headers.len();
+    let mut rows = stmt.query([]).map_err(sql_error)?;
+    let mut table: Vec<Vec<String>> = Vec::new();
+    let mut truncated = false;
+    while let Some(row) = rows.next().map_err(sql_error)? {
+        if table.len() == limit {
+            truncated = true;
+            break;
+        }
+        let mut cells = Vec::with_capacity(column_count);
+        for i in 0..column_count {
+            cells.push(format_cell(row.get_ref(i).map_err(sql_error)?));
+        }
+        table.push(cells);
+    }
+
+    if column_count == 0 {
+        return Ok("Query executed (no columns returned)".to_string());
+    }
+
+    let mut out = format_table(&headers, &table);
+    let _ = write!(
+        out,
+        "\n({} row{}",
+        table.len(),
+        if table.len() == 1 { "" } else { "s" }
+    );
+    if truncated {
+        let _ = write!(
+            out,
+            "; truncated at limit {}, refine the query or raise `limit`",
+            limit
+        );
+    }
+    out.push(')');
+    Ok(out)
+}
+
+fn run_statements(conn: &mut Connection, sql: &str) -> Result<u64> {
+    let tx = conn.transaction().map_err(sql_error)?;
+    let before = tx.total_changes();
+    tx.execute_batch(sql).map_err(sql_error)?;
+    let changed = tx.total_changes() - before;
+    tx.commit().map_err(sql_error)?;
+    Ok(changed)
+}
+
+fn format_cell(value: ValueRef<'_>) -> String {
+    let text = match value {
+        ValueRef::Null => "NULL".to_string(),
+        ValueRef::Integer(i) => i.to_string(),
+        ValueRef::Real(f) => f.to_string(),
+        ValueRef::Text(t) => String::from_utf8_lossy(t)
+            .replace('\n', "\\n")
+            .replace('\t', " "),
+        ValueRef::Blob(b) => format!("<blob {} bytes>", b.len()),
+    };
+    if text.chars().count() > MAX_CELL_WIDTH {
+        let cut: String = text.chars().take(MAX_CELL_WIDTH - 3).collect();
+        format!("{}...", cut)
+    } else {
+        text
+    }
+}
+
+fn format_table(headers: &[String], rows: &[Vec<String>]) -> String {
+    let mut widths: Vec<usize> = headers.iter().map(|h| h.chars().count()).collect();
+    for row in rows {
+        for (i, cell) in row.iter().enumerate() {
+            widths[i] = widths[i].max(cell.chars().count());
+        }
+    }
+
+    let format_row = |cells: &[String]| {
+        let padded: Vec<String> = cells
+            .iter()
+            .zip(&widths)
+            .map(|(cell, width)| format!("{:<width$}", cell, width = width))
+            .collect();
+        format!("| {} |\n", padded.join(" | "))
+    };
+
+    let mut out = format_row(headers);
+    let rule: Vec<String> = widths.iter().map(|w| "-".repeat(*w)).collect();
+    let _ = writeln!(out, "|-{}-|", rule.join("-|-"));
+    for row in rows {
+        out.push_str(&format_row(row));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn context(dir: &TempDir) -> ToolContext {
+        ToolContext::new(
+            dir.path().to_path_buf(),
+            Some(dir.path().to_path_buf()),
+            uuid::Uuid::new_v4(),
+            true,
+        )
+    }
+
+    fn create_db(dir: &TempDir, name: &str) -> PathBuf {
+        let path = dir.path().join(name);
+        let conn = Connection::open(&path).unwrap();
+        conn.execute_batch(
+            "CREATE TABLE orgs (id INTEGER PRIMARY KEY, name TEXT NOT NULL);
+             CREATE TABLE users (
+                 id INTEGER PRIMARY KEY,
+                 email TEXT NOT NULL UNIQUE,
+                 org_id INTEGER REFERENCES orgs(id) ON DELETE CASCADE,
+                 active INTEGER DEFAULT 1
+             );
+             CREATE INDEX idx_users_org ON users(org_id);
+             INSERT INTO orgs VALUES (1, 'Acme');
+             INSERT INTO users VALUES (1, 'a@example.com', 1, 1), (2, 'b@example.com', 1, 0);",
+        )
+        .unwrap();
+        path
+    }
+
+    #[tokio::test]
+    async fn test_schema_lists_columns_indexes_and_foreign_keys() {
+        let dir = TempDir::new().unwrap();
+        create_db(&dir, "app.db");
+
+        let result = SqliteSchemaTool
+            .execute("t".to_string(), json!({}), &context(&dir))
+            .await
+            .unwrap();
+
+        assert!(!result.is_error(), "{}", result.output_text());
+        let output = result.output_text();
+        assert!(output.contains("Table users"));
+        assert!(output.contains("PRIMARY KEY"));
+        assert!(output.contains("DEFAULT 1"));
+        assert!(output.contains("idx_users_org (org_id)"));
+        assert!(output.contains("(email) UNIQUE"));
+        assert!(output.contains("org_id -> orgs(id) ON DELETE CASCADE"));
+    }
+
+    #[tokio::test]
+    async fn test_query_returns_table_and_respects_limit() {
+        let dir = TempDir::new().unwrap();
+        create_db(&dir, "app.sqlite");
+
+        let result = SqliteQueryTool
+            .execute(
+                "t".to_string(),
+                json!({"query": "SELECT id, email FROM users ORDER BY id", "limit": 1}),
+                &context(&dir),
+            )
+            .await
+            .unwrap();
+
+        assert!(!result.is_error(), "{}", result.output_text());
+        let output = result.output_text();
+        assert!(output.contains("| id | email"));
+        assert!(output.contains("a@example.com"));
+        assert!(!output.contains("b@example.com"));
+        assert!(output.contains("truncated at limit 1"));
+    }
+
+    #[tokio::test]
+    async fn test_query_rejects_writes() {
+        let dir = TempDir::new().unwrap();
+        create_db(&dir, "app.db");
+
+        let result = SqliteQueryTool
+            .execute(
+                "t".to_string(),
+                json!({"query": "DELETE FROM users"}),
+                &context(&dir),
+            )
+            .await
+            .unwrap();
+
+        assert!(result.is_error());
+        assert!(result.output_text().contains("database_execute"));
+    }
+
+    #[tokio::test]
+    async fn test_execute_commits_or_rolls_back() {
+        let dir = TempDir::new().unwrap();
+        let path = create_db(&dir, "app.db");
+        let ctx = context(&dir);
+
+        let result = SqliteExecuteTool
+            .execute(
+                "t".to_string(),
+                json!({"sql": "UPDATE users SET active = 1; DELETE FROM users WHERE id = 2;"}),
+                &ctx,
+            )
+            .await
+            .unwrap();
+        assert!(!result.is_error(), "{}", result.output_text());
+        assert!(result.output_text().contains("3 rows changed"));
+
+        let result = SqliteExecuteTool
+            .execute(
+                "t".to_string(),
+                json!({"sql": "DELETE FROM users; INSERT INTO missing VALUES (1);"}),
+                &ctx,
+            )
+            .await
+            .unwrap();
+        assert!(result.is_error());
+        assert!(result.output_text().contains("rolled back"));
+
+        let conn = Connection::open(&path).unwrap();
+        let count: i64 = conn
+            .query_row("SELECT COUNT(*) FROM users", [], |r| r.get(0))
+            .unwrap();
+        assert_eq!(count, 1);
+    }
+
+    #[test]
+    fn test_execute_requires_destructive_permission() {
+        let request = SqliteExecuteTool
+            .permission_request(&json!({"sql": "DROP TABLE users", "database": "app.db"}))
+            .unwrap();
+        assert!(SqliteExecuteTool.requires_permission());
+        assert!(request.is_destructive);
+        assert_eq!(request.affected_paths, vec!["app.db".to_string()]);
+    }
+
+    #[test]
+    fn test_discovery_requires_choice_between_databases() {
+        let dir = TempDir::new().unwrap();
+        let ctx = context(&dir);
+        assert!(resolve_database(&json!({}), &ctx)
+            .unwrap_err()
+            .contains("No SQLite database found"));
+
+        create_db(&dir, "a.db");
+        std::fs::create_dir_all(dir.path().join("node_modules")).unwrap();
+        create_db(&dir, "node_modules/skip.db");
+        std::fs::write(dir.path().join("notes.db"), "not sqlite at all").unwrap();
+        assert_eq!(
+            resolve_database(&json!({}), &ctx).unwrap(),
+            DatabaseTarget::Sqlite(dir.path().join("a.db"))
+        );
+
+        create_db(&dir, "b.sqlite");
+        let err = resolve_database(&json!({}), &ctx).unwrap_err();
+        assert!(err.contains("Found 2 SQLite databases"));
+        assert!(resolve_database(&json!({"database": "b.sqlite"}), &ctx).is_ok());
+        assert!(resolve_database(&json!({"database": "notes.db"}), &ctx)
+            .unwrap_err()
+            .contains("Not a SQLite database"));
+    }
+
+    #[test]
+    fn test_prisma_fallback_for_other_providers() {
+        let dir = TempDir::new().unwrap();
+        std::fs::create_dir_all(dir.path().join("prisma")).unwrap();
+        std::fs::write(
+            dir.path().join("prisma/schema.prisma"),
+            "datasource db {\n  provider = \"postgresql\"\n  url = env(\"DATABASE_URL\")\n}\n",
+        )
+        .unwrap();
+
+        assert!(matches!(
+            resolve_database(&json!({}), &context(&dir)),
+            Ok(DatabaseTarget::Prisma(_))
+        ));
+    }
+}
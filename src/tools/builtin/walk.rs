@@ -0,0 +1,332 @@
+// SPDX-License-Identifier: AGPL-3.0-or-later
+// Copyright (C) 2025 Blackman Artificial Intelligence Technologies Inc.
+
+//! Directory walking for the search tools
+//!
+//! `grep` and `glob` share one walker so they agree on what is searchable:
+//! `.gitignore`, `.ignore` and `.tedignore` rules apply, hidden entries and
+//! common build/dependency directories are pruned, and results can be limited
+//! to ripgrep file types such as `rust`, `ts` or `py`. `include_ignored`
+//! lifts the ignore-file, build-directory and hidden-entry filtering, and
+//! `include_hidden` lifts only the latter for patterns that name dotfiles.
+//! `.tedignore` and `.git` internals always stay excluded.
+
+use ignore::types::{Types, TypesBuilder};
+use ignore::{Walk, WalkBuilder};
+use std::path::{Path, PathBuf};
+
+use crate::tools::ToolContext;
+
+/// Directory names skipped even when no ignore file lists them
+const SKIPPED_DIRS: &[&str] = &[
+    "node_modules",
+    "target",
+    "__pycache__",
+    "venv",
+    ".venv",
+    "dist",
+    "build",
+    "vendor",
+    ".git",
+];
+
+/// Build a ripgrep file type matcher from a comma-separated list such as
+/// `rust,ts` or `!js`. Errors name the unknown type.
+pub(crate) fn file_types(spec: &str) -> std::result::Result<Types, String> {
+    let mut builder = TypesBuilder::new();
+    builder.add_defaults();
+    for name in spec.split(',').map(str::trim).filter(|s| !s.is_empty()) {
+        match name.strip_prefix('!') {
+            Some(negated) => builder.negate(negated),
+            None => builder.select(name),
+        };
+    }
+    builder.build().map_err(|e| {
+        format!(
+            "{} (use ripgrep type names such as rust, ts, js, py, go, java, c, cpp, md)",
+            e
+        )
+    })
+}
+
+/// Walk `root` honouring ignore files unless `include_ignored` is set.
+/// Hidden entries are walked when either flag is set.
+pub(crate) fn walk(
+    root: &Path,
+    context: &ToolContext,
+    include_ignored: bool,
+    include_hidden: bool,
+    types: Option<Types>,
+) -> Walk {
+    builder(root, context, include_ignored, include_hidden, types).build()
+}
+
+/// Whether `walk(root, context, false, false, None)` would yield `path`.
+///
+/// Ignore files apply per directory, so each directory between `root` and
+/// `path` is walked one level deep to check that the next entry is kept.
+pub(crate) fn is_walked(root: &Path, path: &Path, context: &ToolContext) -> bool {
+    let Ok(relative) = path.strip_prefix(root) else {
+        return false;
+    };
+    let mut dir = root.to_path_buf();
+    for component in relative.components() {
+        let next = dir.join(component);
+        let found = builder(&dir, context, false, false, None)
+            .max_depth(Some(1))
+            .build()
+            .filter_map(|entry| entry.ok())
+            .any(|entry| entry.depth() == 1 && entry.path() == next);
+        if !found {
+            return false;
+        }
+        dir = next;
+    }
+    true
+}
+
+fn builder(
+    root: &Path,
+    context: &ToolContext,
+    include_ignored: bool,
+    include_hidden: bool,
+    types: Option<Types>,
+) -> WalkBuilder {
+    let mut builder = WalkBuilder::new(root);
+    builder
+        .follow_links(true)
+        .hidden(!include_ignored && !include_hidden)
+        .require_git(false)
+        .git_ignore(!include_ignored)
+        .git_global(!include_ignored)
+        .git_exclude(!include_ignored)
+        .ignore(!include_ignored)
+        .parents(!include_ignored);
+    if let Some(types) = types {
+        builder.types(types);
+    }
+
+    let tedignore = context.tedignore().clone();
+    let root: PathBuf = root.to_path_buf();
+    builder.filter_entry(move |entry| {
+        if tedignore.is_ignored(entry.path()) {
+            return false;
+        }
+        if entry.depth() == 0 {
+            return true;
+        }
+        if entry.file_name() == ".git" {
+            return false;
+        }
+        if include_ignored {
+            return true;
+        }
+        let relative = entry.path().strip_prefix(&root).unwrap_or(entry.path());
+        if include_hidden {
+            !is_in_skipped_dir(relative)
+        } else {
+            !is_hidden_or_ignored(relative)
+        }
+    });
+    builder
+}
+
+/// Whether a path relative to the search root is hidden or inside a
+/// build/dependency directory.
+pub(crate) fn is_hidden_or_ignored(path: &Path) -> bool {
+    has_hidden_component(path) || is_in_skipped_dir(path)
+}
+
+/// Whether a path relative to the search root is inside a build/dependency
+/// directory.
+fn is_in_skipped_dir(path: &Path) -> bool {
+    path.components().any(|component| {
+        matches!(component, std::path::Component::Normal(name)
+            if SKIPPED_DIRS.contains(&name.to_string_lossy().as_ref()))
+    })
+}
+
+/// Whether a glob pattern names a hidden file or directory explicitly, as in
+/// `**/.eslintrc*` or `.github/**`.
+pub(crate) fn names_hidden(pattern: &str) -> bool {
+    has_hidden_component(Path::new(pattern))
+}
+
+fn has_hidden_component(path: &Path) -> bool {
+    path.components().any(|component| {
+        matches!(component, std::path::Component::Normal(name)
+            if name.to_string_lossy().starts_with('.'))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+    use uuid::Uuid;
+
+    fn project() -> TempDir {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path();
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        std::fs::create_dir_all(root.join("generated")).unwrap();
+        std::fs::create_dir_all(root.join("node_modules/pkg")).unwrap();
+        std::fs::write(root.join(".gitignore"), "generated/\n*.log\n").unwrap();
+        std::fs::write(root.join("src/main.rs"), "fn main() {}").unwrap();
+        std::fs::write(root.join("src/app.ts"), "export {}").unwrap();
+        std::fs::write(root.join("generated/out.rs"), "// generated").unwrap();
+        std::fs::write(root.join("debug.log"), "log").unwrap();
+        std::fs::write(root.join("node_modules/pkg/index.js"), "x").unwrap();
+        temp
+    }
+
+    fn files(temp: &TempDir, include_ignored: bool, types: Option<&str>) -> Vec<String> {
+        let context = ToolContext::new(temp.path().to_path_buf(), None, Uuid::new_v4(), true);
+        let types = types.map(|t| file_types(t).unwrap());
+        let mut files: Vec<String> = walk(temp.path(), &context, include_ignored, false, types)
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_type().is_some_and(|t| t.is_file()))
+            .map(|e| {
+                e.path()
+                    .strip_prefix(temp.path())
+                    .unwrap()
+                    .to_string_lossy()
+                    .replace('\\', "/")
+            })
+            .collect();
+        files.sort();
+        files
+    }
+
+    #[test]
+    fn test_walk_honours_gitignore_and_skipped_dirs() {
+        let temp = project();
+        assert_eq!(files(&temp, false, None), vec!["src/app.ts", "src/main.rs"]);
+    }
+
+    #[test]
+    fn test_walk_include_ignored() {
+        let temp = project();
+        assert_eq!(
+            files(&temp, true, None),
+            vec![
+                ".gitignore",
+                "debug.log",
+                "generated/out.rs",
+                "node_modules/pkg/index.js",
+                "src/app.ts",
+                "src/main.rs"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_walk_include_hidden_keeps_other_filters() {
+        let temp = project();
+        std::fs::create_dir_all(temp.path().join(".github")).unwrap();
+        std::fs::create_dir_all(temp.path().join(".git")).unwrap();
+        std::fs::write(temp.path().join(".github/ci.yml"), "on: push").unwrap();
+        std::fs::write(temp.path().join(".git/HEAD"), "ref").unwrap();
+
+        let context = ToolContext::new(temp.path().to_path_buf(), None, Uuid::new_v4(), true);
+        let mut files: Vec<String> = walk(temp.path(), &context, false, true, None)
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_type().is_some_and(|t| t.is_file()))
+            .map(|e| {
+                e.path()
+                    .strip_prefix(temp.path())
+                    .unwrap()
+                    .to_string_lossy()
+                    .replace('\\', "/")
+            })
+            .collect();
+        files.sort();
+        assert_eq!(
+            files,
+            vec![".github/ci.yml", ".gitignore", "src/app.ts", "src/main.rs"]
+        );
+    }
+
+    #[test]
+    fn test_is_walked_matches_walk() {
+        let temp = project();
+        let context = ToolContext::new(temp.path().to_path_buf(), None, Uuid::new_v4(), true);
+        let root = temp.path();
+        assert!(is_walked(root, &root.join("src/main.rs"), &context));
+        assert!(!is_walked(root, &root.join("generated/out.rs"), &context));
+        assert!(!is_walked(root, &root.join("debug.log"), &context));
+        assert!(!is_walked(
+            root,
+            &root.join("node_modules/pkg/index.js"),
+            &context
+        ));
+        assert!(!is_walked(root, &root.join(".gitignore"), &context));
+        assert!(!is_walked(root, Path::new("/etc/hostname"), &context));
+    }
+
+    #[test]
+    fn test_names_hidden() {
+        assert!(names_hidden("**/.eslintrc*"));
+        assert!(names_hidden(".github/**"));
+        assert!(names_hidden(".env.example"));
+        assert!(!names_hidden("./src/**/*.rs"));
+        assert!(!names_hidden("../lib/*.ts"));
+    }
+
+    #[test]
+    fn test_walk_type_filters() {
+        let temp = project();
+        assert_eq!(files(&temp, false, Some("rust")), vec!["src/main.rs"]);
+        assert_eq!(files(&temp, false, Some("rust,ts")).len(), 2);
+        assert_eq!(files(&temp, false, Some("!rust")), vec!["src/app.ts"]);
+        assert!(file_types("klingon").unwrap_err().contains("klingon"));
+    }
+
+    #[test]
+    fn test_is_hidden_or_ignored() {
+        assert!(is_hidden_or_ignored(Path::new(".hidden/file.txt")));
+        assert!(is_hidden_or_ignored(Path::new("node_modules/pkg/file.js")));
+        assert!(is_hidden_or_ignored(Path::new("target/debug/ted")));
+        assert!(is_hidden_or_ignored(Path::new(".git/config")));
+        assert!(!is_hidden_or_ignored(Path::new("src/main.rs")));
+        assert!(!is_hidden_or_ignored(Path::new("lib/utils.rs")));
+    }
+
+    #[test]
+    fn test_is_hidden_or_ignored_all_directories() {
+        assert!(is_hidden_or_ignored(Path::new("__pycache__/cache.pyc")));
+        assert!(is_hidden_or_ignored(Path::new("venv/bin/python")));
+        assert!(is_hidden_or_ignored(Path::new(".venv/lib/site")));
+        assert!(is_hidden_or_ignored(Path::new("dist/bundle.js")));
+        assert!(is_hidden_or_ignored(Path::new("build/output.js")));
+        assert!(is_hidden_or_ignored(Path::new("vendor/pkg/mod.go")));
+    }
+
+    #[test]
+    fn test_is_hidden_or_ignored_nested_paths() {
+        assert!(is_hidden_or_ignored(Path::new(
+            "src/components/.hidden/file.ts"
+        )));
+        assert!(is_hidden_or_ignored(Path::new(
+            "packages/app/node_modules/react/index.js"
+        )));
+        assert!(is_hidden_or_ignored(Path::new(
+            "crates/lib/target/release/binary"
+        )));
+    }
+
+    #[test]
+    fn test_is_hidden_or_ignored_hidden_files() {
+        assert!(is_hidden_or_ignored(Path::new("src/.gitignore")));
+        assert!(is_hidden_or_ignored(Path::new("pkg/.eslintrc")));
+        assert!(is_hidden_or_ignored(Path::new(".env")));
+        assert!(!is_hidden_or_ignored(Path::new("src/gitignore")));
+    }
+
+    #[test]
+    fn test_is_hidden_or_ignored_similar_names() {
+        assert!(!is_hidden_or_ignored(Path::new("not_node_modules/file.js")));
+        assert!(!is_hidden_or_ignored(Path::new("target_practice/file.rs")));
+        assert!(!is_hidden_or_ignored(Path::new("my_dist/file.js")));
+    }
+}
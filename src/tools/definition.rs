@@ -85,6 +85,46 @@ impl SchemaBuilder {
         self
     }
 
+    /// Add an object property whose values are strings
+    pub fn string_map(mut self, name: &str, description: &str, required: bool) -> Self {
+        self.properties.insert(
+            name.to_string(),
+            serde_json::json!({
+                "type": "object",
+                "description": description,
+                "additionalProperties": {
+                    "type": "string"
+                }
+            }),
+        );
+        if required {
+            self.required.push(name.to_string());
+        }
+        self
+    }
+
+    /// Add a string property restricted to `values`
+    pub fn string_enum(
+        mut self,
+        name: &str,
+        description: &str,
+        values: &[&str],
+        required: bool,
+    ) -> Self {
+        self.properties.insert(
+            name.to_string(),
+            serde_json::json!({
+                "type": "string",
+                "description": description,
+                "enum": values
+            }),
+        );
+        if required {
+            self.required.push(name.to_string());
+        }
+        self
+    }
+
     /// Build the schema
     pub fn build(self) -> crate::llm::provider::ToolInputSchema {
         crate::llm::provider::ToolInputSchema {
@@ -190,6 +230,26 @@ mod tests {
         assert!(!builder.required.contains(&"items".to_string()));
     }
 
+    #[test]
+    fn test_schema_builder_string_enum() {
+        let builder = SchemaBuilder::new().string_enum("mode", "Mode", &["fast", "slow"], true);
+
+        let prop = builder.properties.get("mode").unwrap();
+        assert_eq!(prop["type"], "string");
+        assert_eq!(prop["enum"], serde_json::json!(["fast", "slow"]));
+        assert!(builder.required.contains(&"mode".to_string()));
+    }
+
+    #[test]
+    fn test_schema_builder_string_map() {
+        let builder = SchemaBuilder::new().string_map("headers", "Headers", false);
+
+        let prop = builder.properties.get("headers").unwrap();
+        assert_eq!(prop["type"], "object");
+        assert_eq!(prop["additionalProperties"]["type"], "string");
+        assert!(!builder.required.contains(&"headers".to_string()));
+    }
+
     #[test]
     fn test_schema_builder_chaining() {
         let builder = SchemaBuilder::new()
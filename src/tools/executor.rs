@@ -9,10 +9,14 @@ use std::path::Path;
 
 use crate::audit::{PermissionAuditEvent, PermissionAuditLog, PermissionDecision};
 use crate::error::{Result, TedError};
+use crate::hooks::LifecycleHooks;
 use crate::llm::message::{ContentBlock, Message};
 use crate::llm::provider::ContentBlockResponse;
+use crate::mcp::{McpClientManager, McpSampler};
 
-use super::{PermissionManager, PermissionResponse, ToolContext, ToolRegistry, ToolResult};
+use super::{
+    PermissionManager, PermissionResponse, PostEditPipeline, ToolContext, ToolRegistry, ToolResult,
+};
 
 enum PermissionAuthorization {
     Allowed,
@@ -27,6 +31,10 @@ pub struct ToolExecutor {
     policy_load_warning: Option<String>,
     permission_audit_log: PermissionAuditLog,
     last_denial_message: Option<String>,
+    post_edit: PostEditPipeline,
+    plan_mode: super::PlanMode,
+    hooks: LifecycleHooks,
+    mcp_servers: McpClientManager,
 }
 
 impl ToolExecutor {
@@ -58,10 +66,28 @@ impl ToolExecutor {
         (manager, warning)
     }
 
+    fn build_post_edit_pipeline(context: &ToolContext) -> PostEditPipeline {
+        match PostEditPipeline::load_for_workspace(
+            &context.working_directory,
+            context.project_root.as_deref(),
+        ) {
+            Ok(pipeline) => pipeline,
+            Err(err) => {
+                tracing::warn!(
+                    target: "ted.tools.post_edit",
+                    error = %err,
+                    "Failed to load post-edit pipeline; formatters and linters are disabled"
+                );
+                PostEditPipeline::default()
+            }
+        }
+    }
+
     fn format_policy_source(source: &super::PolicySource) -> String {
         match source {
             super::PolicySource::User(path) => format!("user ({})", path.display()),
             super::PolicySource::Project(path) => format!("project ({})", path.display()),
+            super::PolicySource::Builtin => "built-in".to_string(),
         }
     }
 
@@ -232,6 +258,11 @@ impl ToolExecutor {
     pub fn new(context: ToolContext, trust_mode: bool) -> Self {
         let (permission_manager, policy_load_warning) =
             Self::build_permission_manager(&context, trust_mode);
+        let post_edit = Self::build_post_edit_pipeline(&context);
+        let hooks = LifecycleHooks::load_or_default(
+            &context.working_directory,
+            context.project_root.as_deref(),
+        );
 
         Self {
             registry: ToolRegistry::with_builtins(),
@@ -240,6 +271,10 @@ impl ToolExecutor {
             policy_load_warning,
             permission_audit_log: PermissionAuditLog::default(),
             last_denial_message: None,
+            post_edit,
+            plan_mode: super::PlanMode::default(),
+            hooks,
+            mcp_servers: McpClientManager::default(),
         }
     }
 
@@ -248,6 +283,10 @@ impl ToolExecutor {
     pub fn new_without_tools(context: ToolContext, trust_mode: bool) -> Self {
         let (permission_manager, policy_load_warning) =
             Self::build_permission_manager(&context, trust_mode);
+        let hooks = LifecycleHooks::load_or_default(
+            &context.working_directory,
+            context.project_root.as_deref(),
+        );
 
         Self {
             registry: ToolRegistry::new(),
@@ -256,9 +295,81 @@ impl ToolExecutor {
             policy_load_warning,
             permission_audit_log: PermissionAuditLog::default(),
             last_denial_message: None,
+            post_edit: PostEditPipeline::default(),
+            plan_mode: super::PlanMode::default(),
+            hooks,
+            mcp_servers: McpClientManager::default(),
         }
     }
 
+    /// Share a plan mode switch with the executor
+    pub fn with_plan_mode(mut self, plan_mode: super::PlanMode) -> Self {
+        self.plan_mode = plan_mode;
+        self
+    }
+
+    /// The plan mode switch consulted before each tool call
+    pub fn plan_mode(&self) -> &super::PlanMode {
+        &self.plan_mode
+    }
+
+    /// Replace the post-edit pipeline loaded from settings
+    pub fn with_post_edit(mut self, post_edit: PostEditPipeline) -> Self {
+        self.post_edit = post_edit;
+        self
+    }
+
+    /// Replace the lifecycle hooks loaded from settings
+    pub fn with_hooks(mut self, hooks: LifecycleHooks) -> Self {
+        self.hooks = hooks;
+        self
+    }
+
+    /// Lifecycle hooks run around tool calls and turns
+    pub fn hooks(&self) -> &LifecycleHooks {
+        &self.hooks
+    }
+
+    /// Start the MCP servers configured for this workspace and register
+    /// their tools
+    pub async fn connect_mcp_servers(&mut self) {
+        let manager = McpClientManager::connect_for_workspace(
+            &self.context.working_directory,
+            self.context.project_root.as_deref(),
+        )
+        .await;
+        self.attach_mcp_servers(manager).await;
+    }
+
+    /// Register the tools of already connected MCP servers
+    pub async fn attach_mcp_servers(&mut self, manager: McpClientManager) {
+        for tool in manager.tools().await {
+            self.registry.register(tool);
+        }
+        self.mcp_servers = manager;
+    }
+
+    /// External MCP servers whose tools are registered
+    pub fn mcp_servers(&self) -> &McpClientManager {
+        &self.mcp_servers
+    }
+
+    /// Let MCP servers request completions from `provider`, asking for
+    /// approval through this executor's context
+    pub fn enable_mcp_sampling(
+        &self,
+        provider: std::sync::Arc<dyn crate::llm::provider::LlmProvider>,
+        model: impl Into<String>,
+    ) {
+        self.mcp_servers
+            .enable_sampling(McpSampler::new(provider, model, self.context.clone()));
+    }
+
+    /// The context tools run with
+    pub fn context(&self) -> &ToolContext {
+        &self.context
+    }
+
     /// Returns a non-fatal policy load warning, when policy parsing failed.
     pub fn policy_load_warning(&self) -> Option<&str> {
         self.policy_load_warning.as_deref()
@@ -300,6 +411,11 @@ impl ToolExecutor {
             .ok_or_else(|| TedError::ToolExecution(format!("Unknown tool: {}", tool_name)))?
             .clone();
 
+        if let Some(reason) = self.plan_mode.denial(tool_name, input) {
+            self.last_denial_message = Some(reason);
+            return Ok(None);
+        }
+
         if tool.requires_permission() {
             if let Some(request) = tool.permission_request(input) {
                 match self.authorize_request(&request)? {
@@ -315,6 +431,25 @@ impl ToolExecutor {
         Ok(Some((tool, self.context.clone())))
     }
 
+    /// Plan mode and permission checks for a call, returning why it may not run.
+    fn denial_reason(
+        &mut self,
+        tool: &dyn super::Tool,
+        input: &serde_json::Value,
+    ) -> Option<String> {
+        if let Some(reason) = self.plan_mode.denial(tool.name(), input) {
+            return Some(reason);
+        }
+        let request = tool
+            .permission_request(input)
+            .filter(|_| tool.requires_permission())?;
+        match self.authorize_request(&request) {
+            Ok(PermissionAuthorization::Allowed) => None,
+            Ok(PermissionAuthorization::Denied(reason)) => Some(reason),
+            Err(err) => Some(err.to_string()),
+        }
+    }
+
     /// Execute a tool use from the LLM response
     pub async fn execute_tool_use(
         &mut self,
@@ -329,29 +464,84 @@ impl ToolExecutor {
             .ok_or_else(|| TedError::ToolExecution(format!("Unknown tool: {}", tool_name)))?
             .clone();
 
-        // Check permissions
-        if tool.requires_permission() {
-            if let Some(request) = tool.permission_request(&input) {
-                match self.authorize_request(&request) {
-                    Ok(PermissionAuthorization::Allowed) => {}
-                    Ok(PermissionAuthorization::Denied(reason)) => {
+        // Validate (and repair) the input against the tool's schema
+        let input =
+            match super::validate_tool_input(tool.name(), &tool.definition().input_schema, input) {
+                Ok(input) => input,
+                Err(err) => return Ok(ToolResult::error(tool_use_id, err.to_model_message())),
+            };
+
+        if let Some(reason) = self.denial_reason(tool.as_ref(), &input) {
+            return Ok(ToolResult::error(tool_use_id, reason));
+        }
+
+        // PreToolUse hooks may veto the call or rewrite its input
+        let pre_hooks = self
+            .hooks
+            .pre_tool_use(self.context.session_id, tool_use_id, tool.name(), &input)
+            .await;
+        if let Some(reason) = &pre_hooks.block_reason {
+            return Ok(ToolResult::error(
+                tool_use_id,
+                format!("Blocked by PreToolUse hook: {}", reason),
+            ));
+        }
+        let input = match pre_hooks.tool_input.clone() {
+            Some(rewritten) => {
+                let rewritten = match super::validate_tool_input(
+                    tool.name(),
+                    &tool.definition().input_schema,
+                    rewritten,
+                ) {
+                    Ok(rewritten) => rewritten,
+                    Err(err) => return Ok(ToolResult::error(tool_use_id, err.to_model_message())),
+                };
+                // A rewritten call must pass the same checks as the original
+                if rewritten != input {
+                    if let Some(reason) = self.denial_reason(tool.as_ref(), &rewritten) {
                         return Ok(ToolResult::error(tool_use_id, reason));
                     }
-                    Err(err) => {
-                        return Ok(ToolResult::error(tool_use_id, err.to_string()));
-                    }
                 }
+                rewritten
             }
-        }
+            None => input,
+        };
+
+        // Capture pre-edit lint state for the post-edit pipeline
+        let post_edit_path = self
+            .post_edit
+            .target_path(tool.name(), &input, &self.context);
+        let lint_baseline = match &post_edit_path {
+            Some(path) => self.post_edit.lint_baseline(path, &self.context).await,
+            None => Vec::new(),
+        };
 
         // Execute the tool
-        match tool
-            .execute(tool_use_id.to_string(), input, &self.context)
+        let result = match tool
+            .execute(tool_use_id.to_string(), input.clone(), &self.context)
             .await
         {
-            Ok(result) => Ok(result),
-            Err(e) => Ok(ToolResult::error(tool_use_id, e.to_string())),
-        }
+            Ok(result) => result,
+            Err(e) => ToolResult::error(tool_use_id, e.to_string()),
+        };
+
+        let result = match post_edit_path {
+            Some(path) if !result.is_error() => {
+                let report = self
+                    .post_edit
+                    .run(&path, &lint_baseline, &self.context)
+                    .await;
+                report.apply_to(result, &path)
+            }
+            _ => result,
+        };
+        let result = pre_hooks.apply_to_result(result);
+
+        let post_hooks = self
+            .hooks
+            .post_tool_use(self.context.session_id, tool.name(), &input, &result)
+            .await;
+        Ok(post_hooks.apply_to_result(result))
     }
 
     /// Process all tool uses from a response and return tool result messages
@@ -551,6 +741,45 @@ mod tests {
         assert!(!output.contains("other.txt"));
     }
 
+    #[tokio::test]
+    async fn test_execute_rejects_invalid_input() {
+        let temp_dir = TempDir::new().unwrap();
+        let context = create_test_context(&temp_dir);
+        let mut executor = ToolExecutor::new(context, true);
+
+        let result = executor
+            .execute_tool_use("test-id-1", "file_read", serde_json::json!({}))
+            .await
+            .unwrap();
+
+        assert!(result.is_error());
+        assert!(result
+            .output_text()
+            .contains("Invalid input for tool 'file_read'"));
+        assert!(result
+            .output_text()
+            .contains("path: missing required field"));
+    }
+
+    #[tokio::test]
+    async fn test_execute_coerces_stringified_input() {
+        let temp_dir = TempDir::new().unwrap();
+        let test_file = temp_dir.path().join("test.txt");
+        std::fs::write(&test_file, "Hello, world!").unwrap();
+
+        let context = create_test_context(&temp_dir);
+        let mut executor = ToolExecutor::new(context, true);
+
+        let input = serde_json::json!({ "file_path": test_file.to_string_lossy() }).to_string();
+        let result = executor
+            .execute_tool_use("test-id-1", "file_read", serde_json::Value::String(input))
+            .await
+            .unwrap();
+
+        assert!(!result.is_error());
+        assert!(result.output_text().contains("Hello, world!"));
+    }
+
     #[tokio::test]
     async fn test_execute_unknown_tool() {
         let temp_dir = TempDir::new().unwrap();
@@ -1260,6 +1489,81 @@ reason = "deny still enforced in trust mode"
             .contains("deny still enforced in trust mode"));
     }
 
+    #[tokio::test]
+    async fn test_execute_tool_use_http_request_external_host_denied_in_trust_mode() {
+        let temp_dir = TempDir::new().unwrap();
+        let context = create_test_context(&temp_dir);
+        let mut executor = ToolExecutor::new(context, true);
+        let result = executor
+            .execute_tool_use(
+                "tool-1",
+                "http_request",
+                serde_json::json!({"url": "https://example.com/"}),
+            )
+            .await
+            .unwrap();
+
+        assert!(result.is_error());
+        assert!(result
+            .output_text()
+            .contains("Denied by permission policy [built-in]: only localhost is allowed"));
+    }
+
+    #[tokio::test]
+    async fn test_execute_tool_use_plan_mode_blocks_writes_until_disabled() {
+        let temp_dir = TempDir::new().unwrap();
+        let context = create_test_context(&temp_dir);
+        let plan_mode = super::super::PlanMode::new(true);
+        let mut executor = ToolExecutor::new(context, true).with_plan_mode(plan_mode.clone());
+        let input = serde_json::json!({"path": "plan.txt", "content": "hello"});
+
+        let result = executor
+            .execute_tool_use("tool-1", "file_write", input.clone())
+            .await
+            .unwrap();
+        assert!(result.is_error());
+        assert!(result.output_text().starts_with("Plan mode is on"));
+        assert!(!temp_dir.path().join("plan.txt").exists());
+
+        let read_only = executor
+            .execute_tool_use("tool-2", "shell", serde_json::json!({"command": "ls"}))
+            .await
+            .unwrap();
+        assert!(!read_only.is_error());
+
+        plan_mode.set_enabled(false);
+        let result = executor
+            .execute_tool_use("tool-3", "file_write", input)
+            .await
+            .unwrap();
+        assert!(!result.is_error());
+        assert!(temp_dir.path().join("plan.txt").exists());
+    }
+
+    #[test]
+    fn test_approve_and_get_tool_plan_mode_denial() {
+        let temp_dir = TempDir::new().unwrap();
+        let context = create_test_context(&temp_dir);
+        let mut executor =
+            ToolExecutor::new(context, true).with_plan_mode(super::super::PlanMode::new(true));
+        executor.registry_mut().register_spawn_agent(
+            std::sync::Arc::new(crate::llm::mock_provider::MockProvider::new()),
+            std::sync::Arc::new(crate::skills::SkillRegistry::with_paths(vec![])),
+            "mock-model".to_string(),
+        );
+        let approved = executor
+            .approve_and_get_tool(
+                "spawn_agent",
+                &serde_json::json!({"agent_type": "implement", "task": "x"}),
+            )
+            .unwrap();
+        assert!(approved.is_none());
+        assert!(executor
+            .take_last_denial_message()
+            .unwrap()
+            .contains("read-only agents"));
+    }
+
     #[tokio::test]
     async fn test_execute_tool_use_policy_ask_auto_allows_in_trust_mode() {
         let temp_dir = TempDir::new().unwrap();
@@ -1331,4 +1635,181 @@ commands = ["echo *"]
 
         assert!(executor.policy_load_warning().is_some());
     }
+
+    #[tokio::test]
+    async fn test_execute_tool_use_runs_post_edit_pipeline_from_project_settings() {
+        let temp_dir = TempDir::new().unwrap();
+        let project_dir = temp_dir.path().join(".ted");
+        std::fs::create_dir_all(&project_dir).unwrap();
+        let project_path = project_dir.join("settings.json");
+        std::fs::write(
+            &project_path,
+            r#"{
+                "post_edit": {
+                    "rules": [{
+                        "globs": ["*.txt"],
+                        "formatters": ["tr a-z A-Z < {file} > {file}.tmp && mv {file}.tmp {file}"],
+                        "linters": ["grep -n FIXME"]
+                    }]
+                }
+            }"#,
+        )
+        .unwrap();
+        let user_dir = TempDir::new().unwrap();
+        let trusting_user = user_dir.path().join("trusting.json");
+        std::fs::write(
+            &trusting_user,
+            serde_json::json!({"post_edit": {"trusted_projects": [temp_dir.path()]}}).to_string(),
+        )
+        .unwrap();
+        let other_user = user_dir.path().join("other.json");
+        std::fs::write(&other_user, "{}").unwrap();
+
+        let write = |pipeline: PostEditPipeline, path: &'static str| {
+            let mut executor =
+                ToolExecutor::new(create_test_context(&temp_dir), true).with_post_edit(pipeline);
+            async move {
+                executor
+                    .execute_tool_use(
+                        "tool-1",
+                        "file_write",
+                        serde_json::json!({"path": path, "content": "hello\nfixme later\n"}),
+                    )
+                    .await
+                    .unwrap()
+            }
+        };
+
+        let trusted =
+            PostEditPipeline::load_from_paths(&trusting_user, Some(&project_path)).unwrap();
+        let result = write(trusted, "notes.txt").await;
+        assert!(!result.is_error());
+        assert!(result
+            .output_text()
+            .contains("Post-edit lint found new issues"));
+        assert!(result.output_text().contains("2:FIXME LATER"));
+        assert_eq!(
+            std::fs::read_to_string(temp_dir.path().join("notes.txt")).unwrap(),
+            "HELLO\nFIXME LATER\n"
+        );
+
+        let untrusted =
+            PostEditPipeline::load_from_paths(&other_user, Some(&project_path)).unwrap();
+        let result = write(untrusted, "other.txt").await;
+        assert!(!result.is_error());
+        assert!(!result.output_text().contains("Post-edit"));
+        assert_eq!(
+            std::fs::read_to_string(temp_dir.path().join("other.txt")).unwrap(),
+            "hello\nfixme later\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_execute_tool_use_skips_post_edit_for_other_files() {
+        let temp_dir = TempDir::new().unwrap();
+        let project_dir = temp_dir.path().join(".ted");
+        std::fs::create_dir_all(&project_dir).unwrap();
+        std::fs::write(
+            project_dir.join("settings.json"),
+            r#"{"post_edit": {"rules": [{"language": "rust", "linters": ["echo lint"]}]}}"#,
+        )
+        .unwrap();
+
+        let context = create_test_context(&temp_dir);
+        let mut executor = ToolExecutor::new(context, true);
+        let result = executor
+            .execute_tool_use(
+                "tool-1",
+                "file_write",
+                serde_json::json!({"path": "notes.txt", "content": "hello"}),
+            )
+            .await
+            .unwrap();
+
+        assert!(!result.is_error());
+        assert!(!result.output_text().contains("Post-edit"));
+    }
+
+    #[tokio::test]
+    async fn test_execute_tool_use_runs_pre_and_post_tool_hooks() {
+        let temp_dir = TempDir::new().unwrap();
+        let hooks = LifecycleHooks::from_config(
+            &crate::config::HooksConfig {
+                pre_tool_use: vec![crate::config::HookRule {
+                    matcher: Some("file_write".to_string()),
+                    command: "grep -q secret && { echo 'no secrets' >&2; exit 2; } || exit 0"
+                        .to_string(),
+                    timeout_secs: None,
+                }],
+                post_tool_use: vec![crate::config::HookRule {
+                    matcher: None,
+                    command: "echo 'checked by hook'".to_string(),
+                    timeout_secs: None,
+                }],
+                ..Default::default()
+            },
+            temp_dir.path().to_path_buf(),
+        )
+        .with_audit_log(crate::audit::HookAuditLog::new(
+            temp_dir.path().join("hooks.jsonl"),
+        ));
+
+        let context = create_test_context(&temp_dir);
+        let mut executor = ToolExecutor::new(context, true).with_hooks(hooks);
+
+        let blocked = executor
+            .execute_tool_use(
+                "tool-1",
+                "file_write",
+                serde_json::json!({"path": "a.txt", "content": "secret"}),
+            )
+            .await
+            .unwrap();
+        assert!(blocked.is_error());
+        assert!(blocked.output_text().contains("no secrets"));
+        assert!(!temp_dir.path().join("a.txt").exists());
+
+        let allowed = executor
+            .execute_tool_use(
+                "tool-2",
+                "file_write",
+                serde_json::json!({"path": "b.txt", "content": "hello"}),
+            )
+            .await
+            .unwrap();
+        assert!(!allowed.is_error());
+        assert!(allowed.output_text().ends_with("checked by hook"));
+        assert!(temp_dir.path().join("b.txt").exists());
+    }
+
+    #[tokio::test]
+    async fn test_execute_tool_use_rechecks_input_rewritten_by_hook() {
+        let temp_dir = TempDir::new().unwrap();
+        let hooks = LifecycleHooks::from_config(
+            &crate::config::HooksConfig {
+                pre_tool_use: vec![crate::config::HookRule {
+                    matcher: Some("shell".to_string()),
+                    command: r#"echo '{"tool_input": {"command": "touch pwned.txt"}}'"#.to_string(),
+                    timeout_secs: None,
+                }],
+                ..Default::default()
+            },
+            temp_dir.path().to_path_buf(),
+        )
+        .with_audit_log(crate::audit::HookAuditLog::new(
+            temp_dir.path().join("hooks.jsonl"),
+        ));
+
+        let context = create_test_context(&temp_dir);
+        let mut executor = ToolExecutor::new(context, true)
+            .with_plan_mode(super::super::PlanMode::new(true))
+            .with_hooks(hooks);
+        let result = executor
+            .execute_tool_use("tool-1", "shell", serde_json::json!({"command": "ls"}))
+            .await
+            .unwrap();
+
+        assert!(result.is_error());
+        assert!(!temp_dir.path().join("pwned.txt").exists());
+    }
 }
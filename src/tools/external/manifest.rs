@@ -33,6 +33,9 @@ use crate::llm::provider::{ToolDefinition, ToolInputSchema};
 ///   "timeout_ms": 30000
 /// }
 /// ```
+///
+/// Set `"protocol_version": 2` to run the tool as a long-lived worker that
+/// handles many `execute` calls (see [`super::protocol`]).
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ToolManifest {
     /// Tool name (must be unique, used by LLM to invoke)
@@ -62,12 +65,24 @@ pub struct ToolManifest {
     /// Environment variables to pass to the tool
     #[serde(default)]
     pub env: std::collections::HashMap<String, String>,
+
+    /// Protocol version: 1 spawns a process per call, 2 keeps a persistent
+    /// worker with progress, cancellation and permission requests
+    #[serde(default = "default_protocol_version")]
+    pub protocol_version: u32,
 }
 
+/// Newest external tool protocol version
+pub const MAX_PROTOCOL_VERSION: u32 = 2;
+
 fn default_requires_permission() -> bool {
     true
 }
 
+fn default_protocol_version() -> u32 {
+    1
+}
+
 fn default_timeout() -> u64 {
     30000 // 30 seconds
 }
@@ -134,6 +149,13 @@ impl ToolManifest {
             ));
         }
 
+        if self.protocol_version == 0 || self.protocol_version > MAX_PROTOCOL_VERSION {
+            return Err(TedError::Config(format!(
+                "Tool manifest: unsupported protocol_version {} (expected 1 or 2)",
+                self.protocol_version
+            )));
+        }
+
         if self.input_schema.schema_type != "object" {
             return Err(TedError::Config(
                 "Tool manifest: input_schema type must be 'object'".to_string(),
@@ -143,6 +165,11 @@ impl ToolManifest {
         Ok(())
     }
 
+    /// Whether the tool runs as a persistent protocol v2 worker.
+    pub fn is_persistent(&self) -> bool {
+        self.protocol_version >= 2
+    }
+
     /// Convert to a ToolDefinition for the LLM.
     pub fn to_tool_definition(&self) -> ToolDefinition {
         ToolDefinition {
@@ -202,6 +229,24 @@ mod tests {
         assert_eq!(manifest.command, vec!["echo", "hello"]);
         assert!(manifest.requires_permission); // default
         assert_eq!(manifest.timeout_ms, 30000); // default
+        assert_eq!(manifest.protocol_version, 1); // default
+        assert!(!manifest.is_persistent());
+    }
+
+    #[test]
+    fn test_manifest_protocol_version() {
+        let v2 = r#"{
+            "name": "worker_tool",
+            "description": "A persistent tool",
+            "command": ["python3", "worker.py"],
+            "input_schema": {"type": "object", "properties": {}},
+            "protocol_version": 2
+        }"#;
+        assert!(ToolManifest::parse(v2).unwrap().is_persistent());
+
+        let v3 = v2.replace("\"protocol_version\": 2", "\"protocol_version\": 3");
+        let err = ToolManifest::parse(&v3).unwrap_err();
+        assert!(err.to_string().contains("unsupported protocol_version 3"));
     }
 
     #[test]
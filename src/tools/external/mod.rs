@@ -54,7 +54,9 @@
 //!
 //! # Protocol
 //!
-//! See the [`protocol`] module for the JSON-RPC format.
+//! See the [`protocol`] module for the JSON-RPC format. Manifests with
+//! `"protocol_version": 2` run as a persistent [`worker`] that serves many
+//! calls and can stream progress, be cancelled, and ask for permission.
 //!
 //! # Memory Integration
 //!
@@ -65,6 +67,7 @@
 pub mod loader;
 pub mod manifest;
 pub mod protocol;
+pub mod worker;
 
 use std::io::{BufRead, BufReader, Write};
 use std::path::PathBuf;
@@ -82,6 +85,7 @@ use crate::tools::{PermissionRequest, Tool, ToolContext, ToolResult};
 pub use loader::ToolLoader;
 pub use manifest::ToolManifest;
 pub use protocol::{RecallPayload, Request, Response};
+pub use worker::Worker;
 
 /// Global request ID counter for JSON-RPC
 static REQUEST_ID: AtomicU64 = AtomicU64::new(1);
@@ -90,12 +94,17 @@ static REQUEST_ID: AtomicU64 = AtomicU64::new(1);
 pub struct ExternalTool {
     /// The tool manifest
     manifest: ToolManifest,
+    /// Persistent worker for protocol v2 tools, started on first call
+    worker: tokio::sync::Mutex<Option<Worker>>,
 }
 
 impl ExternalTool {
     /// Create a new external tool from a manifest.
     pub fn new(manifest: ToolManifest) -> Self {
-        Self { manifest }
+        Self {
+            manifest,
+            worker: tokio::sync::Mutex::new(None),
+        }
     }
 
     /// Get the manifest.
@@ -178,6 +187,33 @@ impl ExternalTool {
         })
     }
 
+    /// Execute a call on the persistent worker, starting it if needed.
+    ///
+    /// The worker is discarded after a failed call (timeout, crash) so the
+    /// next call starts a fresh process.
+    async fn execute_worker(
+        &self,
+        tool_use_id: &str,
+        input: Value,
+        context: &ToolContext,
+    ) -> Result<Response> {
+        let mut guard = self.worker.lock().await;
+        if !guard.as_mut().is_some_and(|worker| worker.is_alive()) {
+            *guard = Some(Worker::spawn(&self.manifest, &context.working_directory)?);
+        }
+        let Some(worker) = guard.as_mut() else {
+            return Err(TedError::ToolExecution("Worker unavailable".to_string()));
+        };
+
+        let request = Request::execute(input, REQUEST_ID.fetch_add(1, Ordering::SeqCst));
+        let timeout = Duration::from_millis(self.manifest.timeout_ms);
+        let result = worker.call(request, timeout, tool_use_id, context).await;
+        if result.is_err() {
+            *guard = None;
+        }
+        result
+    }
+
     /// Process recall data from the response.
     fn process_recall(&self, recall: &RecallPayload, context: &ToolContext) {
         // Emit file read events
@@ -218,8 +254,13 @@ impl Tool for ExternalTool {
         input: Value,
         context: &ToolContext,
     ) -> Result<ToolResult> {
-        // Execute the tool process (blocking, but we're in async context)
-        let response = match self.execute_process(input, &context.working_directory) {
+        let response = if self.manifest.is_persistent() {
+            self.execute_worker(&tool_use_id, input, context).await
+        } else {
+            // Execute the tool process (blocking, but we're in async context)
+            self.execute_process(input, &context.working_directory)
+        };
+        let response = match response {
             Ok(r) => r,
             Err(e) => return Ok(ToolResult::error(tool_use_id, e.to_string())),
         };
@@ -625,6 +666,149 @@ echo '{"jsonrpc": "2.0", "error": {"code": -32000, "message": "Something went wr
         assert!(result.output_text().contains("Something went wrong"));
     }
 
+    /// Write a bash protocol v2 worker and return a tool for it.
+    #[cfg(unix)]
+    fn create_worker_tool(temp_dir: &TempDir) -> ExternalTool {
+        let script_path = temp_dir.path().join("worker.sh");
+        std::fs::write(
+            &script_path,
+            r#"#!/bin/bash
+count=0
+while read -r line; do
+  id=$(echo "$line" | sed -n 's/.*"id":\([0-9]*\).*/\1/p')
+  method=$(echo "$line" | sed -n 's/.*"method":"\([^"]*\)".*/\1/p')
+  if [ "$method" = '$/cancel' ]; then
+    echo "cancelled" > cancelled.txt
+  elif [ "$method" = "execute" ]; then
+    count=$((count+1))
+    if echo "$line" | grep -q '"slow"'; then
+      continue
+    fi
+    if echo "$line" | grep -q '"ask"'; then
+      echo '{"jsonrpc":"2.0","method":"permission/request","params":{"action":"Delete build/"},"id":"p1"}'
+      read -r reply
+      granted=$(echo "$reply" | sed -n 's/.*"granted":\([a-z]*\).*/\1/p')
+      echo "{\"jsonrpc\":\"2.0\",\"result\":{\"output\":\"granted=$granted\"},\"id\":$id}"
+      continue
+    fi
+    echo '{"jsonrpc":"2.0","method":"progress","params":{"message":"working","percent":50}}'
+    echo "{\"jsonrpc\":\"2.0\",\"result\":{\"output\":\"call $count pid $$\"},\"id\":$id}"
+  fi
+done
+"#,
+        )
+        .unwrap();
+
+        let manifest = ToolManifest::parse(&format!(
+            r#"{{
+                "name": "worker_tool",
+                "description": "Worker tool",
+                "command": ["bash", "{}"],
+                "input_schema": {{"type": "object", "properties": {{}}}},
+                "protocol_version": 2,
+                "timeout_ms": 5000
+            }}"#,
+            script_path.display()
+        ))
+        .unwrap();
+        ExternalTool::new(manifest)
+    }
+
+    #[tokio::test]
+    #[cfg(unix)] // Uses bash scripts not available on Windows
+    async fn test_worker_is_reused_and_streams_progress() {
+        let temp_dir = TempDir::new().unwrap();
+        let (progress_tx, mut progress_rx) = tokio::sync::mpsc::unbounded_channel();
+        let context = create_test_context(&temp_dir).with_progress_sender(progress_tx);
+        let tool = create_worker_tool(&temp_dir);
+
+        let first = tool
+            .execute("t1".to_string(), serde_json::json!({}), &context)
+            .await
+            .unwrap();
+        let second = tool
+            .execute("t2".to_string(), serde_json::json!({}), &context)
+            .await
+            .unwrap();
+
+        assert!(first.output_text().starts_with("call 1 pid "));
+        assert!(second.output_text().starts_with("call 2 pid "));
+        let pid = |text: &str| text.rsplit(' ').next().unwrap().to_string();
+        assert_eq!(pid(first.output_text()), pid(second.output_text()));
+
+        let progress = progress_rx.recv().await.unwrap();
+        assert_eq!(progress.tool_use_id, "t1");
+        assert_eq!(progress.tool_name, "worker_tool");
+        assert_eq!(progress.message, "working");
+        assert_eq!(progress.percent, Some(50));
+    }
+
+    #[tokio::test]
+    #[cfg(unix)] // Uses bash scripts not available on Windows
+    async fn test_worker_permission_request() {
+        let temp_dir = TempDir::new().unwrap();
+        let tool = create_worker_tool(&temp_dir);
+
+        // Trust mode grants without asking
+        let trusted = create_test_context(&temp_dir);
+        let result = tool
+            .execute("t1".to_string(), serde_json::json!({"ask": true}), &trusted)
+            .await
+            .unwrap();
+        assert_eq!(result.output_text(), "granted=true");
+
+        // Otherwise the interactive user decides
+        let (question_tx, mut question_rx) = tokio::sync::mpsc::unbounded_channel();
+        let untrusted = ToolContext::new(
+            temp_dir.path().to_path_buf(),
+            Some(temp_dir.path().to_path_buf()),
+            Uuid::new_v4(),
+            false,
+        )
+        .with_question_sender(question_tx);
+        tokio::spawn(async move {
+            let pending: crate::tools::PendingQuestion = question_rx.recv().await.unwrap();
+            assert!(pending.question.question.contains("Delete build/"));
+            pending.answer(Some("Deny".to_string()));
+        });
+        let result = tool
+            .execute(
+                "t2".to_string(),
+                serde_json::json!({"ask": true}),
+                &untrusted,
+            )
+            .await
+            .unwrap();
+        assert_eq!(result.output_text(), "granted=false");
+    }
+
+    #[tokio::test]
+    #[cfg(unix)] // Uses bash scripts not available on Windows
+    async fn test_worker_cancelled_call_sends_cancel() {
+        let temp_dir = TempDir::new().unwrap();
+        let context = create_test_context(&temp_dir);
+        let tool = create_worker_tool(&temp_dir);
+
+        let abandoned = tokio::time::timeout(
+            Duration::from_millis(300),
+            tool.execute(
+                "t1".to_string(),
+                serde_json::json!({"slow": true}),
+                &context,
+            ),
+        )
+        .await;
+        assert!(abandoned.is_err());
+
+        // The worker survives the cancellation and serves the next call
+        let result = tool
+            .execute("t2".to_string(), serde_json::json!({}), &context)
+            .await
+            .unwrap();
+        assert!(result.output_text().starts_with("call 2 pid "));
+        assert!(temp_dir.path().join("cancelled.txt").exists());
+    }
+
     #[test]
     fn test_load_external_tools_from_empty_dir() {
         let temp_dir = TempDir::new().unwrap();
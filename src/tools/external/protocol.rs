@@ -46,6 +46,24 @@
 //!   "id": 1
 //! }
 //! ```
+//!
+//! # Protocol v2 (persistent workers)
+//!
+//! With `"protocol_version": 2` in the manifest, Ted starts the tool once and
+//! keeps it running. Each call is an `execute` request as above; the worker
+//! answers with a response carrying the same `id` and must not exit. Calls to
+//! one worker are never concurrent. While a call runs the worker may also
+//! send:
+//!
+//! ```json
+//! {"jsonrpc": "2.0", "method": "progress", "params": {"message": "Indexing", "percent": 40}}
+//! {"jsonrpc": "2.0", "method": "permission/request", "params": {"action": "Delete build/", "affected_paths": ["build"], "is_destructive": true}, "id": "p1"}
+//! ```
+//!
+//! Ted answers a permission request with `{"result": {"granted": bool}}` and
+//! the same `id`. When the user cancels a call, Ted sends
+//! `{"jsonrpc": "2.0", "method": "$/cancel", "params": {"id": 1}}`; the
+//! worker should stop that call and may still respond to it.
 
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
@@ -80,6 +98,135 @@ impl Request {
     }
 }
 
+/// Method for progress notifications from a v2 worker
+pub const METHOD_PROGRESS: &str = "progress";
+/// Method for permission requests from a v2 worker
+pub const METHOD_PERMISSION_REQUEST: &str = "permission/request";
+/// Method for cancelling an in-flight v2 call
+pub const METHOD_CANCEL: &str = "$/cancel";
+
+/// JSON-RPC notification sent to a v2 worker (no response expected).
+#[derive(Debug, Clone, Serialize)]
+pub struct Notification {
+    pub jsonrpc: &'static str,
+    pub method: &'static str,
+    pub params: serde_json::Value,
+}
+
+impl Notification {
+    /// Create a `$/cancel` notification for an in-flight request.
+    pub fn cancel(id: u64) -> Self {
+        Self {
+            jsonrpc: "2.0",
+            method: METHOD_CANCEL,
+            params: serde_json::json!({ "id": id }),
+        }
+    }
+
+    /// Serialize to JSON string.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap_or_else(|_| "{}".to_string())
+    }
+}
+
+/// JSON-RPC reply from Ted to a request made by a v2 worker.
+#[derive(Debug, Clone, Serialize)]
+pub struct Reply {
+    pub jsonrpc: &'static str,
+    pub result: serde_json::Value,
+    pub id: serde_json::Value,
+}
+
+impl Reply {
+    /// Reply to a `permission/request`.
+    pub fn permission(id: serde_json::Value, granted: bool) -> Self {
+        Self {
+            jsonrpc: "2.0",
+            result: serde_json::json!({ "granted": granted }),
+            id,
+        }
+    }
+
+    /// Serialize to JSON string.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap_or_else(|_| "{}".to_string())
+    }
+}
+
+/// A message read from a v2 worker's stdout.
+#[derive(Debug, Clone)]
+pub enum WorkerMessage {
+    /// Response to an `execute` request
+    Response(Response),
+    /// Notification from the worker (e.g. `progress`)
+    Notification {
+        method: String,
+        params: serde_json::Value,
+    },
+    /// Request from the worker that needs a reply (e.g. `permission/request`)
+    Request {
+        id: serde_json::Value,
+        method: String,
+        params: serde_json::Value,
+    },
+}
+
+impl WorkerMessage {
+    /// Parse a line of worker output.
+    pub fn parse(json: &str) -> Result<Self, serde_json::Error> {
+        let value: serde_json::Value = serde_json::from_str(json)?;
+        match value.get("method").and_then(|m| m.as_str()) {
+            Some(method) => {
+                let method = method.to_string();
+                let params = value
+                    .get("params")
+                    .cloned()
+                    .unwrap_or(serde_json::Value::Null);
+                match value.get("id") {
+                    Some(id) if !id.is_null() => Ok(Self::Request {
+                        id: id.clone(),
+                        method,
+                        params,
+                    }),
+                    _ => Ok(Self::Notification { method, params }),
+                }
+            }
+            None => serde_json::from_value(value).map(Self::Response),
+        }
+    }
+}
+
+/// Params of a `progress` notification.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ProgressParams {
+    /// Human-readable progress message
+    #[serde(default)]
+    pub message: String,
+    /// Completion percentage (0-100)
+    #[serde(default)]
+    pub percent: Option<f64>,
+}
+
+impl ProgressParams {
+    /// Percentage clamped to 0-100.
+    pub fn percent(&self) -> Option<u8> {
+        self.percent.map(|p| p.clamp(0.0, 100.0).round() as u8)
+    }
+}
+
+/// Params of a `permission/request` from a worker.
+#[derive(Debug, Clone, Deserialize)]
+pub struct PermissionParams {
+    /// What the tool wants to do
+    pub action: String,
+    /// Paths affected by the action
+    #[serde(default)]
+    pub affected_paths: Vec<String>,
+    /// Whether the action is destructive
+    #[serde(default)]
+    pub is_destructive: bool,
+}
+
 /// JSON-RPC response from external tool.
 #[derive(Debug, Clone, Deserialize)]
 pub struct Response {
@@ -384,6 +531,46 @@ mod tests {
         assert_eq!(cloned.params, request.params);
     }
 
+    #[test]
+    fn test_worker_message_parse() {
+        let response =
+            WorkerMessage::parse(r#"{"jsonrpc":"2.0","result":{"output":"ok"},"id":7}"#).unwrap();
+        assert!(matches!(response, WorkerMessage::Response(r) if r.id == 7));
+
+        let progress = WorkerMessage::parse(
+            r#"{"jsonrpc":"2.0","method":"progress","params":{"message":"Half","percent":50.4}}"#,
+        )
+        .unwrap();
+        match progress {
+            WorkerMessage::Notification { method, params } => {
+                assert_eq!(method, METHOD_PROGRESS);
+                let params: ProgressParams = serde_json::from_value(params).unwrap();
+                assert_eq!(params.message, "Half");
+                assert_eq!(params.percent(), Some(50));
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+
+        let request = WorkerMessage::parse(
+            r#"{"jsonrpc":"2.0","method":"permission/request","params":{"action":"rm -rf build"},"id":"p1"}"#,
+        )
+        .unwrap();
+        assert!(matches!(request, WorkerMessage::Request { id, .. } if id == "p1"));
+
+        assert!(WorkerMessage::parse("not json").is_err());
+    }
+
+    #[test]
+    fn test_cancel_and_reply_json() {
+        let cancel = Notification::cancel(9).to_json();
+        assert!(cancel.contains("\"method\":\"$/cancel\""));
+        assert!(cancel.contains("\"id\":9"));
+
+        let reply = Reply::permission(serde_json::json!("p1"), true).to_json();
+        assert!(reply.contains("\"granted\":true"));
+        assert!(reply.contains("\"id\":\"p1\""));
+    }
+
     #[test]
     fn test_response_clone() {
         let json = r#"{
@@ -0,0 +1,232 @@
+// SPDX-License-Identifier: AGPL-3.0-or-later
+// Copyright (C) 2025 Blackman Artificial Intelligence Technologies Inc.
+
+//! Persistent worker processes for protocol v2 external tools
+//!
+//! A worker is spawned on first use and kept alive across calls. Stdout is
+//! read by a background task that forwards parsed [`WorkerMessage`]s; stdin
+//! is fed by a writer task so a `$/cancel` can be queued from a `Drop` impl
+//! when a call is abandoned mid-flight.
+
+use std::path::Path;
+use std::process::Stdio;
+use std::time::Duration;
+
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::process::{Child, Command};
+use tokio::sync::mpsc;
+
+use crate::error::{Result, TedError};
+use crate::tools::{PermissionRequest, ToolContext};
+
+use super::manifest::ToolManifest;
+use super::protocol::{
+    Notification, PermissionParams, ProgressParams, Reply, Request, Response, WorkerMessage,
+    METHOD_PERMISSION_REQUEST, METHOD_PROGRESS,
+};
+
+/// A running protocol v2 worker process.
+pub struct Worker {
+    name: String,
+    child: Child,
+    outgoing: mpsc::UnboundedSender<String>,
+    incoming: mpsc::UnboundedReceiver<WorkerMessage>,
+}
+
+impl Worker {
+    /// Spawn the worker described by `manifest`.
+    pub fn spawn(manifest: &ToolManifest, working_dir: &Path) -> Result<Self> {
+        let command = manifest.expand_command();
+        let (program, args) = command
+            .split_first()
+            .ok_or_else(|| TedError::ToolExecution("Empty command".to_string()))?;
+
+        let mut child = Command::new(program)
+            .args(args)
+            .current_dir(manifest.expand_working_directory(working_dir))
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .envs(&manifest.env)
+            .kill_on_drop(true)
+            .spawn()
+            .map_err(|e| {
+                TedError::ToolExecution(format!(
+                    "Failed to spawn external tool '{}': {}",
+                    manifest.name, e
+                ))
+            })?;
+
+        let mut stdin = child
+            .stdin
+            .take()
+            .ok_or_else(|| TedError::ToolExecution("Worker stdin unavailable".to_string()))?;
+        let stdout = child
+            .stdout
+            .take()
+            .ok_or_else(|| TedError::ToolExecution("Worker stdout unavailable".to_string()))?;
+
+        let (outgoing, mut outgoing_rx) = mpsc::unbounded_channel::<String>();
+        tokio::spawn(async move {
+            while let Some(line) = outgoing_rx.recv().await {
+                if stdin.write_all(line.as_bytes()).await.is_err()
+                    || stdin.write_all(b"\n").await.is_err()
+                    || stdin.flush().await.is_err()
+                {
+                    break;
+                }
+            }
+        });
+
+        let (incoming_tx, incoming) = mpsc::unbounded_channel();
+        let name = manifest.name.clone();
+        let reader_name = name.clone();
+        tokio::spawn(async move {
+            let mut lines = BufReader::new(stdout).lines();
+            while let Ok(Some(line)) = lines.next_line().await {
+                match WorkerMessage::parse(&line) {
+                    Ok(message) => {
+                        if incoming_tx.send(message).is_err() {
+                            break;
+                        }
+                    }
+                    Err(_) => tracing::debug!("[{}] {}", reader_name, line),
+                }
+            }
+        });
+
+        if let Some(stderr) = child.stderr.take() {
+            let stderr_name = name.clone();
+            tokio::spawn(async move {
+                let mut lines = BufReader::new(stderr).lines();
+                while let Ok(Some(line)) = lines.next_line().await {
+                    tracing::debug!("[{} stderr] {}", stderr_name, line);
+                }
+            });
+        }
+
+        Ok(Self {
+            name,
+            child,
+            outgoing,
+            incoming,
+        })
+    }
+
+    /// Whether the process is still running.
+    pub fn is_alive(&mut self) -> bool {
+        matches!(self.child.try_wait(), Ok(None))
+    }
+
+    fn send(&self, line: String) -> Result<()> {
+        self.outgoing.send(line).map_err(|_| {
+            TedError::ToolExecution(format!("External tool '{}' is not running", self.name))
+        })
+    }
+
+    /// Run one `execute` call, relaying progress and permission requests.
+    ///
+    /// If the returned future is dropped before the response arrives, a
+    /// `$/cancel` notification is sent for the call.
+    pub async fn call(
+        &mut self,
+        request: Request,
+        timeout: Duration,
+        tool_use_id: &str,
+        context: &ToolContext,
+    ) -> Result<Response> {
+        let id = request.id;
+        self.send(request.to_json())?;
+        let mut cancel = CancelOnDrop {
+            outgoing: self.outgoing.clone(),
+            id,
+            armed: true,
+        };
+
+        let deadline = tokio::time::Instant::now() + timeout;
+        loop {
+            let message = match tokio::time::timeout_at(deadline, self.incoming.recv()).await {
+                Ok(Some(message)) => message,
+                Ok(None) => {
+                    cancel.armed = false;
+                    return Err(TedError::ToolExecution(format!(
+                        "External tool '{}' exited before responding",
+                        self.name
+                    )));
+                }
+                Err(_) => {
+                    return Err(TedError::ToolExecution(format!(
+                        "External tool timed out after {:?}",
+                        timeout
+                    )));
+                }
+            };
+
+            match message {
+                WorkerMessage::Response(response) if response.id == id => {
+                    cancel.armed = false;
+                    return Ok(response);
+                }
+                // Late response to an earlier, cancelled call
+                WorkerMessage::Response(_) => {}
+                WorkerMessage::Notification { method, params } if method == METHOD_PROGRESS => {
+                    let progress: ProgressParams =
+                        serde_json::from_value(params).unwrap_or_default();
+                    context.emit_tool_progress(
+                        tool_use_id,
+                        &self.name,
+                        progress.message.clone(),
+                        progress.percent(),
+                    );
+                }
+                WorkerMessage::Notification { .. } => {}
+                WorkerMessage::Request { id, method, params }
+                    if method == METHOD_PERMISSION_REQUEST =>
+                {
+                    let granted = match serde_json::from_value::<PermissionParams>(params) {
+                        Ok(params) => {
+                            context
+                                .request_permission(&PermissionRequest {
+                                    tool_name: self.name.clone(),
+                                    action_description: params.action,
+                                    affected_paths: params.affected_paths,
+                                    is_destructive: params.is_destructive,
+                                })
+                                .await
+                        }
+                        Err(_) => false,
+                    };
+                    self.send(Reply::permission(id, granted).to_json())?;
+                }
+                WorkerMessage::Request { id, method, .. } => {
+                    self.send(
+                        serde_json::json!({
+                            "jsonrpc": "2.0",
+                            "error": {
+                                "code": super::protocol::error_codes::METHOD_NOT_FOUND,
+                                "message": format!("Unknown method: {}", method),
+                            },
+                            "id": id,
+                        })
+                        .to_string(),
+                    )?;
+                }
+            }
+        }
+    }
+}
+
+/// Sends `$/cancel` for a call that is abandoned before it completes.
+struct CancelOnDrop {
+    outgoing: mpsc::UnboundedSender<String>,
+    id: u64,
+    armed: bool,
+}
+
+impl Drop for CancelOnDrop {
+    fn drop(&mut self) {
+        if self.armed {
+            let _ = self.outgoing.send(Notification::cancel(self.id).to_json());
+        }
+    }
+}
@@ -19,12 +19,20 @@ pub mod definition;
 pub mod executor;
 pub mod external;
 pub mod permission;
+pub mod plan_mode;
 pub mod policy;
+pub mod post_edit;
+pub mod read_tracker;
+pub mod validation;
 
 pub use definition::*;
 pub use executor::*;
 pub use permission::*;
+pub use plan_mode::PlanMode;
 pub use policy::*;
+pub use post_edit::{PostEditPipeline, PostEditReport};
+pub use read_tracker::{FileReadTracker, ReadCheck};
+pub use validation::{validate_tool_input, InputValidationError};
 
 use async_trait::async_trait;
 use serde_json::Value;
@@ -32,6 +40,7 @@ use std::collections::HashMap;
 use std::path::PathBuf;
 use std::sync::Arc;
 
+use crate::config::TedIgnore;
 use crate::error::Result;
 use crate::indexer::RecallSender;
 use crate::llm::provider::{LlmProvider, ToolDefinition};
@@ -50,6 +59,54 @@ pub struct ShellOutputEvent {
 /// Sender for shell output events
 pub type ShellOutputSender = mpsc::UnboundedSender<ShellOutputEvent>;
 
+/// A structured clarifying question from the `ask_user` tool
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct UserQuestion {
+    /// Identifier used to match the answer to the question
+    pub id: String,
+    /// Question text shown to the user
+    pub question: String,
+    /// Multiple-choice options (empty for free-text only questions)
+    #[serde(default)]
+    pub options: Vec<String>,
+    /// Whether the user may type an answer not in `options`
+    pub allow_free_text: bool,
+    /// Answer to use when no interactive user is available
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default: Option<String>,
+}
+
+/// A question waiting for the frontend to answer it.
+///
+/// Answering with `None` means the user dismissed the question.
+#[derive(Debug)]
+pub struct PendingQuestion {
+    pub question: UserQuestion,
+    pub respond_to: tokio::sync::oneshot::Sender<Option<String>>,
+}
+
+impl PendingQuestion {
+    /// Send the user's answer (or `None` if dismissed) back to the tool.
+    pub fn answer(self, answer: Option<String>) {
+        let _ = self.respond_to.send(answer);
+    }
+}
+
+/// Sender for questions that need an interactive answer
+pub type QuestionSender = mpsc::UnboundedSender<PendingQuestion>;
+
+/// Progress reported by a running tool
+#[derive(Debug, Clone, PartialEq)]
+pub struct ToolProgressEvent {
+    pub tool_use_id: String,
+    pub tool_name: String,
+    pub message: String,
+    pub percent: Option<u8>,
+}
+
+/// Sender for tool progress events
+pub type ToolProgressSender = mpsc::UnboundedSender<ToolProgressEvent>;
+
 /// Mode for file change sets
 #[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 #[serde(rename_all = "snake_case")]
@@ -110,6 +167,14 @@ pub struct ToolContext {
     shell_output_sender: Option<ShellOutputSender>,
     /// Files already provided in the context (to avoid re-reading)
     pub files_in_context: Vec<String>,
+    /// Optional tracker of file content hashes for stale-read protection
+    read_tracker: Option<FileReadTracker>,
+    /// `.tedignore` rules for paths the model may never read or list
+    tedignore: TedIgnore,
+    /// Optional sender for questions to an interactive user
+    question_sender: Option<QuestionSender>,
+    /// Optional sender for progress from long-running tools
+    progress_sender: Option<ToolProgressSender>,
 }
 
 impl std::fmt::Debug for ToolContext {
@@ -125,18 +190,26 @@ impl std::fmt::Debug for ToolContext {
                 &self.shell_output_sender.is_some(),
             )
             .field("files_in_context", &self.files_in_context.len())
+            .field("has_read_tracker", &self.read_tracker.is_some())
+            .field("has_tedignore_rules", &!self.tedignore.is_empty())
+            .field("has_question_sender", &self.question_sender.is_some())
+            .field("has_progress_sender", &self.progress_sender.is_some())
             .finish()
     }
 }
 
 impl ToolContext {
     /// Create a new tool context.
+    ///
+    /// `.tedignore` rules are loaded for the project root (or the working
+    /// directory when there is no project).
     pub fn new(
         working_directory: PathBuf,
         project_root: Option<PathBuf>,
         session_id: uuid::Uuid,
         trust_mode: bool,
     ) -> Self {
+        let tedignore = TedIgnore::load(project_root.as_ref().unwrap_or(&working_directory));
         Self {
             working_directory,
             project_root,
@@ -145,6 +218,10 @@ impl ToolContext {
             recall_sender: None,
             shell_output_sender: None,
             files_in_context: Vec::new(),
+            read_tracker: None,
+            tedignore,
+            question_sender: None,
+            progress_sender: None,
         }
     }
 
@@ -178,6 +255,172 @@ impl ToolContext {
         self
     }
 
+    /// Enable stale-read protection using the given tracker.
+    ///
+    /// With a tracker set, `file_edit` rejects edits to files that were never
+    /// read or that changed on disk since the last read.
+    pub fn with_read_tracker(mut self, tracker: FileReadTracker) -> Self {
+        self.read_tracker = Some(tracker);
+        self
+    }
+
+    /// Get the read tracker, if stale-read protection is enabled.
+    pub fn read_tracker(&self) -> Option<&FileReadTracker> {
+        self.read_tracker.as_ref()
+    }
+
+    /// Record that the model has seen `content` for `path`.
+    pub fn record_file_content(&self, path: &std::path::Path, content: &str) {
+        if let Some(tracker) = &self.read_tracker {
+            tracker.record(path, content);
+        }
+    }
+
+    /// Verify the model's view of `path` is current before mutating it.
+    ///
+    /// Returns an error message for the model when the file was never read
+    /// or changed since the last read. Files provided in the project context
+    /// count as read.
+    pub fn check_read_is_current(
+        &self,
+        path: &std::path::Path,
+        path_str: &str,
+        current_content: &str,
+    ) -> Option<String> {
+        let tracker = self.read_tracker.as_ref()?;
+        match tracker.check(path, current_content) {
+            ReadCheck::Fresh => None,
+            ReadCheck::NeverRead if self.is_file_in_context(path_str) => None,
+            ReadCheck::NeverRead => Some(format!(
+                "File has not been read yet: {}. Use file_read to see its current content before editing it.",
+                path.display()
+            )),
+            ReadCheck::Stale => Some(format!(
+                "File has changed on disk since it was last read: {}. It may have been modified by the user, a formatter, or another agent. Use file_read to re-read it before editing.",
+                path.display()
+            )),
+        }
+    }
+
+    /// Replace the `.tedignore` rules.
+    pub fn with_tedignore(mut self, tedignore: TedIgnore) -> Self {
+        self.tedignore = tedignore;
+        self
+    }
+
+    /// Get the `.tedignore` rules.
+    pub fn tedignore(&self) -> &TedIgnore {
+        &self.tedignore
+    }
+
+    /// Whether `path` is hidden from the model by `.tedignore`.
+    pub fn is_path_ignored(&self, path: &std::path::Path) -> bool {
+        self.tedignore.is_ignored(path)
+    }
+
+    /// Error message for the model when `path` is hidden by `.tedignore`.
+    pub fn check_path_not_ignored(&self, path: &std::path::Path) -> Option<String> {
+        self.tedignore.check(path)
+    }
+
+    /// Set the sender used to ask the interactive user questions.
+    pub fn with_question_sender(mut self, sender: QuestionSender) -> Self {
+        self.question_sender = Some(sender);
+        self
+    }
+
+    /// Whether an interactive user can answer `ask_user` questions.
+    pub fn can_ask_user(&self) -> bool {
+        self.question_sender
+            .as_ref()
+            .is_some_and(|sender| !sender.is_closed())
+    }
+
+    /// Ask the interactive user a question and wait for the answer.
+    ///
+    /// Returns `None` when no frontend is attached, the frontend went away,
+    /// or the user dismissed the question.
+    pub async fn ask_user(&self, question: UserQuestion) -> Option<String> {
+        let sender = self.question_sender.as_ref()?;
+        let (respond_to, response) = tokio::sync::oneshot::channel();
+        sender
+            .send(PendingQuestion {
+                question,
+                respond_to,
+            })
+            .ok()?;
+        response.await.ok().flatten()
+    }
+
+    /// Ask the user to approve an action a tool wants to take mid-call.
+    ///
+    /// Trust mode approves everything. Otherwise the question goes to the
+    /// interactive frontend when there is one, or to a terminal prompt.
+    pub async fn request_permission(&self, request: &PermissionRequest) -> bool {
+        if self.trust_mode {
+            return true;
+        }
+
+        if self.can_ask_user() {
+            let mut question = format!(
+                "Tool '{}' wants to: {}",
+                request.tool_name, request.action_description
+            );
+            if !request.affected_paths.is_empty() {
+                question.push_str(&format!(
+                    " (affects: {})",
+                    request.affected_paths.join(", ")
+                ));
+            }
+            let answer = self
+                .ask_user(UserQuestion {
+                    id: format!("permission-{}", uuid::Uuid::new_v4()),
+                    question,
+                    options: vec!["Allow".to_string(), "Deny".to_string()],
+                    allow_free_text: false,
+                    default: None,
+                })
+                .await;
+            return answer.is_some_and(|a| a.eq_ignore_ascii_case("allow"));
+        }
+
+        let request = request.clone();
+        let response = tokio::task::spawn_blocking(move || {
+            PermissionManager::new().request_permission(&request)
+        })
+        .await;
+        matches!(
+            response,
+            Ok(Ok(PermissionResponse::Allow
+                | PermissionResponse::AllowAll
+                | PermissionResponse::TrustAll))
+        )
+    }
+
+    /// Set the sender for progress reported by long-running tools.
+    pub fn with_progress_sender(mut self, sender: ToolProgressSender) -> Self {
+        self.progress_sender = Some(sender);
+        self
+    }
+
+    /// Report progress for a running tool call.
+    pub fn emit_tool_progress(
+        &self,
+        tool_use_id: &str,
+        tool_name: &str,
+        message: String,
+        percent: Option<u8>,
+    ) {
+        if let Some(sender) = &self.progress_sender {
+            let _ = sender.send(ToolProgressEvent {
+                tool_use_id: tool_use_id.to_string(),
+                tool_name: tool_name.to_string(),
+                message,
+                percent,
+            });
+        }
+    }
+
     /// Emit shell output (for streaming command output).
     pub fn emit_shell_output(
         &self,
@@ -377,13 +620,20 @@ impl ToolRegistry {
         registry.register(Arc::new(builtin::ShellTool::new()));
         registry.register(Arc::new(builtin::GlobTool));
         registry.register(Arc::new(builtin::GrepTool));
+        registry.register(Arc::new(builtin::CodeSearchTool::new()));
+        registry.register(Arc::new(builtin::NotebookReadTool));
+        registry.register(Arc::new(builtin::NotebookEditTool));
         registry.register(Arc::new(builtin::PlanUpdateTool));
+        registry.register(Arc::new(builtin::AskUserTool));
+        registry.register(Arc::new(builtin::HttpRequestTool));
 
         // Database tools
         registry.register(Arc::new(builtin::DatabaseInitTool));
         registry.register(Arc::new(builtin::DatabaseMigrateTool));
-        registry.register(Arc::new(builtin::DatabaseQueryTool));
         registry.register(Arc::new(builtin::DatabaseSeedTool));
+        registry.register(Arc::new(builtin::SqliteSchemaTool));
+        registry.register(Arc::new(builtin::SqliteQueryTool));
+        registry.register(Arc::new(builtin::SqliteExecuteTool));
 
         // Beads (task tracking) tools
         registry.register(Arc::new(builtin::BeadsAddTool));
@@ -752,7 +1002,7 @@ mod tests {
     #[test]
     fn test_tool_registry_len() {
         let registry = ToolRegistry::with_builtins();
-        assert_eq!(registry.len(), 15); // 15 built-in tools (11 core + 4 database)
+        assert_eq!(registry.len(), 22); // 22 built-in tools (16 core + 6 database)
     }
 
     #[test]
@@ -0,0 +1,185 @@
+// SPDX-License-Identifier: AGPL-3.0-or-later
+// Copyright (C) 2025 Blackman Artificial Intelligence Technologies Inc.
+
+//! Plan mode enforcement
+//!
+//! While plan mode is on, the executor only runs tool calls that leave the
+//! workspace untouched, so the model can explore and write a plan with
+//! `plan_update` but cannot start implementing it. The switch is shared
+//! between the executor and the chat loop, which turns it off when the user
+//! approves the plan.
+
+use serde_json::Value;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use super::builtin::ShellTool;
+use crate::plans::Plan;
+
+/// Tools that never change the workspace
+const READ_ONLY_TOOLS: &[&str] = &[
+    "file_read",
+    "glob",
+    "grep",
+    "code_search",
+    "notebook_read",
+    "database_schema",
+    "database_query",
+    "ask_user",
+    "plan_update",
+    "beads_list",
+];
+
+/// Context pinned to the conversation while plan mode is on
+pub const PLAN_MODE_CONTEXT: &str = "## Plan mode\n\
+You are in plan mode. Tools that change files, run mutating shell commands or write to databases are disabled until the user approves a plan.\n\
+1. Explore the codebase with read-only tools (file_read, glob, grep, code_search, read-only shell commands).\n\
+2. Write the plan with plan_update (action 'create', then 'update' as it evolves), as a markdown checklist of concrete tasks.\n\
+3. Summarise the plan and ask the user to review it. They approve it with /plan-mode approve.";
+
+/// Context pinned to the conversation once a plan is approved
+pub fn approved_plan_context(plan: &Plan) -> String {
+    format!(
+        "## Approved plan: {}\n\
+The user approved this plan (plan_id {}). Implement it task by task and tick off tasks with plan_update as you complete them.\n\n{}",
+        plan.info.title,
+        plan.info.id,
+        plan.content.trim()
+    )
+}
+
+/// Shared plan mode switch
+#[derive(Debug, Clone, Default)]
+pub struct PlanMode {
+    enabled: Arc<AtomicBool>,
+}
+
+impl PlanMode {
+    pub fn new(enabled: bool) -> Self {
+        Self {
+            enabled: Arc::new(AtomicBool::new(enabled)),
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.load(Ordering::SeqCst)
+    }
+
+    pub fn set_enabled(&self, enabled: bool) {
+        self.enabled.store(enabled, Ordering::SeqCst);
+    }
+
+    /// Why the call can't run in plan mode, or `None` if it may run
+    pub fn denial(&self, tool_name: &str, input: &Value) -> Option<String> {
+        if !self.is_enabled() || is_read_only_call(tool_name, input) {
+            return None;
+        }
+        let reason = match tool_name {
+            "shell" => "this shell command may change the workspace. Only read-only commands such as ls, cat, grep, find and git status/log/diff are allowed.".to_string(),
+            "http_request" => "only GET, HEAD and OPTIONS requests are allowed.".to_string(),
+            "spawn_agent" => "only read-only agents (explore, plan, review) can be spawned.".to_string(),
+            _ => format!("{} can change the workspace.", tool_name),
+        };
+        Some(format!(
+            "Plan mode is on: {} Explore with read-only tools and write the plan with plan_update. Changes are allowed once the user approves the plan.",
+            reason
+        ))
+    }
+}
+
+/// Whether a tool call leaves the workspace untouched
+fn is_read_only_call(tool_name: &str, input: &Value) -> bool {
+    match tool_name {
+        "shell" => input["command"]
+            .as_str()
+            .is_some_and(ShellTool::is_read_only_command),
+        "http_request" => matches!(
+            input["method"]
+                .as_str()
+                .unwrap_or("GET")
+                .to_uppercase()
+                .as_str(),
+            "GET" | "HEAD" | "OPTIONS"
+        ),
+        "spawn_agent" => input["agent_type"]
+            .as_str()
+            .and_then(crate::agents::get_agent_type)
+            .is_some_and(|agent| !agent.can_write && !agent.can_execute),
+        name => READ_ONLY_TOOLS.contains(&name),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_plan_mode_off_allows_everything() {
+        let mode = PlanMode::default();
+        assert!(mode
+            .denial("file_write", &json!({"path": "a", "content": ""}))
+            .is_none());
+    }
+
+    #[test]
+    fn test_plan_mode_blocks_mutating_tools() {
+        let mode = PlanMode::new(true);
+        for tool in [
+            "file_write",
+            "file_edit",
+            "propose_file_changes",
+            "notebook_edit",
+            "database_execute",
+            "database_migrate",
+            "beads_add",
+            "some_external_tool",
+        ] {
+            let denial = mode.denial(tool, &json!({})).unwrap();
+            assert!(denial.starts_with("Plan mode is on"), "{}", tool);
+        }
+        assert!(mode.denial("file_read", &json!({"path": "a"})).is_none());
+        assert!(mode.denial("plan_update", &json!({})).is_none());
+    }
+
+    #[test]
+    fn test_plan_mode_conditional_tools() {
+        let mode = PlanMode::new(true);
+        assert!(mode
+            .denial("shell", &json!({"command": "git diff"}))
+            .is_none());
+        assert!(mode
+            .denial("shell", &json!({"command": "cargo build"}))
+            .unwrap()
+            .contains("read-only commands"));
+        assert!(mode
+            .denial("http_request", &json!({"url": "http://localhost"}))
+            .is_none());
+        assert!(mode
+            .denial("http_request", &json!({"method": "post"}))
+            .is_some());
+        assert!(mode
+            .denial("spawn_agent", &json!({"agent_type": "explore"}))
+            .is_none());
+        assert!(mode
+            .denial("spawn_agent", &json!({"agent_type": "implement"}))
+            .is_some());
+    }
+
+    #[test]
+    fn test_approved_plan_context_includes_plan() {
+        let plan = Plan::with_content("Add caching", "- [ ] Add cache layer\n");
+        let context = approved_plan_context(&plan);
+        assert!(context.starts_with("## Approved plan: Add caching"));
+        assert!(context.contains(&plan.info.id.to_string()));
+        assert!(context.ends_with("- [ ] Add cache layer"));
+    }
+
+    #[test]
+    fn test_plan_mode_switch_is_shared() {
+        let mode = PlanMode::new(true);
+        let handle = mode.clone();
+        handle.set_enabled(false);
+        assert!(!mode.is_enabled());
+    }
+}
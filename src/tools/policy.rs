@@ -9,6 +9,9 @@
 //!
 //! Rules are evaluated in source order, with project rules appended after user
 //! rules so project policy can override user policy.
+//!
+//! `http_request` actions are limited to loopback hosts unless a rule with a
+//! matching `hosts` pattern covers the target host.
 
 use std::path::{Path, PathBuf};
 
@@ -39,6 +42,8 @@ fn default_policy_effect() -> PolicyEffect {
 pub enum PolicySource {
     User(PathBuf),
     Project(PathBuf),
+    /// Built-in default, e.g. the localhost restriction for `http_request`.
+    Builtin,
 }
 
 /// A concrete rule match result.
@@ -81,6 +86,10 @@ struct PermissionRule {
     #[serde(default)]
     paths: Vec<String>,
 
+    /// Glob patterns for network hosts (e.g. `api.example.com`, `*.internal`).
+    #[serde(default)]
+    hosts: Vec<String>,
+
     /// Optional destructive flag matcher.
     #[serde(default)]
     destructive: Option<bool>,
@@ -223,50 +232,53 @@ impl PermissionPolicy {
         affected_paths: &[String],
         is_destructive: bool,
     ) -> Option<PolicyMatch> {
-        let mut matched: Option<PolicyMatch> = None;
         let command_text = extract_command_text(action_description).unwrap_or(action_description);
-
-        for entry in &self.rules {
-            if !matches_patterns(&entry.rule.tools, tool_name) {
-                continue;
-            }
-            if !matches_patterns(&entry.rule.commands, command_text) {
-                continue;
-            }
-            if !matches_paths(&entry.rule.paths, affected_paths) {
-                continue;
-            }
-            if !matches_destructive(entry.rule.destructive, is_destructive) {
-                continue;
+        let host = extract_request_host(action_description);
+        let mut host_rule_matched = false;
+
+        let mut evaluate_rules = |entries: &[PermissionRuleEntry]| {
+            let mut matched: Option<PolicyMatch> = None;
+            for entry in entries {
+                if !matches_patterns(&entry.rule.tools, tool_name) {
+                    continue;
+                }
+                if !matches_patterns(&entry.rule.commands, command_text) {
+                    continue;
+                }
+                if !matches_paths(&entry.rule.paths, affected_paths) {
+                    continue;
+                }
+                if !matches_hosts(&entry.rule.hosts, host.as_deref()) {
+                    continue;
+                }
+                if !matches_destructive(entry.rule.destructive, is_destructive) {
+                    continue;
+                }
+
+                host_rule_matched |= !entry.rule.hosts.is_empty();
+                matched = Some(PolicyMatch {
+                    effect: entry.rule.effect,
+                    reason: entry.rule.reason.clone(),
+                    source: entry.source.clone(),
+                });
             }
+            matched
+        };
 
-            matched = Some(PolicyMatch {
-                effect: entry.rule.effect,
-                reason: entry.rule.reason.clone(),
-                source: entry.source.clone(),
-            });
-        }
+        let matched = evaluate_rules(&self.rules);
+        let matched_lock = evaluate_rules(&self.lock_rules);
 
-        let mut matched_lock: Option<PolicyMatch> = None;
-        for entry in &self.lock_rules {
-            if !matches_patterns(&entry.rule.tools, tool_name) {
-                continue;
-            }
-            if !matches_patterns(&entry.rule.commands, command_text) {
-                continue;
-            }
-            if !matches_paths(&entry.rule.paths, affected_paths) {
-                continue;
-            }
-            if !matches_destructive(entry.rule.destructive, is_destructive) {
-                continue;
+        if let Some(host) = host.as_deref() {
+            if !host_rule_matched && !is_local_host(host) {
+                return Some(PolicyMatch {
+                    effect: PolicyEffect::Deny,
+                    reason: Some(format!(
+                        "only localhost is allowed by default; add a rule with hosts = [\"{}\"] to allow it",
+                        host
+                    )),
+                    source: PolicySource::Builtin,
+                });
             }
-
-            matched_lock = Some(PolicyMatch {
-                effect: entry.rule.effect,
-                reason: entry.rule.reason.clone(),
-                source: entry.source.clone(),
-            });
         }
 
         matched_lock.or(matched)
@@ -280,10 +292,42 @@ impl PermissionPolicy {
 fn extract_command_text(action_description: &str) -> Option<&str> {
     action_description
         .strip_prefix("Execute:")
+        .or_else(|| action_description.strip_prefix("Request:"))
         .map(str::trim)
         .filter(|s| !s.is_empty())
 }
 
+/// Host of an `http_request` action described as `Request: <METHOD> <URL>`.
+fn extract_request_host(action_description: &str) -> Option<String> {
+    let request = action_description.strip_prefix("Request:")?.trim();
+    let url = request.split_whitespace().nth(1)?;
+    let url = reqwest::Url::parse(url).ok()?;
+    let host = url
+        .host_str()?
+        .trim_start_matches('[')
+        .trim_end_matches(']');
+    Some(host.to_ascii_lowercase())
+}
+
+/// Whether `host` refers to this machine.
+pub fn is_local_host(host: &str) -> bool {
+    if host == "localhost" || host.ends_with(".localhost") {
+        return true;
+    }
+    host.parse::<std::net::IpAddr>()
+        .map(|ip| ip.is_loopback() || ip.is_unspecified())
+        .unwrap_or(false)
+}
+
+fn matches_hosts(host_patterns: &[String], host: Option<&str>) -> bool {
+    if host_patterns.is_empty() {
+        return true;
+    }
+
+    host.map(|host| matches_patterns(host_patterns, host))
+        .unwrap_or(false)
+}
+
 fn matches_destructive(filter: Option<bool>, actual: bool) -> bool {
     filter.map(|expected| expected == actual).unwrap_or(true)
 }
@@ -334,6 +378,7 @@ mod tests {
                     tools: vec!["shell".to_string()],
                     commands: vec!["cargo *".to_string()],
                     paths: Vec::new(),
+                    hosts: Vec::new(),
                     destructive: None,
                     reason: Some("safe cargo workflow".to_string()),
                 },
@@ -357,6 +402,7 @@ mod tests {
                     tools: vec!["file_edit".to_string()],
                     commands: Vec::new(),
                     paths: vec!["secrets/**".to_string()],
+                    hosts: Vec::new(),
                     destructive: None,
                     reason: None,
                 },
@@ -383,6 +429,7 @@ mod tests {
                     tools: vec!["shell".to_string()],
                     commands: vec!["git push*".to_string()],
                     paths: Vec::new(),
+                    hosts: Vec::new(),
                     destructive: Some(true),
                     reason: None,
                 },
@@ -513,4 +560,125 @@ reason = "force push is always blocked"
             Some("force push is always blocked".to_string())
         );
     }
+
+    fn host_rule(effect: PolicyEffect, hosts: &[&str]) -> PermissionRuleEntry {
+        PermissionRuleEntry {
+            rule: PermissionRule {
+                effect,
+                tools: vec!["http_request".to_string()],
+                commands: Vec::new(),
+                paths: Vec::new(),
+                hosts: hosts.iter().map(|h| h.to_string()).collect(),
+                destructive: None,
+                reason: None,
+            },
+            source: PolicySource::Project(PathBuf::from("/tmp/project-policy")),
+        }
+    }
+
+    #[test]
+    fn test_http_requests_default_to_localhost_only() {
+        let policy = PermissionPolicy::default();
+
+        for url in [
+            "http://localhost:3000/api",
+            "http://127.0.0.1:8080/",
+            "http://[::1]:9000/health",
+            "http://app.localhost/",
+        ] {
+            let action = format!("Request: GET {}", url);
+            assert!(
+                policy
+                    .evaluate("http_request", &action, &[], false)
+                    .is_none(),
+                "{} should be allowed",
+                url
+            );
+        }
+
+        let matched = policy
+            .evaluate(
+                "http_request",
+                "Request: GET https://api.example.com/v1",
+                &[],
+                false,
+            )
+            .expect("external host should be denied");
+        assert_eq!(matched.effect, PolicyEffect::Deny);
+        assert_eq!(matched.source, PolicySource::Builtin);
+        assert!(matched.reason.unwrap().contains("api.example.com"));
+    }
+
+    #[test]
+    fn test_host_rules_open_external_hosts() {
+        let policy = PermissionPolicy {
+            rules: vec![
+                host_rule(PolicyEffect::Allow, &["*.example.com"]),
+                host_rule(PolicyEffect::Deny, &["admin.example.com"]),
+            ],
+            lock_rules: Vec::new(),
+        };
+
+        let allowed = policy
+            .evaluate(
+                "http_request",
+                "Request: POST https://api.example.com/v1",
+                &[],
+                false,
+            )
+            .unwrap();
+        assert_eq!(allowed.effect, PolicyEffect::Allow);
+
+        let denied = policy
+            .evaluate(
+                "http_request",
+                "Request: GET https://admin.example.com/",
+                &[],
+                false,
+            )
+            .unwrap();
+        assert_eq!(denied.effect, PolicyEffect::Deny);
+        assert!(matches!(denied.source, PolicySource::Project(_)));
+
+        let other = policy
+            .evaluate(
+                "http_request",
+                "Request: GET https://other.org/",
+                &[],
+                false,
+            )
+            .unwrap();
+        assert_eq!(other.source, PolicySource::Builtin);
+    }
+
+    #[test]
+    fn test_tool_rules_without_hosts_do_not_lift_localhost_restriction() {
+        let mut rule = host_rule(PolicyEffect::Allow, &[]);
+        rule.rule.commands = vec!["GET *".to_string()];
+        let policy = PermissionPolicy {
+            rules: vec![rule],
+            lock_rules: Vec::new(),
+        };
+
+        let local = policy
+            .evaluate(
+                "http_request",
+                "Request: GET http://localhost:3000/",
+                &[],
+                false,
+            )
+            .unwrap();
+        assert_eq!(local.effect, PolicyEffect::Allow);
+
+        let external = policy
+            .evaluate(
+                "http_request",
+                "Request: GET https://example.com/",
+                &[],
+                false,
+            )
+            .unwrap();
+        assert_eq!(external.effect, PolicyEffect::Deny);
+        assert_eq!(external.source, PolicySource::Builtin);
+    }
 }
@@ -0,0 +1,167 @@
+// SPDX-License-Identifier: AGPL-3.0-or-later
+// Copyright (C) 2025 Blackman Artificial Intelligence Technologies Inc.
+
+//! Stale-read protection for file mutations
+//!
+//! Records a content hash for every file the model reads (or writes) through
+//! the builtin tools. Before `file_edit` touches a file, the current on-disk
+//! content is compared against the last recorded hash so that edits are never
+//! applied to content the model has not seen — for example after the user
+//! edited the file, a formatter ran, or a concurrent sub-agent changed it.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use sha2::{Digest, Sha256};
+
+/// Result of checking a file against the recorded read state
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReadCheck {
+    /// The file is unchanged since it was last read
+    Fresh,
+    /// The file was never read in this session
+    NeverRead,
+    /// The file changed on disk since it was last read
+    Stale,
+}
+
+/// Tracks content hashes of files read by the model.
+///
+/// Cloning is cheap and clones share the same state, so a single tracker can
+/// follow a `ToolContext` through the executor and concurrently running tools.
+#[derive(Debug, Clone, Default)]
+pub struct FileReadTracker {
+    hashes: Arc<Mutex<HashMap<PathBuf, String>>>,
+}
+
+impl FileReadTracker {
+    /// Create an empty tracker
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record the content the model has now seen for `path`.
+    pub fn record(&self, path: &Path, content: &str) {
+        let key = normalize_key(path);
+        if let Ok(mut hashes) = self.hashes.lock() {
+            hashes.insert(key, hash_content(content));
+        }
+    }
+
+    /// Forget any recorded state for `path`.
+    pub fn forget(&self, path: &Path) {
+        if let Ok(mut hashes) = self.hashes.lock() {
+            hashes.remove(&normalize_key(path));
+        }
+    }
+
+    /// Compare `current_content` against the last recorded read of `path`.
+    pub fn check(&self, path: &Path, current_content: &str) -> ReadCheck {
+        let hashes = match self.hashes.lock() {
+            Ok(hashes) => hashes,
+            Err(_) => return ReadCheck::NeverRead,
+        };
+        match hashes.get(&normalize_key(path)) {
+            None => ReadCheck::NeverRead,
+            Some(hash) if *hash == hash_content(current_content) => ReadCheck::Fresh,
+            Some(_) => ReadCheck::Stale,
+        }
+    }
+
+    /// Whether `path` has been read at least once.
+    pub fn has_read(&self, path: &Path) -> bool {
+        self.hashes
+            .lock()
+            .map(|hashes| hashes.contains_key(&normalize_key(path)))
+            .unwrap_or(false)
+    }
+
+    /// Number of tracked files
+    pub fn len(&self) -> usize {
+        self.hashes.lock().map(|hashes| hashes.len()).unwrap_or(0)
+    }
+
+    /// Check if no files are tracked
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// Canonicalize when possible so `./a.rs` and `/abs/a.rs` share an entry.
+fn normalize_key(path: &Path) -> PathBuf {
+    path.canonicalize().unwrap_or_else(|_| path.to_path_buf())
+}
+
+pub(crate) fn hash_content(content: &str) -> String {
+    let digest = Sha256::digest(content.as_bytes());
+    digest.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_check_never_read() {
+        let tracker = FileReadTracker::new();
+        assert_eq!(
+            tracker.check(Path::new("/tmp/unknown.txt"), "content"),
+            ReadCheck::NeverRead
+        );
+        assert!(tracker.is_empty());
+    }
+
+    #[test]
+    fn test_check_fresh_and_stale() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("a.txt");
+        std::fs::write(&path, "hello").unwrap();
+
+        let tracker = FileReadTracker::new();
+        tracker.record(&path, "hello");
+        assert!(tracker.has_read(&path));
+        assert_eq!(tracker.check(&path, "hello"), ReadCheck::Fresh);
+        assert_eq!(tracker.check(&path, "hello world"), ReadCheck::Stale);
+    }
+
+    #[test]
+    fn test_record_overwrites_previous_hash() {
+        let tracker = FileReadTracker::new();
+        let path = Path::new("/tmp/ted-read-tracker-overwrite.txt");
+        tracker.record(path, "v1");
+        tracker.record(path, "v2");
+        assert_eq!(tracker.check(path, "v2"), ReadCheck::Fresh);
+        assert_eq!(tracker.len(), 1);
+    }
+
+    #[test]
+    fn test_forget() {
+        let tracker = FileReadTracker::new();
+        let path = Path::new("/tmp/ted-read-tracker-forget.txt");
+        tracker.record(path, "v1");
+        tracker.forget(path);
+        assert!(!tracker.has_read(path));
+    }
+
+    #[test]
+    fn test_clones_share_state() {
+        let tracker = FileReadTracker::new();
+        let clone = tracker.clone();
+        let path = Path::new("/tmp/ted-read-tracker-shared.txt");
+        clone.record(path, "shared");
+        assert_eq!(tracker.check(path, "shared"), ReadCheck::Fresh);
+    }
+
+    #[test]
+    fn test_relative_and_absolute_paths_share_entry() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("b.txt");
+        std::fs::write(&path, "x").unwrap();
+
+        let tracker = FileReadTracker::new();
+        tracker.record(&temp_dir.path().join(".").join("b.txt"), "x");
+        assert_eq!(tracker.check(&path, "x"), ReadCheck::Fresh);
+    }
+}
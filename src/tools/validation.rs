@@ -0,0 +1,538 @@
+// SPDX-License-Identifier: AGPL-3.0-or-later
+// Copyright (C) 2025 Blackman Artificial Intelligence Technologies Inc.
+
+//! Tool input validation
+//!
+//! Validates model-provided tool input against the tool's declared
+//! `input_schema` before execution, so every tool reports bad input the same
+//! way. Common model mistakes are repaired first:
+//!
+//! - the whole input sent as a JSON string
+//! - well-known alternate parameter names (`file_path` for `path`, ...)
+//! - stringified numbers, booleans, arrays and objects
+//! - numbers or booleans where a string is expected
+//! - `null` for optional fields
+//!
+//! Supported keywords are the subset tools use: `type`, `properties`,
+//! `required`, `items`, `enum`, `const`, `oneOf` and `anyOf`. Anything else
+//! is accepted as-is.
+
+use serde_json::{Map, Value};
+
+use crate::llm::provider::ToolInputSchema;
+
+/// Alternate parameter names models commonly use, keyed by the schema name.
+///
+/// An alias is only applied when the tool's schema declares the canonical
+/// name, the input lacks it, and the alias itself isn't a declared property.
+const PARAMETER_ALIASES: &[(&str, &[&str])] = &[
+    (
+        "path",
+        &["file", "file_path", "filepath", "dir", "directory", "base"],
+    ),
+    ("content", &["text", "body", "data"]),
+    ("command", &["cmd", "run", "exec"]),
+    ("pattern", &["glob", "query", "search", "regex"]),
+    (
+        "old_string",
+        &["old", "old_text", "search", "find", "original", "from"],
+    ),
+    (
+        "new_string",
+        &["new", "new_text", "replace", "replacement", "to"],
+    ),
+    ("limit", &["max", "count"]),
+    ("title", &["name", "task"]),
+    ("description", &["desc", "details"]),
+    ("id", &["bead_id"]),
+    ("agent_type", &["type"]),
+    ("task", &["prompt", "instruction"]),
+    ("sql", &["statement", "query"]),
+];
+
+/// A single problem found in tool input
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationIssue {
+    /// Dotted path to the field (e.g. `operations[0].path`)
+    pub field: String,
+    /// What is wrong with it
+    pub problem: String,
+}
+
+/// Tool input that failed schema validation
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InputValidationError {
+    pub tool_name: String,
+    pub issues: Vec<ValidationIssue>,
+    /// One-line summary of the expected top-level fields
+    pub expected: String,
+}
+
+impl InputValidationError {
+    /// Error text for the model, listing every problem and the expected fields.
+    pub fn to_model_message(&self) -> String {
+        let mut message = format!("Invalid input for tool '{}':\n", self.tool_name);
+        for issue in &self.issues {
+            message.push_str(&format!("  - {}: {}\n", issue.field, issue.problem));
+        }
+        if !self.expected.is_empty() {
+            message.push_str(&format!("Expected fields: {}\n", self.expected));
+        }
+        message.push_str("Fix the input and call the tool again.");
+        message
+    }
+}
+
+impl std::fmt::Display for InputValidationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.to_model_message())
+    }
+}
+
+/// Validate `input` against `schema`, returning the (possibly repaired) input.
+pub fn validate_tool_input(
+    tool_name: &str,
+    schema: &ToolInputSchema,
+    input: Value,
+) -> std::result::Result<Value, InputValidationError> {
+    let mut input = match input {
+        Value::Null => Value::Object(Map::new()),
+        Value::String(text) => match serde_json::from_str::<Value>(text.trim()) {
+            Ok(parsed @ Value::Object(_)) => parsed,
+            _ => Value::String(text),
+        },
+        other => other,
+    };
+
+    let properties = schema.properties.as_object();
+    if let (Some(object), Some(properties)) = (input.as_object_mut(), properties) {
+        apply_parameter_aliases(object, properties);
+    }
+
+    let root = serde_json::json!({
+        "type": schema.schema_type,
+        "properties": schema.properties,
+        "required": schema.required,
+    });
+    let mut issues = Vec::new();
+    check(&mut input, &root, "", &mut issues);
+
+    if issues.is_empty() {
+        Ok(input)
+    } else {
+        Err(InputValidationError {
+            tool_name: tool_name.to_string(),
+            issues,
+            expected: describe_fields(schema),
+        })
+    }
+}
+
+fn apply_parameter_aliases(input: &mut Map<String, Value>, properties: &Map<String, Value>) {
+    for (canonical, aliases) in PARAMETER_ALIASES {
+        if !properties.contains_key(*canonical) || input.contains_key(*canonical) {
+            continue;
+        }
+        let alias = aliases
+            .iter()
+            .find(|alias| !properties.contains_key(**alias) && input.contains_key(**alias));
+        if let Some(alias) = alias {
+            if let Some(value) = input.remove(*alias) {
+                input.insert(canonical.to_string(), value);
+            }
+        }
+    }
+}
+
+fn check(value: &mut Value, schema: &Value, path: &str, issues: &mut Vec<ValidationIssue>) {
+    let Some(schema) = schema.as_object() else {
+        return;
+    };
+
+    if let Some(branches) = schema
+        .get("oneOf")
+        .or_else(|| schema.get("anyOf"))
+        .and_then(|b| b.as_array())
+    {
+        check_branches(value, branches, path, issues);
+        return;
+    }
+
+    if let Some(expected) = schema.get("type") {
+        let types: Vec<&str> = match expected {
+            Value::String(t) => vec![t.as_str()],
+            Value::Array(ts) => ts.iter().filter_map(|t| t.as_str()).collect(),
+            _ => Vec::new(),
+        };
+        if !types.is_empty() && !types.iter().any(|t| matches_type(value, t)) {
+            if let Some(coerced) = types.iter().find_map(|t| coerce(value, t)) {
+                *value = coerced;
+            } else {
+                issues.push(issue(
+                    path,
+                    format!("expected {}, got {}", types.join(" or "), describe(value)),
+                ));
+                return;
+            }
+        }
+    }
+
+    if let Some(constant) = schema.get("const") {
+        if value != constant {
+            issues.push(issue(path, format!("must be {}", constant)));
+            return;
+        }
+    }
+
+    if let Some(allowed) = schema.get("enum").and_then(|e| e.as_array()) {
+        if !allowed.contains(value) {
+            let choices: Vec<String> = allowed.iter().map(|v| v.to_string()).collect();
+            issues.push(issue(
+                path,
+                format!("must be one of {}, got {}", choices.join(", "), value),
+            ));
+            return;
+        }
+    }
+
+    match value {
+        Value::Object(object) => check_object(object, schema, path, issues),
+        Value::Array(items) => {
+            if let Some(item_schema) = schema.get("items") {
+                for (i, item) in items.iter_mut().enumerate() {
+                    check(item, item_schema, &format!("{}[{}]", path, i), issues);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+fn check_object(
+    object: &mut Map<String, Value>,
+    schema: &Map<String, Value>,
+    path: &str,
+    issues: &mut Vec<ValidationIssue>,
+) {
+    let required: Vec<&str> = schema
+        .get("required")
+        .and_then(|r| r.as_array())
+        .map(|r| r.iter().filter_map(|v| v.as_str()).collect())
+        .unwrap_or_default();
+    let properties = schema.get("properties").and_then(|p| p.as_object());
+
+    // `null` for an optional field means "not provided"
+    object.retain(|key, value| !value.is_null() || required.contains(&key.as_str()));
+
+    for name in &required {
+        if object.get(*name).is_none_or(|v| v.is_null()) {
+            let expected = properties
+                .and_then(|p| p.get(*name))
+                .and_then(|p| p.get("type"))
+                .and_then(|t| t.as_str())
+                .map(|t| format!(" ({})", t))
+                .unwrap_or_default();
+            issues.push(issue(
+                &join(path, name),
+                format!("missing required field{}", expected),
+            ));
+        }
+    }
+
+    if let Some(properties) = properties {
+        for (name, value) in object.iter_mut() {
+            if let Some(property_schema) = properties.get(name) {
+                check(value, property_schema, &join(path, name), issues);
+            }
+        }
+    }
+}
+
+/// Accept the first branch that validates; otherwise report the closest one,
+/// preferring branches whose `const` discriminators match the input.
+fn check_branches(
+    value: &mut Value,
+    branches: &[Value],
+    path: &str,
+    issues: &mut Vec<ValidationIssue>,
+) {
+    let mut best: Option<((bool, usize), Vec<ValidationIssue>)> = None;
+    for branch in branches {
+        let mut candidate = value.clone();
+        let mut branch_issues = Vec::new();
+        check(&mut candidate, branch, path, &mut branch_issues);
+        if branch_issues.is_empty() {
+            *value = candidate;
+            return;
+        }
+        let rank = (!discriminators_match(value, branch), branch_issues.len());
+        if best.as_ref().is_none_or(|(best_rank, _)| rank < *best_rank) {
+            best = Some((rank, branch_issues));
+        }
+    }
+    match best {
+        Some(((false, _), best)) => issues.extend(best),
+        Some((_, best)) if best.len() == 1 => issues.extend(best),
+        _ => issues.push(issue(path, "does not match any allowed form".to_string())),
+    }
+}
+
+fn discriminators_match(value: &Value, branch: &Value) -> bool {
+    let Some(properties) = branch.get("properties").and_then(|p| p.as_object()) else {
+        return false;
+    };
+    let mut constants = properties
+        .iter()
+        .filter_map(|(name, property)| property.get("const").map(|c| (name, c)))
+        .peekable();
+    constants.peek().is_some()
+        && constants.all(|(name, constant)| value.get(name) == Some(constant))
+}
+
+fn matches_type(value: &Value, expected: &str) -> bool {
+    match expected {
+        "string" => value.is_string(),
+        "integer" => value.is_i64() || value.is_u64(),
+        "number" => value.is_number(),
+        "boolean" => value.is_boolean(),
+        "array" => value.is_array(),
+        "object" => value.is_object(),
+        "null" => value.is_null(),
+        _ => true,
+    }
+}
+
+/// Repair a value that has the wrong JSON type, if there's an obvious fix.
+fn coerce(value: &Value, expected: &str) -> Option<Value> {
+    match (expected, value) {
+        ("integer", Value::String(s)) => s.trim().parse::<i64>().ok().map(Value::from),
+        ("integer", Value::Number(n)) => n
+            .as_f64()
+            .filter(|f| f.fract() == 0.0 && f.abs() < i64::MAX as f64)
+            .map(|f| Value::from(f as i64)),
+        ("number", Value::String(s)) => s
+            .trim()
+            .parse::<f64>()
+            .ok()
+            .and_then(serde_json::Number::from_f64)
+            .map(Value::Number),
+        ("boolean", Value::String(s)) => match s.trim().to_ascii_lowercase().as_str() {
+            "true" | "yes" | "1" => Some(Value::Bool(true)),
+            "false" | "no" | "0" => Some(Value::Bool(false)),
+            _ => None,
+        },
+        ("string", Value::Number(n)) => Some(Value::String(n.to_string())),
+        ("string", Value::Bool(b)) => Some(Value::String(b.to_string())),
+        ("array", Value::String(s)) => match serde_json::from_str::<Value>(s.trim()) {
+            Ok(parsed @ Value::Array(_)) => Some(parsed),
+            _ => None,
+        },
+        ("object", Value::String(s)) => match serde_json::from_str::<Value>(s.trim()) {
+            Ok(parsed @ Value::Object(_)) => Some(parsed),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn describe(value: &Value) -> String {
+    let kind = match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    };
+    match value {
+        Value::Array(_) | Value::Object(_) | Value::Null => kind.to_string(),
+        _ => {
+            let text = value.to_string();
+            let text = if text.chars().count() > 40 {
+                format!("{}...", text.chars().take(37).collect::<String>())
+            } else {
+                text
+            };
+            format!("{} {}", kind, text)
+        }
+    }
+}
+
+fn describe_fields(schema: &ToolInputSchema) -> String {
+    let Some(properties) = schema.properties.as_object() else {
+        return String::new();
+    };
+    properties
+        .iter()
+        .map(|(name, property)| {
+            let kind = property
+                .get("type")
+                .and_then(|t| t.as_str())
+                .unwrap_or("any");
+            if schema.required.iter().any(|r| r == name) {
+                format!("{} ({}, required)", name, kind)
+            } else {
+                format!("{} ({})", name, kind)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn join(path: &str, name: &str) -> String {
+    if path.is_empty() {
+        name.to_string()
+    } else {
+        format!("{}.{}", path, name)
+    }
+}
+
+fn issue(path: &str, problem: String) -> ValidationIssue {
+    ValidationIssue {
+        field: if path.is_empty() {
+            "input".to_string()
+        } else {
+            path.to_string()
+        },
+        problem,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tools::SchemaBuilder;
+    use serde_json::json;
+
+    fn edit_schema() -> ToolInputSchema {
+        SchemaBuilder::new()
+            .string("path", "File", true)
+            .string("old_string", "Old", true)
+            .string("new_string", "New", true)
+            .boolean("replace_all", "All", false)
+            .integer("limit", "Limit", false)
+            .array("tags", "Tags", "string", false)
+            .build()
+    }
+
+    #[test]
+    fn test_valid_input_passes_unchanged() {
+        let input = json!({"path": "a.rs", "old_string": "a", "new_string": "b"});
+        let output = validate_tool_input("file_edit", &edit_schema(), input.clone()).unwrap();
+        assert_eq!(output, input);
+    }
+
+    #[test]
+    fn test_reports_missing_and_wrong_fields() {
+        let err = validate_tool_input(
+            "file_edit",
+            &edit_schema(),
+            json!({"path": "a.rs", "replace_all": "sometimes", "tags": {"a": 1}}),
+        )
+        .unwrap_err();
+
+        let fields: Vec<&str> = err.issues.iter().map(|i| i.field.as_str()).collect();
+        assert!(fields.contains(&"old_string"));
+        assert!(fields.contains(&"new_string"));
+        assert!(fields.contains(&"replace_all"));
+        assert!(fields.contains(&"tags"));
+
+        let message = err.to_model_message();
+        assert!(message.starts_with("Invalid input for tool 'file_edit':"));
+        assert!(message.contains("old_string: missing required field (string)"));
+        assert!(message.contains("replace_all: expected boolean, got string \"sometimes\""));
+        assert!(message.contains("Expected fields: "));
+        assert!(message.contains("path (string, required)"));
+    }
+
+    #[test]
+    fn test_coerces_common_mistakes() {
+        let output = validate_tool_input(
+            "file_edit",
+            &edit_schema(),
+            json!({
+                "file_path": "a.rs",
+                "old": "a",
+                "new_string": 42,
+                "replace_all": "true",
+                "limit": "10",
+                "tags": "[\"x\", \"y\"]"
+            }),
+        )
+        .unwrap();
+
+        assert_eq!(
+            output,
+            json!({
+                "path": "a.rs",
+                "old_string": "a",
+                "new_string": "42",
+                "replace_all": true,
+                "limit": 10,
+                "tags": ["x", "y"]
+            })
+        );
+    }
+
+    #[test]
+    fn test_stringified_input_and_optional_nulls() {
+        let input = json!(
+            "{\"path\": \"a.rs\", \"old_string\": \"a\", \"new_string\": \"b\", \"limit\": null}"
+        );
+        let output = validate_tool_input("file_edit", &edit_schema(), input).unwrap();
+        assert_eq!(output["path"], "a.rs");
+        assert!(output.get("limit").is_none());
+
+        let output = validate_tool_input("glob", &SchemaBuilder::new().build(), Value::Null);
+        assert_eq!(output.unwrap(), json!({}));
+    }
+
+    #[test]
+    fn test_alias_not_applied_when_declared() {
+        // "search" is both an alias of pattern and a real property here
+        let schema = SchemaBuilder::new()
+            .string("pattern", "Pattern", true)
+            .string("search", "Search mode", false)
+            .build();
+        let err = validate_tool_input("grep", &schema, json!({"search": "x"})).unwrap_err();
+        assert_eq!(err.issues[0].field, "pattern");
+    }
+
+    #[test]
+    fn test_nested_one_of_and_enum() {
+        let schema = ToolInputSchema {
+            schema_type: "object".to_string(),
+            properties: json!({
+                "mode": {"type": "string", "enum": ["atomic", "incremental"]},
+                "operations": {
+                    "type": "array",
+                    "items": {"oneOf": [
+                        {"type": "object", "required": ["type", "path"], "properties": {
+                            "type": {"const": "read"}, "path": {"type": "string"}}},
+                        {"type": "object", "required": ["type", "path", "content"], "properties": {
+                            "type": {"const": "write"}, "path": {"type": "string"},
+                            "content": {"type": "string"}}}
+                    ]}
+                }
+            }),
+            required: vec!["operations".to_string()],
+        };
+
+        assert!(validate_tool_input(
+            "changes",
+            &schema,
+            json!({"operations": [{"type": "read", "path": "a"}, {"type": "write", "path": "b", "content": "c"}]}),
+        )
+        .is_ok());
+
+        let err = validate_tool_input(
+            "changes",
+            &schema,
+            json!({"mode": "eventual", "operations": [{"type": "write", "path": "b"}]}),
+        )
+        .unwrap_err();
+        let message = err.to_model_message();
+        assert!(message.contains("mode: must be one of \"atomic\", \"incremental\""));
+        assert!(message.contains("operations[0].content: missing required field (string)"));
+    }
+}
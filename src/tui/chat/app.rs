@@ -44,6 +44,10 @@ pub enum ChatMode {
     Confirm,
     /// Editing settings
     Settings,
+    /// Answering an `ask_user` question
+    Question,
+    /// Reviewing a pending file edit hunk by hunk
+    Review,
 }
 
 /// Result of a tick (event loop iteration)
@@ -163,7 +163,7 @@ impl ChatApp {
             ChatMode::Help => self.handle_help_key(key)?,
             ChatMode::CommandPalette => self.handle_command_palette_key(key)?,
             ChatMode::Confirm => self.handle_confirm_key(key)?,
-            ChatMode::Settings => {} // Handled by runner.rs.
+            ChatMode::Settings | ChatMode::Question | ChatMode::Review => {} // Handled by runner.rs.
         }
 
         Ok(())
@@ -146,6 +146,46 @@ pub fn bindings_for_mode(mode: super::ChatMode) -> Vec<KeyBinding> {
                 description: "Close",
             },
         ],
+        super::ChatMode::Question => vec![
+            KeyBinding {
+                keys: "↑/↓ or 1-9",
+                description: "Choose",
+            },
+            KeyBinding {
+                keys: "Enter",
+                description: "Answer",
+            },
+            KeyBinding {
+                keys: "Esc",
+                description: "Dismiss",
+            },
+        ],
+        super::ChatMode::Review => vec![
+            KeyBinding {
+                keys: "↑/↓ ←/→",
+                description: "Hunk/file",
+            },
+            KeyBinding {
+                keys: "y/n",
+                description: "Accept/reject hunk",
+            },
+            KeyBinding {
+                keys: "e",
+                description: "Edit hunk",
+            },
+            KeyBinding {
+                keys: "m",
+                description: "Note",
+            },
+            KeyBinding {
+                keys: "Enter",
+                description: "Apply",
+            },
+            KeyBinding {
+                keys: "Esc",
+                description: "Reject all",
+            },
+        ],
     }
 }
 
@@ -240,6 +280,8 @@ mod tests {
             ChatMode::CommandPalette,
             ChatMode::Confirm,
             ChatMode::Settings,
+            ChatMode::Question,
+            ChatMode::Review,
         ];
 
         for mode in modes {
@@ -17,6 +17,7 @@ use crossterm::{
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
 use ratatui::prelude::*;
+use tokio::sync::mpsc;
 
 use crate::caps::available_caps;
 use crate::caps::render::render_system_prompt;
@@ -26,11 +27,12 @@ use crate::error::{Result, TedError};
 use crate::history::{HistoryStore, SessionInfo};
 use crate::llm::message::{Conversation, Message};
 use crate::llm::provider::LlmProvider;
+use crate::mcp::McpClientManager;
 use crate::tools::builtin::ProgressTracker;
-use crate::tools::ToolExecutor;
+use crate::tools::{PendingQuestion, PlanMode, ToolExecutor, ToolProgressEvent};
 
 use super::app::ChatMode;
-use super::state::{AgentTracker, DisplayMessage, InputState};
+use super::state::{AgentTracker, DiffReview, DisplayMessage, InputState, QuestionPicker};
 use super::ChatTuiConfig;
 
 mod commands;
@@ -46,7 +48,7 @@ use keymap::handle_key;
 use keymap::{handle_help_key, handle_input_key, handle_normal_key, handle_settings_key};
 use render::*;
 use settings::{SettingsField, SettingsSection, SettingsState};
-use turn::process_llm_response;
+use turn::{process_llm_response, run_prompt_hooks};
 
 /// Simplified TUI state for the runner
 pub struct TuiState {
@@ -92,6 +94,18 @@ pub struct TuiState {
     pub pending_messages: Vec<String>,
     /// Tool call ID of the agent whose conversation is shown in the split pane
     pub focused_agent_tool_id: Option<String>,
+    /// Incoming `ask_user` questions from tool execution
+    pub question_rx: Option<mpsc::UnboundedReceiver<PendingQuestion>>,
+    /// Question currently shown in the picker
+    pub question_picker: Option<QuestionPicker>,
+    /// File edit currently under review
+    pub diff_review: Option<DiffReview>,
+    /// Progress reported by long-running tools
+    pub tool_progress_rx: Option<mpsc::UnboundedReceiver<ToolProgressEvent>>,
+    /// Plan mode switch shared with the tool executor
+    pub plan_mode: PlanMode,
+    /// External MCP servers mounted in the tool executor
+    pub mcp_servers: McpClientManager,
 }
 
 impl TuiState {
@@ -134,6 +148,12 @@ impl TuiState {
             disallowed_caps: settings.defaults.disallowed_caps.clone(),
             pending_messages: Vec::new(),
             focused_agent_tool_id: None,
+            question_rx: None,
+            question_picker: None,
+            diff_review: None,
+            tool_progress_rx: None,
+            plan_mode: PlanMode::default(),
+            mcp_servers: McpClientManager::default(),
         }
     }
 
@@ -143,6 +163,110 @@ impl TuiState {
         self
     }
 
+    /// Set the receiver for `ask_user` questions
+    pub fn with_question_receiver(
+        mut self,
+        receiver: mpsc::UnboundedReceiver<PendingQuestion>,
+    ) -> Self {
+        self.question_rx = Some(receiver);
+        self
+    }
+
+    /// Set the receiver for progress reported by long-running tools
+    pub fn with_tool_progress_receiver(
+        mut self,
+        receiver: mpsc::UnboundedReceiver<ToolProgressEvent>,
+    ) -> Self {
+        self.tool_progress_rx = Some(receiver);
+        self
+    }
+
+    /// Share the executor's plan mode switch
+    pub fn with_plan_mode(mut self, plan_mode: PlanMode) -> Self {
+        self.plan_mode = plan_mode;
+        self
+    }
+
+    /// Share the executor's MCP servers for /mcp
+    pub fn with_mcp_servers(mut self, mcp_servers: McpClientManager) -> Self {
+        self.mcp_servers = mcp_servers;
+        self
+    }
+
+    /// Show the latest reported progress on running tool calls
+    pub fn poll_tool_progress(&mut self) {
+        let Some(rx) = self.tool_progress_rx.as_mut() else {
+            return;
+        };
+        while let Ok(event) = rx.try_recv() {
+            let text = match event.percent {
+                Some(percent) => format!("{}% {}", percent, event.message),
+                None => event.message,
+            };
+            if let Some(tc) = self
+                .messages
+                .last_mut()
+                .and_then(|msg| msg.find_tool_call_mut(&event.tool_use_id))
+            {
+                tc.set_progress_text(&text);
+            }
+        }
+    }
+
+    /// Open the picker for the next pending `ask_user` question, and close
+    /// it if the tool stopped waiting (e.g. the turn was cancelled).
+    pub fn poll_questions(&mut self) {
+        if self
+            .question_picker
+            .as_ref()
+            .is_some_and(|picker| picker.is_abandoned())
+        {
+            self.close_question();
+        }
+        if self.question_picker.is_some() {
+            return;
+        }
+        if let Some(pending) = self.question_rx.as_mut().and_then(|rx| rx.try_recv().ok()) {
+            self.question_picker = Some(QuestionPicker::new(pending));
+            self.mode = ChatMode::Question;
+        }
+    }
+
+    /// Close the question picker and return to input mode
+    pub fn close_question(&mut self) {
+        self.question_picker = None;
+        if self.mode == ChatMode::Question {
+            self.mode = ChatMode::Input;
+        }
+    }
+
+    /// Dismiss the open question without answering it
+    pub fn dismiss_question(&mut self) {
+        if let Some(picker) = self.question_picker.take() {
+            picker.dismiss();
+        }
+        self.close_question();
+    }
+
+    /// Whether file edits are reviewed before they run
+    pub fn review_enabled(&self) -> bool {
+        !self.config.trust_mode
+    }
+
+    /// Show `review` in the review overlay
+    pub fn open_review(&mut self, review: DiffReview) {
+        self.diff_review = Some(review);
+        self.mode = ChatMode::Review;
+    }
+
+    /// Close the review overlay and return to input mode
+    pub fn close_review(&mut self) -> Option<DiffReview> {
+        if self.mode == ChatMode::Review {
+            self.mode = ChatMode::Input;
+        }
+        self.diff_review.take()
+    }
+
     /// Advance the animation frame (called on each render tick)
     pub fn tick_animation(&mut self) {
         self.animation_frame = self.animation_frame.wrapping_add(1);
@@ -246,6 +370,8 @@ pub async fn run_chat_tui_loop(
     mut history_store: HistoryStore,
     mut session_info: SessionInfo,
     agent_progress_tracker: ProgressTracker,
+    question_rx: mpsc::UnboundedReceiver<PendingQuestion>,
+    tool_progress_rx: mpsc::UnboundedReceiver<ToolProgressEvent>,
 ) -> Result<()> {
     // Setup terminal with panic hook to restore terminal on crash
     let original_panic_hook = std::panic::take_hook();
@@ -264,8 +390,12 @@ pub async fn run_chat_tui_loop(
     let mut terminal = Terminal::new(backend).map_err(|e| TedError::Tui(e.to_string()))?;
 
     // Create TUI state with agent progress tracker
-    let mut state =
-        TuiState::new(config.clone(), &settings).with_progress_tracker(agent_progress_tracker);
+    let mut state = TuiState::new(config.clone(), &settings)
+        .with_progress_tracker(agent_progress_tracker)
+        .with_question_receiver(question_rx)
+        .with_tool_progress_receiver(tool_progress_rx)
+        .with_plan_mode(tool_executor.plan_mode().clone())
+        .with_mcp_servers(tool_executor.mcp_servers().clone());
     let mut governed_caps = state.enabled_caps.clone();
     crate::caps::enforce_governance(
         &mut governed_caps,
@@ -367,6 +497,14 @@ pub async fn run_chat_tui_loop(
                                     .draw(|f| draw_tui(f, &state))
                                     .map_err(|e| TedError::Tui(e.to_string()))?;
 
+                                let Some(llm_message) =
+                                    run_prompt_hooks(&tool_executor, &mut state, &llm_message)
+                                        .await
+                                else {
+                                    state.is_processing = false;
+                                    continue;
+                                };
+
                                 // Store in context
                                 context_manager
                                     .store_message("user", &llm_message, None)
@@ -450,6 +588,13 @@ pub async fn run_chat_tui_loop(
                             .draw(|f| draw_tui(f, &state))
                             .map_err(|e| TedError::Tui(e.to_string()))?;
 
+                        let Some(input_text) =
+                            run_prompt_hooks(&tool_executor, &mut state, &input_text).await
+                        else {
+                            state.is_processing = false;
+                            continue;
+                        };
+
                         // Store in context
                         context_manager
                             .store_message("user", &input_text, None)
@@ -534,6 +679,13 @@ pub async fn run_chat_tui_loop(
                                 .draw(|f| draw_tui(f, &state))
                                 .map_err(|e| TedError::Tui(e.to_string()))?;
 
+                            let Some(queued_text) =
+                                run_prompt_hooks(&tool_executor, &mut state, &queued_text).await
+                            else {
+                                state.is_processing = false;
+                                continue;
+                            };
+
                             // Store in context
                             context_manager
                                 .store_message("user", &queued_text, None)
@@ -2,12 +2,13 @@
 // Copyright (C) 2025 Blackman Artificial Intelligence Technologies Inc.
 
 use crate::chat::input_parser::{
-    parse_beads_command, parse_commit_command, parse_explain_command, parse_fix_command,
-    parse_model_command, parse_review_command, parse_skills_command, parse_test_command,
+    is_mcp_command, parse_beads_command, parse_commit_command, parse_explain_command,
+    parse_fix_command, parse_model_command, parse_plan_mode_command, parse_review_command,
+    parse_skills_command, parse_test_command,
 };
 use crate::chat::slash_commands::{
-    execute_beads, execute_commit, execute_explain, execute_fix, execute_model, execute_review,
-    execute_skills, execute_test, SlashCommandResult,
+    execute_beads, execute_commit, execute_explain, execute_fix, execute_model, execute_plan_mode,
+    execute_review, execute_skills, execute_test, SlashCommandResult,
 };
 use crate::error::Result;
 use crate::llm::message::{Conversation, Message};
@@ -65,6 +66,39 @@ pub(super) fn handle_command(
         }
     }
 
+    if let Some(args) = parse_plan_mode_command(trimmed) {
+        let Some(conv) = conversation else {
+            state.set_error("/plan-mode is not available while a response is running");
+            return Ok(());
+        };
+        let plan_mode = state.plan_mode.clone();
+        match execute_plan_mode(&args, &plan_mode, conv, state.config.session_id) {
+            SlashCommandResult::SendToLlm(msg) => {
+                state.pending_messages.push(msg);
+                state.set_status("Plan approved - executing");
+            }
+            SlashCommandResult::Message(msg) => {
+                state.messages.push(DisplayMessage::system(msg));
+                state.auto_scroll();
+            }
+            SlashCommandResult::Error(e) => {
+                state.set_error(&e);
+            }
+            SlashCommandResult::SpawnAgent { task, .. } => {
+                state.pending_messages.push(task);
+                state.set_status("Processing...");
+            }
+        }
+        return Ok(());
+    }
+
+    if is_mcp_command(trimmed) {
+        let status = state.mcp_servers.format_status();
+        state.messages.push(DisplayMessage::system(status));
+        state.auto_scroll();
+        return Ok(());
+    }
+
     if lower.starts_with("/cap ") {
         // Toggle a specific cap: /cap <name>
         let cap_name = trimmed[5..].trim();
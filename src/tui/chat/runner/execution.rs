@@ -14,7 +14,10 @@ use crate::tools::builtin::{AgentConversationEntry, ToolCallEntryStatus};
 use crate::tools::{ToolExecutor, ToolResult};
 use crate::tui::chat::app::ChatMode;
 use crate::tui::chat::state::agents::AgentStatus;
-use crate::tui::chat::state::{AgentTracker, DisplayMessage, DisplayToolCall};
+use crate::tui::chat::state::review::{simulate_changes, REVIEWED_TOOLS};
+use crate::tui::chat::state::{
+    AgentTracker, DiffReview, DisplayMessage, DisplayToolCall, ReviewOutcome,
+};
 
 use super::render::draw_tui;
 use super::{handle_command, handle_key, TuiState};
@@ -239,6 +242,65 @@ pub(super) struct TuiToolExecutionStrategy<'a, B: Backend> {
     pub(super) terminal: &'a mut Terminal<B>,
 }
 
+impl<B: Backend> TuiToolExecutionStrategy<'_, B> {
+    /// Show the review overlay for a file-editing tool call and wait for the
+    /// user's decision. Calls that can't be simulated run unreviewed; `None`
+    /// means the turn was interrupted.
+    async fn review_tool_call(
+        &mut self,
+        tool_executor: &ToolExecutor,
+        id: &str,
+        name: &str,
+        input: &serde_json::Value,
+        interrupted: &Arc<AtomicBool>,
+    ) -> Option<ReviewOutcome> {
+        let changes = simulate_changes(name, input, &tool_executor.context().working_directory);
+        let Some(review) = changes.and_then(|changes| {
+            DiffReview::new(id.to_string(), name.to_string(), input.clone(), changes)
+        }) else {
+            return Some(ReviewOutcome::Approve);
+        };
+        self.state.open_review(review);
+
+        loop {
+            if interrupted.load(Ordering::SeqCst) {
+                self.state.close_review();
+                return None;
+            }
+            if self
+                .state
+                .diff_review
+                .as_ref()
+                .is_none_or(DiffReview::is_finished)
+            {
+                break;
+            }
+            self.state.tick_animation();
+            self.state.poll_tool_progress();
+            handle_tool_execution_input(self.state, interrupted);
+            let _ = self.terminal.draw(|f| draw_tui(f, self.state));
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+
+        let outcome = self
+            .state
+            .close_review()
+            .map_or(ReviewOutcome::Approve, |review| review.outcome());
+        let _ = self.terminal.draw(|f| draw_tui(f, self.state));
+        Some(outcome)
+    }
+}
+
+/// Append the user's review notes to a tool result
+fn append_review_summary(result: ToolResult, summary: &str) -> ToolResult {
+    let output = format!("{}\n\n{}", result.output_text(), summary);
+    if result.is_error() {
+        ToolResult::error(&result.tool_use_id, output)
+    } else {
+        ToolResult::success(&result.tool_use_id, output)
+    }
+}
+
 #[async_trait::async_trait(?Send)]
 impl<B: Backend> crate::chat::engine::ToolExecutionStrategy for TuiToolExecutionStrategy<'_, B> {
     async fn execute_tool_calls(
@@ -286,13 +348,33 @@ impl<B: Backend> crate::chat::engine::ToolExecutionStrategy for TuiToolExecution
 
             match tool_executor.approve_and_get_tool("spawn_agent", parsed_input) {
                 Ok(Some((tool, ctx))) => {
+                    let hooks = tool_executor.hooks().clone();
+                    let pre_hooks = hooks
+                        .pre_tool_use(ctx.session_id, id, "spawn_agent", parsed_input)
+                        .await;
+                    if let Some(reason) = &pre_hooks.block_reason {
+                        tool_results.push(ToolResult::error(
+                            id,
+                            format!("Blocked by PreToolUse hook: {}", reason),
+                        ));
+                        continue;
+                    }
                     let tool_id = id.clone();
-                    let input = parsed_input.clone();
+                    let input = pre_hooks
+                        .tool_input
+                        .clone()
+                        .unwrap_or_else(|| parsed_input.clone());
                     let handle = tokio::spawn(async move {
-                        match tool.execute(tool_id.clone(), input, &ctx).await {
+                        let result = match tool.execute(tool_id.clone(), input.clone(), &ctx).await
+                        {
                             Ok(result) => result,
                             Err(e) => ToolResult::error(&tool_id, e.to_string()),
-                        }
+                        };
+                        let result = pre_hooks.apply_to_result(result);
+                        hooks
+                            .post_tool_use(ctx.session_id, "spawn_agent", &input, &result)
+                            .await
+                            .apply_to_result(result)
                     });
                     agent_handles.push((id.clone(), handle));
                 }
@@ -315,14 +397,56 @@ impl<B: Backend> crate::chat::engine::ToolExecutionStrategy for TuiToolExecution
                 break;
             }
 
+            // Let the user review file edits hunk by hunk before they run.
+            let mut input = parsed_input.clone();
+            let mut review_summary = None;
+            // Plan mode denies the call anyway, so don't ask the user to review it.
+            if self.state.review_enabled()
+                && REVIEWED_TOOLS.contains(&name.as_str())
+                && tool_executor
+                    .plan_mode()
+                    .denial(name, parsed_input)
+                    .is_none()
+            {
+                match self
+                    .review_tool_call(tool_executor, id, name, parsed_input, interrupted)
+                    .await
+                {
+                    Some(ReviewOutcome::Approve) => {}
+                    Some(ReviewOutcome::Modify {
+                        input: reviewed,
+                        summary,
+                    }) => {
+                        input = reviewed;
+                        review_summary = Some(summary);
+                    }
+                    Some(ReviewOutcome::Reject { message }) => {
+                        let result = ToolResult::error(id, message);
+                        update_tool_call_ui(self.state, id, &result);
+                        let _ = self.terminal.draw(|f| draw_tui(f, self.state));
+                        tool_results.push(result);
+                        continue;
+                    }
+                    None => {
+                        if let Some(msg) = self.state.messages.last_mut() {
+                            if let Some(tc) = msg.find_tool_call_mut(id) {
+                                tc.complete_failed("Cancelled by user".to_string());
+                            }
+                        }
+                        cancelled_ids.insert(id.clone());
+                        break;
+                    }
+                }
+            }
+
             let id_clone = id.clone();
             let name_clone = name.clone();
-            let tool_future =
-                tool_executor.execute_tool_use(&id_clone, &name_clone, parsed_input.clone());
+            let tool_future = tool_executor.execute_tool_use(&id_clone, &name_clone, input);
             tokio::pin!(tool_future);
 
             let result = loop {
                 if interrupted.load(Ordering::SeqCst) {
+                    self.state.dismiss_question();
                     if let Some(msg) = self.state.messages.last_mut() {
                         if let Some(tc) = msg.find_tool_call_mut(id) {
                             tc.complete_failed("Cancelled by user".to_string());
@@ -340,6 +464,8 @@ impl<B: Backend> crate::chat::engine::ToolExecutionStrategy for TuiToolExecution
                     _ = tokio::time::sleep(Duration::from_millis(100)) => {
                         self.state.tick_animation();
                         sync_all_agents_from_tracker(self.state, &agent_tools);
+                        self.state.poll_tool_progress();
+                        self.state.poll_questions();
                         handle_tool_execution_input(self.state, interrupted);
                         self.state.auto_scroll();
                         let _ = self.terminal.draw(|f| draw_tui(f, self.state));
@@ -351,7 +477,10 @@ impl<B: Backend> crate::chat::engine::ToolExecutionStrategy for TuiToolExecution
                 break;
             }
 
-            if let Some(result) = result {
+            if let Some(mut result) = result {
+                if let Some(summary) = review_summary {
+                    result = append_review_summary(result, &summary);
+                }
                 update_tool_call_ui(self.state, id, &result);
                 let _ = self.terminal.draw(|f| draw_tui(f, self.state));
                 tool_results.push(result);
@@ -367,6 +496,7 @@ impl<B: Backend> crate::chat::engine::ToolExecutionStrategy for TuiToolExecution
 
                 let all_done = agent_handles.iter().all(|(_, h)| h.is_finished());
                 sync_all_agents_from_tracker(self.state, &agent_tools);
+                self.state.poll_questions();
                 handle_tool_execution_input(self.state, interrupted);
 
                 self.state.tick_animation();
@@ -7,6 +7,7 @@ use crate::config::Settings;
 use crate::error::Result;
 
 use super::super::app::ChatMode;
+use super::super::state::review::ReviewInput;
 use super::{SettingsField, SettingsSection, TuiState};
 
 pub(super) fn handle_key(state: &mut TuiState, key: KeyEvent) -> Result<()> {
@@ -16,6 +17,8 @@ pub(super) fn handle_key(state: &mut TuiState, key: KeyEvent) -> Result<()> {
         ChatMode::Help => handle_help_key(state, key),
         ChatMode::Settings => handle_settings_key(state, key),
         ChatMode::AgentFocus => handle_agent_focus_key(state, key),
+        ChatMode::Question => handle_question_key(state, key),
+        ChatMode::Review => handle_review_key(state, key),
         _ => Ok(()),
     }
 }
@@ -255,6 +258,116 @@ pub(super) fn handle_help_key(state: &mut TuiState, key: KeyEvent) -> Result<()>
     Ok(())
 }
 
+pub(super) fn handle_question_key(state: &mut TuiState, key: KeyEvent) -> Result<()> {
+    let Some(picker) = state.question_picker.as_mut() else {
+        state.mode = ChatMode::Input;
+        return Ok(());
+    };
+
+    match (key.modifiers, key.code) {
+        (_, KeyCode::Esc) => {
+            state.dismiss_question();
+            state.set_status("Question dismissed");
+        }
+        (_, KeyCode::Up) => picker.select_prev(),
+        (_, KeyCode::Down) | (_, KeyCode::Tab) => picker.select_next(),
+        (_, KeyCode::Backspace) => picker.backspace(),
+        (_, KeyCode::Enter) => match picker.current_answer() {
+            Some(answer) => {
+                if let Some(picker) = state.question_picker.take() {
+                    picker.submit(answer.clone());
+                }
+                state.close_question();
+                state.set_status(&format!("Answered: {}", answer));
+            }
+            None => state.set_error("Type an answer or pick an option"),
+        },
+        (KeyModifiers::NONE, KeyCode::Char(c))
+            if c.is_ascii_digit() && picker.free_text.is_empty() =>
+        {
+            // Number keys pick an option directly when not typing.
+            let index = c.to_digit(10).unwrap_or(0) as usize;
+            if index >= 1 && index <= picker.question().options.len() {
+                picker.selected = index - 1;
+            } else {
+                picker.push_char(c);
+            }
+        }
+        (KeyModifiers::NONE, KeyCode::Char(c)) | (KeyModifiers::SHIFT, KeyCode::Char(c)) => {
+            picker.push_char(c);
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+pub(super) fn handle_review_key(state: &mut TuiState, key: KeyEvent) -> Result<()> {
+    let Some(review) = state.diff_review.as_mut() else {
+        state.mode = ChatMode::Input;
+        return Ok(());
+    };
+
+    match &mut review.input {
+        ReviewInput::Edit(editor) => match (key.modifiers, key.code) {
+            (KeyModifiers::CONTROL, KeyCode::Char('s')) => review.save_edit(),
+            (_, KeyCode::Esc) => review.browse(),
+            (_, KeyCode::Enter) => editor.insert_newline(),
+            (_, KeyCode::Backspace) => editor.backspace(),
+            (_, KeyCode::Left) => editor.move_left(),
+            (_, KeyCode::Right) => editor.move_right(),
+            (_, KeyCode::Up) => editor.move_up(),
+            (_, KeyCode::Down) => editor.move_down(),
+            (_, KeyCode::Tab) => {
+                for _ in 0..4 {
+                    editor.insert_char(' ');
+                }
+            }
+            (KeyModifiers::NONE, KeyCode::Char(c)) | (KeyModifiers::SHIFT, KeyCode::Char(c)) => {
+                editor.insert_char(c);
+            }
+            _ => {}
+        },
+        ReviewInput::Note(_) => match (key.modifiers, key.code) {
+            (_, KeyCode::Esc) => review.cancel_note(),
+            (_, KeyCode::Enter) => review.browse(),
+            (_, KeyCode::Backspace) => {
+                review.note.pop();
+            }
+            (KeyModifiers::NONE, KeyCode::Char(c)) | (KeyModifiers::SHIFT, KeyCode::Char(c)) => {
+                review.note.push(c);
+            }
+            _ => {}
+        },
+        ReviewInput::Browse => match (key.modifiers, key.code) {
+            (_, KeyCode::Esc) => {
+                review.reject();
+                state.set_status("Change rejected");
+            }
+            (_, KeyCode::Enter) => {
+                let accepted: usize = review.files.iter().map(|f| f.accepted_count()).sum();
+                let total = review.total_hunks();
+                review.submit();
+                state.set_status(&format!("Applied {} of {} hunks", accepted, total));
+            }
+            (_, KeyCode::Up) | (KeyModifiers::NONE, KeyCode::Char('k')) => review.prev_hunk(),
+            (_, KeyCode::Down) | (KeyModifiers::NONE, KeyCode::Char('j')) => review.next_hunk(),
+            (_, KeyCode::Left) | (KeyModifiers::NONE, KeyCode::Char('h')) => review.prev_file(),
+            (_, KeyCode::Right) | (KeyModifiers::NONE, KeyCode::Char('l')) | (_, KeyCode::Tab) => {
+                review.next_file()
+            }
+            (KeyModifiers::NONE, KeyCode::Char('y')) => review.decide_current(true),
+            (KeyModifiers::NONE, KeyCode::Char('n')) => review.decide_current(false),
+            (KeyModifiers::NONE, KeyCode::Char(' ')) => review.toggle_current(),
+            (KeyModifiers::NONE, KeyCode::Char('a')) => review.decide_all(true),
+            (KeyModifiers::NONE, KeyCode::Char('r')) => review.decide_all(false),
+            (KeyModifiers::NONE, KeyCode::Char('e')) => review.start_edit(),
+            (KeyModifiers::NONE, KeyCode::Char('m')) => review.start_note(),
+            _ => {}
+        },
+    }
+    Ok(())
+}
+
 pub(super) fn handle_settings_key(state: &mut TuiState, key: KeyEvent) -> Result<()> {
     let settings = match &mut state.settings_state {
         Some(s) => s,
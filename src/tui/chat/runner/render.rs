@@ -5,6 +5,7 @@ use ratatui::prelude::*;
 
 use crate::tui::chat::app::ChatMode;
 use crate::tui::chat::state::agents::TrackedAgent;
+use crate::tui::chat::state::review::{DiffLine, ReviewInput};
 
 use super::{SettingsField, SettingsSection, TuiState};
 
@@ -42,6 +43,8 @@ pub(super) fn draw_tui(frame: &mut Frame, state: &TuiState) {
     match state.mode {
         ChatMode::Help => draw_help_overlay(frame, area),
         ChatMode::Settings => draw_settings_overlay(frame, state, area),
+        ChatMode::Question => draw_question_overlay(frame, state, area),
+        ChatMode::Review => draw_review_overlay(frame, state, area),
         _ => {}
     }
 }
@@ -322,6 +325,14 @@ pub(super) fn draw_title_bar(frame: &mut Frame, state: &TuiState, area: ratatui:
         Style::default().fg(Color::White).bg(Color::DarkGray),
     )];
 
+    if state.plan_mode.is_enabled() {
+        title_spans.push(ratatui::text::Span::styled(
+            " PLAN MODE ",
+            Style::default().fg(Color::Black).bg(Color::Yellow).bold(),
+        ));
+        title_spans.push(ratatui::text::Span::raw(" "));
+    }
+
     // Add caps badges (filter out "base" - it's always applied silently)
     for cap in &state.config.caps {
         if cap == "base" {
@@ -498,6 +509,8 @@ pub(super) fn draw_help_overlay(frame: &mut Frame, area: ratatui::layout::Rect)
         ratatui::text::Line::from("  /model X    Quick switch model"),
         ratatui::text::Line::from("  /agents     Toggle agent pane"),
         ratatui::text::Line::from("  /clear      Clear chat history"),
+        ratatui::text::Line::from("  /plan-mode  Plan read-only (approve, off)"),
+        ratatui::text::Line::from("  /mcp        MCP server status"),
         ratatui::text::Line::from("  /quit       Exit Ted"),
         ratatui::text::Line::from(""),
         ratatui::text::Line::from(ratatui::text::Span::styled(
@@ -519,6 +532,277 @@ pub(super) fn draw_help_overlay(frame: &mut Frame, area: ratatui::layout::Rect)
     frame.render_widget(help, popup_area);
 }
 
+pub(super) fn draw_question_overlay(
+    frame: &mut Frame,
+    state: &TuiState,
+    area: ratatui::layout::Rect,
+) {
+    use ratatui::text::{Line, Span};
+    use ratatui::widgets::{Block, Borders, Clear, Paragraph, Wrap};
+
+    let Some(picker) = state.question_picker.as_ref() else {
+        return;
+    };
+    let question = picker.question();
+
+    let mut lines = vec![
+        Line::from(Span::styled(
+            question.question.clone(),
+            Style::default().fg(Color::White).bold(),
+        )),
+        Line::from(""),
+    ];
+
+    for (i, option) in question.options.iter().enumerate() {
+        let selected = picker.selected == i;
+        let style = if selected {
+            Style::default().fg(Color::Black).bg(Color::Cyan)
+        } else {
+            Style::default()
+        };
+        let marker = if selected { "▸" } else { " " };
+        lines.push(Line::from(Span::styled(
+            format!("{} {}. {}", marker, i + 1, option),
+            style,
+        )));
+    }
+
+    if picker.has_free_text() {
+        let selected = picker.is_free_text_selected();
+        let marker = if selected { "▸" } else { " " };
+        let text = if picker.free_text.is_empty() && !selected {
+            Span::styled("Type an answer…", Style::default().fg(Color::DarkGray))
+        } else {
+            Span::styled(
+                format!("{}{}", picker.free_text, if selected { "▏" } else { "" }),
+                Style::default().fg(Color::Yellow),
+            )
+        };
+        lines.push(Line::from(vec![Span::raw(format!("{} ", marker)), text]));
+    }
+
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(
+        "↑/↓ choose · Enter answer · Esc dismiss",
+        Style::default().fg(Color::DarkGray),
+    )));
+
+    let popup_width = (area.width * 60 / 100).clamp(30.min(area.width), area.width);
+    let inner_width = popup_width.saturating_sub(2).max(1) as usize;
+    let wrapped_rows: usize = lines
+        .iter()
+        .map(|line| line.width().max(1).div_ceil(inner_width))
+        .sum();
+    let popup_height = ((wrapped_rows + 2) as u16).min(area.height);
+    let popup_area = ratatui::layout::Rect {
+        x: area.x + (area.width - popup_width) / 2,
+        y: area.y + (area.height - popup_height) / 2,
+        width: popup_width,
+        height: popup_height,
+    };
+
+    frame.render_widget(Clear, popup_area);
+    let widget = Paragraph::new(lines)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(Color::Cyan))
+                .title(" Question ")
+                .title_style(Style::default().fg(Color::White).bold()),
+        )
+        .wrap(Wrap { trim: false });
+    frame.render_widget(widget, popup_area);
+}
+
+pub(super) fn draw_review_overlay(
+    frame: &mut Frame,
+    state: &TuiState,
+    area: ratatui::layout::Rect,
+) {
+    use ratatui::text::{Line, Span};
+    use ratatui::widgets::{Block, Borders, Clear, Paragraph};
+
+    let Some(review) = state.diff_review.as_ref() else {
+        return;
+    };
+    let file = review.current_file();
+
+    let popup_width = (area.width * 90 / 100).max(40.min(area.width));
+    let popup_height = (area.height * 85 / 100).max(12.min(area.height));
+    let popup_area = ratatui::layout::Rect {
+        x: area.x + (area.width - popup_width) / 2,
+        y: area.y + (area.height - popup_height) / 2,
+        width: popup_width,
+        height: popup_height,
+    };
+    frame.render_widget(Clear, popup_area);
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Cyan))
+        .title(format!(" Review {} ", review.tool_name))
+        .title_style(Style::default().fg(Color::White).bold());
+    let inner = block.inner(popup_area);
+    frame.render_widget(block, popup_area);
+
+    let editor_height = match &review.input {
+        ReviewInput::Edit(editor) => (editor.lines.len() as u16 + 1).min(inner.height / 3),
+        _ => 0,
+    };
+    let [header_area, body_area, editor_area, note_area, footer_area] =
+        ratatui::layout::Layout::vertical([
+            Constraint::Length(1),
+            Constraint::Min(1),
+            Constraint::Length(editor_height),
+            Constraint::Length(1),
+            Constraint::Length(1),
+        ])
+        .areas(inner);
+
+    let mut title = vec![Span::styled(
+        file.path.clone(),
+        Style::default().fg(Color::White).bold(),
+    )];
+    if file.deleted {
+        title.push(Span::styled(" (deleted)", Style::default().fg(Color::Red)));
+    } else if file.original().is_empty() {
+        title.push(Span::styled(
+            " (new file)",
+            Style::default().fg(Color::Green),
+        ));
+    }
+    title.push(Span::styled(
+        format!(
+            "  file {}/{} · hunk {}/{}",
+            review.selected_file + 1,
+            review.files.len(),
+            review.selected_hunk + 1,
+            file.hunks.len()
+        ),
+        Style::default().fg(Color::DarkGray),
+    ));
+    frame.render_widget(Paragraph::new(Line::from(title)), header_area);
+
+    let mut body = Vec::new();
+    let mut selected_row = 0;
+    let mut hunk_accepted = true;
+    for row in file.diff_lines() {
+        let line = match row {
+            DiffLine::Header { hunk, text } => {
+                let selected = hunk == review.selected_hunk;
+                if selected {
+                    selected_row = body.len();
+                }
+                let info = &file.hunks[hunk];
+                hunk_accepted = info.accepted;
+                let (status, color) = match (info.accepted, info.is_edited()) {
+                    (false, _) => ("rejected", Color::Red),
+                    (true, true) => ("edited", Color::Yellow),
+                    (true, false) => ("accepted", Color::Green),
+                };
+                let style = if selected {
+                    Style::default().fg(Color::Black).bg(Color::Cyan)
+                } else {
+                    Style::default().fg(Color::Cyan)
+                };
+                Line::from(vec![
+                    Span::styled(
+                        format!("{} {}", if selected { "▸" } else { " " }, text),
+                        style,
+                    ),
+                    Span::styled(format!(" [{}]", status), Style::default().fg(color)),
+                ])
+            }
+            DiffLine::Context(text) => Line::from(Span::styled(
+                format!("   {}", text),
+                Style::default().fg(Color::DarkGray),
+            )),
+            DiffLine::Removed(text) => Line::from(Span::styled(
+                format!("  -{}", text),
+                Style::default().fg(Color::Red),
+            )),
+            DiffLine::Added(text) => {
+                let style = if hunk_accepted {
+                    Style::default().fg(Color::Green)
+                } else {
+                    Style::default()
+                        .fg(Color::DarkGray)
+                        .add_modifier(Modifier::CROSSED_OUT)
+                };
+                Line::from(Span::styled(format!("  +{}", text), style))
+            }
+        };
+        body.push(line);
+    }
+    let visible = body_area.height as usize;
+    let scroll = selected_row
+        .saturating_sub(2)
+        .min(body.len().saturating_sub(visible));
+    frame.render_widget(Paragraph::new(body).scroll((scroll as u16, 0)), body_area);
+
+    if let ReviewInput::Edit(editor) = &review.input {
+        let mut lines = vec![Line::from(Span::styled(
+            "Replacement (Ctrl+S save · Esc cancel):",
+            Style::default().fg(Color::Yellow),
+        ))];
+        for (row, text) in editor.lines.iter().enumerate() {
+            let text = if row == editor.row {
+                let (before, after): (String, String) = {
+                    let split = text
+                        .char_indices()
+                        .nth(editor.col)
+                        .map_or(text.len(), |(i, _)| i);
+                    (text[..split].to_string(), text[split..].to_string())
+                };
+                format!("{}▏{}", before, after)
+            } else {
+                text.clone()
+            };
+            lines.push(Line::from(Span::styled(
+                format!("  +{}", text),
+                Style::default().fg(Color::Yellow),
+            )));
+        }
+        let editor_scroll =
+            (editor.row + 1).saturating_sub((editor_area.height as usize).saturating_sub(1));
+        frame.render_widget(
+            Paragraph::new(lines).scroll((editor_scroll as u16, 0)),
+            editor_area,
+        );
+    }
+
+    let note = match &review.input {
+        ReviewInput::Note(_) => Line::from(vec![
+            Span::styled("Note: ", Style::default().fg(Color::Yellow)),
+            Span::raw(format!("{}▏", review.note)),
+        ]),
+        _ if review.note.is_empty() => Line::from(Span::styled(
+            "No note for the model",
+            Style::default().fg(Color::DarkGray),
+        )),
+        _ => Line::from(vec![
+            Span::styled("Note: ", Style::default().fg(Color::Yellow)),
+            Span::raw(review.note.clone()),
+        ]),
+    };
+    frame.render_widget(Paragraph::new(note), note_area);
+
+    let hints = match &review.input {
+        ReviewInput::Edit(_) => "Type to edit · Enter newline · Ctrl+S save · Esc cancel",
+        ReviewInput::Note(_) => "Type a note for the model · Enter done · Esc cancel",
+        ReviewInput::Browse => {
+            "↑/↓ hunk · ←/→ file · y/n accept/reject · a/r all · e edit · m note · Enter apply · Esc reject"
+        }
+    };
+    frame.render_widget(
+        Paragraph::new(Line::from(Span::styled(
+            hints,
+            Style::default().fg(Color::DarkGray),
+        ))),
+        footer_area,
+    );
+}
+
 pub(super) fn draw_settings_overlay(
     frame: &mut Frame,
     state: &TuiState,
@@ -688,6 +688,29 @@ fn test_handle_command_agents() {
     assert!(state.agent_pane_visible);
 }
 
+#[test]
+fn test_handle_command_plan_mode_toggles_shared_switch() {
+    let plan_mode = crate::tools::PlanMode::default();
+    let mut state = create_test_tui_state().with_plan_mode(plan_mode.clone());
+    let mut conversation = Conversation::new();
+
+    handle_command("/plan-mode", &mut state, Some(&mut conversation)).unwrap();
+    assert!(plan_mode.is_enabled());
+    assert!(conversation.pinned_context.is_some());
+
+    handle_command("/plan-mode off", &mut state, Some(&mut conversation)).unwrap();
+    assert!(!plan_mode.is_enabled());
+    assert!(conversation.pinned_context.is_none());
+}
+
+#[test]
+fn test_handle_command_plan_mode_needs_conversation() {
+    let mut state = create_test_tui_state();
+    handle_command("/plan-mode", &mut state, None).unwrap();
+    assert!(state.status_is_error);
+    assert!(!state.plan_mode.is_enabled());
+}
+
 #[test]
 fn test_handle_command_model_with_arg() {
     let mut state = create_test_tui_state();
@@ -1283,6 +1306,231 @@ fn test_draw_tui_help_mode() {
     assert!(buffer.area.width > 0);
 }
 
+// ===== ask_user Question Picker Tests =====
+
+fn queue_test_question(
+    state: &mut TuiState,
+    options: &[&str],
+) -> tokio::sync::oneshot::Receiver<Option<String>> {
+    let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+    let (respond_to, answer_rx) = tokio::sync::oneshot::channel();
+    tx.send(crate::tools::PendingQuestion {
+        question: crate::tools::UserQuestion {
+            id: "q1".to_string(),
+            question: "Which database?".to_string(),
+            options: options.iter().map(|o| o.to_string()).collect(),
+            allow_free_text: false,
+            default: None,
+        },
+        respond_to,
+    })
+    .unwrap();
+    state.question_rx = Some(rx);
+    answer_rx
+}
+
+fn press(state: &mut TuiState, code: crossterm::event::KeyCode) {
+    let key = crossterm::event::KeyEvent::new(code, crossterm::event::KeyModifiers::NONE);
+    handle_key(state, key).unwrap();
+}
+
+#[test]
+fn test_question_picker_answer() {
+    let mut state = create_test_tui_state();
+    let mut answer_rx = queue_test_question(&mut state, &["sqlite", "postgres"]);
+
+    state.poll_questions();
+    assert_eq!(state.mode, ChatMode::Question);
+
+    press(&mut state, crossterm::event::KeyCode::Down);
+    press(&mut state, crossterm::event::KeyCode::Enter);
+
+    assert_eq!(state.mode, ChatMode::Input);
+    assert!(state.question_picker.is_none());
+    assert_eq!(answer_rx.try_recv().unwrap().as_deref(), Some("postgres"));
+}
+
+#[test]
+fn test_question_picker_number_key_and_dismiss() {
+    let mut state = create_test_tui_state();
+    let mut answer_rx = queue_test_question(&mut state, &["a", "b", "c"]);
+    state.poll_questions();
+
+    press(&mut state, crossterm::event::KeyCode::Char('3'));
+    assert_eq!(state.question_picker.as_ref().unwrap().selected, 2);
+
+    press(&mut state, crossterm::event::KeyCode::Esc);
+    assert_eq!(state.mode, ChatMode::Input);
+    assert_eq!(answer_rx.try_recv().unwrap(), None);
+}
+
+#[test]
+fn test_question_picker_closes_when_abandoned() {
+    let mut state = create_test_tui_state();
+    let answer_rx = queue_test_question(&mut state, &["yes", "no"]);
+    state.poll_questions();
+    assert_eq!(state.mode, ChatMode::Question);
+
+    drop(answer_rx);
+    state.poll_questions();
+    assert_eq!(state.mode, ChatMode::Input);
+    assert!(state.question_picker.is_none());
+}
+
+#[test]
+fn test_poll_tool_progress_updates_running_call() {
+    let mut state = create_test_tui_state();
+    let mut msg = DisplayMessage::assistant("working".to_string(), vec![]);
+    msg.add_tool_call(crate::tui::chat::state::DisplayToolCall::new(
+        "toolu_1".to_string(),
+        "indexer".to_string(),
+        serde_json::json!({}),
+    ));
+    state.messages.push(msg);
+
+    let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+    state.tool_progress_rx = Some(rx);
+    tx.send(crate::tools::ToolProgressEvent {
+        tool_use_id: "toolu_1".to_string(),
+        tool_name: "indexer".to_string(),
+        message: "Indexing".to_string(),
+        percent: Some(40),
+    })
+    .unwrap();
+
+    state.poll_tool_progress();
+    let tc = state.messages[0].tool_calls[0].clone();
+    assert_eq!(tc.result_preview.as_deref(), Some("40% Indexing"));
+}
+
+#[test]
+fn test_draw_tui_question_mode() {
+    let mut state = create_test_tui_state();
+    let _answer_rx = queue_test_question(&mut state, &["sqlite", "postgres"]);
+    state.poll_questions();
+
+    let mut terminal = create_test_terminal(80, 24);
+    terminal.draw(|f| draw_tui(f, &state)).unwrap();
+
+    let content: String = terminal
+        .backend()
+        .buffer()
+        .content()
+        .iter()
+        .map(|cell| cell.symbol())
+        .collect();
+    assert!(content.contains("Which database?"));
+    assert!(content.contains("2. postgres"));
+}
+
+// ===== Diff Review Tests =====
+
+fn open_test_review(state: &mut TuiState) {
+    let original = "a\nb\nc\nd\ne\nf\ng\nh\ni\nj\n";
+    let change = crate::tui::chat::state::review::FileChange {
+        path: "src/lib.rs".to_string(),
+        original: original.to_string(),
+        proposed: original.replace("b\n", "B\n").replace("j\n", "J\n"),
+        deleted: false,
+    };
+    let review = crate::tui::chat::state::DiffReview::new(
+        "toolu_1".to_string(),
+        "file_edit".to_string(),
+        serde_json::json!({"path": "src/lib.rs", "old_string": "b", "new_string": "B"}),
+        vec![change],
+    )
+    .unwrap();
+    state.open_review(review);
+}
+
+#[test]
+fn test_review_enabled_follows_trust_mode() {
+    let mut state = create_test_tui_state();
+    assert!(state.review_enabled());
+    state.config.trust_mode = true;
+    assert!(!state.review_enabled());
+}
+
+#[test]
+fn test_review_keys_reject_hunk_and_submit() {
+    let mut state = create_test_tui_state();
+    open_test_review(&mut state);
+    assert_eq!(state.mode, ChatMode::Review);
+
+    press(&mut state, crossterm::event::KeyCode::Char('n'));
+    press(&mut state, crossterm::event::KeyCode::Enter);
+
+    let review = state.close_review().unwrap();
+    assert!(review.is_finished());
+    assert_eq!(state.mode, ChatMode::Input);
+    assert_eq!(review.files[0].accepted_count(), 1);
+    assert!(matches!(
+        review.outcome(),
+        crate::tui::chat::state::ReviewOutcome::Modify { .. }
+    ));
+}
+
+#[test]
+fn test_review_keys_edit_hunk_and_note() {
+    let mut state = create_test_tui_state();
+    open_test_review(&mut state);
+
+    press(&mut state, crossterm::event::KeyCode::Char('e'));
+    press(&mut state, crossterm::event::KeyCode::Backspace);
+    press(&mut state, crossterm::event::KeyCode::Char('x'));
+    let save = crossterm::event::KeyEvent::new(
+        crossterm::event::KeyCode::Char('s'),
+        crossterm::event::KeyModifiers::CONTROL,
+    );
+    handle_key(&mut state, save).unwrap();
+
+    press(&mut state, crossterm::event::KeyCode::Char('m'));
+    for c in "use x".chars() {
+        press(&mut state, crossterm::event::KeyCode::Char(c));
+    }
+    press(&mut state, crossterm::event::KeyCode::Enter);
+
+    let review = state.diff_review.as_ref().unwrap();
+    assert!(!review.is_finished());
+    assert_eq!(review.note, "use x");
+    assert_eq!(review.files[0].hunks[0].new_lines, vec!["x\n"]);
+}
+
+#[test]
+fn test_review_esc_rejects_everything() {
+    let mut state = create_test_tui_state();
+    open_test_review(&mut state);
+    press(&mut state, crossterm::event::KeyCode::Esc);
+
+    let review = state.diff_review.as_ref().unwrap();
+    assert!(review.is_finished());
+    assert!(matches!(
+        review.outcome(),
+        crate::tui::chat::state::ReviewOutcome::Reject { .. }
+    ));
+}
+
+#[test]
+fn test_draw_tui_review_mode() {
+    let mut state = create_test_tui_state();
+    open_test_review(&mut state);
+
+    let mut terminal = create_test_terminal(100, 30);
+    terminal.draw(|f| draw_tui(f, &state)).unwrap();
+
+    let content: String = terminal
+        .backend()
+        .buffer()
+        .content()
+        .iter()
+        .map(|cell| cell.symbol())
+        .collect();
+    assert!(content.contains("src/lib.rs"));
+    assert!(content.contains("@@ -1,5 +1,5 @@"));
+    assert!(content.contains("+B"));
+    assert!(content.contains("[accepted]"));
+}
+
 #[test]
 fn test_draw_tui_settings_mode() {
     let mut state = create_test_tui_state();
@@ -21,6 +21,25 @@ use super::execution::{
 use super::render::draw_tui;
 use super::TuiState;
 
+/// Run UserPromptSubmit hooks on a prompt before it joins the conversation.
+///
+/// Returns the prompt to send, or `None` when a hook blocked it.
+pub(super) async fn run_prompt_hooks(
+    tool_executor: &ToolExecutor,
+    state: &mut TuiState,
+    prompt: &str,
+) -> Option<String> {
+    let outcome = tool_executor
+        .hooks()
+        .user_prompt_submit(tool_executor.context().session_id, prompt)
+        .await;
+    if let Some(reason) = &outcome.block_reason {
+        state.set_error(&format!("Prompt blocked by hook: {}", reason));
+        return None;
+    }
+    Some(outcome.apply_to_prompt(prompt))
+}
+
 /// Process LLM response and handle tool calls
 #[allow(clippy::too_many_arguments)]
 pub(super) async fn process_llm_response<B: Backend>(
@@ -39,6 +58,7 @@ pub(super) async fn process_llm_response<B: Backend>(
     let mut tool_call_tracker =
         crate::chat::ToolCallTracker::new(crate::chat::engine::MAX_RECENT_TOOL_CALLS);
     let mut turn_index: usize = 0;
+    let mut stop_continuations: usize = 0;
 
     let run_result: Result<bool> = async {
         loop {
@@ -246,6 +266,24 @@ pub(super) async fn process_llm_response<B: Backend>(
                 continue;
             }
 
+            // Stop hooks may send the agent back to work before the turn ends
+            if stop_continuations < crate::hooks::MAX_STOP_CONTINUATIONS {
+                let outcome = tool_executor
+                    .hooks()
+                    .stop(
+                        tool_executor.context().session_id,
+                        &response_text,
+                        stop_continuations > 0,
+                    )
+                    .await;
+                if let Some(reason) = outcome.block_reason {
+                    stop_continuations += 1;
+                    state.set_status(&format!("Stop hook: {}", reason));
+                    conversation.push(Message::user(crate::hooks::stop_feedback_message(&reason)));
+                    continue;
+                }
+            }
+
             // No more tool calls, we're done
             tracing::debug!(
                 target: "ted.tui.runner",
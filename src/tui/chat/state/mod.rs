@@ -6,9 +6,13 @@
 pub mod agents;
 pub mod input;
 pub mod messages;
+pub mod question;
+pub mod review;
 pub mod scroll;
 
 pub use agents::{AgentStatus, AgentTracker, TrackedAgent};
 pub use input::InputState;
 pub use messages::{truncate_string, DisplayMessage, DisplayToolCall, MessageRole, ToolCallStatus};
+pub use question::QuestionPicker;
+pub use review::{DiffReview, ReviewOutcome};
 pub use scroll::ScrollState;
@@ -0,0 +1,173 @@
+// SPDX-License-Identifier: AGPL-3.0-or-later
+// Copyright (C) 2025 Blackman Artificial Intelligence Technologies Inc.
+
+//! Picker state for `ask_user` questions
+
+use crate::tools::{PendingQuestion, UserQuestion};
+
+/// State of an open `ask_user` question picker.
+///
+/// Rows are the question's options followed by a free-text row when the
+/// question allows typed answers.
+#[derive(Debug)]
+pub struct QuestionPicker {
+    pending: PendingQuestion,
+    /// Index of the highlighted row
+    pub selected: usize,
+    /// Text typed into the free-text row
+    pub free_text: String,
+}
+
+impl QuestionPicker {
+    pub fn new(pending: PendingQuestion) -> Self {
+        Self {
+            pending,
+            selected: 0,
+            free_text: String::new(),
+        }
+    }
+
+    pub fn question(&self) -> &UserQuestion {
+        &self.pending.question
+    }
+
+    /// Whether the picker has a free-text row
+    pub fn has_free_text(&self) -> bool {
+        let question = self.question();
+        question.allow_free_text || question.options.is_empty()
+    }
+
+    /// Number of selectable rows
+    pub fn row_count(&self) -> usize {
+        self.question().options.len() + usize::from(self.has_free_text())
+    }
+
+    /// Whether the free-text row is highlighted
+    pub fn is_free_text_selected(&self) -> bool {
+        self.has_free_text() && self.selected == self.question().options.len()
+    }
+
+    pub fn select_next(&mut self) {
+        if self.row_count() > 0 {
+            self.selected = (self.selected + 1) % self.row_count();
+        }
+    }
+
+    pub fn select_prev(&mut self) {
+        if self.row_count() > 0 {
+            self.selected = (self.selected + self.row_count() - 1) % self.row_count();
+        }
+    }
+
+    /// Type into the free-text row, selecting it.
+    pub fn push_char(&mut self, c: char) {
+        if self.has_free_text() {
+            self.selected = self.question().options.len();
+            self.free_text.push(c);
+        }
+    }
+
+    pub fn backspace(&mut self) {
+        self.free_text.pop();
+    }
+
+    /// The answer for the highlighted row, if it has one
+    pub fn current_answer(&self) -> Option<String> {
+        if self.is_free_text_selected() {
+            let text = self.free_text.trim();
+            (!text.is_empty()).then(|| text.to_string())
+        } else {
+            self.question().options.get(self.selected).cloned()
+        }
+    }
+
+    /// Whether the tool stopped waiting (e.g. the turn was cancelled)
+    pub fn is_abandoned(&self) -> bool {
+        self.pending.respond_to.is_closed()
+    }
+
+    /// Answer the question.
+    pub fn submit(self, answer: String) {
+        self.pending.answer(Some(answer));
+    }
+
+    /// Dismiss the question without answering.
+    pub fn dismiss(self) {
+        self.pending.answer(None);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::sync::oneshot;
+
+    fn picker(
+        options: &[&str],
+        allow_free_text: bool,
+    ) -> (QuestionPicker, oneshot::Receiver<Option<String>>) {
+        let (respond_to, rx) = oneshot::channel();
+        let pending = PendingQuestion {
+            question: UserQuestion {
+                id: "q".to_string(),
+                question: "Which database?".to_string(),
+                options: options.iter().map(|o| o.to_string()).collect(),
+                allow_free_text,
+                default: None,
+            },
+            respond_to,
+        };
+        (QuestionPicker::new(pending), rx)
+    }
+
+    #[test]
+    fn test_navigation_wraps() {
+        let (mut p, _rx) = picker(&["sqlite", "postgres"], true);
+        assert_eq!(p.row_count(), 3);
+        p.select_prev();
+        assert!(p.is_free_text_selected());
+        p.select_next();
+        assert_eq!(p.current_answer().as_deref(), Some("sqlite"));
+    }
+
+    #[test]
+    fn test_submit_option() {
+        let (mut p, mut rx) = picker(&["sqlite", "postgres"], false);
+        p.select_next();
+        let answer = p.current_answer().unwrap();
+        p.submit(answer);
+        assert_eq!(rx.try_recv().unwrap().as_deref(), Some("postgres"));
+    }
+
+    #[test]
+    fn test_free_text_requires_input() {
+        let (mut p, mut rx) = picker(&[], false);
+        assert!(p.has_free_text());
+        assert_eq!(p.current_answer(), None);
+        p.push_char('h');
+        p.push_char('i');
+        let answer = p.current_answer().unwrap();
+        assert_eq!(answer, "hi");
+        p.submit(answer);
+        assert_eq!(rx.try_recv().unwrap().as_deref(), Some("hi"));
+    }
+
+    #[test]
+    fn test_typing_ignored_without_free_text() {
+        let (mut p, _rx) = picker(&["yes", "no"], false);
+        p.push_char('x');
+        assert!(p.free_text.is_empty());
+        assert_eq!(p.current_answer().as_deref(), Some("yes"));
+    }
+
+    #[test]
+    fn test_dismiss_and_abandon() {
+        let (p, mut rx) = picker(&["yes"], false);
+        p.dismiss();
+        assert_eq!(rx.try_recv().unwrap(), None);
+
+        let (p, rx) = picker(&["yes"], false);
+        drop(rx);
+        assert!(p.is_abandoned());
+    }
+}
@@ -0,0 +1,1051 @@
+// SPDX-License-Identifier: AGPL-3.0-or-later
+// Copyright (C) 2025 Blackman Artificial Intelligence Technologies Inc.
+
+//! Hunk-level review of pending file edits
+//!
+//! Before `file_edit`, `file_write` or `propose_file_changes` runs in the
+//! TUI, the change it would make is simulated and split into hunks. The user
+//! accepts or rejects each hunk, can rewrite a hunk's replacement, and can
+//! leave a note for the model. [`DiffReview::outcome`] turns those decisions
+//! into a rewritten tool input or a rejection.
+
+use std::path::{Path, PathBuf};
+
+use difflib::sequencematcher::SequenceMatcher;
+use serde_json::Value;
+
+use crate::tools::FileOperation;
+
+/// Tools whose changes are reviewed before they run
+pub const REVIEWED_TOOLS: &[&str] = &["file_edit", "file_write", "propose_file_changes"];
+
+/// Unchanged lines shown around each hunk
+const CONTEXT_LINES: usize = 3;
+
+const PATH_KEYS: &[&str] = &["path", "file", "file_path", "filepath"];
+
+/// The effect a tool call would have on one file
+#[derive(Debug, Clone, PartialEq)]
+pub struct FileChange {
+    /// Path as written by the model
+    pub path: String,
+    pub original: String,
+    pub proposed: String,
+    /// Whether the call deletes the file
+    pub deleted: bool,
+}
+
+/// Simulate a reviewed tool call without touching the filesystem.
+///
+/// Returns `None` when the call changes nothing or would fail anyway, in
+/// which case it runs unreviewed and reports its own error.
+pub fn simulate_changes(
+    tool_name: &str,
+    input: &Value,
+    working_directory: &Path,
+) -> Option<Vec<FileChange>> {
+    let changes = match tool_name {
+        "file_edit" => {
+            let path = first_str(input, PATH_KEYS)?;
+            let old = first_str(
+                input,
+                &[
+                    "old_string",
+                    "old",
+                    "old_text",
+                    "search",
+                    "find",
+                    "original",
+                    "from",
+                ],
+            )?;
+            let new = first_str(
+                input,
+                &[
+                    "new_string",
+                    "new",
+                    "new_text",
+                    "replace",
+                    "replacement",
+                    "to",
+                ],
+            )?;
+            let replace_all = input["replace_all"].as_bool().unwrap_or(false);
+            let original = std::fs::read_to_string(resolve(working_directory, path)).ok()?;
+            let occurrences = original.matches(old).count();
+            if old.is_empty() || occurrences == 0 || (occurrences > 1 && !replace_all) {
+                return None;
+            }
+            let proposed = if replace_all {
+                original.replace(old, new)
+            } else {
+                original.replacen(old, new, 1)
+            };
+            vec![FileChange {
+                path: path.to_string(),
+                original,
+                proposed,
+                deleted: false,
+            }]
+        }
+        "file_write" => {
+            let path = first_str(input, PATH_KEYS)?;
+            let content = first_str(input, &["content", "text", "body", "data"])?;
+            let full = resolve(working_directory, path);
+            let original = if full.exists() {
+                std::fs::read_to_string(&full).ok()?
+            } else {
+                String::new()
+            };
+            vec![FileChange {
+                path: path.to_string(),
+                original,
+                proposed: content.to_string(),
+                deleted: false,
+            }]
+        }
+        "propose_file_changes" => {
+            let operations: Vec<FileOperation> =
+                serde_json::from_value(input.get("operations")?.clone()).ok()?;
+            let mut changes: Vec<FileChange> = Vec::new();
+            for operation in operations {
+                let path = match &operation {
+                    FileOperation::Read { .. } => continue,
+                    FileOperation::Edit { path, .. }
+                    | FileOperation::Write { path, .. }
+                    | FileOperation::Delete { path } => path.clone(),
+                };
+                let index = match changes.iter().position(|c| c.path == path) {
+                    Some(index) => index,
+                    None => {
+                        let original = std::fs::read_to_string(resolve(working_directory, &path))
+                            .unwrap_or_default();
+                        changes.push(FileChange {
+                            path,
+                            proposed: original.clone(),
+                            original,
+                            deleted: false,
+                        });
+                        changes.len() - 1
+                    }
+                };
+                let change = &mut changes[index];
+                match operation {
+                    FileOperation::Edit {
+                        old_string,
+                        new_string,
+                        ..
+                    } => {
+                        if old_string.is_empty() || !change.proposed.contains(&old_string) {
+                            return None;
+                        }
+                        change.proposed = change.proposed.replacen(&old_string, &new_string, 1);
+                        change.deleted = false;
+                    }
+                    FileOperation::Write { content, .. } => {
+                        change.proposed = content;
+                        change.deleted = false;
+                    }
+                    FileOperation::Delete { .. } => {
+                        change.proposed = String::new();
+                        change.deleted = true;
+                    }
+                    FileOperation::Read { .. } => {}
+                }
+            }
+            changes.retain(|c| c.deleted || c.proposed != c.original);
+            changes
+        }
+        _ => return None,
+    };
+    (!changes.is_empty()).then_some(changes)
+}
+
+fn first_str<'a>(input: &'a Value, keys: &[&str]) -> Option<&'a str> {
+    keys.iter().find_map(|key| input[*key].as_str())
+}
+
+fn resolve(working_directory: &Path, path: &str) -> PathBuf {
+    let path = PathBuf::from(path);
+    if path.is_absolute() {
+        path
+    } else {
+        working_directory.join(path)
+    }
+}
+
+/// Split text into lines, keeping line endings so the text can be rebuilt
+/// byte for byte.
+fn split_lines(text: &str) -> Vec<String> {
+    text.split_inclusive('\n').map(str::to_string).collect()
+}
+
+/// A line without its line ending, for display
+pub fn display_line(line: &str) -> &str {
+    line.trim_end_matches('\n').trim_end_matches('\r')
+}
+
+/// One contiguous change within a file
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReviewHunk {
+    /// Index of the first original line the hunk replaces
+    pub old_start: usize,
+    pub old_lines: Vec<String>,
+    /// Replacement lines as proposed by the model
+    pub proposed_lines: Vec<String>,
+    /// Replacement lines applied when the hunk is accepted
+    pub new_lines: Vec<String>,
+    /// Unchanged lines shown before and after the change
+    pub before: Vec<String>,
+    pub after: Vec<String>,
+    pub accepted: bool,
+}
+
+impl ReviewHunk {
+    /// Whether the user rewrote the replacement
+    pub fn is_edited(&self) -> bool {
+        self.new_lines != self.proposed_lines
+    }
+
+    /// Whether the replacement should end with a line ending
+    fn ends_with_newline(&self) -> bool {
+        self.proposed_lines
+            .last()
+            .or(self.old_lines.last())
+            .is_none_or(|line| line.ends_with('\n'))
+    }
+}
+
+/// A row of the rendered diff
+#[derive(Debug, Clone, PartialEq)]
+pub enum DiffLine {
+    /// `@@ -a,b +c,d @@` header of the hunk at this index
+    Header {
+        hunk: usize,
+        text: String,
+    },
+    Context(String),
+    Removed(String),
+    Added(String),
+}
+
+/// Review state for one file
+#[derive(Debug, Clone)]
+pub struct FileReview {
+    pub path: String,
+    pub deleted: bool,
+    original: String,
+    original_lines: Vec<String>,
+    pub hunks: Vec<ReviewHunk>,
+}
+
+impl FileReview {
+    pub fn new(change: FileChange) -> Self {
+        let original_lines = split_lines(&change.original);
+        let proposed_lines = split_lines(&change.proposed);
+        let mut matcher = SequenceMatcher::new(&original_lines, &proposed_lines);
+        let hunks = matcher
+            .get_grouped_opcodes(CONTEXT_LINES)
+            .into_iter()
+            .filter_map(|group| {
+                let changed: Vec<_> = group.iter().filter(|op| op.tag != "equal").collect();
+                let first = changed.first()?;
+                let last = changed.last()?;
+                let before = match group.first() {
+                    Some(op) if op.tag == "equal" => {
+                        original_lines[op.first_start..op.first_end].to_vec()
+                    }
+                    _ => Vec::new(),
+                };
+                let after = match group.last() {
+                    Some(op) if op.tag == "equal" && group.len() > 1 => {
+                        original_lines[op.first_start..op.first_end].to_vec()
+                    }
+                    _ => Vec::new(),
+                };
+                let new_lines = proposed_lines[first.second_start..last.second_end].to_vec();
+                Some(ReviewHunk {
+                    old_start: first.first_start,
+                    old_lines: original_lines[first.first_start..last.first_end].to_vec(),
+                    proposed_lines: new_lines.clone(),
+                    new_lines,
+                    before,
+                    after,
+                    accepted: true,
+                })
+            })
+            .collect();
+        Self {
+            path: change.path,
+            deleted: change.deleted,
+            original: change.original,
+            original_lines,
+            hunks,
+        }
+    }
+
+    pub fn original(&self) -> &str {
+        &self.original
+    }
+
+    /// The file content after applying accepted hunks
+    pub fn result(&self) -> String {
+        let mut result = String::new();
+        let mut cursor = 0;
+        for hunk in &self.hunks {
+            for line in &self.original_lines[cursor..hunk.old_start] {
+                result.push_str(line);
+            }
+            let lines = if hunk.accepted {
+                &hunk.new_lines
+            } else {
+                &hunk.old_lines
+            };
+            for line in lines {
+                result.push_str(line);
+            }
+            cursor = hunk.old_start + hunk.old_lines.len();
+        }
+        for line in &self.original_lines[cursor..] {
+            result.push_str(line);
+        }
+        result
+    }
+
+    pub fn accepted_count(&self) -> usize {
+        self.hunks.iter().filter(|h| h.accepted).count()
+    }
+
+    /// Whether every hunk is accepted exactly as proposed
+    pub fn is_as_proposed(&self) -> bool {
+        self.hunks.iter().all(|h| h.accepted && !h.is_edited())
+    }
+
+    /// Rows of the unified diff, with hunk coordinates reflecting the current
+    /// decisions
+    pub fn diff_lines(&self) -> Vec<DiffLine> {
+        let mut rows = Vec::new();
+        let mut delta: isize = 0;
+        for (index, hunk) in self.hunks.iter().enumerate() {
+            let context = hunk.before.len() + hunk.after.len();
+            let old_start = hunk.old_start - hunk.before.len();
+            let old_len = hunk.old_lines.len() + context;
+            let new_len = hunk.new_lines.len() + context;
+            let new_start = (old_start as isize + delta).max(0) as usize;
+            rows.push(DiffLine::Header {
+                hunk: index,
+                text: format!(
+                    "@@ -{},{} +{},{} @@",
+                    range_start(old_start, old_len),
+                    old_len,
+                    range_start(new_start, new_len),
+                    new_len
+                ),
+            });
+            rows.extend(
+                hunk.before
+                    .iter()
+                    .map(|l| DiffLine::Context(display_line(l).to_string())),
+            );
+            rows.extend(
+                hunk.old_lines
+                    .iter()
+                    .map(|l| DiffLine::Removed(display_line(l).to_string())),
+            );
+            rows.extend(
+                hunk.new_lines
+                    .iter()
+                    .map(|l| DiffLine::Added(display_line(l).to_string())),
+            );
+            rows.extend(
+                hunk.after
+                    .iter()
+                    .map(|l| DiffLine::Context(display_line(l).to_string())),
+            );
+            if hunk.accepted {
+                delta += hunk.new_lines.len() as isize - hunk.old_lines.len() as isize;
+            }
+        }
+        rows
+    }
+
+    /// Describe the review of this file for the model
+    fn summary(&self) -> String {
+        let total = self.hunks.len();
+        let mut summary = format!(
+            "{}: applied {} of {} hunk{}",
+            self.path,
+            self.accepted_count(),
+            total,
+            if total == 1 { "" } else { "s" }
+        );
+        let rejected: Vec<String> = self
+            .hunks
+            .iter()
+            .filter(|h| !h.accepted)
+            .map(|h| line_label(h.old_start, h.old_lines.len()))
+            .collect();
+        if !rejected.is_empty() {
+            summary.push_str(&format!("; rejected {}", rejected.join(", ")));
+        }
+        let edited: Vec<String> = self
+            .hunks
+            .iter()
+            .filter(|h| h.accepted && h.is_edited())
+            .map(|h| line_label(h.old_start, h.old_lines.len()))
+            .collect();
+        if !edited.is_empty() {
+            summary.push_str(&format!(
+                "; the user rewrote the replacement for {}",
+                edited.join(", ")
+            ));
+        }
+        summary
+    }
+}
+
+/// Unified diff ranges start at the line before an empty range
+fn range_start(start: usize, len: usize) -> usize {
+    if len == 0 {
+        start
+    } else {
+        start + 1
+    }
+}
+
+fn line_label(start: usize, len: usize) -> String {
+    match len {
+        0 => format!("the insertion after line {}", start),
+        1 => format!("line {}", start + 1),
+        _ => format!("lines {}-{}", start + 1, start + len),
+    }
+}
+
+/// Inline editor for a hunk's replacement lines
+#[derive(Debug, Clone, PartialEq)]
+pub struct HunkEditor {
+    pub lines: Vec<String>,
+    pub row: usize,
+    /// Cursor column in characters
+    pub col: usize,
+}
+
+impl HunkEditor {
+    pub fn new(lines: &[String]) -> Self {
+        let mut lines: Vec<String> = lines.iter().map(|l| display_line(l).to_string()).collect();
+        if lines.is_empty() {
+            lines.push(String::new());
+        }
+        let row = lines.len() - 1;
+        let col = lines[row].chars().count();
+        Self { lines, row, col }
+    }
+
+    fn byte_index(&self) -> usize {
+        self.lines[self.row]
+            .char_indices()
+            .nth(self.col)
+            .map_or(self.lines[self.row].len(), |(i, _)| i)
+    }
+
+    pub fn insert_char(&mut self, c: char) {
+        let index = self.byte_index();
+        self.lines[self.row].insert(index, c);
+        self.col += 1;
+    }
+
+    pub fn insert_newline(&mut self) {
+        let index = self.byte_index();
+        let rest = self.lines[self.row].split_off(index);
+        self.row += 1;
+        self.lines.insert(self.row, rest);
+        self.col = 0;
+    }
+
+    pub fn backspace(&mut self) {
+        if self.col > 0 {
+            self.col -= 1;
+            let index = self.byte_index();
+            self.lines[self.row].remove(index);
+        } else if self.row > 0 {
+            let line = self.lines.remove(self.row);
+            self.row -= 1;
+            self.col = self.lines[self.row].chars().count();
+            self.lines[self.row].push_str(&line);
+        }
+    }
+
+    pub fn move_left(&mut self) {
+        if self.col > 0 {
+            self.col -= 1;
+        } else if self.row > 0 {
+            self.row -= 1;
+            self.col = self.lines[self.row].chars().count();
+        }
+    }
+
+    pub fn move_right(&mut self) {
+        if self.col < self.lines[self.row].chars().count() {
+            self.col += 1;
+        } else if self.row + 1 < self.lines.len() {
+            self.row += 1;
+            self.col = 0;
+        }
+    }
+
+    pub fn move_up(&mut self) {
+        if self.row > 0 {
+            self.row -= 1;
+            self.col = self.col.min(self.lines[self.row].chars().count());
+        }
+    }
+
+    pub fn move_down(&mut self) {
+        if self.row + 1 < self.lines.len() {
+            self.row += 1;
+            self.col = self.col.min(self.lines[self.row].chars().count());
+        }
+    }
+
+    /// The edited lines with line endings restored. A single empty line
+    /// means the replacement is empty.
+    fn into_lines(self, ends_with_newline: bool) -> Vec<String> {
+        if self.lines.len() == 1 && self.lines[0].is_empty() {
+            return Vec::new();
+        }
+        let count = self.lines.len();
+        self.lines
+            .into_iter()
+            .enumerate()
+            .map(|(i, line)| {
+                if i + 1 < count || ends_with_newline {
+                    format!("{}\n", line)
+                } else {
+                    line
+                }
+            })
+            .collect()
+    }
+}
+
+/// What the review is currently taking keyboard input for
+#[derive(Debug, Clone, PartialEq)]
+pub enum ReviewInput {
+    Browse,
+    /// Rewriting the selected hunk's replacement
+    Edit(HunkEditor),
+    /// Typing a note for the model; holds the note from before editing
+    Note(String),
+}
+
+/// What to do with a reviewed tool call
+#[derive(Debug, Clone, PartialEq)]
+pub enum ReviewOutcome {
+    /// Run the call as proposed
+    Approve,
+    /// Run the call with `input` and append `summary` to its result
+    Modify { input: Value, summary: String },
+    /// Don't run the call; report `message` to the model as an error
+    Reject { message: String },
+}
+
+/// An open review of one tool call
+#[derive(Debug, Clone)]
+pub struct DiffReview {
+    pub tool_use_id: String,
+    pub tool_name: String,
+    tool_input: Value,
+    pub files: Vec<FileReview>,
+    pub selected_file: usize,
+    pub selected_hunk: usize,
+    /// Note sent back to the model with the result
+    pub note: String,
+    pub input: ReviewInput,
+    finished: bool,
+}
+
+impl DiffReview {
+    /// Open a review of `changes`, or `None` if they produce no hunks
+    pub fn new(
+        tool_use_id: String,
+        tool_name: String,
+        tool_input: Value,
+        changes: Vec<FileChange>,
+    ) -> Option<Self> {
+        let files: Vec<FileReview> = changes
+            .into_iter()
+            .map(FileReview::new)
+            .filter(|f| !f.hunks.is_empty())
+            .collect();
+        if files.is_empty() {
+            return None;
+        }
+        Some(Self {
+            tool_use_id,
+            tool_name,
+            tool_input,
+            files,
+            selected_file: 0,
+            selected_hunk: 0,
+            note: String::new(),
+            input: ReviewInput::Browse,
+            finished: false,
+        })
+    }
+
+    pub fn current_file(&self) -> &FileReview {
+        &self.files[self.selected_file]
+    }
+
+    pub fn current_hunk(&self) -> &ReviewHunk {
+        &self.current_file().hunks[self.selected_hunk]
+    }
+
+    fn current_hunk_mut(&mut self) -> &mut ReviewHunk {
+        &mut self.files[self.selected_file].hunks[self.selected_hunk]
+    }
+
+    pub fn total_hunks(&self) -> usize {
+        self.files.iter().map(|f| f.hunks.len()).sum()
+    }
+
+    /// Select the next hunk, continuing into the next file
+    pub fn next_hunk(&mut self) {
+        if self.selected_hunk + 1 < self.current_file().hunks.len() {
+            self.selected_hunk += 1;
+        } else if self.selected_file + 1 < self.files.len() {
+            self.selected_file += 1;
+            self.selected_hunk = 0;
+        }
+    }
+
+    /// Select the previous hunk, continuing into the previous file
+    pub fn prev_hunk(&mut self) {
+        if self.selected_hunk > 0 {
+            self.selected_hunk -= 1;
+        } else if self.selected_file > 0 {
+            self.selected_file -= 1;
+            self.selected_hunk = self.current_file().hunks.len() - 1;
+        }
+    }
+
+    pub fn next_file(&mut self) {
+        self.selected_file = (self.selected_file + 1) % self.files.len();
+        self.selected_hunk = 0;
+    }
+
+    pub fn prev_file(&mut self) {
+        self.selected_file = (self.selected_file + self.files.len() - 1) % self.files.len();
+        self.selected_hunk = 0;
+    }
+
+    /// Accept or reject the selected hunk and move to the next one
+    pub fn decide_current(&mut self, accepted: bool) {
+        self.current_hunk_mut().accepted = accepted;
+        self.next_hunk();
+    }
+
+    pub fn toggle_current(&mut self) {
+        let hunk = self.current_hunk_mut();
+        hunk.accepted = !hunk.accepted;
+    }
+
+    pub fn decide_all(&mut self, accepted: bool) {
+        for hunk in self.files.iter_mut().flat_map(|f| f.hunks.iter_mut()) {
+            hunk.accepted = accepted;
+        }
+    }
+
+    pub fn start_edit(&mut self) {
+        self.input = ReviewInput::Edit(HunkEditor::new(&self.current_hunk().new_lines));
+    }
+
+    /// Apply the inline edit to the selected hunk, accepting it
+    pub fn save_edit(&mut self) {
+        if let ReviewInput::Edit(editor) = std::mem::replace(&mut self.input, ReviewInput::Browse) {
+            let hunk = self.current_hunk_mut();
+            hunk.new_lines = editor.into_lines(hunk.ends_with_newline());
+            hunk.accepted = true;
+        }
+    }
+
+    pub fn start_note(&mut self) {
+        self.input = ReviewInput::Note(self.note.clone());
+    }
+
+    /// Restore the note from before editing started
+    pub fn cancel_note(&mut self) {
+        if let ReviewInput::Note(previous) = std::mem::replace(&mut self.input, ReviewInput::Browse)
+        {
+            self.note = previous;
+        }
+    }
+
+    /// Leave the editor or note prompt without further changes
+    pub fn browse(&mut self) {
+        self.input = ReviewInput::Browse;
+    }
+
+    /// Submit the current decisions
+    pub fn submit(&mut self) {
+        self.input = ReviewInput::Browse;
+        self.finished = true;
+    }
+
+    /// Reject every hunk and submit
+    pub fn reject(&mut self) {
+        self.decide_all(false);
+        self.submit();
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    /// Turn the decisions into what should happen to the tool call
+    pub fn outcome(&self) -> ReviewOutcome {
+        let note = self.note.trim();
+        let note_text = if note.is_empty() {
+            String::new()
+        } else {
+            format!("\n\nNote from the user: {}", note)
+        };
+
+        let changed: Vec<&FileReview> = self
+            .files
+            .iter()
+            .filter(|f| (f.deleted && f.is_as_proposed()) || f.result() != f.original)
+            .collect();
+        if changed.is_empty() {
+            let paths: Vec<&str> = self.files.iter().map(|f| f.path.as_str()).collect();
+            return ReviewOutcome::Reject {
+                message: format!(
+                    "The user rejected the proposed change to {}.{}",
+                    paths.join(", "),
+                    note_text
+                ),
+            };
+        }
+
+        if self.files.iter().all(FileReview::is_as_proposed) {
+            return if note.is_empty() {
+                ReviewOutcome::Approve
+            } else {
+                ReviewOutcome::Modify {
+                    input: self.tool_input.clone(),
+                    summary: note_text.trim_start().to_string(),
+                }
+            };
+        }
+
+        let mut summary = String::from("User review:");
+        for file in &self.files {
+            summary.push_str("\n- ");
+            summary.push_str(&file.summary());
+        }
+        summary.push_str("\nRe-read these files before editing them again.");
+        summary.push_str(&note_text);
+        ReviewOutcome::Modify {
+            input: self.rewrite_input(),
+            summary,
+        }
+    }
+
+    /// The tool input that applies exactly the reviewed result
+    fn rewrite_input(&self) -> Value {
+        let mut input = self.tool_input.clone();
+        match self.tool_name.as_str() {
+            "file_edit" => {
+                let file = &self.files[0];
+                input["old_string"] = Value::String(file.original().to_string());
+                input["new_string"] = Value::String(file.result());
+                input["replace_all"] = Value::Bool(false);
+            }
+            "file_write" => {
+                input["content"] = Value::String(self.files[0].result());
+            }
+            "propose_file_changes" => {
+                let mut operations: Vec<FileOperation> = input
+                    .get("operations")
+                    .cloned()
+                    .and_then(|ops| serde_json::from_value(ops).ok())
+                    .unwrap_or_default();
+                for file in self.files.iter().filter(|f| !f.is_as_proposed()) {
+                    let touches = |op: &FileOperation| match op {
+                        FileOperation::Read { .. } => false,
+                        FileOperation::Edit { path, .. }
+                        | FileOperation::Write { path, .. }
+                        | FileOperation::Delete { path } => path == &file.path,
+                    };
+                    let position = operations.iter().position(touches);
+                    operations.retain(|op| !touches(op));
+                    let result = file.result();
+                    if result != file.original {
+                        let write = FileOperation::Write {
+                            path: file.path.clone(),
+                            content: result,
+                        };
+                        let index = position.unwrap_or(operations.len()).min(operations.len());
+                        operations.insert(index, write);
+                    }
+                }
+                if let Ok(operations) = serde_json::to_value(operations) {
+                    input["operations"] = operations;
+                }
+            }
+            _ => {}
+        }
+        input
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    const ORIGINAL: &str =
+        "one\ntwo\nthree\nfour\nfive\nsix\nseven\neight\nnine\nten\neleven\ntwelve\n";
+
+    fn two_hunk_review() -> DiffReview {
+        let proposed = ORIGINAL
+            .replace("two\n", "TWO\n")
+            .replace("eleven\n", "ELEVEN\n");
+        let change = FileChange {
+            path: "a.txt".to_string(),
+            original: ORIGINAL.to_string(),
+            proposed,
+            deleted: false,
+        };
+        DiffReview::new(
+            "t1".to_string(),
+            "file_edit".to_string(),
+            json!({"path": "a.txt", "old_string": "two", "new_string": "TWO"}),
+            vec![change],
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_file_review_splits_distant_changes_into_hunks() {
+        let review = two_hunk_review();
+        let file = review.current_file();
+        assert_eq!(file.hunks.len(), 2);
+        assert_eq!(file.hunks[0].old_start, 1);
+        assert_eq!(file.hunks[0].old_lines, vec!["two\n"]);
+        assert_eq!(file.hunks[0].new_lines, vec!["TWO\n"]);
+        assert_eq!(file.hunks[0].before, vec!["one\n"]);
+        assert_eq!(file.hunks[0].after.len(), CONTEXT_LINES);
+        assert_eq!(
+            file.result(),
+            ORIGINAL.replace("two", "TWO").replace("eleven", "ELEVEN")
+        );
+    }
+
+    #[test]
+    fn test_file_review_result_respects_rejections() {
+        let mut review = two_hunk_review();
+        review.decide_current(false);
+        assert_eq!(review.selected_hunk, 1);
+        assert_eq!(
+            review.current_file().result(),
+            ORIGINAL.replace("eleven", "ELEVEN")
+        );
+    }
+
+    #[test]
+    fn test_diff_lines_headers_track_accepted_hunks() {
+        let change = FileChange {
+            path: "a.txt".to_string(),
+            original: ORIGINAL.to_string(),
+            proposed: ORIGINAL
+                .replace("two\n", "two\nextra\n")
+                .replace("eleven\n", "ELEVEN\n"),
+            deleted: false,
+        };
+        let file = FileReview::new(change);
+        let headers: Vec<String> = file
+            .diff_lines()
+            .into_iter()
+            .filter_map(|row| match row {
+                DiffLine::Header { text, .. } => Some(text),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(headers, vec!["@@ -1,5 +1,6 @@", "@@ -8,5 +9,5 @@"]);
+        assert!(file
+            .diff_lines()
+            .contains(&DiffLine::Added("extra".to_string())));
+    }
+
+    #[test]
+    fn test_outcome_approve_when_all_accepted() {
+        let mut review = two_hunk_review();
+        review.submit();
+        assert!(review.is_finished());
+        assert_eq!(review.outcome(), ReviewOutcome::Approve);
+    }
+
+    #[test]
+    fn test_outcome_reject_includes_note() {
+        let mut review = two_hunk_review();
+        review.note = "Keep the lowercase names".to_string();
+        review.reject();
+        match review.outcome() {
+            ReviewOutcome::Reject { message } => {
+                assert!(message.contains("rejected the proposed change to a.txt"));
+                assert!(message.contains("Note from the user: Keep the lowercase names"));
+            }
+            other => panic!("expected rejection, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_outcome_partial_rewrites_file_edit_input() {
+        let mut review = two_hunk_review();
+        review.next_hunk();
+        review.decide_current(false);
+        match review.outcome() {
+            ReviewOutcome::Modify { input, summary } => {
+                assert_eq!(input["old_string"], ORIGINAL);
+                assert_eq!(input["new_string"], ORIGINAL.replace("two", "TWO"));
+                assert_eq!(input["replace_all"], false);
+                assert!(summary.contains("a.txt: applied 1 of 2 hunks; rejected line 11"));
+            }
+            other => panic!("expected modification, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_outcome_approve_with_note_keeps_input() {
+        let mut review = two_hunk_review();
+        review.note = "Looks good".to_string();
+        match review.outcome() {
+            ReviewOutcome::Modify { input, summary } => {
+                assert_eq!(input["old_string"], "two");
+                assert_eq!(summary, "Note from the user: Looks good");
+            }
+            other => panic!("expected modification, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_inline_edit_replaces_hunk_and_accepts_it() {
+        let mut review = two_hunk_review();
+        review.decide_current(false);
+        review.prev_hunk();
+        review.start_edit();
+        if let ReviewInput::Edit(editor) = &mut review.input {
+            editor.insert_char('!');
+            editor.insert_newline();
+            editor.insert_char('x');
+        }
+        review.save_edit();
+        let hunk = review.current_hunk();
+        assert!(hunk.accepted);
+        assert!(hunk.is_edited());
+        assert_eq!(hunk.new_lines, vec!["TWO!\n", "x\n"]);
+        match review.outcome() {
+            ReviewOutcome::Modify { input, summary } => {
+                assert!(input["new_string"]
+                    .as_str()
+                    .unwrap()
+                    .starts_with("one\nTWO!\nx\nthree\n"));
+                assert!(summary.contains("rewrote the replacement for line 2"));
+            }
+            other => panic!("expected modification, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_note_cancel_restores_previous_note() {
+        let mut review = two_hunk_review();
+        review.note = "first".to_string();
+        review.start_note();
+        review.note.push_str(" draft");
+        review.cancel_note();
+        assert_eq!(review.note, "first");
+        assert_eq!(review.input, ReviewInput::Browse);
+    }
+
+    #[test]
+    fn test_hunk_editor_cursor_movement() {
+        let mut editor = HunkEditor::new(&["ab\n".to_string(), "cd\n".to_string()]);
+        assert_eq!((editor.row, editor.col), (1, 2));
+        editor.move_up();
+        editor.move_left();
+        editor.backspace();
+        assert_eq!(editor.lines, vec!["b", "cd"]);
+        editor.move_right();
+        editor.move_right();
+        assert_eq!((editor.row, editor.col), (1, 0));
+        editor.backspace();
+        assert_eq!(editor.lines, vec!["bcd"]);
+        assert_eq!(editor.clone().into_lines(false), vec!["bcd"]);
+        assert!(HunkEditor::new(&[]).into_lines(true).is_empty());
+    }
+
+    #[test]
+    fn test_simulate_file_edit_and_write() {
+        let temp = TempDir::new().unwrap();
+        std::fs::write(temp.path().join("a.txt"), "x = 1\nx = 1\n").unwrap();
+
+        let single = json!({"path": "a.txt", "old_string": "x = 1", "new_string": "x = 2"});
+        assert!(simulate_changes("file_edit", &single, temp.path()).is_none());
+
+        let all = json!({"path": "a.txt", "old": "x = 1", "new": "x = 2", "replace_all": true});
+        let changes = simulate_changes("file_edit", &all, temp.path()).unwrap();
+        assert_eq!(changes[0].proposed, "x = 2\nx = 2\n");
+
+        let write = json!({"path": "b.txt", "content": "new\n"});
+        let changes = simulate_changes("file_write", &write, temp.path()).unwrap();
+        assert_eq!(changes[0].original, "");
+        let existing = json!({"path": "a.txt", "content": "new\n"});
+        let changes = simulate_changes("file_write", &existing, temp.path()).unwrap();
+        assert_eq!(changes[0].original, "x = 1\nx = 1\n");
+        assert_eq!(changes[0].proposed, "new\n");
+        assert!(simulate_changes("file_read", &write, temp.path()).is_none());
+    }
+
+    #[test]
+    fn test_simulate_and_rewrite_propose_file_changes() {
+        let temp = TempDir::new().unwrap();
+        std::fs::write(temp.path().join("a.txt"), ORIGINAL).unwrap();
+        std::fs::write(temp.path().join("old.txt"), "bye\n").unwrap();
+        let input = json!({
+            "id": "cs",
+            "description": "demo",
+            "operations": [
+                {"type": "read", "path": "a.txt"},
+                {"type": "edit", "path": "a.txt", "old_string": "two", "new_string": "TWO"},
+                {"type": "edit", "path": "a.txt", "old_string": "eleven", "new_string": "ELEVEN"},
+                {"type": "delete", "path": "old.txt"}
+            ]
+        });
+        let changes = simulate_changes("propose_file_changes", &input, temp.path()).unwrap();
+        assert_eq!(changes.len(), 2);
+        assert!(changes[1].deleted);
+
+        let mut review = DiffReview::new(
+            "t1".to_string(),
+            "propose_file_changes".to_string(),
+            input,
+            changes,
+        )
+        .unwrap();
+        review.next_hunk();
+        review.decide_current(false);
+        match review.outcome() {
+            ReviewOutcome::Modify { input, .. } => {
+                let ops = input["operations"].as_array().unwrap();
+                assert_eq!(ops.len(), 3);
+                assert_eq!(ops[0]["type"], "read");
+                assert_eq!(ops[1]["type"], "write");
+                assert_eq!(ops[1]["content"], ORIGINAL.replace("two", "TWO"));
+                assert_eq!(ops[2]["type"], "delete");
+            }
+            other => panic!("expected modification, got {other:?}"),
+        }
+    }
+}
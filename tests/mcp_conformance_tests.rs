@@ -0,0 +1,446 @@
+// SPDX-License-Identifier: AGPL-3.0-or-later
+// Copyright (C) 2025 Blackman Artificial Intelligence Technologies Inc.
+
+//! MCP conformance tests
+//!
+//! Drive `McpServer::serve` over an in-memory pipe exactly as a stdio client
+//! would: newline-delimited JSON-RPC in, responses and notifications out.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use serde_json::{json, Value};
+use ted::llm::{ToolDefinition, ToolInputSchema};
+use ted::mcp::{McpServer, PROTOCOL_VERSION, SUPPORTED_PROTOCOL_VERSIONS};
+use ted::tools::{PermissionRequest, Tool, ToolContext, ToolExecutor, ToolRegistry, ToolResult};
+use tempfile::TempDir;
+use tokio::io::{
+    AsyncBufReadExt, AsyncWrite, AsyncWriteExt, BufReader, DuplexStream, Lines, ReadHalf, WriteHalf,
+};
+
+const READ_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Sleeps until cancelled
+struct SlowTool;
+
+/// Reports three progress steps, then succeeds
+struct StepsTool;
+
+fn schema() -> ToolInputSchema {
+    ToolInputSchema {
+        schema_type: "object".to_string(),
+        properties: json!({}),
+        required: vec![],
+    }
+}
+
+#[async_trait::async_trait]
+impl Tool for SlowTool {
+    fn name(&self) -> &str {
+        "slow"
+    }
+
+    fn definition(&self) -> ToolDefinition {
+        ToolDefinition {
+            name: "slow".to_string(),
+            description: "Never finishes on its own".to_string(),
+            input_schema: schema(),
+        }
+    }
+
+    fn permission_request(&self, _input: &Value) -> Option<PermissionRequest> {
+        None
+    }
+
+    async fn execute(
+        &self,
+        tool_use_id: String,
+        _input: Value,
+        _context: &ToolContext,
+    ) -> ted::Result<ToolResult> {
+        tokio::time::sleep(Duration::from_secs(3600)).await;
+        Ok(ToolResult::success(tool_use_id, "late"))
+    }
+}
+
+#[async_trait::async_trait]
+impl Tool for StepsTool {
+    fn name(&self) -> &str {
+        "steps"
+    }
+
+    fn definition(&self) -> ToolDefinition {
+        ToolDefinition {
+            name: "steps".to_string(),
+            description: "Reports progress".to_string(),
+            input_schema: schema(),
+        }
+    }
+
+    fn permission_request(&self, _input: &Value) -> Option<PermissionRequest> {
+        None
+    }
+
+    async fn execute(
+        &self,
+        tool_use_id: String,
+        _input: Value,
+        context: &ToolContext,
+    ) -> ted::Result<ToolResult> {
+        for step in 1..=3u8 {
+            context.emit_tool_progress(&tool_use_id, "steps", format!("step {}", step), None);
+        }
+        Ok(ToolResult::success(tool_use_id, "done"))
+    }
+}
+
+/// Client end of the pipe
+struct PipeClient {
+    writer: WriteHalf<DuplexStream>,
+    lines: Lines<BufReader<ReadHalf<DuplexStream>>>,
+    /// Notifications read while waiting for a response
+    notifications: Vec<Value>,
+    server: tokio::task::JoinHandle<ted::Result<()>>,
+    _temp: TempDir,
+}
+
+impl PipeClient {
+    async fn start() -> Self {
+        Self::start_with(|server| server).await
+    }
+
+    async fn start_with(configure: impl FnOnce(McpServer) -> McpServer) -> Self {
+        let temp = TempDir::new().unwrap();
+        let workspace = temp.path().to_path_buf();
+        let context = ToolContext::new(
+            workspace.clone(),
+            Some(workspace),
+            uuid::Uuid::new_v4(),
+            false,
+        );
+        let server = configure(McpServer::new(ToolExecutor::new(context, false)));
+        let registry = ToolRegistry::with_builtins();
+        for name in registry.names() {
+            if let Some(tool) = registry.get(name) {
+                server.register_tool(tool.clone()).await;
+            }
+        }
+        server.register_tool(Arc::new(SlowTool)).await;
+        server.register_tool(Arc::new(StepsTool)).await;
+
+        let (client_io, server_io) = tokio::io::duplex(1 << 16);
+        let (server_read, server_write) = tokio::io::split(server_io);
+        let server = tokio::spawn(async move { server.serve(server_read, server_write).await });
+        let (client_read, writer) = tokio::io::split(client_io);
+
+        Self {
+            writer,
+            lines: BufReader::new(client_read).lines(),
+            notifications: Vec::new(),
+            server,
+            _temp: temp,
+        }
+    }
+
+    async fn send_raw(&mut self, line: &str) {
+        send_line(&mut self.writer, line).await;
+    }
+
+    async fn send(&mut self, message: Value) {
+        self.send_raw(&message.to_string()).await;
+    }
+
+    async fn read(&mut self) -> Value {
+        let line = tokio::time::timeout(READ_TIMEOUT, self.lines.next_line())
+            .await
+            .expect("timed out waiting for the server")
+            .unwrap()
+            .expect("server closed the pipe");
+        serde_json::from_str(&line).unwrap()
+    }
+
+    /// Read until a response (anything that is not a notification) arrives
+    async fn response(&mut self) -> Value {
+        loop {
+            let message = self.read().await;
+            if message.get("method").is_some() && message.get("id").is_none() {
+                self.notifications.push(message);
+            } else {
+                return message;
+            }
+        }
+    }
+
+    async fn request(&mut self, id: Value, method: &str, params: Option<Value>) -> Value {
+        let mut message = json!({"jsonrpc": "2.0", "id": id, "method": method});
+        if let Some(params) = params {
+            message["params"] = params;
+        }
+        self.send(message).await;
+        let response = self.response().await;
+        assert_eq!(response["jsonrpc"], "2.0");
+        assert_eq!(response["id"], id, "response id must echo the request id");
+        response
+    }
+
+    async fn initialize(&mut self, version: &str) -> Value {
+        let response = self
+            .request(
+                json!(0),
+                "initialize",
+                Some(json!({
+                    "protocolVersion": version,
+                    "capabilities": {},
+                    "clientInfo": {"name": "conformance", "version": "1.0"}
+                })),
+            )
+            .await;
+        self.send(json!({"jsonrpc": "2.0", "method": "notifications/initialized"}))
+            .await;
+        response
+    }
+
+    /// Wait briefly for notifications that trail a response
+    async fn drain_notifications(&mut self) {
+        while let Ok(Ok(Some(line))) =
+            tokio::time::timeout(Duration::from_millis(500), self.lines.next_line()).await
+        {
+            self.notifications
+                .push(serde_json::from_str(&line).unwrap());
+        }
+    }
+}
+
+async fn send_line<W: AsyncWrite + Unpin>(writer: &mut W, line: &str) {
+    writer.write_all(line.as_bytes()).await.unwrap();
+    writer.write_all(b"\n").await.unwrap();
+    writer.flush().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_initialize_negotiates_every_supported_version() {
+    for version in SUPPORTED_PROTOCOL_VERSIONS {
+        let mut client = PipeClient::start().await;
+        let response = client.initialize(version).await;
+        let result = &response["result"];
+        assert_eq!(result["protocolVersion"], *version);
+        assert_eq!(result["serverInfo"]["name"], "ted");
+        for capability in ["tools", "resources", "prompts", "logging"] {
+            assert!(
+                result["capabilities"][capability].is_object(),
+                "missing capability {}",
+                capability
+            );
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_initialize_offers_latest_version_for_unknown_request() {
+    let mut client = PipeClient::start().await;
+    let response = client.initialize("2023-01-01").await;
+    assert_eq!(response["result"]["protocolVersion"], PROTOCOL_VERSION);
+}
+
+#[tokio::test]
+async fn test_notifications_get_no_response() {
+    let mut client = PipeClient::start().await;
+    client.initialize(PROTOCOL_VERSION).await;
+    client
+        .send(json!({"jsonrpc": "2.0", "method": "notifications/unknown"}))
+        .await;
+    // The next line must be the ping response, not a reply to a notification
+    let response = client.request(json!(1), "ping", None).await;
+    assert_eq!(response["result"], json!({}));
+}
+
+#[tokio::test]
+async fn test_errors_follow_json_rpc() {
+    let mut client = PipeClient::start().await;
+    client.initialize(PROTOCOL_VERSION).await;
+
+    let response = client.request(json!("abc"), "no/such/method", None).await;
+    assert_eq!(response["error"]["code"], -32601);
+
+    client.send_raw("{this is not json").await;
+    let response = client.response().await;
+    assert_eq!(response["error"]["code"], -32700);
+    assert!(response["id"].is_null());
+
+    client
+        .send(json!({"jsonrpc": "2.0", "id": 9, "method": 42}))
+        .await;
+    let response = client.response().await;
+    assert_eq!(response["error"]["code"], -32600);
+    assert_eq!(response["id"], 9);
+
+    let response = client
+        .request(json!(10), "tools/call", Some(json!({"arguments": {}})))
+        .await;
+    assert_eq!(response["error"]["code"], -32602);
+}
+
+#[tokio::test]
+async fn test_batch_returns_array_of_responses() {
+    let mut client = PipeClient::start().await;
+    client.initialize(PROTOCOL_VERSION).await;
+    client
+        .send(json!([
+            {"jsonrpc": "2.0", "id": 1, "method": "ping"},
+            {"jsonrpc": "2.0", "method": "notifications/initialized"},
+            {"jsonrpc": "2.0", "id": 2, "method": "prompts/list"}
+        ]))
+        .await;
+    let response = client.response().await;
+    let replies = response.as_array().expect("batch reply must be an array");
+    assert_eq!(replies.len(), 2);
+    assert_eq!(replies[0]["id"], 1);
+    assert_eq!(replies[1]["id"], 2);
+}
+
+#[tokio::test]
+async fn test_tools_list_pages_cover_every_tool_once() {
+    let mut client = PipeClient::start_with(|server| server.with_page_size(3)).await;
+    client.initialize(PROTOCOL_VERSION).await;
+
+    let mut names = Vec::new();
+    let mut cursor: Option<Value> = None;
+    let mut pages = 0;
+    loop {
+        let params = cursor.take().map(|cursor| json!({"cursor": cursor}));
+        let response = client.request(json!(pages + 1), "tools/list", params).await;
+        let result = &response["result"];
+        let tools = result["tools"].as_array().unwrap();
+        assert!(tools.len() <= 3);
+        names.extend(
+            tools
+                .iter()
+                .map(|t| t["name"].as_str().unwrap().to_string()),
+        );
+        pages += 1;
+        match result.get("nextCursor") {
+            Some(next) => cursor = Some(next.clone()),
+            None => break,
+        }
+    }
+
+    assert!(pages > 1);
+    let mut unique = names.clone();
+    unique.sort();
+    unique.dedup();
+    assert_eq!(unique.len(), names.len());
+    assert!(names.contains(&"slow".to_string()));
+    assert!(names.contains(&"file_read".to_string()));
+}
+
+#[tokio::test]
+async fn test_cancelled_request_gets_no_response() {
+    let mut client = PipeClient::start().await;
+    client.initialize(PROTOCOL_VERSION).await;
+
+    client
+        .send(json!({
+            "jsonrpc": "2.0", "id": "long", "method": "tools/call",
+            "params": {"name": "slow", "arguments": {}}
+        }))
+        .await;
+    // Let the call start before cancelling it
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    client
+        .send(json!({
+            "jsonrpc": "2.0", "method": "notifications/cancelled",
+            "params": {"requestId": "long", "reason": "user pressed stop"}
+        }))
+        .await;
+
+    // The server keeps serving, and the cancelled call never answers
+    let response = client.request(json!(2), "ping", None).await;
+    assert_eq!(response["result"], json!({}));
+    client.drain_notifications().await;
+    assert!(client
+        .notifications
+        .iter()
+        .all(|message| message.get("id").is_none()));
+}
+
+#[tokio::test]
+async fn test_progress_notifications_carry_the_token() {
+    let mut client = PipeClient::start().await;
+    client.initialize(PROTOCOL_VERSION).await;
+
+    let response = client
+        .request(
+            json!(3),
+            "tools/call",
+            Some(json!({
+                "name": "steps",
+                "arguments": {},
+                "_meta": {"progressToken": 77}
+            })),
+        )
+        .await;
+    assert_eq!(response["result"]["content"][0]["text"], "done");
+    client.drain_notifications().await;
+
+    let progress: Vec<&Value> = client
+        .notifications
+        .iter()
+        .filter(|n| n["method"] == "notifications/progress")
+        .collect();
+    assert_eq!(progress.len(), 3);
+    let mut last = 0.0;
+    for notification in progress {
+        let params = &notification["params"];
+        assert_eq!(params["progressToken"], 77);
+        let value = params["progress"].as_f64().unwrap();
+        assert!(value > last, "progress must increase");
+        last = value;
+    }
+}
+
+#[tokio::test]
+async fn test_logging_respects_set_level() {
+    let mut client = PipeClient::start().await;
+    client.initialize(PROTOCOL_VERSION).await;
+
+    client
+        .request(json!(1), "tools/call", Some(json!({"name": "steps"})))
+        .await;
+    client.drain_notifications().await;
+    assert!(client
+        .notifications
+        .iter()
+        .all(|n| n["method"] != "notifications/message"));
+
+    let response = client
+        .request(json!(2), "logging/setLevel", Some(json!({"level": "info"})))
+        .await;
+    assert_eq!(response["result"], json!({}));
+    client
+        .request(json!(3), "tools/call", Some(json!({"name": "steps"})))
+        .await;
+    client.drain_notifications().await;
+    let message = client
+        .notifications
+        .iter()
+        .find(|n| n["method"] == "notifications/message")
+        .expect("expected a log message");
+    assert_eq!(message["params"]["level"], "info");
+    assert!(message["params"]["data"]
+        .as_str()
+        .unwrap()
+        .contains("steps"));
+}
+
+#[tokio::test]
+async fn test_server_stops_cleanly_at_eof() {
+    let mut client = PipeClient::start().await;
+    client.initialize(PROTOCOL_VERSION).await;
+    client.writer.shutdown().await.unwrap();
+    let PipeClient { server, .. } = client;
+    let result = tokio::time::timeout(READ_TIMEOUT, server)
+        .await
+        .expect("server did not stop at EOF")
+        .unwrap();
+    assert!(result.is_ok());
+}